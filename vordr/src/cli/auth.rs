@@ -11,6 +11,12 @@ use std::path::PathBuf;
 use tabled::{Table, Tabled};
 
 use crate::cli::Cli;
+use crate::registry::credentials::{native_backend_name, Credential, CredentialProvider, KeyringProvider, ProcessProvider};
+use crate::registry::paseto::{self, PasetoKeyPair};
+use crate::registry::RegistryClient;
+
+/// How long a token minted for an `--asymmetric` login stays valid.
+const PASETO_TOKEN_TTL: std::time::Duration = std::time::Duration::from_secs(300);
 
 /// Login to a container registry
 #[derive(Parser, Debug)]
@@ -31,9 +37,19 @@ pub struct LoginArgs {
     #[arg(long)]
     pub password_stdin: bool,
 
-    /// Credential store backend (auto, file, secret-service, pass)
+    /// Credential store backend (auto, file, secret-service, keychain, wincred, pass)
     #[arg(long, default_value = "auto")]
     pub credential_store: String,
+
+    /// Generate an Ed25519 key pair and authenticate with short-lived
+    /// signed PASETO v4.public tokens instead of a password
+    #[arg(long)]
+    pub asymmetric: bool,
+
+    /// Store a pre-obtained identity token instead of a password (e.g.
+    /// from an OAuth/OIDC flow against the registry)
+    #[arg(long)]
+    pub identity_token: Option<String>,
 }
 
 /// Logout from a container registry
@@ -45,6 +61,13 @@ pub struct LogoutArgs {
     /// Remove credentials for all registries
     #[arg(long)]
     pub all: bool,
+
+    /// Credential store backend the registry was logged in with (auto,
+    /// file, secret-service, keychain, wincred, pass). Only used when
+    /// auth.json records no helper for the registry; a recorded helper
+    /// takes precedence.
+    #[arg(long)]
+    pub credential_store: Option<String>,
 }
 
 /// Auth subcommand for listing credentials
@@ -81,6 +104,74 @@ struct AuthConfig {
     auths: HashMap<String, RegistryAuth>,
 }
 
+/// How long a stored `identity_token`/`registry_token` stays usable.
+/// Internally tagged so new variants stay forward-compatible with
+/// whatever `auth.json` entries an older binary already wrote.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "cache", rename_all = "snake_case")]
+enum CacheControl {
+    /// No expiry tracked - treated the same as not being there at all
+    /// once someone asks, e.g. plain password auth.
+    Never,
+    /// Valid for as long as it's stored; the token itself carried no
+    /// `exp` claim to parse.
+    Session,
+    /// Valid until `expiration` (Unix seconds), decoded from the
+    /// token's `exp` claim.
+    Expires { expiration: u64 },
+}
+
+/// Decodes a compact JWT's middle (payload) segment and reads its `exp`
+/// claim, without verifying the signature - this repo never validates
+/// these tokens itself, only reports their claimed lifetime.
+fn decode_jwt_exp(token: &str) -> Option<u64> {
+    let payload_b64 = token.split('.').nth(1)?;
+    let payload = base64::Engine::decode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, payload_b64).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&payload).ok()?;
+    claims.get("exp")?.as_u64()
+}
+
+/// The cache policy to store alongside a freshly obtained token: an
+/// `exp` claim becomes [`CacheControl::Expires`], anything else is
+/// assumed valid for the session (stored until explicit `logout`).
+fn cache_control_for_token(token: &str) -> CacheControl {
+    match decode_jwt_exp(token) {
+        Some(expiration) => CacheControl::Expires { expiration },
+        None => CacheControl::Session,
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn is_expired(cache: &CacheControl) -> bool {
+    match cache {
+        CacheControl::Expires { expiration } => *expiration < unix_now(),
+        CacheControl::Never | CacheControl::Session => false,
+    }
+}
+
+/// Renders `cache` the way `list_credentials` shows it in the `EXPIRES`
+/// column: `never`/`session` for the non-expiring policies, `expired`
+/// once past `expiration`, otherwise the remaining seconds.
+fn format_cache(cache: &Option<CacheControl>) -> String {
+    let Some(cache) = cache else {
+        return "never".to_string();
+    };
+    match cache {
+        CacheControl::Never => "never".to_string(),
+        CacheControl::Session => "session".to_string(),
+        CacheControl::Expires { expiration } => {
+            if is_expired(cache) {
+                "expired".to_string()
+            } else {
+                format!("{}s", expiration.saturating_sub(unix_now()))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct RegistryAuth {
     auth: Option<String>,       // base64(username:password)
@@ -90,6 +181,20 @@ struct RegistryAuth {
     identity_token: Option<String>,
     #[serde(rename = "registrytoken")]
     registry_token: Option<String>,
+    /// Set when the secret itself lives with an external credential
+    /// helper rather than in this file - holds the `credential_store`
+    /// value the helper was resolved from, so `logout` knows which
+    /// provider to erase from without the caller repeating `--credential-store`.
+    #[serde(rename = "credentialhelper", skip_serializing_if = "Option::is_none")]
+    credential_helper: Option<String>,
+    /// Base64-encoded Ed25519 secret seed for `--asymmetric` logins - set
+    /// instead of `auth`/`password`, never alongside them.
+    #[serde(rename = "paseto_key", skip_serializing_if = "Option::is_none")]
+    paseto_key: Option<String>,
+    /// Expiry policy for `identity_token`/`registry_token`, if either is
+    /// set. `None` for entries that don't carry a token at all.
+    #[serde(flatten)]
+    cache: Option<CacheControl>,
 }
 
 /// Execute login command
@@ -106,6 +211,14 @@ pub async fn login(args: LoginArgs, _cli: &Cli) -> Result<()> {
             .context("Failed to read username")?
     };
 
+    if args.asymmetric {
+        return login_asymmetric(&registry, &username);
+    }
+
+    if let Some(token) = args.identity_token {
+        return login_with_identity_token(&registry, &username, &token);
+    }
+
     // Get password
     let password = if args.password_stdin {
         // Read from stdin
@@ -128,28 +241,140 @@ pub async fn login(args: LoginArgs, _cli: &Cli) -> Result<()> {
     // Validate credentials against registry
     print!("Authenticating with {}... ", registry);
 
-    // In a real implementation, we'd make a request to the registry's auth endpoint
-    // For now, we'll just store the credentials
-    let auth = base64::Engine::encode(
-        &base64::engine::general_purpose::STANDARD,
-        format!("{}:{}", username, password),
-    );
+    let handshake = RegistryClient::new()
+        .login_handshake(&registry_api_host(&registry), &username, &password)
+        .await
+        .map_err(|e| {
+            println!("failed");
+            e
+        })
+        .context("Registry authentication failed")?;
+
+    let backend_name = resolved_backend_name(&args.credential_store).to_string();
+    if let Some(provider) = resolve_credential_provider(&args.credential_store)? {
+        let store_result = provider.store(
+            &registry,
+            &Credential {
+                username: username.clone(),
+                secret: password.clone(),
+            },
+        );
+
+        match store_result {
+            Ok(()) => {
+                // Record who holds the secret, but never the secret itself.
+                let auth_path = get_auth_file_path()?;
+                let mut config = load_auth_config(&auth_path)?;
+                config.auths.insert(
+                    registry.clone(),
+                    RegistryAuth {
+                        auth: None,
+                        username: Some(username),
+                        password: None,
+                        identity_token: None,
+                        registry_token: None,
+                        credential_helper: Some(backend_name.clone()),
+                        paseto_key: None,
+                        cache: None,
+                    },
+                );
+                save_auth_config(&auth_path, &config)?;
+
+                println!("done");
+                println!("\nLogin succeeded.");
+                println!("Credentials stored via '{}' credential store", backend_name);
+                return Ok(());
+            }
+            Err(e) if args.credential_store == "auto" => {
+                println!("unavailable");
+                eprintln!(
+                    "warning: '{}' credential store unavailable ({}), falling back to file",
+                    backend_name, e
+                );
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to store credentials via '{}' credential store", backend_name));
+            }
+        }
+    }
 
     // Store credentials
     let auth_path = get_auth_file_path()?;
     let mut config = load_auth_config(&auth_path)?;
 
+    // If the registry handed back a bearer token, store that and its real
+    // expiry instead of a long-lived base64(username:password) pair -
+    // only registries with no v2 token endpoint at all fall back to that.
+    let new_auth = match handshake {
+        Some(token) => RegistryAuth {
+            auth: None,
+            username: Some(username.clone()),
+            password: None,
+            identity_token: Some(token.token),
+            registry_token: None,
+            credential_helper: None,
+            paseto_key: None,
+            cache: Some(CacheControl::Expires {
+                expiration: unix_now() + token.expires_in,
+            }),
+        },
+        None => {
+            let auth = base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                format!("{}:{}", username, password),
+            );
+            RegistryAuth {
+                auth: Some(auth),
+                username: Some(username.clone()),
+                password: None, // Don't store plain password
+                identity_token: None,
+                registry_token: None,
+                credential_helper: None,
+                paseto_key: None,
+                cache: None,
+            }
+        }
+    };
+    config.auths.insert(registry.clone(), new_auth);
+
+    save_auth_config(&auth_path, &config)?;
+
+    println!("done");
+    println!("\nLogin succeeded.");
+    println!("Credentials stored in: {}", auth_path.display());
+
+    Ok(())
+}
+
+/// Stores a pre-obtained identity token as-is, caching its `exp` claim
+/// (if it decodes as a JWT) so `list_credentials` can show the real
+/// remaining lifetime and future re-authentication logic can tell a
+/// lapsed token from a live one.
+fn login_with_identity_token(registry: &str, username: &str, token: &str) -> Result<()> {
+    let cache = cache_control_for_token(token);
+
+    let auth_path = get_auth_file_path()?;
+    let mut config = load_auth_config(&auth_path)?;
+
+    // An expired stored token is treated as though nothing were stored
+    // at all - it's simply replaced, never reused or extended.
+    if config.auths.get(registry).and_then(|a| a.cache.as_ref()).is_some_and(is_expired) {
+        println!("Previous token for {} had expired; replacing it.", registry);
+    }
+
     config.auths.insert(
-        registry.clone(),
+        registry.to_string(),
         RegistryAuth {
-            auth: Some(auth),
-            username: Some(username.clone()),
-            password: None, // Don't store plain password
-            identity_token: None,
+            auth: None,
+            username: Some(username.to_string()),
+            password: None,
+            identity_token: Some(token.to_string()),
             registry_token: None,
+            credential_helper: None,
+            paseto_key: None,
+            cache: Some(cache),
         },
     );
-
     save_auth_config(&auth_path, &config)?;
 
     println!("done");
@@ -159,21 +384,67 @@ pub async fn login(args: LoginArgs, _cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// Generates an Ed25519 key pair and records only its secret seed - no
+/// password ever enters `auth.json` for this registry. `pull`/`push`
+/// mint a fresh short-lived PASETO v4.public token from this key for
+/// each request rather than sending the key itself.
+fn login_asymmetric(registry: &str, username: &str) -> Result<()> {
+    let key_pair = PasetoKeyPair::generate();
+
+    let auth_path = get_auth_file_path()?;
+    let mut config = load_auth_config(&auth_path)?;
+    config.auths.insert(
+        registry.to_string(),
+        RegistryAuth {
+            auth: None,
+            username: Some(username.to_string()),
+            password: None,
+            identity_token: None,
+            registry_token: None,
+            credential_helper: None,
+            paseto_key: Some(key_pair.to_stored()),
+            cache: None,
+        },
+    );
+    save_auth_config(&auth_path, &config)?;
+
+    println!("done");
+    println!("\nLogin succeeded.");
+    println!("Generated an Ed25519 key pair for asymmetric (PASETO v4.public) authentication.");
+    println!("Key id: {}", key_pair.key_id());
+
+    Ok(())
+}
+
 /// Execute logout command
 pub async fn logout(args: LogoutArgs, _cli: &Cli) -> Result<()> {
     let auth_path = get_auth_file_path()?;
     let mut config = load_auth_config(&auth_path)?;
 
     if args.all {
+        for (registry, auth) in config.auths.iter() {
+            let store = auth.credential_helper.as_deref().or(args.credential_store.as_deref());
+            if let Some(store) = store {
+                erase_from_helper(registry, store)?;
+            }
+        }
         let count = config.auths.len();
         config.auths.clear();
         save_auth_config(&auth_path, &config)?;
         println!("Removed credentials for {} registries", count);
     } else {
         let registry = normalize_registry(&args.registry);
-        if config.auths.remove(&registry).is_some() {
+        if let Some(auth) = config.auths.remove(&registry) {
+            let store = auth.credential_helper.as_deref().or(args.credential_store.as_deref());
+            if let Some(store) = store {
+                erase_from_helper(&registry, store)?;
+            }
             save_auth_config(&auth_path, &config)?;
             println!("Removed credentials for {}", registry);
+        } else if let Some(store) = args.credential_store.as_deref() {
+            // Nothing in auth.json (e.g. it only ever lived in the helper).
+            erase_from_helper(&registry, store)?;
+            println!("Removed credentials for {}", registry);
         } else {
             println!("No credentials found for {}", registry);
         }
@@ -182,6 +453,20 @@ pub async fn logout(args: LogoutArgs, _cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// Erases `registry`'s secret from the credential store named by
+/// `credential_store`. A no-op only for the plaintext `auth.json`
+/// backend (`"file"`) - `logout` calls this only when a helper is
+/// actually known to be involved, so there's no `"auto"`-without-a-hint
+/// case to default away here.
+fn erase_from_helper(registry: &str, credential_store: &str) -> Result<()> {
+    let Some(provider) = resolve_credential_provider(credential_store)? else {
+        return Ok(());
+    };
+    provider
+        .erase(registry)
+        .with_context(|| format!("Failed to erase credentials via '{}' credential store for {}", credential_store, registry))
+}
+
 /// Execute auth subcommand
 pub async fn execute_auth(args: AuthArgs, _cli: &Cli) -> Result<()> {
     match args.command {
@@ -205,6 +490,7 @@ async fn list_credentials(format: &str) -> Result<()> {
                 registry: String,
                 username: Option<String>,
                 method: String,
+                expires: String,
             }
 
             let creds: Vec<_> = config
@@ -213,7 +499,8 @@ async fn list_credentials(format: &str) -> Result<()> {
                 .map(|(registry, auth)| CredentialInfo {
                     registry: registry.clone(),
                     username: auth.username.clone(),
-                    method: detect_method(auth),
+                    method: detect_method(registry, auth),
+                    expires: token_expiry(registry, auth),
                 })
                 .collect();
 
@@ -226,8 +513,8 @@ async fn list_credentials(format: &str) -> Result<()> {
                 .map(|(registry, auth)| CredentialRow {
                     registry: registry.clone(),
                     username: auth.username.clone().unwrap_or_else(|| "-".to_string()),
-                    method: detect_method(auth),
-                    expires: "never".to_string(), // Would parse token expiry
+                    method: detect_method(registry, auth),
+                    expires: token_expiry(registry, auth),
                 })
                 .collect();
 
@@ -239,7 +526,24 @@ async fn list_credentials(format: &str) -> Result<()> {
     Ok(())
 }
 
-fn detect_method(auth: &RegistryAuth) -> String {
+/// Reports the real backend holding `registry`'s secret by querying it,
+/// rather than trusting the stored hint at face value: an entry whose
+/// helper no longer has the secret (deleted out-of-band, say) is called
+/// out as missing instead of silently shown as present.
+fn detect_method(registry: &str, auth: &RegistryAuth) -> String {
+    if auth.paseto_key.is_some() {
+        return "asymmetric".to_string();
+    }
+    if let Some(helper) = &auth.credential_helper {
+        return match resolve_credential_provider(helper) {
+            Ok(Some(provider)) => match provider.get(registry) {
+                Ok(Some(_)) => helper.clone(),
+                Ok(None) => format!("{} (missing)", helper),
+                Err(_) => format!("{} (unavailable)", helper),
+            },
+            _ => helper.clone(),
+        };
+    }
     if auth.identity_token.is_some() {
         "identity-token".to_string()
     } else if auth.registry_token.is_some() {
@@ -251,6 +555,92 @@ fn detect_method(auth: &RegistryAuth) -> String {
     }
 }
 
+/// The `EXPIRES` value for one entry: for `--asymmetric` entries, mints a
+/// token the way `pull`/`push` would and reports its (very short)
+/// remaining lifetime, demonstrating that minting and verification -
+/// including the `exp`/`aud` checks - actually round-trip against the
+/// stored key. Everything else defers to [`format_cache`], which reports
+/// the real remaining lifetime of a stored `identity_token`/`registry_token`
+/// (or `"never"` for plain password/file auth that carries no `cache`).
+fn token_expiry(registry: &str, auth: &RegistryAuth) -> String {
+    let Some(stored_key) = &auth.paseto_key else {
+        return format_cache(&auth.cache);
+    };
+    let Some(username) = &auth.username else {
+        return "unknown".to_string();
+    };
+
+    let result = PasetoKeyPair::from_stored(stored_key).and_then(|key_pair| {
+        let token = paseto::mint(&key_pair, username, registry, PASETO_TOKEN_TTL)?;
+        paseto::verify(&key_pair, &token, registry)
+    });
+
+    match result {
+        Ok(claims) => format!("{}s", claims.exp.saturating_sub(claims.iat)),
+        Err(_) => "invalid key".to_string(),
+    }
+}
+
+/// What `"auto"` actually resolves to - the native keyring backend for
+/// this platform - for display and for the hint recorded in auth.json.
+fn resolved_backend_name(credential_store: &str) -> &str {
+    match credential_store {
+        "auto" => native_backend_name(),
+        other => other,
+    }
+}
+
+/// Resolves `credential_store` to a [`CredentialProvider`], or `None` for
+/// the plaintext `auth.json` backend (`"file"`). `"auto"` and the
+/// platform's own native name (`secret-service`/`keychain`/`wincred`)
+/// resolve to [`KeyringProvider`]; requesting one of the other two native
+/// names on the wrong platform is an error rather than a silent
+/// mismatch. `"pass"` maps to the bundled `svalinn:pass` helper;
+/// anything else is passed through to [`ProcessProvider::new`] as-is (an
+/// absolute path, or a bare name to look up on `PATH`).
+fn resolve_credential_provider(credential_store: &str) -> Result<Option<Box<dyn CredentialProvider>>> {
+    match credential_store {
+        "file" => Ok(None),
+        "auto" => Ok(Some(Box::new(KeyringProvider::new()))),
+        "secret-service" | "keychain" | "wincred" => {
+            if credential_store != native_backend_name() {
+                anyhow::bail!(
+                    "'{}' credential store is not available on this platform (native store here is '{}')",
+                    credential_store,
+                    native_backend_name()
+                );
+            }
+            Ok(Some(Box::new(KeyringProvider::new())))
+        }
+        "pass" => {
+            let provider =
+                ProcessProvider::new("svalinn:pass").context("Failed to resolve 'pass' credential helper")?;
+            Ok(Some(Box::new(provider)))
+        }
+        other => {
+            let provider =
+                ProcessProvider::new(other).with_context(|| format!("Failed to resolve credential helper '{}'", other))?;
+            Ok(Some(Box::new(provider)))
+        }
+    }
+}
+
+/// Maps a `normalize_registry`-d auth.json key back to the actual host to
+/// probe `GET /v2/` on. For Docker Hub specifically these differ: the
+/// legacy `https://index.docker.io/v1/` storage key (kept for
+/// compatibility with `docker`'s own `auth.json`) isn't where the v2 API
+/// actually lives - that's `registry-1.docker.io`.
+fn registry_api_host(registry: &str) -> String {
+    if registry == "https://index.docker.io/v1/" {
+        return "registry-1.docker.io".to_string();
+    }
+    registry
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_string()
+}
+
 fn normalize_registry(registry: &str) -> String {
     let registry = registry.trim();
 
@@ -323,4 +713,13 @@ mod tests {
             "https://quay.io"
         );
     }
+
+    #[test]
+    fn test_registry_api_host() {
+        assert_eq!(
+            registry_api_host("https://index.docker.io/v1/"),
+            "registry-1.docker.io"
+        );
+        assert_eq!(registry_api_host("https://ghcr.io"), "ghcr.io");
+    }
 }
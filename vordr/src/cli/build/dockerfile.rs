@@ -0,0 +1,270 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Parses a (small, pragmatic) subset of Dockerfile syntax into an
+//! ordered instruction list for [`super::build_image`] to execute.
+//!
+//! Supported instructions: `FROM`, `RUN`, `COPY`, `ADD`, `ENV`, `WORKDIR`,
+//! `USER`, `CMD`, `ENTRYPOINT`, `EXPOSE`, `VOLUME`, `LABEL`, `ARG`.
+//! `ARG`/`ENV` values are substituted into later instructions as plain
+//! `${NAME}`/`$NAME` text replacement - good enough for the common case,
+//! but it doesn't understand quoting or escapes the way a shell would.
+//! `COPY --from=<stage>` (multi-stage builds) is rejected rather than
+//! silently copying the wrong thing; only the final `FROM` in the file is
+//! built, and everything before it is skipped.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+
+/// One parsed Dockerfile instruction, in source order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    From { image: String },
+    Run(Vec<String>),
+    Copy { sources: Vec<String>, dest: String },
+    Add { sources: Vec<String>, dest: String },
+    Env { key: String, value: String },
+    Workdir(String),
+    User(String),
+    Cmd(Vec<String>),
+    Entrypoint(Vec<String>),
+    Expose(String),
+    Volume(String),
+    Label { key: String, value: String },
+    Arg { name: String, default: Option<String> },
+}
+
+/// Parses `content` into an ordered instruction list, joining `\`-continued
+/// lines, dropping `#` comments, and substituting `ARG`/`ENV` values
+/// (in source order - an instruction only sees values set above it) into
+/// every instruction that follows.
+pub fn parse(content: &str) -> Result<Vec<Instruction>> {
+    let mut vars: HashMap<String, String> = HashMap::new();
+    let mut instructions = Vec::new();
+
+    for raw_line in join_continuations(content) {
+        let line = substitute(&raw_line, &vars);
+        let Some((keyword, rest)) = line.split_once(char::is_whitespace) else {
+            if line.is_empty() {
+                continue;
+            }
+            bail!("Dockerfile instruction '{}' has no arguments", line);
+        };
+        let keyword = keyword.to_ascii_uppercase();
+        let rest = rest.trim();
+
+        match keyword.as_str() {
+            "FROM" => instructions.push(Instruction::From { image: rest.to_string() }),
+            "RUN" => instructions.push(Instruction::Run(parse_command(rest)?)),
+            "CMD" => instructions.push(Instruction::Cmd(parse_command(rest)?)),
+            "ENTRYPOINT" => instructions.push(Instruction::Entrypoint(parse_command(rest)?)),
+            "COPY" => {
+                let (sources, dest) = parse_copy(rest)?;
+                instructions.push(Instruction::Copy { sources, dest });
+            }
+            "ADD" => {
+                let (sources, dest) = parse_copy(rest)?;
+                instructions.push(Instruction::Add { sources, dest });
+            }
+            "ENV" => {
+                for (key, value) in parse_env(rest)? {
+                    vars.insert(key.clone(), value.clone());
+                    instructions.push(Instruction::Env { key, value });
+                }
+            }
+            "LABEL" => {
+                for (key, value) in parse_env(rest)? {
+                    instructions.push(Instruction::Label { key, value });
+                }
+            }
+            "ARG" => {
+                let (name, default) = match rest.split_once('=') {
+                    Some((name, default)) => (name.trim().to_string(), Some(unquote(default.trim()))),
+                    None => (rest.to_string(), None),
+                };
+                if let Some(default) = &default {
+                    vars.insert(name.clone(), default.clone());
+                }
+                instructions.push(Instruction::Arg { name, default });
+            }
+            "WORKDIR" => instructions.push(Instruction::Workdir(rest.to_string())),
+            "USER" => instructions.push(Instruction::User(rest.to_string())),
+            "EXPOSE" => instructions.push(Instruction::Expose(rest.to_string())),
+            "VOLUME" => instructions.push(Instruction::Volume(rest.to_string())),
+            other => bail!("unsupported Dockerfile instruction '{}'", other),
+        }
+    }
+
+    Ok(instructions)
+}
+
+/// Joins `\`-continued lines into single logical lines, and drops blank
+/// lines and `#`-comments.
+fn join_continuations(content: &str) -> Vec<String> {
+    let mut logical = Vec::new();
+    let mut current = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || (current.is_empty() && trimmed.starts_with('#')) {
+            continue;
+        }
+
+        if let Some(prefix) = trimmed.strip_suffix('\\') {
+            current.push_str(prefix.trim_end());
+            current.push(' ');
+            continue;
+        }
+
+        current.push_str(trimmed);
+        logical.push(std::mem::take(&mut current));
+    }
+
+    if !current.is_empty() {
+        logical.push(current);
+    }
+
+    logical
+}
+
+/// Replaces `${NAME}` and `$NAME` with a known `ARG`/`ENV` value, leaving
+/// unknown names untouched.
+fn substitute(line: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+            match vars.get(&name) {
+                Some(value) => out.push_str(value),
+                None => out.push_str(&format!("${{{name}}}")),
+            }
+        } else {
+            let name: String = std::iter::from_fn(|| chars.by_ref().next_if(|c| c.is_alphanumeric() || *c == '_')).collect();
+            match vars.get(&name) {
+                Some(value) => out.push_str(value),
+                None => {
+                    out.push('$');
+                    out.push_str(&name);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Parses a `RUN`/`CMD`/`ENTRYPOINT` argument: exec form (`["a", "b"]`) is
+/// decoded as JSON, anything else is shell form and runs under `/bin/sh -c`.
+fn parse_command(rest: &str) -> Result<Vec<String>> {
+    if rest.trim_start().starts_with('[') {
+        let args: Vec<String> = serde_json::from_str(rest).context("invalid exec-form JSON array")?;
+        return Ok(args);
+    }
+    Ok(vec!["/bin/sh".to_string(), "-c".to_string(), rest.to_string()])
+}
+
+/// Parses a `COPY`/`ADD` argument into `(sources, dest)`, rejecting
+/// `--from=` (multi-stage copies aren't supported) and ignoring other
+/// flags (e.g. `--chown=`) since there's nothing here to apply them to yet.
+fn parse_copy(rest: &str) -> Result<(Vec<String>, String)> {
+    let mut tokens = Vec::new();
+    for token in rest.split_whitespace() {
+        if let Some(stage) = token.strip_prefix("--from=") {
+            bail!("COPY --from={} (multi-stage builds) is not supported", stage);
+        }
+        if token.starts_with("--") {
+            continue;
+        }
+        tokens.push(token.to_string());
+    }
+
+    if tokens.len() < 2 {
+        bail!("COPY/ADD requires at least one source and a destination");
+    }
+    let dest = tokens.pop().unwrap();
+    Ok((tokens, dest))
+}
+
+/// Parses `ENV`/`LABEL` arguments, which come in two forms: `KEY value`
+/// (a single pair, value is the rest of the line) or `KEY1=v1 KEY2=v2 ...`
+/// (one or more `=`-joined pairs).
+fn parse_env(rest: &str) -> Result<Vec<(String, String)>> {
+    if !rest.contains('=') {
+        let (key, value) = rest.split_once(char::is_whitespace).context("ENV/LABEL requires a key and a value")?;
+        return Ok(vec![(key.to_string(), unquote(value.trim()))]);
+    }
+
+    let mut pairs = Vec::new();
+    for token in rest.split_whitespace() {
+        let (key, value) = token.split_once('=').context("ENV/LABEL pair must be KEY=VALUE")?;
+        pairs.push((key.to_string(), unquote(value)));
+    }
+    Ok(pairs)
+}
+
+/// Strips one layer of matching `"..."` or `'...'` quotes, if present.
+fn unquote(value: &str) -> String {
+    let bytes = value.as_bytes();
+    if bytes.len() >= 2 && (value.starts_with('"') && value.ends_with('"') || value.starts_with('\'') && value.ends_with('\'')) {
+        return value[1..value.len() - 1].to_string();
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shell_and_exec_forms() {
+        let instructions = parse("FROM alpine\nRUN echo hi\nCMD [\"/bin/sh\", \"-c\", \"echo hi\"]").unwrap();
+        assert_eq!(instructions[0], Instruction::From { image: "alpine".to_string() });
+        assert_eq!(
+            instructions[1],
+            Instruction::Run(vec!["/bin/sh".to_string(), "-c".to_string(), "echo hi".to_string()])
+        );
+        assert_eq!(
+            instructions[2],
+            Instruction::Cmd(vec!["/bin/sh".to_string(), "-c".to_string(), "echo hi".to_string()])
+        );
+    }
+
+    #[test]
+    fn joins_line_continuations() {
+        let instructions = parse("RUN echo one \\\n    && echo two").unwrap();
+        assert_eq!(
+            instructions[0],
+            Instruction::Run(vec!["/bin/sh".to_string(), "-c".to_string(), "echo one && echo two".to_string()])
+        );
+    }
+
+    #[test]
+    fn substitutes_arg_and_env_into_later_instructions() {
+        let instructions = parse("ARG VERSION=3.18\nFROM alpine:${VERSION}\nENV APP_VERSION=$VERSION\nRUN echo $APP_VERSION").unwrap();
+        assert_eq!(instructions[1], Instruction::From { image: "alpine:3.18".to_string() });
+        assert_eq!(
+            instructions[3],
+            Instruction::Run(vec!["/bin/sh".to_string(), "-c".to_string(), "echo 3.18".to_string()])
+        );
+    }
+
+    #[test]
+    fn rejects_multi_stage_copy() {
+        let err = parse("FROM alpine\nCOPY --from=builder /out /out").unwrap_err();
+        assert!(err.to_string().contains("multi-stage"));
+    }
+
+    #[test]
+    fn parses_multiple_env_pairs() {
+        let instructions = parse("FROM alpine\nENV A=1 B=2").unwrap();
+        assert_eq!(instructions[1], Instruction::Env { key: "A".to_string(), value: "1".to_string() });
+        assert_eq!(instructions[2], Instruction::Env { key: "B".to_string(), value: "2".to_string() });
+    }
+}
@@ -0,0 +1,600 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! `vordr build` command implementation
+//!
+//! Builds an image layer-by-layer from a Dockerfile: the base image is
+//! resolved through the usual pull path, `RUN` executes in an ephemeral
+//! container created through [`ContainerLifecycle`] and its filesystem
+//! diff becomes a new layer, `COPY`/`ADD` package the named context files
+//! into a layer directly (no container needed), and everything else
+//! (`ENV`, `WORKDIR`, `USER`, `CMD`, `ENTRYPOINT`, `EXPOSE`, `VOLUME`,
+//! `LABEL`) folds into the image config that ends up at
+//! [`ImageInfo::config`]. [`dockerfile`] does the parsing.
+
+mod dockerfile;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::cli::{pull, run, Cli};
+use crate::engine::{ContainerInfo, ContainerLifecycle, ImageInfo, StateManager};
+use crate::ffi::{ConfigValidator, NetworkMode};
+use crate::registry::LayerStore;
+use crate::runtime::ShimClient;
+use dockerfile::Instruction;
+
+/// Arguments for the `build` command
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    /// Build context directory; `COPY`/`ADD` sources are resolved
+    /// relative to it
+    #[arg(default_value = ".")]
+    pub context: PathBuf,
+
+    /// Path to the Dockerfile (default: `<context>/Dockerfile`)
+    #[arg(short = 'f', long)]
+    pub file: Option<PathBuf>,
+
+    /// Name and tag for the resulting image (`name:tag`)
+    #[arg(short, long)]
+    pub tag: String,
+}
+
+pub async fn execute(args: BuildArgs, cli: &Cli) -> Result<()> {
+    let dockerfile_path = args.file.unwrap_or_else(|| args.context.join("Dockerfile"));
+    let content = std::fs::read_to_string(&dockerfile_path)
+        .with_context(|| format!("failed to read {}", dockerfile_path.display()))?;
+
+    let image = build_image(cli, &args.context, &content, &args.tag).await?;
+
+    println!("Successfully built {}", &image.id[..12.min(image.id.len())]);
+    println!("Successfully tagged {}", args.tag);
+    Ok(())
+}
+
+/// Runs every instruction of the final stage in `dockerfile_content`
+/// (earlier `FROM`s, i.e. multi-stage builds, are skipped - there is no
+/// `COPY --from=` support to reach back into them) and registers the
+/// resulting image under `tag`. Shared by `vordr build` and the
+/// `vordr_build` MCP tool.
+pub async fn build_image(cli: &Cli, context: &Path, dockerfile_content: &str, tag: &str) -> Result<ImageInfo> {
+    let instructions = dockerfile::parse(dockerfile_content).context("failed to parse Dockerfile")?;
+
+    let from_index = instructions
+        .iter()
+        .rposition(|i| matches!(i, Instruction::From { .. }))
+        .context("Dockerfile has no FROM instruction")?;
+    if instructions[..from_index].iter().any(|i| matches!(i, Instruction::From { .. })) {
+        warn!("Dockerfile has more than one FROM; only the final stage is built");
+    }
+
+    let Instruction::From { image: base_ref } = &instructions[from_index] else {
+        unreachable!("from_index always points at a From instruction");
+    };
+
+    let root_dir = Path::new(&cli.root);
+    let state = StateManager::open(Path::new(&cli.db_path)).context("Failed to open state database")?;
+    let layer_store = LayerStore::open(root_dir.join("layers")).context("failed to open local layer store")?;
+    let mut cache = LayerCache::open(root_dir.join("build-cache.json"))?;
+
+    let base = pull::ensure_image(cli, base_ref).await.context("failed to resolve FROM image")?;
+
+    let mut layers: Vec<(String, i64)> = state
+        .image_layers(&base.id)?
+        .into_iter()
+        .map(|l| (l.digest, l.size))
+        .collect();
+    let mut parent_digest = layers.last().map(|(d, _)| d.clone()).unwrap_or_else(|| base.id.clone());
+    let mut config = BuildConfig::from_base(&base);
+
+    for instruction in &instructions[from_index + 1..] {
+        match instruction {
+            Instruction::From { .. } => unreachable!("later FROMs start a new, unsupported stage"),
+            Instruction::Run(cmd) => {
+                let cache_key = cache_key(&parent_digest, "RUN", &serde_json::to_string(cmd)?, "");
+                let (digest, size) = match cache.get(&cache_key).cloned().filter(|d| layer_store.contains(d)) {
+                    Some(digest) => {
+                        let size = layer_store.blob_path(&digest).metadata().map(|m| m.len() as i64).unwrap_or(0);
+                        (digest, size)
+                    }
+                    None => {
+                        let layer_paths: Vec<PathBuf> = layers.iter().map(|(d, _)| layer_store.blob_path(d)).collect();
+                        let tar_bytes = run_instruction(cli, &base, &layer_paths, cmd, &config)
+                            .await
+                            .with_context(|| format!("RUN {:?} failed", cmd))?;
+                        let (digest, size) = register_layer(&layer_store, &tar_bytes)?;
+                        cache.insert(cache_key, digest.clone());
+                        cache.save(root_dir.join("build-cache.json"))?;
+                        (digest, size)
+                    }
+                };
+                layers.push((digest.clone(), size));
+                parent_digest = digest;
+            }
+            Instruction::Copy { sources, dest } | Instruction::Add { sources, dest } => {
+                let checksum = hash_sources(context, sources)?;
+                let cache_key = cache_key(&parent_digest, "COPY", &serde_json::to_string(&(sources, dest))?, &checksum);
+                let (digest, size) = match cache.get(&cache_key).cloned().filter(|d| layer_store.contains(d)) {
+                    Some(digest) => {
+                        let size = layer_store.blob_path(&digest).metadata().map(|m| m.len() as i64).unwrap_or(0);
+                        (digest, size)
+                    }
+                    None => {
+                        let tar_bytes = build_copy_layer(root_dir, context, sources, dest)
+                            .with_context(|| format!("COPY {:?} {} failed", sources, dest))?;
+                        let (digest, size) = register_layer(&layer_store, &tar_bytes)?;
+                        cache.insert(cache_key, digest.clone());
+                        cache.save(root_dir.join("build-cache.json"))?;
+                        (digest, size)
+                    }
+                };
+                layers.push((digest.clone(), size));
+                parent_digest = digest;
+            }
+            Instruction::Env { key, value } => config.set_env(key, value),
+            Instruction::Workdir(dir) => config.workdir = dir.clone(),
+            Instruction::User(user) => config.user = user.clone(),
+            Instruction::Cmd(cmd) => config.cmd = cmd.clone(),
+            Instruction::Entrypoint(entrypoint) => config.entrypoint = entrypoint.clone(),
+            Instruction::Expose(port) => config.exposed_ports.push(port.clone()),
+            Instruction::Volume(volume) => config.volumes.push(volume.clone()),
+            Instruction::Label { key, value } => {
+                config.labels.insert(key.clone(), value.clone());
+            }
+            Instruction::Arg { .. } => {}
+        }
+    }
+
+    let config_json = config.to_json().to_string();
+    let image_id = format!("sha256:{}", hex::encode(Sha256::digest(config_json.as_bytes())));
+    let size: i64 = layers.iter().map(|(_, s)| s).sum();
+
+    let repository = crate::registry::ImageReference::parse(tag).context("invalid -t/--tag value")?;
+    let canonical_repository = format!("{}/{}", repository.registry, repository.repository);
+    let tag_name = repository.tag.clone().unwrap_or_else(|| "latest".to_string());
+
+    if let Ok(existing) = state.get_image(&image_id) {
+        return Ok(existing);
+    }
+
+    state.create_image(
+        &image_id,
+        &image_id,
+        Some(&canonical_repository),
+        &[tag_name],
+        size,
+        &layers,
+        Some(&config_json),
+    )?;
+
+    Ok(state.get_image(&image_id)?)
+}
+
+/// Executes `cmd` in a fresh, throwaway container seeded with `layer_paths`
+/// unpacked onto its rootfs, and returns a gzip+tar layer of whatever it
+/// changed on disk.
+async fn run_instruction(
+    cli: &Cli,
+    base: &ImageInfo,
+    layer_paths: &[PathBuf],
+    cmd: &[String],
+    config: &BuildConfig,
+) -> Result<Vec<u8>> {
+    let lifecycle = ContainerLifecycle::new(Path::new(&cli.db_path), Path::new(&cli.root), &cli.runtime)
+        .context("failed to open container lifecycle")?;
+
+    let user_id: u32 = config.user.parse().unwrap_or(0);
+    let validated = ConfigValidator::new()
+        .privileged(false)
+        .user_namespace(true)
+        .user_id(user_id)
+        .network_mode(NetworkMode::Restricted)
+        .validate()
+        .context("build step failed gatekeeper validation")?;
+
+    let container_id = run::generate_container_id();
+    let container_name = format!("build-{}", &container_id[..12]);
+
+    // The ephemeral container's `image_id` FK needs a real row in
+    // `images`; the base image is the only one that exists at this
+    // point in the build (the accumulating layer stack has no image row
+    // of its own yet), so it stands in regardless of how many RUN steps
+    // have already layered on top of it.
+    let container = lifecycle
+        .create(
+            &container_id,
+            &container_name,
+            &base.id,
+            &validated,
+            Some(vec!["/bin/sh".to_string(), "-c".to_string(), "sleep infinity".to_string()]),
+        )
+        .context("failed to create build container")?;
+
+    let outcome = execute_run_step(&lifecycle, &container, &cli.runtime, cmd, config, layer_paths).await;
+
+    // Best-effort teardown regardless of how the step went - a failed
+    // build step shouldn't also leak the container it failed in.
+    let _ = lifecycle.stop(&container_id, 5).await;
+    if let Err(e) = lifecycle.delete(&container_id, true) {
+        warn!("failed to delete build container {}: {}", container_id, e);
+    }
+
+    outcome
+}
+
+async fn execute_run_step(
+    lifecycle: &ContainerLifecycle,
+    container: &ContainerInfo,
+    runtime: &str,
+    cmd: &[String],
+    config: &BuildConfig,
+    layer_paths: &[PathBuf],
+) -> Result<Vec<u8>> {
+    let rootfs = Path::new(&container.bundle_path).join("rootfs");
+    crate::unpack::unpack_layers(layer_paths, &rootfs).context("failed to unpack build context rootfs")?;
+
+    let before = snapshot(&rootfs);
+
+    lifecycle.start(&container.id).await.context("failed to start build container")?;
+
+    let shim = ShimClient::new(runtime, &container.bundle_path);
+    let process_spec = serde_json::json!({
+        "terminal": false,
+        "args": cmd,
+        "cwd": config.workdir,
+        "env": config.env,
+    })
+    .to_string();
+
+    let (code, output) = shim
+        .exec_captured(&container.id, &process_spec, Duration::from_secs(600))
+        .await
+        .context("failed to exec build step")?;
+    if code != 0 {
+        bail!("exited with status {}: {}", code, output.trim());
+    }
+
+    let after = snapshot(&rootfs);
+    package_diff(&rootfs, &before, &after)
+}
+
+/// Copies `sources` (resolved against `context`) into a scratch directory
+/// under `dest`, then packages that directory as a layer. Unlike `RUN`,
+/// this needs no container: the layer is exactly the files being added.
+fn build_copy_layer(root_dir: &Path, context: &Path, sources: &[String], dest: &str) -> Result<Vec<u8>> {
+    let scratch = root_dir.join("tmp").join(format!("build-copy-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&scratch)?;
+
+    let dest_rel = dest.trim_start_matches('/');
+    let multiple_sources = sources.len() > 1;
+    let dest_is_dir = multiple_sources || dest.ends_with('/');
+
+    for source in sources {
+        let src_path = context.join(source);
+        let target = if dest_is_dir {
+            let name = src_path
+                .file_name()
+                .with_context(|| format!("COPY source '{}' has no file name", source))?;
+            scratch.join(dest_rel).join(name)
+        } else {
+            scratch.join(dest_rel)
+        };
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        copy_recursive(&src_path, &target).with_context(|| format!("failed to copy {}", source))?;
+    }
+
+    let result = tar_directory(&scratch);
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+fn copy_recursive(src: &Path, dest: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(src)?;
+    if metadata.is_dir() {
+        std::fs::create_dir_all(dest)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        std::fs::copy(src, dest)?;
+    }
+    Ok(())
+}
+
+/// Walks `root` and packages every entry under it as an upsert - used for
+/// `COPY`/`ADD`, where the scratch directory already contains exactly the
+/// new layer's contents and nothing needs diffing.
+fn tar_directory(root: &Path) -> Result<Vec<u8>> {
+    let entries = snapshot(root).into_keys().map(|rel| (rel, Change::Upsert)).collect::<Vec<_>>();
+    package_layer(root, &entries)
+}
+
+/// Diffs `before`/`after` rootfs snapshots into upserts (new or changed
+/// paths) and deletions (present before, gone after - represented as a
+/// whiteout, per the same convention [`crate::unpack`] reads back), then
+/// packages the result as a gzip+tar layer.
+fn package_diff(rootfs: &Path, before: &HashMap<PathBuf, (u64, u64)>, after: &HashMap<PathBuf, (u64, u64)>) -> Result<Vec<u8>> {
+    let mut changes: Vec<(PathBuf, Change)> = after
+        .iter()
+        .filter(|(path, meta)| before.get(*path) != Some(*meta))
+        .map(|(path, _)| (path.clone(), Change::Upsert))
+        .collect();
+    changes.extend(before.keys().filter(|path| !after.contains_key(*path)).map(|path| (path.clone(), Change::Delete)));
+
+    package_layer(rootfs, &changes)
+}
+
+enum Change {
+    Upsert,
+    Delete,
+}
+
+fn package_layer(root: &Path, changes: &[(PathBuf, Change)]) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+
+    for (rel, change) in changes {
+        match change {
+            Change::Upsert => {
+                builder
+                    .append_path_with_name(root.join(rel), rel)
+                    .with_context(|| format!("failed to add {} to layer", rel.display()))?;
+            }
+            Change::Delete => {
+                let parent = rel.parent().unwrap_or_else(|| Path::new(""));
+                let name = rel.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                let whiteout = parent.join(format!(".wh.{name}"));
+                let mut header = tar::Header::new_gnu();
+                header.set_size(0);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append_data(&mut header, whiteout, std::io::empty())?;
+            }
+        }
+    }
+
+    let tar_bytes = builder.into_inner().context("failed to finish layer tar")?;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, &tar_bytes).context("failed to gzip layer")?;
+    encoder.finish().context("failed to finish layer gzip stream")
+}
+
+/// Recursively records every entry under `root` as `relative path ->
+/// (mtime in whole seconds, size)`, the same iterative stack walk
+/// [`crate::engine::usage::dir_size`] uses. Mtime-and-size is a cheap
+/// proxy for "did this change" - it can miss a same-second rewrite that
+/// leaves size unchanged, which is an accepted gap here.
+fn snapshot(root: &Path) -> HashMap<PathBuf, (u64, u64)> {
+    let mut map = HashMap::new();
+    let mut stack = vec![PathBuf::new()];
+
+    while let Some(rel) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(root.join(&rel)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let rel_child = rel.join(entry.file_name());
+            let mtime = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            map.insert(rel_child.clone(), (mtime, metadata.len()));
+            if metadata.is_dir() {
+                stack.push(rel_child);
+            }
+        }
+    }
+
+    map
+}
+
+/// Hashes the contents of every source (recursing into directories, in a
+/// stable sorted order) so the build cache key changes whenever a `COPY`/
+/// `ADD`'s inputs do, even though the instruction text itself didn't.
+fn hash_sources(context: &Path, sources: &[String]) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for source in sources {
+        hash_path(&context.join(source), &mut hasher)?;
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn hash_path(path: &Path, hasher: &mut Sha256) -> Result<()> {
+    let metadata = std::fs::symlink_metadata(path).with_context(|| format!("COPY/ADD source not found: {}", path.display()))?;
+    if metadata.is_dir() {
+        let mut entries: Vec<_> = std::fs::read_dir(path)?.filter_map(|e| e.ok()).collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            hasher.update(entry.file_name().to_string_lossy().as_bytes());
+            hash_path(&entry.path(), hasher)?;
+        }
+    } else {
+        hasher.update(std::fs::read(path)?);
+    }
+    Ok(())
+}
+
+fn cache_key(parent_digest: &str, kind: &str, instruction_text: &str, extra: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(parent_digest.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(kind.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(instruction_text.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(extra.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn register_layer(layer_store: &LayerStore, tar_bytes: &[u8]) -> Result<(String, i64)> {
+    let digest = format!("sha256:{}", hex::encode(Sha256::digest(tar_bytes)));
+    layer_store.insert(&digest, tar_bytes).context("failed to store new layer blob")?;
+    Ok((digest, tar_bytes.len() as i64))
+}
+
+/// The image config assembled from the base image plus every `ENV`/
+/// `WORKDIR`/`USER`/`CMD`/`ENTRYPOINT`/`EXPOSE`/`VOLUME`/`LABEL`
+/// instruction executed on top of it. Serializes to the same shape
+/// [`crate::cli::images::inspect_image`] reads back out of
+/// [`ImageInfo::config`].
+struct BuildConfig {
+    env: Vec<String>,
+    workdir: String,
+    user: String,
+    cmd: Vec<String>,
+    entrypoint: Vec<String>,
+    labels: HashMap<String, String>,
+    exposed_ports: Vec<String>,
+    volumes: Vec<String>,
+}
+
+impl BuildConfig {
+    fn from_base(base: &ImageInfo) -> Self {
+        let parsed: serde_json::Value = base
+            .config
+            .as_deref()
+            .and_then(|c| serde_json::from_str(c).ok())
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        let str_vec = |key: &str| -> Vec<String> {
+            parsed
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+
+        let env = {
+            let env = str_vec("env");
+            if env.is_empty() {
+                vec!["PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin".to_string()]
+            } else {
+                env
+            }
+        };
+
+        let cmd = {
+            let cmd = str_vec("cmd");
+            if cmd.is_empty() {
+                vec!["/bin/sh".to_string()]
+            } else {
+                cmd
+            }
+        };
+
+        Self {
+            env,
+            workdir: parsed.get("workdir").and_then(|v| v.as_str()).unwrap_or("/").to_string(),
+            user: parsed.get("user").and_then(|v| v.as_str()).unwrap_or("0").to_string(),
+            cmd,
+            entrypoint: str_vec("entrypoint"),
+            labels: parsed
+                .get("labels")
+                .and_then(|v| v.as_object())
+                .map(|m| m.iter().filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string()))).collect())
+                .unwrap_or_default(),
+            exposed_ports: str_vec("exposed_ports"),
+            volumes: str_vec("volumes"),
+        }
+    }
+
+    fn set_env(&mut self, key: &str, value: &str) {
+        let prefix = format!("{key}=");
+        match self.env.iter_mut().find(|e| e.starts_with(&prefix)) {
+            Some(existing) => *existing = format!("{prefix}{value}"),
+            None => self.env.push(format!("{prefix}{value}")),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "architecture": "amd64",
+            "os": "linux",
+            "labels": self.labels,
+            "entrypoint": self.entrypoint,
+            "cmd": self.cmd,
+            "env": self.env,
+            "workdir": self.workdir,
+            "user": self.user,
+            "exposed_ports": self.exposed_ports,
+            "volumes": self.volumes,
+        })
+    }
+}
+
+/// A JSON-file-backed cache mapping a [`cache_key`] to the layer digest it
+/// produced, so rebuilding with unchanged `RUN`/`COPY`/`ADD` steps reuses
+/// the layer instead of redoing the work.
+struct LayerCache {
+    entries: HashMap<String, String>,
+}
+
+impl LayerCache {
+    fn open(path: PathBuf) -> Result<Self> {
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e).context("failed to read build cache"),
+        };
+        Ok(Self { entries })
+    }
+
+    fn get(&self, key: &str) -> Option<&String> {
+        self.entries.get(key)
+    }
+
+    fn insert(&mut self, key: String, digest: String) {
+        self.entries.insert(key, digest);
+    }
+
+    fn save(&self, path: PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(&self.entries)?).context("failed to write build cache")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_config_applies_env_overrides() {
+        let base = ImageInfo {
+            id: "sha256:base".to_string(),
+            digest: "sha256:base".to_string(),
+            repository: None,
+            tags: Vec::new(),
+            size: 0,
+            created_at: String::new(),
+            config: Some(serde_json::json!({ "env": ["A=1"] }).to_string()),
+            resolve_mode: crate::engine::ResolveMode::Default,
+        };
+        let mut config = BuildConfig::from_base(&base);
+        config.set_env("A", "2");
+        config.set_env("B", "3");
+        assert_eq!(config.env, vec!["A=2".to_string(), "B=3".to_string()]);
+    }
+
+    #[test]
+    fn cache_key_changes_with_parent_digest() {
+        let a = cache_key("sha256:one", "RUN", "[\"echo\"]", "");
+        let b = cache_key("sha256:two", "RUN", "[\"echo\"]", "");
+        assert_ne!(a, b);
+    }
+}
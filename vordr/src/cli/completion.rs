@@ -4,7 +4,10 @@
 use anyhow::Result;
 use clap::{CommandFactory, Parser};
 use clap_complete::{generate, Shell};
-use std::io;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::cli::Cli;
 
 /// Generate shell completions
 #[derive(Parser, Debug)]
@@ -16,12 +19,184 @@ pub struct CompletionArgs {
 
 /// Execute completion generation
 pub fn execute(args: CompletionArgs) -> Result<()> {
-    let mut cmd = crate::cli::Cli::command();
+    let mut cmd = Cli::command();
     let name = cmd.get_name().to_string();
-    generate(args.shell, &mut cmd, name, &mut io::stdout());
+
+    let mut buf = Vec::new();
+    generate(args.shell, &mut cmd, name.clone(), &mut buf);
+    io::stdout().write_all(&buf)?;
+
+    if let Some(dynamic) = dynamic_completion_snippet(args.shell, &name) {
+        io::stdout().write_all(dynamic.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// The kinds of live names `vordr __complete <kind> <prefix>` knows how to
+/// answer. Keyed to the shell snippets in [`dynamic_completion_snippet`],
+/// which shell out to it for the arguments listed there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompletionKind {
+    Container,
+    Image,
+    Network,
+}
+
+impl CompletionKind {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "container" => Some(CompletionKind::Container),
+            "image" => Some(CompletionKind::Image),
+            "network" => Some(CompletionKind::Network),
+            _ => None,
+        }
+    }
+}
+
+/// Implements the hidden `vordr __complete <kind> <prefix>` subcommand: prints
+/// one matching candidate per line to stdout. Used only by the shell snippets
+/// below - never invoked directly by a user.
+///
+/// Unknown `kind`s and state-store errors (most commonly: no container has
+/// ever been created, so the database doesn't exist yet) print nothing and
+/// return `Ok(())` rather than an error, so a shell calling this as part of
+/// tab-completion just sees an empty candidate list and falls back to the
+/// static `clap_complete` suggestions instead of showing a stray error.
+pub fn execute_complete(kind: &str, prefix: &str, cli: &Cli) -> Result<()> {
+    let Some(kind) = CompletionKind::from_str(kind) else {
+        return Ok(());
+    };
+    let Ok(state) = crate::engine::StateManager::open(Path::new(&cli.db_path)) else {
+        return Ok(());
+    };
+
+    let candidates: Vec<String> = match kind {
+        CompletionKind::Container => state
+            .list_containers(None)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|c| [c.name, c.id])
+            .collect(),
+        CompletionKind::Image => state
+            .list_images()
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|i| {
+                let refs: Vec<String> = match &i.repository {
+                    Some(repo) => i.tags.iter().map(|tag| format!("{repo}:{tag}")).collect(),
+                    None => Vec::new(),
+                };
+                refs.into_iter().chain(std::iter::once(i.id))
+            })
+            .collect(),
+        CompletionKind::Network => state
+            .list_networks()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|n| n.name)
+            .collect(),
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for candidate in candidates {
+        if candidate.starts_with(prefix) {
+            writeln!(out, "{candidate}")?;
+        }
+    }
     Ok(())
 }
 
+/// A hand-written snippet appended to the static `clap_complete` output for
+/// shells where shelling out to `<bin> __complete <kind> <prefix>` is
+/// straightforward, wiring up live container/image/network suggestions for
+/// the arguments that take them. `None` for shells (PowerShell, Elvish)
+/// where that's fiddlier - those fall back to the static completions alone.
+fn dynamic_completion_snippet(shell: Shell, bin: &str) -> Option<String> {
+    match shell {
+        Shell::Bash => Some(format!(
+            r#"
+# --- dynamic completion ({bin}) ---
+# Renames the static completion function generated above out of the way,
+# then replaces it with a wrapper that shells out to `{bin} __complete` for
+# the arguments listed below and falls back to the static one (for flags,
+# subcommand names, and everything else, or if the state store can't be
+# read) whenever that comes back empty.
+eval "$(declare -f _{bin} | sed '1s/_{bin} ()/_{bin}_static ()/')"
+
+_{bin}_complete_dynamic() {{
+    local kind="$1" cur="$2"
+    local IFS=$'\n'
+    COMPREPLY=( $(compgen -W "$({bin} __complete "$kind" "$cur" 2>/dev/null)" -- "$cur") )
+}}
+
+_{bin}() {{
+    local cur words
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    words="${{COMP_WORDS[*]:1:COMP_CWORD-1}}"
+    case "$words" in
+        run|run\ *)
+            _{bin}_complete_dynamic image "$cur" ;;
+        stop|rm|exec|logs|inspect)
+            _{bin}_complete_dynamic container "$cur" ;;
+        network\ rm|network\ inspect|network\ connect|network\ disconnect)
+            _{bin}_complete_dynamic network "$cur" ;;
+    esac
+    if [[ ${{#COMPREPLY[@]}} -eq 0 ]]; then
+        _{bin}_static
+    fi
+}}
+"#,
+            bin = bin
+        )),
+        Shell::Zsh => Some(format!(
+            r#"
+# --- dynamic completion ({bin}) ---
+# Same idea as the Bash snippet: stash the static completion function under
+# a new name, then shell out to `{bin} __complete` for live candidates on
+# the arguments listed below, falling back to the static one otherwise.
+functions[_{bin}_static]=$functions[_{bin}]
+
+_{bin}_complete_dynamic() {{
+    local kind="$1" cur="$2"
+    local -a candidates
+    candidates=(${{(f)"$({bin} __complete "$kind" "$cur" 2>/dev/null)"}})
+    (( ${{#candidates}} )) || return 1
+    compadd -a candidates
+}}
+
+_{bin}() {{
+    local -a leading
+    leading=(${{words[2,CURRENT-1]}})
+    local cur="${{words[CURRENT]}}"
+    case "${{leading[*]}}" in
+        run|run\ *)
+            _{bin}_complete_dynamic image "$cur" && return 0 ;;
+        stop|rm|exec|logs|inspect)
+            _{bin}_complete_dynamic container "$cur" && return 0 ;;
+        network\ rm|network\ inspect|network\ connect|network\ disconnect)
+            _{bin}_complete_dynamic network "$cur" && return 0 ;;
+    esac
+    _{bin}_static
+}}
+"#,
+            bin = bin
+        )),
+        Shell::Fish => Some(format!(
+            r#"
+# --- dynamic completion ({bin}) ---
+# Fish completions are already just "run this command for candidates", so
+# no wrapper is needed - these just add to the static rules generated above.
+complete -c {bin} -n '__fish_seen_subcommand_from run' -f -a '({bin} __complete image (commandline -ct))'
+complete -c {bin} -n '__fish_seen_subcommand_from stop rm exec logs inspect' -f -a '({bin} __complete container (commandline -ct))'
+complete -c {bin} -n '__fish_seen_subcommand_from network; and __fish_seen_subcommand_from rm inspect connect disconnect' -f -a '({bin} __complete network (commandline -ct))'
+"#,
+            bin = bin
+        )),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,4 +207,19 @@ mod tests {
         let args = CompletionArgs { shell: Shell::Bash };
         assert!(matches!(args.shell, Shell::Bash));
     }
+
+    #[test]
+    fn dynamic_snippet_only_for_shells_that_support_it() {
+        assert!(dynamic_completion_snippet(Shell::Bash, "vordr").is_some());
+        assert!(dynamic_completion_snippet(Shell::Zsh, "vordr").is_some());
+        assert!(dynamic_completion_snippet(Shell::Fish, "vordr").is_some());
+        assert!(dynamic_completion_snippet(Shell::PowerShell, "vordr").is_none());
+        assert!(dynamic_completion_snippet(Shell::Elvish, "vordr").is_none());
+    }
+
+    #[test]
+    fn completion_kind_rejects_unknown_values() {
+        assert!(CompletionKind::from_str("bogus").is_none());
+        assert_eq!(CompletionKind::from_str("container"), Some(CompletionKind::Container));
+    }
 }
@@ -0,0 +1,1352 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Docker Compose subset support
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use dialoguer::Confirm;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tabled::{Table, Tabled};
+use tokio::time::sleep;
+
+use crate::cli::Cli;
+
+mod runtime;
+
+use runtime::{BollardRuntime, ComposeRuntime};
+
+/// Compose commands for multi-container applications
+#[derive(Parser, Debug)]
+pub struct ComposeArgs {
+    /// Path to compose file
+    #[arg(short, long, default_value = "compose.yaml")]
+    pub file: PathBuf,
+
+    /// Project name (default: directory name)
+    #[arg(short, long)]
+    pub project_name: Option<String>,
+
+    #[command(subcommand)]
+    pub command: ComposeCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ComposeCommands {
+    /// Create and start containers
+    Up {
+        /// Run in background
+        #[arg(short, long)]
+        detach: bool,
+
+        /// Don't start linked services
+        #[arg(long)]
+        no_deps: bool,
+
+        /// Force recreate containers
+        #[arg(long)]
+        force_recreate: bool,
+
+        /// Specific services to start
+        services: Vec<String>,
+    },
+
+    /// Stop and remove containers
+    Down {
+        /// Remove named volumes
+        #[arg(short, long)]
+        volumes: bool,
+
+        /// Remove images (local|all)
+        #[arg(long)]
+        rmi: Option<String>,
+
+        /// Remove orphan containers
+        #[arg(long)]
+        remove_orphans: bool,
+    },
+
+    /// List containers
+    Ps {
+        /// Show all (including stopped)
+        #[arg(short, long)]
+        all: bool,
+
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+
+    /// View container logs
+    Logs {
+        /// Service name
+        service: Option<String>,
+
+        /// Follow log output
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Number of lines to show
+        #[arg(long)]
+        tail: Option<usize>,
+
+        /// Show timestamps
+        #[arg(short, long)]
+        timestamps: bool,
+    },
+
+    /// Validate and view compose file
+    Config {
+        /// Only check for errors, don't print
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Resolve and print compose file
+        #[arg(long)]
+        resolve: bool,
+    },
+
+    /// Pull service images
+    Pull {
+        /// Service names
+        services: Vec<String>,
+
+        /// Ignore images that don't exist
+        #[arg(long)]
+        ignore_pull_failures: bool,
+    },
+}
+
+/// Supported compose file structure
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ComposeFile {
+    /// Version (ignored, always v3+ semantics)
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// Service definitions
+    #[serde(default)]
+    pub services: HashMap<String, ServiceConfig>,
+
+    /// Network definitions
+    #[serde(default)]
+    pub networks: HashMap<String, NetworkConfig>,
+
+    /// Volume definitions
+    #[serde(default)]
+    pub volumes: HashMap<String, VolumeConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct ServiceConfig {
+    /// Container image (required)
+    pub image: Option<String>,
+
+    /// Build context (UNSUPPORTED)
+    pub build: Option<serde_yaml::Value>,
+
+    /// Override command
+    pub command: Option<StringOrList>,
+
+    /// Container entrypoint
+    pub entrypoint: Option<StringOrList>,
+
+    /// Environment variables
+    #[serde(default)]
+    pub environment: Option<EnvironmentConfig>,
+
+    /// Environment file
+    pub env_file: Option<StringOrList>,
+
+    /// Port mappings
+    #[serde(default)]
+    pub ports: Vec<String>,
+
+    /// Volume mounts
+    #[serde(default)]
+    pub volumes: Vec<String>,
+
+    /// Service dependencies
+    #[serde(default)]
+    pub depends_on: Option<DependsOnConfig>,
+
+    /// Networks to attach
+    #[serde(default)]
+    pub networks: Option<NetworksConfig>,
+
+    /// Restart policy
+    pub restart: Option<String>,
+
+    /// Container name
+    pub container_name: Option<String>,
+
+    /// Working directory
+    pub working_dir: Option<String>,
+
+    /// User
+    pub user: Option<String>,
+
+    /// Privileged mode
+    #[serde(default)]
+    pub privileged: bool,
+
+    /// Health check
+    pub healthcheck: Option<HealthCheck>,
+
+    /// Deploy config (UNSUPPORTED)
+    pub deploy: Option<serde_yaml::Value>,
+
+    /// Configs (UNSUPPORTED)
+    pub configs: Option<serde_yaml::Value>,
+
+    /// Secrets (UNSUPPORTED)
+    pub secrets: Option<serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StringOrList {
+    String(String),
+    List(Vec<String>),
+}
+
+impl StringOrList {
+    /// Normalizes both YAML forms to a list - `command: foo bar` and
+    /// `command: [foo, bar]` mean the same thing. The string form is
+    /// split on whitespace rather than full shell-word parsing, in
+    /// keeping with this module's "subset" support.
+    fn as_vec(&self) -> Vec<String> {
+        match self {
+            StringOrList::String(s) => s.split_whitespace().map(str::to_string).collect(),
+            StringOrList::List(list) => list.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum EnvironmentConfig {
+    List(Vec<String>),
+    Map(HashMap<String, String>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum DependsOnConfig {
+    List(Vec<String>),
+    Map(HashMap<String, DependsOnCondition>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DependsOnCondition {
+    pub condition: Option<String>,
+}
+
+/// A service's `healthcheck`. `test` holds the probe command the way
+/// Compose writes it (`["CMD", "curl", "-f", "http://localhost"]` or a
+/// single shell string) - interpreted by the daemon, not by this module,
+/// since the daemon is what actually runs it inside the container.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HealthCheck {
+    pub test: StringOrList,
+
+    /// Time between checks, e.g. `"30s"`. Defaults to 30s if unset.
+    pub interval: Option<String>,
+
+    /// Time a single check is allowed to run before it counts as failed.
+    pub timeout: Option<String>,
+
+    /// Consecutive failures before the container is considered unhealthy.
+    pub retries: Option<u32>,
+
+    /// Grace period after container start during which failures don't
+    /// count towards `retries`.
+    pub start_period: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum NetworksConfig {
+    List(Vec<String>),
+    Map(HashMap<String, Option<NetworkAttachment>>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NetworkAttachment {
+    pub aliases: Option<Vec<String>>,
+    pub ipv4_address: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct NetworkConfig {
+    pub driver: Option<String>,
+    pub external: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct VolumeConfig {
+    pub driver: Option<String>,
+    pub external: Option<bool>,
+}
+
+#[derive(Tabled)]
+struct ServiceStatusRow {
+    #[tabled(rename = "NAME")]
+    name: String,
+    #[tabled(rename = "IMAGE")]
+    image: String,
+    #[tabled(rename = "STATUS")]
+    status: String,
+    #[tabled(rename = "PORTS")]
+    ports: String,
+}
+
+/// Keys that are explicitly unsupported
+const UNSUPPORTED_KEYS: &[(&str, &str)] = &[
+    ("build", "Use 'vordr build' first, then reference image"),
+    ("deploy", "Deploy config ignored (use Svalinn for orchestration)"),
+    ("configs", "Use volume mounts instead"),
+    ("secrets", "Use environment variables or volume-mounted files"),
+];
+
+/// Execute compose command
+pub async fn execute(args: ComposeArgs, cli: &Cli) -> Result<()> {
+    let project_name = args.project_name.clone().unwrap_or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "default".to_string())
+    });
+
+    match args.command {
+        ComposeCommands::Up {
+            detach,
+            no_deps,
+            force_recreate,
+            services,
+        } => up(cli, &args.file, &project_name, detach, no_deps, force_recreate, services).await,
+        ComposeCommands::Down {
+            volumes,
+            rmi,
+            remove_orphans,
+        } => down(cli, &args.file, &project_name, volumes, rmi, remove_orphans).await,
+        ComposeCommands::Ps { all, format } => ps(cli, &args.file, &project_name, all, &format).await,
+        ComposeCommands::Logs {
+            service,
+            follow,
+            tail,
+            timestamps,
+        } => logs(cli, &args.file, &project_name, service, follow, tail, timestamps).await,
+        ComposeCommands::Config { quiet, resolve } => config(&args.file, quiet, resolve).await,
+        ComposeCommands::Pull {
+            services,
+            ignore_pull_failures,
+        } => pull(cli, &args.file, services, ignore_pull_failures).await,
+    }
+}
+
+fn load_compose_file(path: &PathBuf) -> Result<ComposeFile> {
+    // Try multiple file names
+    let paths_to_try = if path.exists() {
+        vec![path.clone()]
+    } else {
+        vec![
+            PathBuf::from("compose.yaml"),
+            PathBuf::from("compose.yml"),
+            PathBuf::from("docker-compose.yaml"),
+            PathBuf::from("docker-compose.yml"),
+        ]
+    };
+
+    for p in &paths_to_try {
+        if p.exists() {
+            let content = std::fs::read_to_string(p)
+                .with_context(|| format!("Failed to read {}", p.display()))?;
+            let interpolated = interpolate(&content)
+                .with_context(|| format!("Failed to interpolate {}", p.display()))?;
+
+            let mut compose: ComposeFile = serde_yaml::from_str(&interpolated)
+                .with_context(|| format!("Failed to parse {}", p.display()))?;
+
+            let base_dir = p.parent().unwrap_or_else(|| Path::new("."));
+            apply_env_files(&mut compose, base_dir)
+                .with_context(|| format!("Failed to load env_file entries for {}", p.display()))?;
+
+            println!("Using: {}\n", p.display());
+            return Ok(compose);
+        }
+    }
+
+    bail!("No compose file found. Tried: compose.yaml, docker-compose.yaml")
+}
+
+/// Expands `${VAR}`-style references in `raw` against the process
+/// environment, the way Compose does before the result is ever parsed as
+/// YAML - a value is still just text until interpolation replaces it, so
+/// this runs on the raw file content rather than on a parsed
+/// `ComposeFile`. Supported forms: `$$` escapes a literal `$`;
+/// `${VAR}`/`$VAR` substitutes `VAR` (empty string if unset);
+/// `${VAR:-default}` uses `default` if `VAR` is unset or empty;
+/// `${VAR-default}` uses `default` only if `VAR` is unset;
+/// `${VAR:?message}` / `${VAR?message}` fails with `message` if `VAR` is
+/// unset (`:?` additionally fails if it's empty).
+fn interpolate(raw: &str) -> Result<String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.peek().copied() {
+            Some('$') => {
+                chars.next();
+                out.push('$');
+            }
+            Some('{') => {
+                chars.next();
+                let mut expr = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    expr.push(c);
+                }
+                if !closed {
+                    bail!("Unterminated variable reference '${{{}'", expr);
+                }
+                out.push_str(&resolve_var_expr(&expr)?);
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                out.push_str(&std::env::var(&name).unwrap_or_default());
+            }
+            _ => out.push('$'),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Resolves the body of a `${...}` reference: `VAR`, `VAR:-default`,
+/// `VAR-default`, `VAR:?message`, or `VAR?message`.
+fn resolve_var_expr(expr: &str) -> Result<String> {
+    if let Some((name, default)) = expr.split_once(":-") {
+        let value = std::env::var(name).unwrap_or_default();
+        return Ok(if value.is_empty() { default.to_string() } else { value });
+    }
+    if let Some((name, message)) = expr.split_once(":?") {
+        return match std::env::var(name) {
+            Ok(v) if !v.is_empty() => Ok(v),
+            _ => bail!("{}: {}", name, message),
+        };
+    }
+    if let Some((name, default)) = expr.split_once('-') {
+        return Ok(std::env::var(name).unwrap_or_else(|_| default.to_string()));
+    }
+    if let Some((name, message)) = expr.split_once('?') {
+        return std::env::var(name).map_err(|_| anyhow::anyhow!("{}: {}", name, message));
+    }
+    Ok(std::env::var(expr).unwrap_or_default())
+}
+
+/// Loads `KEY=VALUE` lines from `path` (blank lines and `#` comments
+/// skipped), the same format `docker run --env-file` understands.
+fn load_env_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read env file {}", path.display()))?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect())
+}
+
+/// Merges each service's `env_file` entries into its `environment`,
+/// with explicit `environment` entries winning over same-keyed
+/// `env_file` ones. `env_file` paths are resolved relative to
+/// `base_dir` (the compose file's own directory), matching Compose's
+/// own behavior.
+fn apply_env_files(compose: &mut ComposeFile, base_dir: &Path) -> Result<()> {
+    for service in compose.services.values_mut() {
+        let Some(env_file) = &service.env_file else {
+            continue;
+        };
+
+        let mut merged: HashMap<String, String> = HashMap::new();
+        for file in env_file.as_vec() {
+            for (k, v) in load_env_file(&base_dir.join(file))? {
+                merged.insert(k, v);
+            }
+        }
+
+        match &service.environment {
+            Some(EnvironmentConfig::Map(map)) => merged.extend(map.clone()),
+            Some(EnvironmentConfig::List(list)) => {
+                for entry in list {
+                    if let Some((k, v)) = entry.split_once('=') {
+                        merged.insert(k.to_string(), v.to_string());
+                    }
+                }
+            }
+            None => {}
+        }
+
+        service.environment = Some(EnvironmentConfig::Map(merged));
+    }
+
+    Ok(())
+}
+
+/// Parses a Docker-style duration like `"30s"`, `"1m30s"`, or `"500ms"`
+/// into a [`Duration`]. Units (`h`, `m`, `s`, `ms`) chain the way Go's
+/// `time.Duration` strings do; a bare number with no unit is rejected
+/// rather than guessed at, since Compose healthchecks always write one.
+fn parse_duration(spec: &str) -> Option<Duration> {
+    let mut total = Duration::ZERO;
+    let mut rest = spec;
+    let mut parsed_any = false;
+
+    while !rest.is_empty() {
+        let digits_end = rest.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        if digits_end == 0 {
+            return None;
+        }
+        let (num_str, after_num) = rest.split_at(digits_end);
+        let value: f64 = num_str.parse().ok()?;
+
+        let (unit_seconds, after_unit) = if let Some(after) = after_num.strip_prefix("ms") {
+            (value / 1000.0, after)
+        } else if let Some(after) = after_num.strip_prefix('h') {
+            (value * 3600.0, after)
+        } else if let Some(after) = after_num.strip_prefix('m') {
+            (value * 60.0, after)
+        } else if let Some(after) = after_num.strip_prefix('s') {
+            (value, after)
+        } else {
+            return None;
+        };
+
+        total += Duration::from_secs_f64(unit_seconds);
+        rest = after_unit;
+        parsed_any = true;
+    }
+
+    parsed_any.then_some(total)
+}
+
+fn check_unsupported_keys(compose: &ComposeFile) -> Vec<(String, String, String)> {
+    let mut warnings = Vec::new();
+
+    for (service_name, service) in &compose.services {
+        if service.build.is_some() {
+            warnings.push((
+                format!("services.{}.build", service_name),
+                "build".to_string(),
+                UNSUPPORTED_KEYS
+                    .iter()
+                    .find(|(k, _)| *k == "build")
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default(),
+            ));
+        }
+        if service.deploy.is_some() {
+            warnings.push((
+                format!("services.{}.deploy", service_name),
+                "deploy".to_string(),
+                UNSUPPORTED_KEYS
+                    .iter()
+                    .find(|(k, _)| *k == "deploy")
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default(),
+            ));
+        }
+        if service.configs.is_some() {
+            warnings.push((
+                format!("services.{}.configs", service_name),
+                "configs".to_string(),
+                UNSUPPORTED_KEYS
+                    .iter()
+                    .find(|(k, _)| *k == "configs")
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default(),
+            ));
+        }
+        if service.secrets.is_some() {
+            warnings.push((
+                format!("services.{}.secrets", service_name),
+                "secrets".to_string(),
+                UNSUPPORTED_KEYS
+                    .iter()
+                    .find(|(k, _)| *k == "secrets")
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default(),
+            ));
+        }
+    }
+
+    warnings
+}
+
+async fn up(
+    _cli: &Cli,
+    file: &PathBuf,
+    project_name: &str,
+    detach: bool,
+    no_deps: bool,
+    force_recreate: bool,
+    services: Vec<String>,
+) -> Result<()> {
+    let compose = load_compose_file(file)?;
+
+    // Check for unsupported keys
+    let warnings = check_unsupported_keys(&compose);
+    if !warnings.is_empty() {
+        println!("WARNING: Unsupported compose keys detected:");
+        for (path, _key, hint) in &warnings {
+            println!("  {} → {}", path, hint);
+        }
+        println!();
+
+        let confirmed = Confirm::new()
+            .with_prompt("Continue with supported keys?")
+            .default(true)
+            .interact()?;
+
+        if !confirmed {
+            println!("Aborted.");
+            return Ok(());
+        }
+        println!();
+    }
+
+    // Validate all services have images
+    for (name, service) in &compose.services {
+        if service.image.is_none() && service.build.is_none() {
+            bail!("Service '{}' has no image specified", name);
+        }
+    }
+
+    let runtime = BollardRuntime::connect()?;
+
+    // Create networks
+    if compose.networks.is_empty() {
+        print!("Creating network {}_default... ", project_name);
+        runtime.create_network(project_name, "default").await?;
+        println!("done");
+    } else {
+        for name in compose.networks.keys() {
+            print!("Creating network {}_{}... ", project_name, name);
+            runtime.create_network(project_name, name).await?;
+            println!("done");
+        }
+    }
+
+    // Create volumes
+    for name in compose.volumes.keys() {
+        print!("Creating volume {}_{}... ", project_name, name);
+        runtime.create_volume(project_name, name).await?;
+        println!("done");
+    }
+
+    // Sort services by dependency order, restricted to the services the
+    // caller named (plus their dependencies, unless `--no-deps`), or
+    // every service when none were named.
+    let service_order = if services.is_empty() {
+        topological_sort(&compose)?
+    } else {
+        topological_sort_filtered(&compose, Some(&services), !no_deps)?
+    };
+
+    // Create and start containers
+    for service_name in &service_order {
+        if let Some(service) = compose.services.get(service_name) {
+            wait_for_dependencies(&runtime, project_name, &compose, service).await?;
+
+            let image = service
+                .image
+                .as_ref()
+                .cloned()
+                .unwrap_or_else(|| format!("{}_{}:latest", project_name, service_name));
+            let networks = service_networks(&compose, service);
+
+            let existing = runtime.find_container(project_name, service_name).await?;
+            if let Some(container_id) = existing {
+                if force_recreate {
+                    print!("Recreating container {}... ", service_name);
+                    runtime.stop_container(&container_id).await?;
+                    runtime.remove_container(&container_id).await?;
+                } else {
+                    print!("Container {} already exists, starting... ", service_name);
+                    runtime.start_container(&container_id).await?;
+                    println!("done");
+                    continue;
+                }
+            } else {
+                print!("Creating container {} ({})... ", service_name, image);
+            }
+
+            let container_id = runtime
+                .create_container(project_name, service_name, service, &networks)
+                .await?;
+            println!("done");
+
+            print!("Starting container {}... ", service_name);
+            runtime.start_container(&container_id).await?;
+            println!("done");
+        }
+    }
+
+    println!("\nServices started: {}", service_order.len());
+
+    if !detach {
+        attach_and_wait(&runtime, project_name, &service_order).await?;
+    }
+
+    Ok(())
+}
+
+/// ANSI foreground colors cycled across services so interleaved log
+/// lines stay visually distinguishable - the same trick `docker compose
+/// up` itself uses.
+const LOG_COLORS: &[u8] = &[32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+/// Attaches to every started container's combined stdout/stderr, labels
+/// each line with its service name (and a cycling color), and blocks
+/// until Ctrl+C or SIGTERM, at which point it tears the project down in
+/// reverse dependency order - making foreground `up` behave like a real
+/// `docker compose up` session instead of returning immediately.
+async fn attach_and_wait(
+    runtime: &BollardRuntime,
+    project_name: &str,
+    service_order: &[String],
+) -> Result<()> {
+    println!("\nPress Ctrl+C to stop\n");
+
+    let mut streams = Vec::new();
+    for (i, service_name) in service_order.iter().enumerate() {
+        if let Some(container_id) = runtime.find_container(project_name, service_name).await? {
+            let label = service_name.clone();
+            let color = LOG_COLORS[i % LOG_COLORS.len()];
+            let tagged = runtime
+                .stream_logs(&container_id)
+                .await?
+                .map(move |line| (label.clone(), color, line));
+            streams.push(tagged);
+        }
+    }
+
+    let mut logs = stream::select_all(streams);
+    let mut logs_done = logs.is_empty();
+
+    let shutdown = wait_for_shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            line = logs.next(), if !logs_done => {
+                match line {
+                    Some((label, color, Ok(text))) => {
+                        for l in text.lines() {
+                            println!("\x1b[{}m{}\x1b[0m | {}", color, label, l);
+                        }
+                    }
+                    Some((_, _, Err(_))) => {}
+                    None => logs_done = true,
+                }
+            }
+            _ = &mut shutdown => break,
+        }
+    }
+
+    println!("\nStopping...");
+    for service_name in service_order.iter().rev() {
+        if let Some(container_id) = runtime.find_container(project_name, service_name).await? {
+            print!("Stopping container {}... ", service_name);
+            runtime.stop_container(&container_id).await?;
+            runtime.remove_container(&container_id).await?;
+            println!("done");
+        }
+    }
+
+    Ok(())
+}
+
+/// Waits for the first of Ctrl+C or (on Unix) SIGTERM.
+async fn wait_for_shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = sigterm.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+    }
+
+    Ok(())
+}
+
+/// The `depends_on` edges for `service`, each paired with its condition
+/// (`service_started` for a plain list entry, since that's the default
+/// Compose condition when none is given explicitly).
+fn dependency_conditions(service: &ServiceConfig) -> Vec<(String, String)> {
+    match &service.depends_on {
+        Some(DependsOnConfig::List(list)) => list
+            .iter()
+            .map(|name| (name.clone(), "service_started".to_string()))
+            .collect(),
+        Some(DependsOnConfig::Map(map)) => map
+            .iter()
+            .map(|(name, dep)| {
+                (
+                    name.clone(),
+                    dep.condition.clone().unwrap_or_else(|| "service_started".to_string()),
+                )
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Waits on `service`'s `depends_on` edges, per their condition, before
+/// `up` is allowed to create/start it. Since `service_order` is already a
+/// topological sort, every dependency here has already been started by
+/// the time this runs - this only waits for it to reach the specific
+/// readiness condition the dependent asked for.
+async fn wait_for_dependencies(
+    runtime: &BollardRuntime,
+    project_name: &str,
+    compose: &ComposeFile,
+    service: &ServiceConfig,
+) -> Result<()> {
+    for (dep_name, condition) in dependency_conditions(service) {
+        let Some(container_id) = runtime.find_container(project_name, &dep_name).await? else {
+            // Not started this run (e.g. `--no-deps` skipped it) - nothing
+            // to gate on.
+            continue;
+        };
+
+        match condition.as_str() {
+            "service_completed_successfully" => {
+                print!("Waiting for {} to complete... ", dep_name);
+                wait_for_exit(runtime, &container_id)
+                    .await
+                    .with_context(|| format!("Service '{}' did not complete successfully", dep_name))?;
+                println!("done");
+            }
+            "service_healthy" => {
+                print!("Waiting for {} to become healthy... ", dep_name);
+                let healthcheck = compose
+                    .services
+                    .get(&dep_name)
+                    .and_then(|s| s.healthcheck.as_ref());
+                wait_for_healthy(runtime, &container_id, healthcheck)
+                    .await
+                    .with_context(|| format!("Service '{}' never became healthy", dep_name))?;
+                println!("done");
+            }
+            // "service_started" (the default) just needs the dependency
+            // running, which the topological order + earlier start loop
+            // already guaranteed.
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Polls `container_id` until it exits, then checks it exited 0.
+async fn wait_for_exit(runtime: &BollardRuntime, container_id: &str) -> Result<()> {
+    loop {
+        let state = runtime.inspect_state(container_id).await?;
+        if !state.running {
+            return match state.exit_code {
+                Some(0) => Ok(()),
+                Some(code) => bail!("exited with code {}", code),
+                None => Ok(()),
+            };
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Polls `container_id`'s healthcheck status until it reports `healthy`,
+/// honoring the dependency's own `start_period`/`interval`/`retries` -
+/// falling back to Docker's own defaults (0s, 30s, 3) if the dependency
+/// declares no healthcheck of its own, since a container can still have
+/// one configured directly in its image even when the compose file is
+/// silent about it.
+async fn wait_for_healthy(
+    runtime: &BollardRuntime,
+    container_id: &str,
+    healthcheck: Option<&HealthCheck>,
+) -> Result<()> {
+    let interval = healthcheck
+        .and_then(|h| h.interval.as_deref())
+        .and_then(parse_duration)
+        .unwrap_or(Duration::from_secs(30));
+    let start_period = healthcheck
+        .and_then(|h| h.start_period.as_deref())
+        .and_then(parse_duration)
+        .unwrap_or(Duration::ZERO);
+    let retries = healthcheck.and_then(|h| h.retries).unwrap_or(3);
+
+    sleep(start_period).await;
+
+    let mut consecutive_failures = 0;
+    loop {
+        let state = runtime.inspect_state(container_id).await?;
+        match state.health.as_deref() {
+            Some("healthy") => return Ok(()),
+            Some("unhealthy") => {
+                consecutive_failures += 1;
+                if consecutive_failures >= retries {
+                    bail!("unhealthy after {} consecutive check(s)", consecutive_failures);
+                }
+            }
+            // "starting", missing, or any other in-progress state: keep
+            // waiting, don't count it as a failure.
+            _ => {}
+        }
+        sleep(interval).await;
+    }
+}
+
+async fn down(
+    _cli: &Cli,
+    file: &PathBuf,
+    project_name: &str,
+    remove_volumes: bool,
+    rmi: Option<String>,
+    remove_orphans: bool,
+) -> Result<()> {
+    let compose = load_compose_file(file)?;
+    let runtime = BollardRuntime::connect()?;
+
+    // Stop then remove containers in reverse dependency order
+    let service_order = topological_sort(&compose)?;
+    for service_name in service_order.iter().rev() {
+        print!("Stopping container {}... ", service_name);
+        if let Some(container_id) = runtime.find_container(project_name, service_name).await? {
+            runtime.stop_container(&container_id).await?;
+        }
+        println!("done");
+    }
+
+    print!("Removing containers... ");
+    for service_name in service_order.iter().rev() {
+        if let Some(container_id) = runtime.find_container(project_name, service_name).await? {
+            runtime.remove_container(&container_id).await?;
+        }
+    }
+    println!("done");
+
+    if remove_orphans {
+        remove_orphan_containers(&runtime, project_name, &compose).await?;
+    }
+
+    // Remove networks
+    let confirmed = Confirm::new()
+        .with_prompt("Remove networks?")
+        .default(false)
+        .interact()?;
+
+    if confirmed {
+        if compose.networks.is_empty() {
+            print!("Removing network {}_default... ", project_name);
+            runtime.remove_network(project_name, "default").await?;
+            println!("done");
+        } else {
+            for name in compose.networks.keys() {
+                print!("Removing network {}_{}... ", project_name, name);
+                runtime.remove_network(project_name, name).await?;
+                println!("done");
+            }
+        }
+    }
+
+    // Remove volumes if requested
+    if remove_volumes {
+        let confirmed = Confirm::new()
+            .with_prompt("Remove volumes?")
+            .default(false)
+            .interact()?;
+
+        if confirmed {
+            for name in compose.volumes.keys() {
+                print!("Removing volume {}_{}... ", project_name, name);
+                runtime.remove_volume(project_name, name).await?;
+                println!("done");
+            }
+        } else {
+            println!("Volumes retained.");
+        }
+    }
+
+    // Remove images, if requested
+    if let Some(mode) = rmi.as_deref() {
+        remove_images(&runtime, project_name, &compose, mode).await?;
+    }
+
+    Ok(())
+}
+
+/// Removes project-labelled containers that don't correspond to any
+/// service in the current compose file - left behind by a service that
+/// was since renamed or deleted from the file.
+async fn remove_orphan_containers(
+    runtime: &BollardRuntime,
+    project_name: &str,
+    compose: &ComposeFile,
+) -> Result<()> {
+    let running = runtime.list_containers(project_name).await?;
+    for status in running {
+        if compose.services.contains_key(&status.service) {
+            continue;
+        }
+        if let Some(container_id) = runtime.find_container(project_name, &status.service).await? {
+            print!("Removing orphan container {}... ", status.service);
+            runtime.stop_container(&container_id).await?;
+            runtime.remove_container(&container_id).await?;
+            println!("done");
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the images `up` would have used for this project's services.
+/// `"local"` only removes images `up` fell back to `{project}_{service}`
+/// for (i.e. services with no explicit `image:` of their own - as close
+/// to "built for the project" as this module gets, since it doesn't
+/// implement `build` itself); `"all"` removes every image referenced,
+/// including third-party ones like `nginx:latest`.
+async fn remove_images(
+    runtime: &BollardRuntime,
+    project_name: &str,
+    compose: &ComposeFile,
+    mode: &str,
+) -> Result<()> {
+    for (service_name, service) in &compose.services {
+        let is_local_build = service.image.is_none();
+        if mode != "all" && !(mode == "local" && is_local_build) {
+            continue;
+        }
+
+        let image = service
+            .image
+            .clone()
+            .unwrap_or_else(|| format!("{}_{}:latest", project_name, service_name));
+
+        print!("Removing image {}... ", image);
+        runtime.remove_image(&image).await?;
+        println!("done");
+    }
+
+    Ok(())
+}
+
+async fn ps(
+    _cli: &Cli,
+    file: &PathBuf,
+    project_name: &str,
+    all: bool,
+    format: &str,
+) -> Result<()> {
+    // Loaded for its "Using: ..." side effect and to catch a missing
+    // compose file early; the actual status data below comes straight
+    // from the backend, not the file, since that's the only source of
+    // truth for what's actually running.
+    load_compose_file(file)?;
+
+    let runtime = BollardRuntime::connect()?;
+    let statuses: Vec<_> = runtime
+        .list_containers(project_name)
+        .await?
+        .into_iter()
+        .filter(|s| all || s.state == "running")
+        .collect();
+
+    match format {
+        "json" => {
+            #[derive(Serialize)]
+            struct ServiceStatus {
+                name: String,
+                image: String,
+                state: String,
+                status: String,
+                ports: Vec<String>,
+            }
+
+            let out: Vec<_> = statuses
+                .iter()
+                .map(|s| ServiceStatus {
+                    name: s.service.clone(),
+                    image: s.image.clone(),
+                    state: s.state.clone(),
+                    status: s.status.clone(),
+                    ports: s.ports.clone(),
+                })
+                .collect();
+
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        _ => {
+            let rows: Vec<_> = statuses
+                .iter()
+                .map(|s| ServiceStatusRow {
+                    name: s.service.clone(),
+                    image: s.image.clone(),
+                    status: s.status.clone(),
+                    ports: s.ports.join(", "),
+                })
+                .collect();
+
+            let table = Table::new(rows).to_string();
+            println!("{}", table);
+        }
+    }
+
+    Ok(())
+}
+
+async fn logs(
+    _cli: &Cli,
+    _file: &PathBuf,
+    _project_name: &str,
+    service: Option<String>,
+    follow: bool,
+    tail: Option<usize>,
+    _timestamps: bool,
+) -> Result<()> {
+    if let Some(name) = service {
+        println!("Showing logs for service: {}", name);
+    } else {
+        println!("Showing logs for all services");
+    }
+
+    if follow {
+        println!("(Following... press Ctrl+C to stop)");
+    }
+
+    if let Some(n) = tail {
+        println!("(Showing last {} lines)", n);
+    }
+
+    // Would actually stream container logs here
+    println!("(Log output would appear here)");
+
+    Ok(())
+}
+
+async fn config(file: &PathBuf, quiet: bool, resolve: bool) -> Result<()> {
+    let compose = load_compose_file(file)?;
+
+    // Check for errors
+    let warnings = check_unsupported_keys(&compose);
+
+    for (name, service) in &compose.services {
+        if service.image.is_none() && service.build.is_none() {
+            bail!("Service '{}' has no image specified", name);
+        }
+    }
+
+    if resolve {
+        print!("{}", serde_yaml::to_string(&compose)?);
+        return Ok(());
+    }
+
+    if quiet {
+        if warnings.is_empty() {
+            println!("Configuration is valid.");
+        } else {
+            println!(
+                "Configuration valid with {} warning(s).",
+                warnings.len()
+            );
+        }
+    } else {
+        println!("Configuration is valid.\n");
+
+        if !warnings.is_empty() {
+            println!("Warnings:");
+            for (path, _key, hint) in &warnings {
+                println!("  {} → {}", path, hint);
+            }
+            println!();
+        }
+
+        println!("Services:");
+        for (name, service) in &compose.services {
+            println!(
+                "  - {} ({})",
+                name,
+                service.image.as_deref().unwrap_or("build")
+            );
+        }
+
+        if !compose.networks.is_empty() {
+            println!("\nNetworks:");
+            for (name, _) in &compose.networks {
+                println!("  - {}", name);
+            }
+        }
+
+        if !compose.volumes.is_empty() {
+            println!("\nVolumes:");
+            for (name, _) in &compose.volumes {
+                println!("  - {}", name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn pull(
+    _cli: &Cli,
+    file: &PathBuf,
+    services: Vec<String>,
+    ignore_failures: bool,
+) -> Result<()> {
+    let compose = load_compose_file(file)?;
+    let runtime = BollardRuntime::connect()?;
+
+    let services_to_pull: Vec<_> = if services.is_empty() {
+        compose.services.keys().cloned().collect()
+    } else {
+        services
+    };
+
+    for name in &services_to_pull {
+        if let Some(service) = compose.services.get(name) {
+            if let Some(image) = &service.image {
+                println!("Pulling {} ({})...", name, image);
+                match runtime.pull_image(image).await {
+                    Ok(()) => println!("{} pulled successfully.\n", name),
+                    Err(e) if ignore_failures => println!("{} failed to pull ({}), ignoring.\n", name, e),
+                    Err(e) => return Err(e),
+                }
+            } else if !ignore_failures {
+                println!("Skipping {} (no image, build required)", name);
+            }
+        } else if !ignore_failures {
+            bail!("Service '{}' not found in compose file", name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Which project networks a service should join. A service with no
+/// `networks` key of its own joins the implicit `default` network - the
+/// one `up` creates only when the compose file declares no networks of
+/// its own - or, if the file does declare networks, every one of them,
+/// since there's no `networks: {}` top-level default to fall back to.
+fn service_networks(compose: &ComposeFile, service: &ServiceConfig) -> Vec<String> {
+    match &service.networks {
+        Some(NetworksConfig::List(list)) => list.clone(),
+        Some(NetworksConfig::Map(map)) => map.keys().cloned().collect(),
+        None if compose.networks.is_empty() => vec!["default".to_string()],
+        None => compose.networks.keys().cloned().collect(),
+    }
+}
+
+/// Topological sort of services based on depends_on
+fn topological_sort(compose: &ComposeFile) -> Result<Vec<String>> {
+    topological_sort_filtered(compose, None, true)
+}
+
+/// Topological sort restricted to a subgraph. `roots` are the services
+/// `up` was explicitly asked to start (`None` means every service in the
+/// file, the normal `up` with no positional arguments). `follow_deps`
+/// controls whether each root pulls its `depends_on` chain in too - set
+/// to `false` for `--no-deps`, where the caller wants exactly the named
+/// services and nothing else.
+fn topological_sort_filtered(
+    compose: &ComposeFile,
+    roots: Option<&[String]>,
+    follow_deps: bool,
+) -> Result<Vec<String>> {
+    let mut result = Vec::new();
+    let mut visited = std::collections::HashSet::new();
+    let mut visiting = std::collections::HashSet::new();
+
+    fn visit(
+        name: &str,
+        compose: &ComposeFile,
+        follow_deps: bool,
+        visited: &mut std::collections::HashSet<String>,
+        visiting: &mut std::collections::HashSet<String>,
+        result: &mut Vec<String>,
+    ) -> Result<()> {
+        if visited.contains(name) {
+            return Ok(());
+        }
+        if visiting.contains(name) {
+            bail!("Circular dependency detected involving '{}'", name);
+        }
+
+        visiting.insert(name.to_string());
+
+        if follow_deps {
+            if let Some(service) = compose.services.get(name) {
+                if let Some(deps) = &service.depends_on {
+                    let dep_names: Vec<String> = match deps {
+                        DependsOnConfig::List(list) => list.clone(),
+                        DependsOnConfig::Map(map) => map.keys().cloned().collect(),
+                    };
+
+                    for dep in dep_names {
+                        visit(&dep, compose, follow_deps, visited, visiting, result)?;
+                    }
+                }
+            }
+        }
+
+        visiting.remove(name);
+        visited.insert(name.to_string());
+        result.push(name.to_string());
+
+        Ok(())
+    }
+
+    match roots {
+        Some(names) => {
+            for name in names {
+                if !compose.services.contains_key(name) {
+                    bail!("Service '{}' not found in compose file", name);
+                }
+                visit(name, compose, follow_deps, &mut visited, &mut visiting, &mut result)?;
+            }
+        }
+        None => {
+            for name in compose.services.keys() {
+                visit(name, compose, follow_deps, &mut visited, &mut visiting, &mut result)?;
+            }
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,595 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Backend that actually creates, starts, and tears down the containers,
+//! networks, and volumes a compose file describes
+//!
+//! [`ComposeRuntime`] is the seam between `up`/`down`/`pull` and whatever
+//! actually runs containers, the same way [`crate::engine::VolumeDriver`]
+//! separates volume lifecycle from backing storage. [`BollardRuntime`] is
+//! the only implementation today, talking to a local Docker/OCI daemon
+//! over its API via the `bollard` client, but keeping the trait narrow
+//! means a future Vordr-native backend doesn't have to touch `compose/mod.rs`
+//! at all.
+//!
+//! Every network, volume, and container this module creates is labelled
+//! with [`PROJECT_LABEL`] (and containers additionally with
+//! [`SERVICE_LABEL`]), so a later `compose` invocation - possibly a
+//! different process entirely - can find them again by project name
+//! instead of relying on in-memory state from the `up` that created them.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+
+use anyhow::{Context, Result};
+use bollard::container::{
+    Config, CreateContainerOptions, ListContainersOptions, LogsOptions, RemoveContainerOptions,
+    StartContainerOptions, StopContainerOptions,
+};
+use bollard::errors::Error as BollardError;
+use bollard::image::CreateImageOptions;
+use bollard::models::{
+    HealthConfig, HostConfig, PortBinding, RestartPolicy, RestartPolicyNameEnum,
+};
+use bollard::network::{ConnectNetworkOptions, CreateNetworkOptions};
+use bollard::volume::CreateVolumeOptions;
+use bollard::Docker;
+use futures::stream::{Stream, StreamExt, TryStreamExt};
+
+use super::{EnvironmentConfig, HealthCheck, ServiceConfig, StringOrList};
+
+/// A live, line-oriented feed of a single container's combined
+/// stdout/stderr, one item per chunk bollard hands back (already
+/// demultiplexed out of the Docker TTY framing).
+pub type LogStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Identifies every resource a project's `up` created, so `down`/`ps` run
+/// from a separate process invocation can find them by project name.
+pub const PROJECT_LABEL: &str = "com.vordr.compose.project";
+/// Identifies which service within a project a container backs.
+pub const SERVICE_LABEL: &str = "com.vordr.compose.service";
+
+/// A backend container's ground-truth state, keyed back to the compose
+/// service it backs via its [`SERVICE_LABEL`].
+pub struct ContainerStatus {
+    pub service: String,
+    pub image: String,
+    /// Docker's coarse state: `running`, `exited`, `created`,
+    /// `restarting`, `paused`, ...
+    pub state: String,
+    /// Docker's human-readable status, e.g. `Up 3 minutes` or
+    /// `Exited (1) 2 minutes ago`.
+    pub status: String,
+    /// Published ports as `host:hostport->containerport/proto`.
+    pub ports: Vec<String>,
+}
+
+/// A container's live state, as reported by a container inspect - the
+/// detail `depends_on` condition gating needs and [`ContainerStatus`]
+/// (built from the cheaper list-containers call) doesn't carry.
+pub struct ContainerState {
+    pub running: bool,
+    /// Set once the container has exited; `None` while still running.
+    pub exit_code: Option<i64>,
+    /// The daemon's healthcheck verdict (`starting`, `healthy`,
+    /// `unhealthy`), or `None` if the container has no healthcheck.
+    pub health: Option<String>,
+}
+
+/// A backend capable of running the containers, networks, and volumes a
+/// compose project describes. Narrow on purpose: `up`/`down`/`pull` drive
+/// it in `topological_sort` order themselves, this trait just does what
+/// it's told for one resource at a time.
+#[async_trait::async_trait]
+pub trait ComposeRuntime: Send + Sync {
+    /// Creates the project-scoped network `name`, succeeding if it
+    /// already exists (e.g. a previous `up` that was never torn down).
+    async fn create_network(&self, project: &str, name: &str) -> Result<()>;
+
+    /// Removes the project-scoped network `name`. A missing network is
+    /// not an error.
+    async fn remove_network(&self, project: &str, name: &str) -> Result<()>;
+
+    /// Creates the project-scoped named volume `name`.
+    async fn create_volume(&self, project: &str, name: &str) -> Result<()>;
+
+    /// Removes the project-scoped named volume `name`. A missing volume
+    /// is not an error.
+    async fn remove_volume(&self, project: &str, name: &str) -> Result<()>;
+
+    /// Creates (but does not start) a container for `service_name`,
+    /// joining `networks` (the first network is attached at creation
+    /// time, any others are attached afterward - Docker only accepts one
+    /// network per `HostConfig.network_mode`). Returns the new
+    /// container's ID.
+    async fn create_container(
+        &self,
+        project: &str,
+        service_name: &str,
+        service: &ServiceConfig,
+        networks: &[String],
+    ) -> Result<String>;
+
+    /// Starts a previously created container.
+    async fn start_container(&self, container_id: &str) -> Result<()>;
+
+    /// Stops a running container. A container that's already stopped, or
+    /// gone entirely, is not an error.
+    async fn stop_container(&self, container_id: &str) -> Result<()>;
+
+    /// Removes a container outright. A missing container is not an error.
+    async fn remove_container(&self, container_id: &str) -> Result<()>;
+
+    /// Finds the container currently labelled as `service_name` within
+    /// `project`, if one exists.
+    async fn find_container(&self, project: &str, service_name: &str) -> Result<Option<String>>;
+
+    /// Reports the ground-truth state of every container `project` has
+    /// created so far, one entry per container, regardless of whether
+    /// it's currently running.
+    async fn list_containers(&self, project: &str) -> Result<Vec<ContainerStatus>>;
+
+    /// Pulls `image` from its registry.
+    async fn pull_image(&self, image: &str) -> Result<()>;
+
+    /// Removes `image`. A missing image is not an error; one still in use
+    /// by a container is, since Docker itself refuses that.
+    async fn remove_image(&self, image: &str) -> Result<()>;
+
+    /// Reports `container_id`'s current running/exit/health state, for
+    /// `depends_on` condition gating.
+    async fn inspect_state(&self, container_id: &str) -> Result<ContainerState>;
+
+    /// Streams `container_id`'s combined stdout/stderr from now on
+    /// (no backlog - `tail: 0`), for attaching to a foreground `up`.
+    async fn stream_logs(&self, container_id: &str) -> Result<LogStream>;
+}
+
+/// Drives a local Docker/OCI daemon over its API.
+pub struct BollardRuntime {
+    docker: Docker,
+}
+
+impl BollardRuntime {
+    /// Connects using the same defaults as the `docker` CLI (`DOCKER_HOST`,
+    /// falling back to the local Unix socket).
+    pub fn connect() -> Result<Self> {
+        let docker = Docker::connect_with_local_defaults()
+            .context("Failed to connect to the Docker/OCI daemon")?;
+        Ok(Self { docker })
+    }
+
+    fn network_name(project: &str, name: &str) -> String {
+        format!("{}_{}", project, name)
+    }
+
+    fn volume_name(project: &str, name: &str) -> String {
+        format!("{}_{}", project, name)
+    }
+
+    fn default_container_name(project: &str, service_name: &str) -> String {
+        format!("{}_{}_1", project, service_name)
+    }
+
+    fn project_labels(project: &str) -> HashMap<String, String> {
+        HashMap::from([(PROJECT_LABEL.to_string(), project.to_string())])
+    }
+}
+
+/// Whether `err` is the daemon telling us the thing we wanted gone is
+/// already gone - the only "error" `remove_*`/`stop_container` should
+/// swallow, since any other failure (daemon unreachable, permission
+/// denied) should surface to the caller.
+fn is_not_found(err: &BollardError) -> bool {
+    matches!(err, BollardError::DockerResponseServerError { status_code, .. } if *status_code == 404)
+}
+
+#[async_trait::async_trait]
+impl ComposeRuntime for BollardRuntime {
+    async fn create_network(&self, project: &str, name: &str) -> Result<()> {
+        let full_name = Self::network_name(project, name);
+        let options = CreateNetworkOptions {
+            name: full_name.as_str(),
+            driver: "bridge",
+            labels: Self::project_labels(project)
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+            ..Default::default()
+        };
+
+        match self.docker.create_network(options).await {
+            Ok(_) => Ok(()),
+            // 409 Conflict: a network with this name already exists.
+            Err(BollardError::DockerResponseServerError { status_code: 409, .. }) => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to create network {}", full_name)),
+        }
+    }
+
+    async fn remove_network(&self, project: &str, name: &str) -> Result<()> {
+        let full_name = Self::network_name(project, name);
+        match self.docker.remove_network(&full_name).await {
+            Ok(()) => Ok(()),
+            Err(e) if is_not_found(&e) => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove network {}", full_name)),
+        }
+    }
+
+    async fn create_volume(&self, project: &str, name: &str) -> Result<()> {
+        let full_name = Self::volume_name(project, name);
+        let labels = Self::project_labels(project);
+        let options = CreateVolumeOptions {
+            name: full_name.as_str(),
+            labels: labels.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect(),
+            ..Default::default()
+        };
+
+        self.docker
+            .create_volume(options)
+            .await
+            .with_context(|| format!("Failed to create volume {}", full_name))?;
+        Ok(())
+    }
+
+    async fn remove_volume(&self, project: &str, name: &str) -> Result<()> {
+        let full_name = Self::volume_name(project, name);
+        match self.docker.remove_volume(&full_name, None).await {
+            Ok(()) => Ok(()),
+            Err(e) if is_not_found(&e) => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove volume {}", full_name)),
+        }
+    }
+
+    async fn create_container(
+        &self,
+        project: &str,
+        service_name: &str,
+        service: &ServiceConfig,
+        networks: &[String],
+    ) -> Result<String> {
+        let name = service
+            .container_name
+            .clone()
+            .unwrap_or_else(|| Self::default_container_name(project, service_name));
+
+        let mut labels = Self::project_labels(project);
+        labels.insert(SERVICE_LABEL.to_string(), service_name.to_string());
+
+        let network_mode = networks.first().map(|n| Self::network_name(project, n));
+        let config = to_container_config(service, labels, network_mode);
+
+        let options = CreateContainerOptions {
+            name: name.as_str(),
+            platform: None,
+        };
+
+        let created = self
+            .docker
+            .create_container(Some(options), config)
+            .await
+            .with_context(|| format!("Failed to create container for service {}", service_name))?;
+
+        // `network_mode` attaches the first network at creation time;
+        // Docker only accepts one network that way, so anything else the
+        // service lists has to be joined afterward.
+        for extra in networks.iter().skip(1) {
+            let full_name = Self::network_name(project, extra);
+            self.docker
+                .connect_network(
+                    &full_name,
+                    ConnectNetworkOptions {
+                        container: created.id.as_str(),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .with_context(|| format!("Failed to attach {} to network {}", service_name, full_name))?;
+        }
+
+        Ok(created.id)
+    }
+
+    async fn start_container(&self, container_id: &str) -> Result<()> {
+        self.docker
+            .start_container(container_id, None::<StartContainerOptions<String>>)
+            .await
+            .with_context(|| format!("Failed to start container {}", container_id))
+    }
+
+    async fn stop_container(&self, container_id: &str) -> Result<()> {
+        match self
+            .docker
+            .stop_container(container_id, None::<StopContainerOptions>)
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(e) if is_not_found(&e) => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to stop container {}", container_id)),
+        }
+    }
+
+    async fn remove_container(&self, container_id: &str) -> Result<()> {
+        let options = RemoveContainerOptions {
+            force: true,
+            ..Default::default()
+        };
+        match self.docker.remove_container(container_id, Some(options)).await {
+            Ok(()) => Ok(()),
+            Err(e) if is_not_found(&e) => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove container {}", container_id)),
+        }
+    }
+
+    async fn find_container(&self, project: &str, service_name: &str) -> Result<Option<String>> {
+        let mut filters = HashMap::new();
+        filters.insert(
+            "label".to_string(),
+            vec![
+                format!("{}={}", PROJECT_LABEL, project),
+                format!("{}={}", SERVICE_LABEL, service_name),
+            ],
+        );
+
+        let options = ListContainersOptions {
+            all: true,
+            filters,
+            ..Default::default()
+        };
+
+        let containers = self
+            .docker
+            .list_containers(Some(options))
+            .await
+            .with_context(|| format!("Failed to look up container for service {}", service_name))?;
+
+        Ok(containers.into_iter().next().and_then(|c| c.id))
+    }
+
+    async fn list_containers(&self, project: &str) -> Result<Vec<ContainerStatus>> {
+        let mut filters = HashMap::new();
+        filters.insert("label".to_string(), vec![format!("{}={}", PROJECT_LABEL, project)]);
+
+        let options = ListContainersOptions {
+            all: true,
+            filters,
+            ..Default::default()
+        };
+
+        let containers = self
+            .docker
+            .list_containers(Some(options))
+            .await
+            .with_context(|| format!("Failed to list containers for project {}", project))?;
+
+        Ok(containers
+            .into_iter()
+            .filter_map(|c| {
+                let service = c.labels.as_ref()?.get(SERVICE_LABEL)?.clone();
+                let ports = c.ports.unwrap_or_default().iter().map(format_port).collect();
+                Some(ContainerStatus {
+                    service,
+                    image: c.image.unwrap_or_default(),
+                    state: c.state.unwrap_or_default(),
+                    status: c.status.unwrap_or_default(),
+                    ports,
+                })
+            })
+            .collect())
+    }
+
+    async fn pull_image(&self, image: &str) -> Result<()> {
+        let options = CreateImageOptions {
+            from_image: image,
+            ..Default::default()
+        };
+
+        let mut stream = self.docker.create_image(Some(options), None, None);
+        // One line per layer, updated in place each time its status
+        // changes (`Pulling fs layer` -> `Downloading` -> `Extracting` ->
+        // `Pull complete`), the same progression `docker pull` prints.
+        let mut last_status: HashMap<String, String> = HashMap::new();
+
+        while let Some(info) = stream
+            .try_next()
+            .await
+            .with_context(|| format!("Failed to pull image {}", image))?
+        {
+            let status = info.status.unwrap_or_default();
+            match &info.id {
+                Some(id) if last_status.get(id) != Some(&status) => {
+                    let progress = info.progress.as_deref().unwrap_or("");
+                    println!("  {}: {} {}", id, status, progress);
+                    last_status.insert(id.clone(), status);
+                }
+                Some(_) => {}
+                None if !status.is_empty() => println!("{}", status),
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_image(&self, image: &str) -> Result<()> {
+        match self.docker.remove_image(image, None, None).await {
+            Ok(_) => Ok(()),
+            Err(e) if is_not_found(&e) => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove image {}", image)),
+        }
+    }
+
+    async fn inspect_state(&self, container_id: &str) -> Result<ContainerState> {
+        let info = self
+            .docker
+            .inspect_container(container_id, None)
+            .await
+            .with_context(|| format!("Failed to inspect container {}", container_id))?;
+
+        let state = info.state;
+        let running = state.as_ref().and_then(|s| s.running).unwrap_or(false);
+        let exit_code = state.as_ref().and_then(|s| s.exit_code);
+        let health = state
+            .and_then(|s| s.health)
+            .and_then(|h| h.status)
+            .map(|s| s.to_string());
+
+        Ok(ContainerState {
+            running,
+            exit_code,
+            health,
+        })
+    }
+
+    async fn stream_logs(&self, container_id: &str) -> Result<LogStream> {
+        let options = LogsOptions::<String> {
+            follow: true,
+            stdout: true,
+            stderr: true,
+            tail: "0".to_string(),
+            ..Default::default()
+        };
+
+        // Clone the (cheaply-shared) client handle into the stream rather
+        // than borrowing `self`, so the returned stream isn't tied to this
+        // call's lifetime and can be polled from the caller's own event
+        // loop for as long as it likes.
+        let docker = self.docker.clone();
+        let stream = docker
+            .logs(container_id, Some(options))
+            .map(|item| item.map(|log| log.to_string()).map_err(anyhow::Error::from));
+
+        Ok(Box::pin(stream))
+    }
+}
+
+/// Translates a parsed `ServiceConfig` into the container spec bollard's
+/// create-container call expects. `network_mode` is the one network
+/// (already project-scoped) to join at creation time; any further
+/// networks are attached by the caller after creation.
+fn to_container_config(
+    service: &ServiceConfig,
+    labels: HashMap<String, String>,
+    network_mode: Option<String>,
+) -> Config<String> {
+    let cmd = service.command.as_ref().map(StringOrList::as_vec);
+    let entrypoint = service.entrypoint.as_ref().map(StringOrList::as_vec);
+    let env = service.environment.as_ref().map(environment_to_vec);
+
+    let mut exposed_ports = HashMap::new();
+    let mut port_bindings = HashMap::new();
+    for port in &service.ports {
+        let (container_port, host_port) = split_port_mapping(port);
+        exposed_ports.insert(container_port.clone(), HashMap::new());
+        port_bindings.insert(
+            container_port,
+            Some(vec![PortBinding {
+                host_ip: None,
+                host_port,
+            }]),
+        );
+    }
+
+    let host_config = HostConfig {
+        binds: (!service.volumes.is_empty()).then(|| service.volumes.clone()),
+        privileged: Some(service.privileged),
+        port_bindings: (!port_bindings.is_empty()).then_some(port_bindings),
+        restart_policy: service.restart.as_deref().map(restart_policy_from_str),
+        network_mode,
+        ..Default::default()
+    };
+
+    Config {
+        image: service.image.clone(),
+        cmd,
+        entrypoint,
+        env,
+        working_dir: service.working_dir.clone(),
+        user: service.user.clone(),
+        exposed_ports: (!exposed_ports.is_empty()).then_some(exposed_ports),
+        labels: Some(labels),
+        host_config: Some(host_config),
+        healthcheck: service.healthcheck.as_ref().map(to_health_config),
+        ..Default::default()
+    }
+}
+
+/// Translates a `HealthCheck` into the `HealthConfig` bollard's
+/// create-container call expects, which wants durations in nanoseconds
+/// rather than the `"30s"`-style strings Compose files use.
+fn to_health_config(healthcheck: &HealthCheck) -> HealthConfig {
+    let nanos = |spec: &Option<String>| {
+        spec.as_deref()
+            .and_then(super::parse_duration)
+            .map(|d| d.as_nanos() as i64)
+    };
+
+    HealthConfig {
+        test: Some(healthcheck.test.as_vec()),
+        interval: nanos(&healthcheck.interval),
+        timeout: nanos(&healthcheck.timeout),
+        retries: healthcheck.retries.map(|r| r as i64),
+        start_period: nanos(&healthcheck.start_period),
+        ..Default::default()
+    }
+}
+
+/// Renders one published port the way `docker ps` does, e.g.
+/// `0.0.0.0:8080->80/tcp`, falling back to `80/tcp` for a port that isn't
+/// actually published to the host.
+fn format_port(port: &bollard::models::Port) -> String {
+    let proto = port
+        .typ
+        .map(|t| t.to_string())
+        .unwrap_or_else(|| "tcp".to_string());
+
+    match port.public_port {
+        Some(public) => {
+            let host = port.ip.as_deref().unwrap_or("0.0.0.0");
+            format!("{}:{}->{}/{}", host, public, port.private_port, proto)
+        }
+        None => format!("{}/{}", port.private_port, proto),
+    }
+}
+
+fn environment_to_vec(env: &EnvironmentConfig) -> Vec<String> {
+    match env {
+        EnvironmentConfig::List(list) => list.clone(),
+        EnvironmentConfig::Map(map) => map.iter().map(|(k, v)| format!("{}={}", k, v)).collect(),
+    }
+}
+
+/// Splits a compose port mapping (`"80"`, `"8080:80"`, `"127.0.0.1:8080:80"`,
+/// optionally with a trailing `/udp`) into the `"<container-port>/<proto>"`
+/// key Docker's API expects and the host port to bind it to, if any. A
+/// bind-address prefix is accepted but not honored - ports are always
+/// published on every host interface, matching most single-host compose
+/// setups.
+fn split_port_mapping(spec: &str) -> (String, Option<String>) {
+    let (port_part, proto) = spec.split_once('/').unwrap_or((spec, "tcp"));
+    let segments: Vec<&str> = port_part.split(':').collect();
+
+    match segments.as_slice() {
+        [container] => (format!("{}/{}", container, proto), None),
+        [host, container] => (format!("{}/{}", container, proto), Some(host.to_string())),
+        [_bind_addr, host, container] => {
+            (format!("{}/{}", container, proto), Some(host.to_string()))
+        }
+        _ => (format!("{}/{}", port_part, proto), None),
+    }
+}
+
+fn restart_policy_from_str(restart: &str) -> RestartPolicy {
+    let (name, maximum_retry_count) = match restart {
+        "always" => (RestartPolicyNameEnum::ALWAYS, None),
+        "unless-stopped" => (RestartPolicyNameEnum::UNLESS_STOPPED, None),
+        "no" | "" => (RestartPolicyNameEnum::NO, None),
+        other if other == "on-failure" || other.starts_with("on-failure:") => {
+            let retries = other.strip_prefix("on-failure:").and_then(|n| n.parse().ok());
+            (RestartPolicyNameEnum::ON_FAILURE, retries)
+        }
+        _ => (RestartPolicyNameEnum::EMPTY, None),
+    };
+
+    RestartPolicy {
+        name: Some(name),
+        maximum_retry_count,
+    }
+}
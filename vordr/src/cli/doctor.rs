@@ -28,6 +28,19 @@ pub struct DoctorArgs {
     /// Attempt to automatically fix issues
     #[arg(long)]
     pub fix: bool,
+
+    /// Print the fixes `--fix` would run without executing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Confirm running fixes that require sudo (required alongside `--fix`)
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Severity policy to check against: `dev` is lenient, `prod` fails
+    /// the run on conditions that would block safe operation in production
+    #[arg(long, value_enum, default_value = "dev")]
+    pub profile: Profile,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
@@ -37,6 +50,24 @@ pub enum OutputFormat {
     Json,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    #[default]
+    Dev,
+    Prod,
+}
+
+/// `Warn` is a shrug in `dev` but a blocker in `prod` - checks that gate
+/// safe operation call this instead of pushing `CheckStatus::Warn` directly.
+fn escalate(status: CheckStatus, profile: Profile) -> CheckStatus {
+    if profile == Profile::Prod && status == CheckStatus::Warn {
+        CheckStatus::Fail
+    } else {
+        status
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CheckResult {
     pub category: String,
@@ -57,15 +88,41 @@ pub enum CheckStatus {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct FixCommand {
+    pub kind: FixKind,
     pub command: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub persist: Option<String>,
 }
 
+/// What kind of action a [`FixCommand`] performs, so `--fix` can decide how
+/// to run it (and how to undo it) instead of pattern-matching the command
+/// string. `Manual` covers instructions that aren't a real shell command
+/// (upgrade advice, kernel config changes) or are too destructive to ever
+/// run unattended (`rm` on the state database) - those are always left to
+/// the operator.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FixKind {
+    MkDir,
+    Sysctl,
+    Modprobe,
+    Chown,
+    Usermod,
+    InstallBinary,
+    Manual,
+}
+
+impl FixKind {
+    fn is_executable(self) -> bool {
+        self != FixKind::Manual
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct DoctorReport {
     version: String,
     timestamp: String,
+    profile: Profile,
     checks: Vec<CheckResult>,
     summary: Summary,
     next_steps: Vec<String>,
@@ -83,19 +140,20 @@ pub async fn execute(args: DoctorArgs, cli: &Cli) -> Result<()> {
     let mut checks = Vec::new();
 
     // Runtime checks
-    checks.extend(check_runtime(cli));
+    checks.extend(check_runtime(cli, args.profile));
 
     // Networking checks
-    checks.extend(check_networking());
+    checks.extend(check_networking(args.profile));
 
     // Kernel/rootless checks
-    checks.extend(check_kernel());
+    checks.extend(check_kernel(args.profile));
+    checks.extend(check_rootless(args.profile));
 
     // State database checks
-    checks.extend(check_state(cli));
+    checks.extend(check_state(cli, args.profile));
 
     // Gatekeeper checks
-    checks.extend(check_gatekeeper());
+    checks.extend(check_gatekeeper(args.profile));
 
     // Calculate summary
     let passed = checks.iter().filter(|c| c.status == CheckStatus::Pass).count();
@@ -122,7 +180,8 @@ pub async fn execute(args: DoctorArgs, cli: &Cli) -> Result<()> {
             };
             let report = DoctorReport {
                 version: env!("CARGO_PKG_VERSION").to_string(),
-                timestamp: chrono_lite_now(),
+                timestamp: chrono_lite_now(&crate::clock::SystemClock),
+                profile: args.profile,
                 checks: filtered_checks,
                 summary: Summary {
                     passed,
@@ -136,6 +195,9 @@ pub async fn execute(args: DoctorArgs, cli: &Cli) -> Result<()> {
         OutputFormat::Human => {
             println!("{}", style("VORDR SYSTEM CHECK").bold());
             println!("{}", style("==================").dim());
+            if args.profile == Profile::Prod {
+                println!("{}", style("Profile: prod (warnings that block safe operation are treated as errors)").dim());
+            }
             println!();
 
             let categories = ["Runtime", "Networking", "Kernel/Rootless", "State Database", "Gatekeeper"];
@@ -209,27 +271,225 @@ pub async fn execute(args: DoctorArgs, cli: &Cli) -> Result<()> {
     // If fix mode, attempt fixes
     if args.fix {
         println!();
-        println!("{}", style("Attempting automatic fixes...").bold());
-
-        for check in &checks {
-            if check.status == CheckStatus::Fail {
-                if let Some(fix) = &check.fix {
-                    // Only auto-fix safe operations
-                    if is_safe_fix(&fix.command) {
-                        print!("  Running: {} ... ", &fix.command);
-                        // Would actually run the command here
-                        println!("{}", style("(skipped - manual review required)").dim());
-                    }
-                }
+        if args.dry_run {
+            println!("{}", style("Fixes that would run (--dry-run, nothing executed):").bold());
+        } else {
+            println!("{}", style("Attempting automatic fixes...").bold());
+        }
+
+        if run_fixes(&checks, cli, args.profile, args.dry_run, args.yes).is_err() {
+            anyhow::bail!("a fix failed; earlier fixes in this run were rolled back");
+        }
+    }
+
+    if args.profile == Profile::Prod && errors > 0 {
+        anyhow::bail!("{} check(s) failed under the prod profile", errors);
+    }
+
+    Ok(())
+}
+
+/// How to reverse an already-applied fix, recorded in the order applied so
+/// a later failure can be unwound newest-first.
+enum UndoAction {
+    RemoveDir(std::path::PathBuf),
+    RestoreSysctl { key: String, prior_value: String },
+    /// Applied but nothing to automatically undo (e.g. `usermod`, `modprobe`,
+    /// package installs) - left in the log only so rollback reporting is
+    /// honest about what it can't reverse.
+    Unreversible(String),
+}
+
+/// Run every `Fail` check's fix in order, re-running the originating check
+/// afterward to confirm it actually worked. A failed fix (or one that
+/// doesn't resolve the check) rolls back every fix already applied in this
+/// run. Returns `Err` if a rollback happened, so the caller can fail the
+/// process.
+fn run_fixes(
+    checks: &[CheckResult],
+    cli: &Cli,
+    profile: Profile,
+    dry_run: bool,
+    yes: bool,
+) -> std::result::Result<(), ()> {
+    let mut undo_log: Vec<UndoAction> = Vec::new();
+
+    for check in checks {
+        if check.status != CheckStatus::Fail {
+            continue;
+        }
+        let Some(fix) = &check.fix else { continue };
+
+        if !fix.kind.is_executable() {
+            println!("  {} (manual step required)", style(&fix.command).dim());
+            continue;
+        }
+
+        if command_needs_sudo(&fix.command) && !yes {
+            println!(
+                "  {} (needs --yes to run a sudo command)",
+                style(&fix.command).dim()
+            );
+            continue;
+        }
+
+        if dry_run {
+            println!("  would run: {}", style(&fix.command).cyan());
+            if let Some(persist) = &fix.persist {
+                println!("  would persist: {}", style(persist).cyan());
             }
+            continue;
+        }
+
+        print!("  Running: {} ... ", &fix.command);
+        let prior = capture_prior_state(fix);
+        match run_shell(&fix.command) {
+            Ok(()) => println!("{}", style("ok").green()),
+            Err(e) => {
+                println!("{} ({})", style("failed").red(), e);
+                rollback(&mut undo_log);
+                return Err(());
+            }
+        }
+        undo_log.push(make_undo(fix, prior));
+
+        if let Some(persist) = &fix.persist {
+            print!("  Persisting: {} ... ", persist);
+            match run_shell(persist) {
+                Ok(()) => println!("{}", style("ok").green()),
+                Err(e) => println!("{} ({})", style("failed").yellow(), e),
+            }
+        }
+
+        if !rerun_check_resolved(&check.name, cli, profile) {
+            println!(
+                "    {}",
+                style("fix did not resolve the check - rolling back").red()
+            );
+            rollback(&mut undo_log);
+            return Err(());
         }
     }
 
     Ok(())
 }
 
-fn check_runtime(cli: &Cli) -> Vec<CheckResult> {
+fn command_needs_sudo(command: &str) -> bool {
+    command.split_whitespace().any(|token| token == "sudo")
+}
+
+/// Run a fix/persist command through a shell, since several of them chain
+/// multiple commands with `&&` or pipe into `tee`.
+fn run_shell(command: &str) -> std::result::Result<(), String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Read whatever state a fix is about to mutate, before it runs, so a
+/// failed rollback has something to restore. Only `Sysctl` fixes have a
+/// value worth reading back here.
+fn capture_prior_state(fix: &FixCommand) -> Option<String> {
+    match fix.kind {
+        FixKind::Sysctl => {
+            let key = sysctl_key(&fix.command)?;
+            let output = Command::new("sysctl").arg("-n").arg(&key).output().ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+        _ => None,
+    }
+}
+
+fn sysctl_key(command: &str) -> Option<String> {
+    command
+        .split_whitespace()
+        .find_map(|token| token.split_once('=').map(|(key, _)| key.to_string()))
+}
+
+/// The directory a `mkdir`-style fix command creates - its last
+/// whitespace-separated token, which holds for every `MkDir` fix in this
+/// file (a bare `mkdir -p <path>`, or a `mkdir ... && chown ... <path>`
+/// that repeats the same path last).
+fn mkdir_target(command: &str) -> Option<std::path::PathBuf> {
+    command.split_whitespace().last().map(std::path::PathBuf::from)
+}
+
+fn make_undo(fix: &FixCommand, prior: Option<String>) -> UndoAction {
+    match fix.kind {
+        FixKind::MkDir => match mkdir_target(&fix.command) {
+            Some(path) => UndoAction::RemoveDir(path),
+            None => UndoAction::Unreversible(fix.command.clone()),
+        },
+        FixKind::Sysctl => match (sysctl_key(&fix.command), prior) {
+            (Some(key), Some(prior_value)) => UndoAction::RestoreSysctl { key, prior_value },
+            _ => UndoAction::Unreversible(fix.command.clone()),
+        },
+        _ => UndoAction::Unreversible(fix.command.clone()),
+    }
+}
+
+fn rollback(undo_log: &mut Vec<UndoAction>) {
+    while let Some(action) = undo_log.pop() {
+        match action {
+            UndoAction::RemoveDir(path) => {
+                print!("  Rolling back: removing {} ... ", path.display());
+                match std::fs::remove_dir(&path) {
+                    Ok(()) => println!("{}", style("ok").green()),
+                    Err(e) => println!("{} ({})", style("failed").yellow(), e),
+                }
+            }
+            UndoAction::RestoreSysctl { key, prior_value } => {
+                let command = format!("sudo sysctl -w {}={}", key, prior_value);
+                print!("  Rolling back: {} ... ", command);
+                match run_shell(&command) {
+                    Ok(()) => println!("{}", style("ok").green()),
+                    Err(e) => println!("{} ({})", style("failed").yellow(), e),
+                }
+            }
+            UndoAction::Unreversible(command) => {
+                println!(
+                    "  {} {} (no automatic rollback available)",
+                    style("-").dim(),
+                    command
+                );
+            }
+        }
+    }
+}
+
+/// Re-run the checks and report whether the one named `name` no longer
+/// fails. There's no per-check entry point, so this just recomputes every
+/// check in every category and looks the result back up by name - cheap
+/// enough for an interactive `--fix` run.
+fn rerun_check_resolved(name: &str, cli: &Cli, profile: Profile) -> bool {
+    let mut all = Vec::new();
+    all.extend(check_runtime(cli, profile));
+    all.extend(check_networking(profile));
+    all.extend(check_kernel(profile));
+    all.extend(check_rootless(profile));
+    all.extend(check_state(cli, profile));
+    all.extend(check_gatekeeper(profile));
+
+    all.iter()
+        .find(|c| c.name == name)
+        .map(|c| c.status != CheckStatus::Fail)
+        .unwrap_or(true)
+}
+
+fn check_runtime(cli: &Cli, _profile: Profile) -> Vec<CheckResult> {
     let mut results = Vec::new();
+    let mut found_runtime = None;
 
     // Check for youki
     match which::which("youki") {
@@ -242,6 +502,7 @@ fn check_runtime(cli: &Cli) -> Vec<CheckResult> {
                 message: format!("youki {} found at {}", version, path.display()),
                 fix: None,
             });
+            found_runtime = Some("youki");
         }
         Err(_) => {
             // Check for runc as fallback
@@ -255,6 +516,7 @@ fn check_runtime(cli: &Cli) -> Vec<CheckResult> {
                         message: format!("runc {} found at {} (fallback)", version, path.display()),
                         fix: None,
                     });
+                    found_runtime = Some("runc");
                 }
                 Err(_) => {
                     results.push(CheckResult {
@@ -263,6 +525,7 @@ fn check_runtime(cli: &Cli) -> Vec<CheckResult> {
                         status: CheckStatus::Fail,
                         message: "No OCI runtime found (youki or runc)".to_string(),
                         fix: Some(FixCommand {
+                            kind: FixKind::InstallBinary,
                             command: "cargo install youki".to_string(),
                             persist: None,
                         }),
@@ -282,6 +545,7 @@ fn check_runtime(cli: &Cli) -> Vec<CheckResult> {
                 message: format!("Configured runtime '{}' found", cli.runtime),
                 fix: None,
             });
+            found_runtime = Some(cli.runtime.as_str());
         } else {
             results.push(CheckResult {
                 category: "Runtime".to_string(),
@@ -289,6 +553,7 @@ fn check_runtime(cli: &Cli) -> Vec<CheckResult> {
                 status: CheckStatus::Fail,
                 message: format!("Configured runtime '{}' not found", cli.runtime),
                 fix: Some(FixCommand {
+                    kind: FixKind::Manual,
                     command: format!("Install {} or change VORDR_RUNTIME", cli.runtime),
                     persist: None,
                 }),
@@ -296,10 +561,133 @@ fn check_runtime(cli: &Cli) -> Vec<CheckResult> {
         }
     }
 
+    if let Some(binary) = found_runtime {
+        results.extend(check_runtime_features(binary));
+    }
+
     results
 }
 
-fn check_networking() -> Vec<CheckResult> {
+/// Ask the runtime to self-describe via the OCI `features` subcommand and
+/// check the capabilities svalinn actually depends on: a user namespace,
+/// cgroup v2, and seccomp. Older youki/runc builds predate `features`
+/// entirely, which is a Warn rather than a Fail since the runtime may
+/// still work - we just can't confirm it here.
+fn check_runtime_features(binary: &str) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    let output = Command::new(binary).arg("features").output();
+    let stdout = match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).into_owned(),
+        _ => {
+            results.push(CheckResult {
+                category: "Runtime".to_string(),
+                name: "runtime_features".to_string(),
+                status: CheckStatus::Warn,
+                message: format!(
+                    "{} does not support 'features' (too old to self-describe capabilities)",
+                    binary
+                ),
+                fix: Some(FixCommand {
+                    kind: FixKind::Manual,
+                    command: format!("Upgrade {} to a version supporting 'features'", binary),
+                    persist: None,
+                }),
+            });
+            return results;
+        }
+    };
+
+    let features: serde_json::Value = match serde_json::from_str(&stdout) {
+        Ok(v) => v,
+        Err(_) => {
+            results.push(CheckResult {
+                category: "Runtime".to_string(),
+                name: "runtime_features".to_string(),
+                status: CheckStatus::Warn,
+                message: format!("Could not parse '{} features' output as JSON", binary),
+                fix: None,
+            });
+            return results;
+        }
+    };
+
+    let namespaces: Vec<&str> = features
+        .pointer("/linux/namespaces")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    if namespaces.contains(&"user") {
+        results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_user_namespace".to_string(),
+            status: CheckStatus::Pass,
+            message: format!("{} reports user namespace support", binary),
+            fix: None,
+        });
+    } else {
+        results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_user_namespace".to_string(),
+            status: CheckStatus::Fail,
+            message: format!("{} does not report support for the user namespace", binary),
+            fix: None,
+        });
+    }
+
+    match features.pointer("/linux/cgroup/v2").and_then(|v| v.as_bool()) {
+        Some(true) => results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_cgroup_v2".to_string(),
+            status: CheckStatus::Pass,
+            message: format!("{} reports cgroup v2 support", binary),
+            fix: None,
+        }),
+        Some(false) => results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_cgroup_v2".to_string(),
+            status: CheckStatus::Fail,
+            message: format!("{} reports no cgroup v2 support", binary),
+            fix: None,
+        }),
+        None => results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_cgroup_v2".to_string(),
+            status: CheckStatus::Warn,
+            message: format!("{} features output does not report cgroup v2 support", binary),
+            fix: None,
+        }),
+    }
+
+    match features.pointer("/linux/seccomp/enabled").and_then(|v| v.as_bool()) {
+        Some(true) => results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_seccomp".to_string(),
+            status: CheckStatus::Pass,
+            message: format!("{} reports seccomp support", binary),
+            fix: None,
+        }),
+        Some(false) => results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_seccomp".to_string(),
+            status: CheckStatus::Warn,
+            message: format!("{} reports seccomp is disabled", binary),
+            fix: None,
+        }),
+        None => results.push(CheckResult {
+            category: "Runtime".to_string(),
+            name: "feature_seccomp".to_string(),
+            status: CheckStatus::Warn,
+            message: format!("{} features output does not report seccomp support", binary),
+            fix: None,
+        }),
+    }
+
+    results
+}
+
+fn check_networking(profile: Profile) -> Vec<CheckResult> {
     let mut results = Vec::new();
 
     // Check for netavark
@@ -318,9 +706,10 @@ fn check_networking() -> Vec<CheckResult> {
             results.push(CheckResult {
                 category: "Networking".to_string(),
                 name: "netavark_binary".to_string(),
-                status: CheckStatus::Warn,
+                status: escalate(CheckStatus::Warn, profile),
                 message: "netavark not found (container networking will be limited)".to_string(),
                 fix: Some(FixCommand {
+                    kind: FixKind::InstallBinary,
                     command: "cargo install netavark".to_string(),
                     persist: None,
                 }),
@@ -343,9 +732,10 @@ fn check_networking() -> Vec<CheckResult> {
             results.push(CheckResult {
                 category: "Networking".to_string(),
                 name: "aardvark_dns".to_string(),
-                status: CheckStatus::Warn,
+                status: escalate(CheckStatus::Warn, profile),
                 message: "aardvark-dns not found (DNS resolution will be limited)".to_string(),
                 fix: Some(FixCommand {
+                    kind: FixKind::InstallBinary,
                     command: "cargo install aardvark-dns".to_string(),
                     persist: None,
                 }),
@@ -356,7 +746,7 @@ fn check_networking() -> Vec<CheckResult> {
     results
 }
 
-fn check_kernel() -> Vec<CheckResult> {
+fn check_kernel(profile: Profile) -> Vec<CheckResult> {
     let mut results = Vec::new();
 
     // Check kernel version
@@ -400,15 +790,19 @@ fn check_kernel() -> Vec<CheckResult> {
         results.push(CheckResult {
             category: "Kernel/Rootless".to_string(),
             name: "cgroup_v2".to_string(),
-            status: CheckStatus::Warn,
+            status: escalate(CheckStatus::Warn, profile),
             message: "cgroup v2 not detected (using v1)".to_string(),
             fix: Some(FixCommand {
+                kind: FixKind::Manual,
                 command: "Add 'systemd.unified_cgroup_hierarchy=1' to kernel cmdline".to_string(),
                 persist: None,
             }),
         });
     }
 
+    results.extend(check_cgroup_delegation());
+    results.extend(check_hugepages());
+
     // Check user namespaces
     let userns_path = Path::new("/proc/sys/kernel/unprivileged_userns_clone");
     if userns_path.exists() {
@@ -428,6 +822,7 @@ fn check_kernel() -> Vec<CheckResult> {
                     status: CheckStatus::Fail,
                     message: "Unprivileged user namespaces disabled".to_string(),
                     fix: Some(FixCommand {
+                        kind: FixKind::Sysctl,
                         command: "sudo sysctl -w kernel.unprivileged_userns_clone=1".to_string(),
                         persist: Some("echo 'kernel.unprivileged_userns_clone=1' | sudo tee /etc/sysctl.d/99-userns.conf".to_string()),
                     }),
@@ -462,6 +857,7 @@ fn check_kernel() -> Vec<CheckResult> {
                 status: CheckStatus::Warn,
                 message: "Overlay filesystem not available".to_string(),
                 fix: Some(FixCommand {
+                    kind: FixKind::Modprobe,
                     command: "sudo modprobe overlay".to_string(),
                     persist: Some("echo 'overlay' | sudo tee /etc/modules-load.d/overlay.conf".to_string()),
                 }),
@@ -469,10 +865,468 @@ fn check_kernel() -> Vec<CheckResult> {
         }
     }
 
+    results.extend(check_seccomp());
+
     results
 }
 
-fn check_state(cli: &Cli) -> Vec<CheckResult> {
+/// Controllers svalinn needs delegated to the user's cgroup slice so
+/// rootless containers can actually apply resource limits - having the
+/// controller available at the root (`cgroup.controllers`) isn't enough,
+/// systemd also has to hand it down via `cgroup.subtree_control`.
+const REQUIRED_CGROUP_CONTROLLERS: &[&str] = &["cpu", "memory", "pids", "io"];
+
+fn check_cgroup_delegation() -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    let available = match std::fs::read_to_string("/sys/fs/cgroup/cgroup.controllers") {
+        Ok(content) => content,
+        Err(_) => return results, // not cgroup v2; already flagged by the cgroup_v2 check
+    };
+    let available: std::collections::HashSet<&str> = available.split_whitespace().collect();
+
+    let uid = unsafe { libc::getuid() };
+    let candidates = [
+        format!("/sys/fs/cgroup/user.slice/user-{}.slice/cgroup.subtree_control", uid),
+        "/sys/fs/cgroup/user.slice/cgroup.subtree_control".to_string(),
+    ];
+
+    let delegated = candidates
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok());
+
+    let delegated = match delegated {
+        Some(content) => content,
+        None => {
+            results.push(CheckResult {
+                category: "Kernel/Rootless".to_string(),
+                name: "cgroup_delegation".to_string(),
+                status: CheckStatus::Warn,
+                message: "Could not read a user slice cgroup.subtree_control to confirm controller delegation".to_string(),
+                fix: None,
+            });
+            return results;
+        }
+    };
+    let delegated: std::collections::HashSet<&str> = delegated.split_whitespace().collect();
+
+    let missing: Vec<&str> = REQUIRED_CGROUP_CONTROLLERS
+        .iter()
+        .filter(|c| available.contains(*c) && !delegated.contains(*c))
+        .copied()
+        .collect();
+
+    if missing.is_empty() {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "cgroup_delegation".to_string(),
+            status: CheckStatus::Pass,
+            message: "cpu, memory, pids, and io controllers are delegated to the user slice"
+                .to_string(),
+            fix: None,
+        });
+    } else {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "cgroup_delegation".to_string(),
+            status: CheckStatus::Fail,
+            message: format!(
+                "Controller(s) not delegated to the user slice: {} (resource limits will be silently ignored)",
+                missing.join(", ")
+            ),
+            fix: Some(FixCommand {
+                kind: FixKind::MkDir,
+                command: "sudo mkdir -p /etc/systemd/system/user@.service.d".to_string(),
+                persist: Some(format!(
+                    "printf '[Service]\\nDelegate=cpu cpuset memory pids io\\n' | sudo tee /etc/systemd/system/user@.service.d/delegate.conf && sudo systemctl daemon-reload"
+                )),
+            }),
+        });
+    }
+
+    results
+}
+
+/// Report the huge page sizes the kernel supports, so users can tell
+/// whether an OCI spec's `hugepageLimits` entries will actually apply -
+/// the runtime layer relies on this but never validates it today.
+fn check_hugepages() -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    let entries = match std::fs::read_dir("/sys/kernel/mm/hugepages") {
+        Ok(entries) => entries,
+        Err(_) => {
+            results.push(CheckResult {
+                category: "Kernel/Rootless".to_string(),
+                name: "hugepages".to_string(),
+                status: CheckStatus::Warn,
+                message: "No huge page sizes supported by this kernel".to_string(),
+                fix: None,
+            });
+            return results;
+        }
+    };
+
+    let mut sizes: Vec<(u64, String, u64)> = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let Some(kb_str) = name.strip_prefix("hugepages-").and_then(|s| s.strip_suffix("kB"))
+        else {
+            continue;
+        };
+        let Ok(kb) = kb_str.parse::<u64>() else {
+            continue;
+        };
+
+        let nr_hugepages = std::fs::read_to_string(entry.path().join("nr_hugepages"))
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+
+        sizes.push((kb, hugepage_size_moniker(kb), nr_hugepages));
+    }
+
+    if sizes.is_empty() {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "hugepages".to_string(),
+            status: CheckStatus::Warn,
+            message: "No huge page sizes supported by this kernel".to_string(),
+            fix: None,
+        });
+        return results;
+    }
+
+    sizes.sort_by_key(|(kb, _, _)| *kb);
+
+    // The default size is the one /proc/meminfo's "Hugepagesize:" reports;
+    // fall back to the smallest supported size if that line is absent.
+    let default_kb = std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| {
+            meminfo.lines().find_map(|line| {
+                line.strip_prefix("Hugepagesize:")
+                    .and_then(|rest| rest.trim().split_whitespace().next())
+                    .and_then(|n| n.parse::<u64>().ok())
+            })
+        })
+        .unwrap_or(sizes[0].0);
+
+    let size_list = sizes
+        .iter()
+        .map(|(_, moniker, _)| moniker.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let default_reserved = sizes
+        .iter()
+        .find(|(kb, _, _)| *kb == default_kb)
+        .map(|(_, _, nr)| *nr > 0)
+        .unwrap_or(false);
+
+    if default_reserved {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "hugepages".to_string(),
+            status: CheckStatus::Pass,
+            message: format!("Huge page sizes supported: {}", size_list),
+            fix: None,
+        });
+    } else {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "hugepages".to_string(),
+            status: CheckStatus::Warn,
+            message: format!(
+                "Huge page sizes supported ({}) but none are reserved (nr_hugepages=0)",
+                size_list
+            ),
+            fix: Some(FixCommand {
+                kind: FixKind::Sysctl,
+                command: "sudo sysctl -w vm.nr_hugepages=64".to_string(),
+                persist: Some(
+                    "echo 'vm.nr_hugepages=64' | sudo tee /etc/sysctl.d/99-hugepages.conf"
+                        .to_string(),
+                ),
+            }),
+        });
+    }
+
+    results
+}
+
+fn hugepage_size_moniker(kb: u64) -> String {
+    if kb >= 1 << 20 {
+        format!("{}GB", kb >> 20)
+    } else if kb >= 1 << 10 {
+        format!("{}MB", kb >> 10)
+    } else {
+        format!("{}KB", kb)
+    }
+}
+
+/// Youki's default profile relies on seccomp BPF filtering (its own
+/// devcontainer runs with `seccomp=unconfined` specifically to disable it
+/// during debugging), so a host that can't enforce profiles silently
+/// leaves containers unconfined rather than failing to start.
+fn check_seccomp() -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    // `Seccomp:` only appears in /proc/self/status when CONFIG_SECCOMP is
+    // built into the running kernel - its value just reflects this
+    // process's own mode, not host capability, so presence is what matters.
+    let kernel_seccomp = std::fs::read_to_string("/proc/self/status")
+        .map(|status| status.lines().any(|line| line.starts_with("Seccomp:")))
+        .unwrap_or(false);
+
+    if kernel_seccomp {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "seccomp_kernel".to_string(),
+            status: CheckStatus::Pass,
+            message: "Kernel built with CONFIG_SECCOMP".to_string(),
+            fix: None,
+        });
+    } else {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "seccomp_kernel".to_string(),
+            status: CheckStatus::Fail,
+            message: "Kernel has no seccomp support (containers cannot be confined)".to_string(),
+            fix: Some(FixCommand {
+                kind: FixKind::Manual,
+                command: "Use a kernel built with CONFIG_SECCOMP=y and CONFIG_SECCOMP_FILTER=y"
+                    .to_string(),
+                persist: None,
+            }),
+        });
+    }
+
+    match kernel_config_has("CONFIG_SECCOMP_FILTER") {
+        Some(true) => results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "seccomp_filter".to_string(),
+            status: CheckStatus::Pass,
+            message: "CONFIG_SECCOMP_FILTER=y (BPF seccomp filtering available)".to_string(),
+            fix: None,
+        }),
+        Some(false) => results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "seccomp_filter".to_string(),
+            status: CheckStatus::Fail,
+            message: "CONFIG_SECCOMP_FILTER not set (BPF seccomp filtering unavailable)"
+                .to_string(),
+            fix: Some(FixCommand {
+                kind: FixKind::Manual,
+                command: "Use a kernel built with CONFIG_SECCOMP_FILTER=y".to_string(),
+                persist: None,
+            }),
+        }),
+        None => results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "seccomp_filter".to_string(),
+            status: CheckStatus::Warn,
+            message: "Could not locate a kernel config to confirm CONFIG_SECCOMP_FILTER"
+                .to_string(),
+            fix: None,
+        }),
+    }
+
+    let libseccomp_present = which::which("scmp_sys_resolver").is_ok()
+        || [
+            "/usr/lib/x86_64-linux-gnu/libseccomp.so.2",
+            "/usr/lib64/libseccomp.so.2",
+            "/usr/lib/libseccomp.so.2",
+            "/lib/libseccomp.so.2",
+        ]
+        .iter()
+        .any(|path| Path::new(path).exists());
+
+    if libseccomp_present {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "seccomp_userspace".to_string(),
+            status: CheckStatus::Pass,
+            message: "libseccomp found".to_string(),
+            fix: None,
+        });
+    } else {
+        results.push(CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: "seccomp_userspace".to_string(),
+            status: CheckStatus::Warn,
+            message: "libseccomp not found (seccomp policies cannot be compiled)".to_string(),
+            fix: Some(FixCommand {
+                kind: FixKind::InstallBinary,
+                command: "sudo apt install libseccomp2".to_string(),
+                persist: None,
+            }),
+        });
+    }
+
+    results
+}
+
+/// Check whether `key=y` appears in the running kernel's build config,
+/// trying `/boot/config-$(uname -r)` and the gzipped `/proc/config.gz`
+/// (decompressed via `zcat`, mirroring how the rest of doctor shells out
+/// to system tools rather than pulling in a decompression dependency).
+/// Returns `None` when no config source is available to check.
+fn kernel_config_has(key: &str) -> Option<bool> {
+    let release = get_command_version("uname", &["-r"]);
+    let boot_config = format!("/boot/config-{}", release);
+
+    if let Ok(content) = std::fs::read_to_string(&boot_config) {
+        return Some(content.lines().any(|line| line.trim() == format!("{}=y", key)));
+    }
+
+    if Path::new("/proc/config.gz").exists() {
+        if let Ok(output) = Command::new("zcat").arg("/proc/config.gz").output() {
+            if output.status.success() {
+                let content = String::from_utf8_lossy(&output.stdout);
+                return Some(content.lines().any(|line| line.trim() == format!("{}=y", key)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Rootless containers need subordinate UID/GID ranges allocated to the
+/// invoking user (`/etc/subuid` and `/etc/subgid`) plus the setuid-root
+/// `newuidmap`/`newgidmap` helpers that apply those ranges - youki/runc
+/// otherwise fail to build a full UID map with no clear error.
+fn check_rootless(_profile: Profile) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    let username = std::env::var("USER").unwrap_or_default();
+    let uid = unsafe { libc::getuid() }.to_string();
+
+    for (label, path) in [("subuid", "/etc/subuid"), ("subgid", "/etc/subgid")] {
+        let fix = FixCommand {
+            kind: FixKind::Usermod,
+            command: format!("sudo usermod --add-{}s 100000-165535 {}", label, username),
+            persist: Some(format!("Verify with: grep {} {}", username, path)),
+        };
+
+        match parse_subid_entry(Path::new(path), &username, &uid) {
+            Some(count) if count >= 65536 => {
+                results.push(CheckResult {
+                    category: "Kernel/Rootless".to_string(),
+                    name: format!("{}_range", label),
+                    status: CheckStatus::Pass,
+                    message: format!("{} range of {} IDs allocated in {}", label, count, path),
+                    fix: None,
+                });
+            }
+            Some(count) => {
+                results.push(CheckResult {
+                    category: "Kernel/Rootless".to_string(),
+                    name: format!("{}_range", label),
+                    status: CheckStatus::Warn,
+                    message: format!(
+                        "{} range of only {} IDs in {} (recommend at least 65536)",
+                        label, count, path
+                    ),
+                    fix: Some(fix),
+                });
+            }
+            None => {
+                results.push(CheckResult {
+                    category: "Kernel/Rootless".to_string(),
+                    name: format!("{}_range", label),
+                    status: CheckStatus::Fail,
+                    message: format!(
+                        "No {} entry for {} (uid {}) in {}",
+                        label, username, uid, path
+                    ),
+                    fix: Some(fix),
+                });
+            }
+        }
+    }
+
+    results.push(check_idmap_helper("newuidmap"));
+    results.push(check_idmap_helper("newgidmap"));
+
+    results
+}
+
+/// Parse `/etc/subuid` / `/etc/subgid` (`name:start:count` lines) and
+/// return the allocated count for the first entry matching `username` or
+/// `uid`, if any.
+fn parse_subid_entry(path: &Path, username: &str, uid: &str) -> Option<u64> {
+    let content = std::fs::read_to_string(path).ok()?;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.splitn(3, ':');
+        let name = parts.next()?;
+        let _start = parts.next()?;
+        let count: u64 = parts.next()?.parse().ok()?;
+
+        if name == username || name == uid {
+            return Some(count);
+        }
+    }
+
+    None
+}
+
+/// Verify an id-map helper is present and setuid-root; rootless mapping
+/// fails silently (a partial or missing UID map inside the container)
+/// without it.
+fn check_idmap_helper(binary: &str) -> CheckResult {
+    use std::os::unix::fs::MetadataExt;
+
+    match which::which(binary) {
+        Ok(path) => match std::fs::metadata(&path) {
+            Ok(meta) if meta.mode() & libc::S_ISUID != 0 => CheckResult {
+                category: "Kernel/Rootless".to_string(),
+                name: format!("{}_setuid", binary),
+                status: CheckStatus::Pass,
+                message: format!("{} found and setuid-root ({})", binary, path.display()),
+                fix: None,
+            },
+            Ok(_) => CheckResult {
+                category: "Kernel/Rootless".to_string(),
+                name: format!("{}_setuid", binary),
+                status: CheckStatus::Fail,
+                message: format!("{} found at {} but is not setuid-root", binary, path.display()),
+                fix: Some(FixCommand {
+                    kind: FixKind::Chown,
+                    command: format!("sudo chmod u+s {}", path.display()),
+                    persist: None,
+                }),
+            },
+            Err(_) => CheckResult {
+                category: "Kernel/Rootless".to_string(),
+                name: format!("{}_setuid", binary),
+                status: CheckStatus::Warn,
+                message: format!("Could not stat {}", path.display()),
+                fix: None,
+            },
+        },
+        Err(_) => CheckResult {
+            category: "Kernel/Rootless".to_string(),
+            name: format!("{}_present", binary),
+            status: CheckStatus::Fail,
+            message: format!("{} not found in PATH (required for rootless UID mapping)", binary),
+            fix: Some(FixCommand {
+                kind: FixKind::InstallBinary,
+                command: "sudo apt install uidmap".to_string(),
+                persist: None,
+            }),
+        },
+    }
+}
+
+fn check_state(cli: &Cli, profile: Profile) -> Vec<CheckResult> {
     let mut results = Vec::new();
 
     let root_path = Path::new(&cli.root);
@@ -499,6 +1353,7 @@ fn check_state(cli: &Cli) -> Vec<CheckResult> {
                     status: CheckStatus::Fail,
                     message: format!("{} not writable", root_path.display()),
                     fix: Some(FixCommand {
+                        kind: FixKind::Chown,
                         command: format!("sudo chown $USER:$USER {}", root_path.display()),
                         persist: None,
                     }),
@@ -520,6 +1375,7 @@ fn check_state(cli: &Cli) -> Vec<CheckResult> {
             status: CheckStatus::Warn,
             message: format!("{} does not exist (will be created)", root_path.display()),
             fix: Some(FixCommand {
+                kind: FixKind::MkDir,
                 command: format!("sudo mkdir -p {} && sudo chown $USER:$USER {}", root_path.display(), root_path.display()),
                 persist: None,
             }),
@@ -561,6 +1417,7 @@ fn check_state(cli: &Cli) -> Vec<CheckResult> {
                     status: CheckStatus::Fail,
                     message: format!("Could not open database: {}", e),
                     fix: Some(FixCommand {
+                        kind: FixKind::Manual,
                         command: format!("rm {} && vordr ps", db_path.display()),
                         persist: None,
                     }),
@@ -589,9 +1446,10 @@ fn check_state(cli: &Cli) -> Vec<CheckResult> {
                         results.push(CheckResult {
                             category: "State Database".to_string(),
                             name: "nfs_detection".to_string(),
-                            status: CheckStatus::Warn,
+                            status: escalate(CheckStatus::Warn, profile),
                             message: "State directory is on NFS (WAL mode may not work)".to_string(),
                             fix: Some(FixCommand {
+                                kind: FixKind::Manual,
                                 command: "Set journal_mode = 'delete' in config".to_string(),
                                 persist: None,
                             }),
@@ -605,7 +1463,7 @@ fn check_state(cli: &Cli) -> Vec<CheckResult> {
     results
 }
 
-fn check_gatekeeper() -> Vec<CheckResult> {
+fn check_gatekeeper(profile: Profile) -> Vec<CheckResult> {
     let mut results = Vec::new();
 
     let version = crate::ffi::gatekeeper_version();
@@ -613,9 +1471,10 @@ fn check_gatekeeper() -> Vec<CheckResult> {
         results.push(CheckResult {
             category: "Gatekeeper".to_string(),
             name: "gatekeeper_loaded".to_string(),
-            status: CheckStatus::Warn,
+            status: escalate(CheckStatus::Warn, profile),
             message: "Gatekeeper stub loaded (SPARK verification unavailable)".to_string(),
             fix: Some(FixCommand {
+                kind: FixKind::Manual,
                 command: "Install GNAT/SPARK and rebuild: just build-vordr".to_string(),
                 persist: None,
             }),
@@ -669,16 +1528,67 @@ fn get_command_version(cmd: &str, args: &[&str]) -> String {
         .unwrap_or_else(|| "unknown".to_string())
 }
 
-fn is_safe_fix(command: &str) -> bool {
-    // Only auto-fix directory creation and similar safe operations
-    command.starts_with("mkdir") || command.starts_with("sudo mkdir")
+/// Sub-second precision for [`format_timestamp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimestampPrecision {
+    Seconds,
+    #[allow(dead_code)]
+    Millis,
+    #[allow(dead_code)]
+    Micros,
+    #[allow(dead_code)]
+    Nanos,
 }
 
-fn chrono_lite_now() -> String {
-    // Simple timestamp without chrono dependency
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let duration = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default();
-    format!("{}Z", duration.as_secs())
+/// Format a duration-since-epoch as an RFC 3339 / ISO 8601 UTC timestamp
+/// (`2024-02-10T08:18:28Z`, or `...T08:18:28.951575Z` at higher
+/// precision), without pulling in a date crate - doctor's reports run far
+/// too infrequently to justify the dependency.
+fn format_timestamp(duration: std::time::Duration, precision: TimestampPrecision) -> String {
+    let days = (duration.as_secs() / 86400) as i64;
+    let secs_of_day = duration.as_secs() % 86400;
+
+    let (year, month, day) = civil_from_days(days);
+    let hour = secs_of_day / 3600;
+    let minute = (secs_of_day % 3600) / 60;
+    let second = secs_of_day % 60;
+
+    let date_time = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    );
+
+    match precision {
+        TimestampPrecision::Seconds => format!("{}Z", date_time),
+        TimestampPrecision::Millis => format!("{}.{:03}Z", date_time, duration.subsec_millis()),
+        TimestampPrecision::Micros => format!("{}.{:06}Z", date_time, duration.subsec_micros()),
+        TimestampPrecision::Nanos => format!("{}.{:09}Z", date_time, duration.subsec_nanos()),
+    }
+}
+
+/// Howard Hinnant's civil-from-days algorithm: turns a day count since the
+/// Unix epoch (1970-01-01) into a proleptic Gregorian (year, month, day),
+/// without needing a calendar library.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468; // shift epoch to 0000-03-01
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097; // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32; // [1, 31]
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32; // [1, 12]
+    let y = y + i64::from(m <= 2);
+    (y, m, d)
+}
+
+fn chrono_lite_now(clock: &dyn crate::clock::Clock) -> String {
+    let epoch = crate::clock::epoch_seconds(clock.now());
+    if epoch < 0 {
+        // Surface the skew instead of silently collapsing to 1970-01-01.
+        return format!("1970-01-01T00:00:00Z (clock reads {}s before epoch)", -epoch);
+    }
+    let duration = std::time::Duration::from_secs(epoch as u64);
+    format_timestamp(duration, TimestampPrecision::Seconds)
 }
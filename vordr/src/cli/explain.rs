@@ -1,30 +1,64 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
 //! Policy explanation command - helps users understand why actions were blocked
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Parser, ValueEnum};
 use console::style;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
 
 use crate::cli::Cli;
+use crate::engine::{EventFilter, EventStore, JsonlEventStore, PolicyEvent, Severity};
 
 /// Explain why a policy blocked an action
 #[derive(Parser, Debug)]
 pub struct ExplainArgs {
     /// Event ID or container ID to explain
-    #[arg(required_unless_present = "last")]
+    #[arg(required_unless_present_any = ["last", "list"])]
     pub event_id: Option<String>,
 
     /// Explain the last rejection
     #[arg(short, long)]
     pub last: bool,
 
+    /// List recent rejections instead of explaining a single one
+    #[arg(long)]
+    pub list: bool,
+
+    /// Filter by severity (critical, high, medium, low)
+    #[arg(long)]
+    pub severity: Option<String>,
+
+    /// Filter by container ID or name
+    #[arg(long)]
+    pub container: Option<String>,
+
+    /// Only show events in the last window, e.g. `24h`, `7d`, `30m`
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Filter by policy rule, e.g. `network.egress.blocked`
+    #[arg(long)]
+    pub policy_rule: Option<String>,
+
     /// Output format
     #[arg(long, value_enum, default_value = "human")]
     pub format: OutputFormat,
 
+    /// With `--format asff`, emit a JSON array (capped at 100 findings, the
+    /// Security Hub `BatchImportFindings` limit) instead of a single object
+    #[arg(long)]
+    pub batch: bool,
+
     /// Show fix suggestions
     #[arg(long, default_value = "true")]
     pub suggest: bool,
+
+    /// Additional directory of rule files (YAML) to merge on top of the
+    /// built-in ruleset and `<config_dir>/vordr/rules`. Can be passed more
+    /// than once; later directories win on matching rule `id`.
+    #[arg(long = "rules-dir", value_name = "DIR")]
+    pub rules_dir: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
@@ -32,30 +66,8 @@ pub enum OutputFormat {
     #[default]
     Human,
     Json,
-}
-
-/// Policy rejection event
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct PolicyEvent {
-    pub id: String,
-    pub timestamp: String,
-    pub container_id: String,
-    pub container_name: String,
-    pub policy_rule: String,
-    pub action: String,
-    pub target: String,
-    pub reason: String,
-    pub severity: Severity,
-    pub profile: String,
-}
-
-#[derive(Debug, Clone, Copy, serde::Serialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Severity {
-    Critical,
-    High,
-    Medium,
-    Low,
+    /// AWS Security Hub Finding Format, for `BatchImportFindings`.
+    Asff,
 }
 
 /// Explanation with suggestions
@@ -76,17 +88,157 @@ pub struct Suggestion {
     pub risk_level: String,
 }
 
+const ASFF_SCHEMA_VERSION: &str = "2018-10-08";
+const ASFF_GENERATOR_ID: &str = "svalinn-vordr-policy-engine";
+/// `BatchImportFindings` rejects requests with more than 100 findings.
+const ASFF_BATCH_LIMIT: usize = 100;
+
+/// An AWS Security Finding Format finding, ready for
+/// `securityhub:BatchImportFindings`. Field names match the ASFF spec
+/// exactly, which happens to line up with `rename_all = "PascalCase"` for
+/// every field except `resource_type` (ASFF calls it `Type`).
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffFinding {
+    pub schema_version: String,
+    pub id: String,
+    pub product_arn: String,
+    pub generator_id: String,
+    pub aws_account_id: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub types: Vec<String>,
+    pub title: String,
+    pub description: String,
+    pub severity: AsffSeverity,
+    pub resources: Vec<AsffResource>,
+    pub remediation: AsffRemediation,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffSeverity {
+    pub label: String,
+    pub normalized: u32,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffResource {
+    #[serde(rename = "Type")]
+    pub resource_type: String,
+    pub id: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffRemediation {
+    pub recommendation: AsffRecommendation,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffRecommendation {
+    pub text: String,
+    pub url: String,
+}
+
+/// Builds one ASFF finding from an [`Explanation`]. `AwsAccountId` and the
+/// region embedded in `ProductArn` come from the environment rather than
+/// the container's own config, since nothing else in vordr has a notion of
+/// an AWS account - operators wire these up the same way they configure
+/// any other Security Hub custom integration.
+fn build_asff_finding(explanation: &Explanation) -> AsffFinding {
+    let event = &explanation.event;
+    let account_id = std::env::var("AWS_ACCOUNT_ID").unwrap_or_else(|_| "000000000000".to_string());
+    let region = std::env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+
+    AsffFinding {
+        schema_version: ASFF_SCHEMA_VERSION.to_string(),
+        id: event.id.clone(),
+        product_arn: format!(
+            "arn:aws:securityhub:{}:{}:product/{}/default",
+            region, account_id, account_id
+        ),
+        generator_id: ASFF_GENERATOR_ID.to_string(),
+        aws_account_id: account_id,
+        created_at: event.timestamp.clone(),
+        updated_at: event.timestamp.clone(),
+        types: asff_types(event),
+        title: explanation.explanation.clone(),
+        description: explanation.context.clone(),
+        severity: asff_severity(event.severity),
+        resources: vec![AsffResource {
+            resource_type: "Container".to_string(),
+            id: event.container_id.clone(),
+        }],
+        remediation: AsffRemediation {
+            recommendation: build_asff_recommendation(explanation),
+        },
+    }
+}
+
+fn asff_types(event: &PolicyEvent) -> Vec<String> {
+    if event.policy_rule == "network.egress.blocked" && event.target.starts_with("169.254.169.254") {
+        vec!["TTPs/Defense Evasion".to_string()]
+    } else {
+        vec!["Software and Configuration Checks".to_string()]
+    }
+}
+
+/// Maps our four-level [`Severity`] to ASFF's `Label` plus a `Normalized`
+/// 0-100 score, picking a representative value from the middle of each of
+/// ASFF's own suggested bands (CRITICAL 90-100, HIGH 70-89, MEDIUM 40-69,
+/// LOW 1-39).
+fn asff_severity(severity: Severity) -> AsffSeverity {
+    let (label, normalized) = match severity {
+        Severity::Critical => ("CRITICAL", 95),
+        Severity::High => ("HIGH", 75),
+        Severity::Medium => ("MEDIUM", 50),
+        Severity::Low => ("LOW", 20),
+    };
+    AsffSeverity {
+        label: label.to_string(),
+        normalized,
+    }
+}
+
+fn build_asff_recommendation(explanation: &Explanation) -> AsffRecommendation {
+    let text = explanation
+        .suggestions
+        .first()
+        .map(|s| format!("{}: {}", s.title, s.description))
+        .unwrap_or_else(|| explanation.explanation.clone());
+
+    AsffRecommendation {
+        text,
+        url: explanation.documentation_url.clone(),
+    }
+}
+
 /// Execute explain command
 pub async fn execute(args: ExplainArgs, cli: &Cli) -> Result<()> {
+    let store = JsonlEventStore::open(Path::new(&cli.root))
+        .context("Failed to open policy event log")?;
+
+    if args.list {
+        return list_rejections(&store, &args);
+    }
+
     let event = if args.last {
-        get_last_rejection(cli)?
+        store
+            .get_last()?
+            .ok_or_else(|| anyhow::anyhow!("No rejection events have been recorded yet"))?
     } else if let Some(ref id) = args.event_id {
-        get_rejection_by_id(id, cli)?
+        store
+            .get_by_id(id)?
+            .ok_or_else(|| anyhow::anyhow!("No rejection event found with id '{}'", id))?
     } else {
-        bail!("Must specify either --last or an event ID");
+        bail!("Must specify either --last, --list, or an event ID");
     };
 
-    let explanation = generate_explanation(&event);
+    let rules = load_rules(&args.rules_dir)?;
+    let explanation = generate_explanation(&event, &rules);
 
     match args.format {
         OutputFormat::Json => {
@@ -95,264 +247,328 @@ pub async fn execute(args: ExplainArgs, cli: &Cli) -> Result<()> {
         OutputFormat::Human => {
             print_human_explanation(&explanation, args.suggest);
         }
+        OutputFormat::Asff => {
+            let finding = build_asff_finding(&explanation);
+            if args.batch {
+                println!("{}", serde_json::to_string_pretty(&vec![finding])?);
+            } else {
+                println!("{}", serde_json::to_string_pretty(&finding)?);
+            }
+        }
     }
 
     Ok(())
 }
 
-fn get_last_rejection(_cli: &Cli) -> Result<PolicyEvent> {
-    // In a real implementation, this would query the event log
-    // For now, return a sample rejection for demonstration
-    Ok(PolicyEvent {
-        id: "evt_abc123".to_string(),
-        timestamp: chrono::Utc::now().to_rfc3339(),
-        container_id: "c7d8e9f0".to_string(),
-        container_name: "my-app".to_string(),
-        policy_rule: "network.egress.blocked".to_string(),
-        action: "connect".to_string(),
-        target: "169.254.169.254:80".to_string(),
-        reason: "Blocked access to cloud metadata endpoint".to_string(),
-        severity: Severity::Critical,
-        profile: "balanced".to_string(),
-    })
-}
+/// Handles `--list`: applies the requested filters and prints matching
+/// events instead of explaining a single one.
+fn list_rejections(store: &dyn EventStore, args: &ExplainArgs) -> Result<()> {
+    let mut filter = EventFilter::default();
+    if let Some(ref severity) = args.severity {
+        filter.severity = Some(parse_severity(severity)?);
+    }
+    if let Some(ref container) = args.container {
+        filter.container_id = Some(container.clone());
+    }
+    if let Some(ref policy_rule) = args.policy_rule {
+        filter.policy_rule = Some(policy_rule.clone());
+    }
+    if let Some(ref since) = args.since {
+        filter.since = Some(parse_since(since)?);
+    }
 
-fn get_rejection_by_id(id: &str, _cli: &Cli) -> Result<PolicyEvent> {
-    // In a real implementation, would query by ID
-    // For now, return a sample based on common patterns
-
-    if id.starts_with("cap_") || id.contains("capability") {
-        return Ok(PolicyEvent {
-            id: id.to_string(),
-            timestamp: chrono::Utc::now().to_rfc3339(),
-            container_id: "a1b2c3d4".to_string(),
-            container_name: "privileged-app".to_string(),
-            policy_rule: "capability.denied".to_string(),
-            action: "add_capability".to_string(),
-            target: "CAP_SYS_ADMIN".to_string(),
-            reason: "Capability CAP_SYS_ADMIN is not allowed".to_string(),
-            severity: Severity::High,
-            profile: "strict".to_string(),
-        });
+    let events = store.query(&filter)?;
+    let rules = load_rules(&args.rules_dir)?;
+
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&events)?),
+        OutputFormat::Human => {
+            if events.is_empty() {
+                println!("No rejection events match the given filters.");
+                return Ok(());
+            }
+
+            println!(
+                "{:<14} {:<24} {:<20} {:<10} {:<26} TARGET",
+                "EVENT ID", "TIME", "CONTAINER", "SEVERITY", "RULE"
+            );
+            for event in &events {
+                println!(
+                    "{:<14} {:<24} {:<20} {:<10} {:<26} {}",
+                    truncate(&event.id, 14),
+                    event.timestamp,
+                    truncate(&event.container_name, 20),
+                    severity_str(event.severity),
+                    truncate(&event.policy_rule, 26),
+                    event.target,
+                );
+            }
+        }
+        OutputFormat::Asff => {
+            let mut findings: Vec<AsffFinding> = events
+                .iter()
+                .map(|event| build_asff_finding(&generate_explanation(event, &rules)))
+                .collect();
+
+            if findings.len() > ASFF_BATCH_LIMIT {
+                eprintln!(
+                    "warning: {} findings exceed the BatchImportFindings limit of {}; \
+                     truncating to the most recent {}",
+                    findings.len(),
+                    ASFF_BATCH_LIMIT,
+                    ASFF_BATCH_LIMIT
+                );
+                findings.truncate(ASFF_BATCH_LIMIT);
+            }
+            println!("{}", serde_json::to_string_pretty(&findings)?);
+        }
     }
 
-    if id.starts_with("mount_") || id.contains("mount") {
-        return Ok(PolicyEvent {
-            id: id.to_string(),
-            timestamp: chrono::Utc::now().to_rfc3339(),
-            container_id: "e5f6g7h8".to_string(),
-            container_name: "volume-app".to_string(),
-            policy_rule: "mount.sensitive.blocked".to_string(),
-            action: "mount".to_string(),
-            target: "/etc/shadow".to_string(),
-            reason: "Mount of sensitive host path denied".to_string(),
-            severity: Severity::Critical,
-            profile: "balanced".to_string(),
-        });
+    Ok(())
+}
+
+fn severity_str(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
     }
+}
 
-    // Default to network rejection
-    Ok(PolicyEvent {
-        id: id.to_string(),
-        timestamp: chrono::Utc::now().to_rfc3339(),
-        container_id: "c7d8e9f0".to_string(),
-        container_name: "web-app".to_string(),
-        policy_rule: "network.egress.blocked".to_string(),
-        action: "connect".to_string(),
-        target: "169.254.169.254:80".to_string(),
-        reason: "Blocked access to cloud metadata endpoint".to_string(),
-        severity: Severity::Critical,
-        profile: "balanced".to_string(),
-    })
+fn parse_severity(value: &str) -> Result<Severity> {
+    match value.to_lowercase().as_str() {
+        "critical" => Ok(Severity::Critical),
+        "high" => Ok(Severity::High),
+        "medium" => Ok(Severity::Medium),
+        "low" => Ok(Severity::Low),
+        other => bail!("Invalid severity '{}' (expected critical, high, medium, or low)", other),
+    }
 }
 
-fn generate_explanation(event: &PolicyEvent) -> Explanation {
-    let (explanation, context, suggestions, doc_url) = match event.policy_rule.as_str() {
-        "network.egress.blocked" => {
-            if event.target.starts_with("169.254.169.254") {
-                (
-                    "The container attempted to access the cloud instance metadata service. \
-                     This is a common attack vector for credential theft in cloud environments."
-                        .to_string(),
-                    "Cloud metadata endpoints (169.254.169.254) provide sensitive information \
-                     including temporary credentials, instance identity, and configuration. \
-                     Attackers who compromise a container often attempt to access this endpoint \
-                     to escalate privileges or move laterally."
-                        .to_string(),
-                    vec![
-                        Suggestion {
-                            title: "Use IMDSv2 with hop limit".to_string(),
-                            description: "Configure your cloud instance to require IMDSv2 with \
-                                         a hop limit of 1, which prevents containers from accessing metadata."
-                                .to_string(),
-                            command: Some(
-                                "aws ec2 modify-instance-metadata-options --instance-id <id> \
-                                 --http-tokens required --http-put-response-hop-limit 1"
-                                    .to_string(),
-                            ),
-                            risk_level: "none".to_string(),
-                        },
-                        Suggestion {
-                            title: "Use explicit credentials".to_string(),
-                            description: "If your application needs AWS credentials, use \
-                                         environment variables or mounted secrets instead of IMDS."
-                                .to_string(),
-                            command: Some(
-                                "vordr run --env AWS_ACCESS_KEY_ID=... --env AWS_SECRET_ACCESS_KEY=..."
-                                    .to_string(),
-                            ),
-                            risk_level: "low".to_string(),
-                        },
-                        Suggestion {
-                            title: "Allow metadata access (not recommended)".to_string(),
-                            description: "If your application legitimately needs metadata access, \
-                                         you can use the dev profile or create a custom profile."
-                                .to_string(),
-                            command: Some("vordr run --profile dev ...".to_string()),
-                            risk_level: "high".to_string(),
-                        },
-                    ],
-                    "https://svalinn.dev/docs/security/cloud-metadata".to_string(),
-                )
-            } else {
-                (
-                    format!(
-                        "The container attempted to connect to {} which is not allowed \
-                         by the current network policy.",
-                        event.target
-                    ),
-                    "Network egress controls prevent containers from making unauthorized \
-                     connections. This helps contain breaches and prevents data exfiltration."
-                        .to_string(),
-                    vec![
-                        Suggestion {
-                            title: "Add network allow rule".to_string(),
-                            description: "Allow specific destinations in your container configuration."
-                                .to_string(),
-                            command: Some(format!(
-                                "vordr run --network-allow {} ...",
-                                event.target
-                            )),
-                            risk_level: "medium".to_string(),
-                        },
-                        Suggestion {
-                            title: "Use bridge networking".to_string(),
-                            description: "Switch to bridge network mode for general connectivity."
-                                .to_string(),
-                            command: Some("vordr run --network bridge ...".to_string()),
-                            risk_level: "medium".to_string(),
-                        },
-                    ],
-                    "https://svalinn.dev/docs/security/networking".to_string(),
+/// Parses a `--since` value into an RFC3339 cutoff: a relative window like
+/// `24h`/`7d`/`30m`, or an absolute RFC3339 timestamp.
+fn parse_since(value: &str) -> Result<String> {
+    use chrono::{Duration, Utc};
+
+    let cutoff = if let Some(hours) = value.strip_suffix('h') {
+        Utc::now()
+            - Duration::hours(
+                hours
+                    .parse()
+                    .with_context(|| format!("Invalid duration '{}'", value))?,
+            )
+    } else if let Some(days) = value.strip_suffix('d') {
+        Utc::now()
+            - Duration::days(
+                days.parse()
+                    .with_context(|| format!("Invalid duration '{}'", value))?,
+            )
+    } else if let Some(minutes) = value.strip_suffix('m') {
+        Utc::now()
+            - Duration::minutes(
+                minutes
+                    .parse()
+                    .with_context(|| format!("Invalid duration '{}'", value))?,
+            )
+    } else {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .with_context(|| {
+                format!(
+                    "Invalid --since value '{}' (expected a duration like 24h/7d/30m or an RFC3339 timestamp)",
+                    value
                 )
-            }
+            })?
+            .with_timezone(&Utc)
+    };
+
+    Ok(cutoff.to_rfc3339())
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", &s[..max.saturating_sub(3)])
+    }
+}
+
+/// A single Falco-style rule in a rule file: a matcher plus the
+/// explanation/suggestions to emit when it fires. `explanation`, `context`,
+/// and each suggestion's `command` are templates - see [`substitute`].
+#[derive(Debug, Clone, Deserialize)]
+struct PolicyRuleDef {
+    id: String,
+    /// `policy_rule` glob, at most one `*` wildcard (see `glob_match`).
+    policy_rule: String,
+    /// Optional prefix the event's `target` must also start with, e.g.
+    /// `169.254.169.254` to single out the metadata endpoint from the rest
+    /// of `network.egress.blocked`.
+    #[serde(default)]
+    target_prefix: Option<String>,
+    /// Overrides the event's own severity for this explanation, mirroring
+    /// Falco's per-rule `priority`.
+    #[serde(default)]
+    priority: Option<Severity>,
+    explanation: String,
+    context: String,
+    documentation_url: String,
+    #[serde(default)]
+    suggestions: Vec<RuleSuggestionDef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RuleSuggestionDef {
+    title: String,
+    description: String,
+    #[serde(default)]
+    command: Option<String>,
+    risk_level: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct RuleFile {
+    #[serde(default)]
+    rules: Vec<PolicyRuleDef>,
+}
+
+/// Default, built-in ruleset, shipped as data (`rules/default.yaml`) rather
+/// than hardcoded match arms, so the built-ins follow exactly the same
+/// format and matching semantics as anything an operator ships themselves.
+const DEFAULT_RULES_YAML: &str = include_str!("../../rules/default.yaml");
+
+/// Matches `value` against `pattern`, which may contain at most one `*`
+/// wildcard. Mirrors [`crate::cli::profile`]'s image-matching glob, kept
+/// local here since that one is private to its own module.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == value,
+        Some((prefix, suffix)) => {
+            value.len() >= prefix.len() + suffix.len()
+                && value.starts_with(prefix)
+                && value.ends_with(suffix)
         }
+    }
+}
 
-        "capability.denied" => (
-            format!(
-                "The container requested capability {} which is not permitted \
-                 in the '{}' security profile.",
-                event.target, event.profile
-            ),
-            "Linux capabilities divide root privileges into distinct units. \
-             The requested capability would grant significant system access. \
-             Vordr denies dangerous capabilities by default to limit blast radius."
-                .to_string(),
-            vec![
-                Suggestion {
-                    title: "Use a less restrictive profile".to_string(),
-                    description: "The 'balanced' or 'dev' profile allows more capabilities."
-                        .to_string(),
-                    command: Some("vordr run --profile balanced ...".to_string()),
-                    risk_level: "medium".to_string(),
-                },
-                Suggestion {
-                    title: "Add specific capability".to_string(),
-                    description: format!(
-                        "Explicitly grant only {} if truly needed.",
-                        event.target
-                    ),
-                    command: Some(format!("vordr run --cap-add {} ...", event.target)),
-                    risk_level: "high".to_string(),
-                },
-                Suggestion {
-                    title: "Review if capability is needed".to_string(),
-                    description: "Many applications request capabilities they don't actually need. \
-                                 Check if the application works without it."
-                        .to_string(),
-                    command: None,
-                    risk_level: "none".to_string(),
-                },
-            ],
-            "https://svalinn.dev/docs/security/capabilities".to_string(),
-        ),
+/// Directory holding on-disk custom rules, `<config_dir>/vordr/rules`.
+fn default_rules_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("vordr").join("rules"))
+}
 
-        "mount.sensitive.blocked" => (
-            format!(
-                "The container attempted to mount '{}' which is a sensitive host path.",
-                event.target
-            ),
-            "Mounting sensitive host paths like /etc/shadow, /etc/passwd, or system \
-             directories can allow container escape or host compromise. Vordr blocks \
-             these mounts by default."
-                .to_string(),
-            vec![
-                Suggestion {
-                    title: "Use a volume instead".to_string(),
-                    description: "Create a named volume for persistent data instead of \
-                                 mounting host paths."
-                        .to_string(),
-                    command: Some("vordr volume create mydata && vordr run -v mydata:/data ...".to_string()),
-                    risk_level: "low".to_string(),
-                },
-                Suggestion {
-                    title: "Mount a subdirectory".to_string(),
-                    description: "Mount only the specific directory needed, not system paths."
-                        .to_string(),
-                    command: Some("vordr run -v /home/user/app/data:/data ...".to_string()),
-                    risk_level: "low".to_string(),
-                },
-                Suggestion {
-                    title: "Use read-only mount".to_string(),
-                    description: "If you must mount sensitive paths, make them read-only."
-                        .to_string(),
-                    command: Some(format!("vordr run -v {}:/target:ro ...", event.target)),
-                    risk_level: "medium".to_string(),
-                },
-            ],
-            "https://svalinn.dev/docs/security/volumes".to_string(),
-        ),
+/// Loads every `*.yaml`/`*.yml` rule file under `dir` (sorted by filename
+/// for deterministic override order) and merges each rule into `rules` by
+/// `id` - a later file's rule with the same id replaces the earlier one in
+/// place, keeping its original match-order position.
+fn load_rules_dir(dir: &Path, rules: &mut Vec<PolicyRuleDef>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("yaml") | Some("yml")))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read rule file {}", path.display()))?;
+        let file: RuleFile = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse rule file {}", path.display()))?;
+        merge_rules(rules, file.rules);
+    }
+
+    Ok(())
+}
+
+fn merge_rules(rules: &mut Vec<PolicyRuleDef>, overrides: Vec<PolicyRuleDef>) {
+    for rule in overrides {
+        match rules.iter().position(|existing| existing.id == rule.id) {
+            Some(index) => rules[index] = rule,
+            None => rules.push(rule),
+        }
+    }
+}
+
+/// Builds the effective ruleset: the built-in default ruleset, overlaid
+/// with `<config_dir>/vordr/rules`, overlaid with each `--rules-dir` in the
+/// order given. Later directories win on matching rule `id`.
+fn load_rules(extra_dirs: &[PathBuf]) -> Result<Vec<PolicyRuleDef>> {
+    let default_file: RuleFile = serde_yaml::from_str(DEFAULT_RULES_YAML)
+        .context("Failed to parse built-in rules/default.yaml")?;
+    let mut rules = default_file.rules;
+
+    if let Some(dir) = default_rules_dir() {
+        load_rules_dir(&dir, &mut rules)?;
+    }
+    for dir in extra_dirs {
+        load_rules_dir(dir, &mut rules)?;
+    }
+
+    Ok(rules)
+}
 
-        _ => (
-            format!(
+/// Substitutes `{target}`, `{profile}`, `{action}`, and `{container_name}`
+/// in `template` with the corresponding fields of `event`.
+fn substitute(template: &str, event: &PolicyEvent) -> String {
+    template
+        .replace("{target}", &event.target)
+        .replace("{profile}", &event.profile)
+        .replace("{action}", &event.action)
+        .replace("{container_name}", &event.container_name)
+}
+
+fn generate_explanation(event: &PolicyEvent, rules: &[PolicyRuleDef]) -> Explanation {
+    let rule = rules
+        .iter()
+        .find(|rule| {
+            glob_match(&rule.policy_rule, &event.policy_rule)
+                && rule
+                    .target_prefix
+                    .as_ref()
+                    .is_none_or(|prefix| event.target.starts_with(prefix.as_str()))
+        });
+
+    let Some(rule) = rule else {
+        // No rule matched, not even the built-in catch-all - only possible
+        // if an operator's `--rules-dir` shadows `default-fallback` without
+        // also providing a `*` rule of their own.
+        return Explanation {
+            event: event.clone(),
+            explanation: format!(
                 "Policy '{}' blocked action '{}' on target '{}'.",
                 event.policy_rule, event.action, event.target
             ),
-            "This action was blocked by Vordr's security policies.".to_string(),
-            vec![
-                Suggestion {
-                    title: "Try a different profile".to_string(),
-                    description: "Use 'vordr profile ls' to see available profiles.".to_string(),
-                    command: Some("vordr profile ls".to_string()),
-                    risk_level: "varies".to_string(),
-                },
-                Suggestion {
-                    title: "Check documentation".to_string(),
-                    description: "Review the security documentation for this policy.".to_string(),
-                    command: None,
-                    risk_level: "none".to_string(),
-                },
-            ],
-            "https://svalinn.dev/docs/security".to_string(),
-        ),
+            context: "This action was blocked by Vordr's security policies.".to_string(),
+            suggestions: Vec::new(),
+            documentation_url: "https://svalinn.dev/docs/security".to_string(),
+        };
     };
 
+    let mut explained_event = event.clone();
+    if let Some(priority) = rule.priority {
+        explained_event.severity = priority;
+    }
+
+    let suggestions = rule
+        .suggestions
+        .iter()
+        .map(|suggestion| Suggestion {
+            title: suggestion.title.clone(),
+            description: suggestion.description.clone(),
+            command: suggestion.command.as_ref().map(|cmd| substitute(cmd, event)),
+            risk_level: suggestion.risk_level.clone(),
+        })
+        .collect();
+
     Explanation {
-        event: event.clone(),
-        explanation,
-        context,
+        event: explained_event,
+        explanation: substitute(&rule.explanation, event),
+        context: substitute(&rule.context, event),
         suggestions,
-        documentation_url: doc_url,
+        documentation_url: rule.documentation_url.clone(),
     }
 }
 
@@ -6,7 +6,7 @@ use clap::Args;
 use serde_json::json;
 use std::path::Path;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, OutputFormat};
 use crate::engine::StateManager;
 
 /// Arguments for the `inspect` command
@@ -15,7 +15,7 @@ pub struct InspectArgs {
     /// Container ID or name
     pub container: String,
 
-    /// Format output using a Go template (limited support)
+    /// Format output using a Go template
     #[arg(short, long)]
     pub format: Option<String>,
 
@@ -65,22 +65,40 @@ pub async fn execute(args: InspectArgs, cli: &Cli) -> Result<()> {
         },
         "Mounts": config.get("volumes").unwrap_or(&json!([])),
         "NetworkSettings": {
-            "Ports": config.get("ports").unwrap_or(&json!([])),
+            "Ports": crate::cli::ports::published_ports(&container),
+        },
+        "Health": {
+            "Status": container.health_status.as_str(),
+            "Log": container.health_log.iter().map(|probe| json!({
+                "Start": probe.at,
+                "ExitCode": if probe.success { 0 } else { 1 },
+                "Output": probe.output,
+            })).collect::<Vec<_>>(),
         },
         "Path": container.bundle_path,
     });
 
     if let Some(ref format) = args.format {
-        // Simple format string support
-        let formatted = format
-            .replace("{{.Id}}", &container.id)
-            .replace("{{.Name}}", &container.name)
-            .replace("{{.State.Status}}", container.state.as_str())
-            .replace("{{.Image}}", &container.image_id);
-        println!("{}", formatted);
-    } else {
-        // Pretty print JSON
+        let rendered = crate::cli::template::render(format, &output)
+            .context("failed to render --format template")?;
+        println!("{}", rendered);
+    } else if cli.format == OutputFormat::Json {
+        // Pretty print the full JSON document
         println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("ID:      {}", container.id);
+        println!("Name:    {}", container.name);
+        println!("Image:   {}", container.image_id);
+        println!("Status:  {}", container.state.as_str());
+        if container.health_status != crate::engine::HealthStatus::None {
+            println!("Health:  {}", container.health_status.as_str());
+        }
+        if let Some(pid) = container.pid {
+            println!("Pid:     {}", pid);
+        }
+        if let Some(code) = container.exit_code {
+            println!("Exit:    {}", code);
+        }
     }
 
     Ok(())
@@ -0,0 +1,147 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! `vordr manager` - long-lived listener that executes commands on behalf
+//! of remote clients connecting with `--host vordr://<addr>`
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use tokio::io::BufReader;
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{info, warn};
+
+use crate::cli::Cli;
+use crate::engine::StateManager;
+use crate::remote::{self, Handshake, RemoteRequest, RemoteResponse, PROTOCOL_VERSION, SUPPORTED_COMMANDS};
+
+/// Arguments for the `manager` command
+#[derive(Parser, Debug)]
+pub struct ManagerArgs {
+    /// Unix socket to listen on (defaults to `<root>/vordr.sock`)
+    #[arg(long)]
+    pub bind: Option<String>,
+}
+
+pub async fn execute(args: ManagerArgs, cli: &Cli) -> Result<()> {
+    let bind_path = args
+        .bind
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::Path::new(&cli.root).join("vordr.sock"));
+
+    if bind_path.exists() {
+        std::fs::remove_file(&bind_path).context("failed to remove stale manager socket")?;
+    }
+    if let Some(parent) = bind_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let listener = UnixListener::bind(&bind_path)
+        .with_context(|| format!("failed to bind manager socket at {}", bind_path.display()))?;
+    info!(
+        "vordr manager v{} listening on {} (commands: {:?})",
+        PROTOCOL_VERSION,
+        bind_path.display(),
+        SUPPORTED_COMMANDS
+    );
+
+    if let Ok(state) = StateManager::open(std::path::Path::new(&cli.db_path)) {
+        if let Ok(networks) = state.list_networks() {
+            crate::network::sql_dns::start_all(&cli.db_path, &networks);
+        }
+    }
+
+    let db_path = cli.db_path.clone();
+    loop {
+        let (stream, _addr) = listener.accept().await?;
+        let db_path = db_path.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &db_path).await {
+                warn!("manager connection error: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: UnixStream, db_path: &str) -> Result<()> {
+    let (read_half, mut write_half) = stream.into_split();
+    let mut reader = BufReader::new(read_half);
+
+    remote::write_json_line(
+        &mut write_half,
+        &Handshake {
+            version: PROTOCOL_VERSION.to_string(),
+            capabilities: SUPPORTED_COMMANDS.iter().map(|s| s.to_string()).collect(),
+        },
+    )
+    .await?;
+    let _client_handshake: Handshake = remote::read_json_line(&mut reader).await?;
+
+    loop {
+        let request: RemoteRequest = match remote::read_json_line(&mut reader).await {
+            Ok(request) => request,
+            Err(_) => return Ok(()), // client disconnected
+        };
+
+        let response = dispatch(&request, db_path);
+        remote::write_json_line(&mut write_half, &response).await?;
+    }
+}
+
+fn dispatch(request: &RemoteRequest, db_path: &str) -> RemoteResponse {
+    match request.command.as_str() {
+        "ps" => list_containers_response(db_path),
+        other => RemoteResponse {
+            ok: false,
+            payload: serde_json::Value::Null,
+            error: Some(format!("manager does not support command {:?} yet", other)),
+        },
+    }
+}
+
+fn list_containers_response(db_path: &str) -> RemoteResponse {
+    let path = std::path::Path::new(db_path);
+    if !path.exists() {
+        return RemoteResponse {
+            ok: true,
+            payload: serde_json::json!([]),
+            error: None,
+        };
+    }
+
+    let state = match StateManager::open(path) {
+        Ok(state) => state,
+        Err(e) => {
+            return RemoteResponse {
+                ok: false,
+                payload: serde_json::Value::Null,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    match state.list_containers(None) {
+        Ok(containers) => {
+            let entries: Vec<serde_json::Value> = containers
+                .iter()
+                .map(|container| {
+                    serde_json::json!({
+                        "Id": container.id,
+                        "Name": container.name,
+                        "Status": container.state.as_str(),
+                        "Image": container.image_id,
+                        "Pid": container.pid,
+                        "ExitCode": container.exit_code,
+                    })
+                })
+                .collect();
+            RemoteResponse {
+                ok: true,
+                payload: serde_json::Value::Array(entries),
+                error: None,
+            }
+        }
+        Err(e) => RemoteResponse {
+            ok: false,
+            payload: serde_json::Value::Null,
+            error: Some(e.to_string()),
+        },
+    }
+}
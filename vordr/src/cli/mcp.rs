@@ -0,0 +1,27 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! `vordr mcp` command implementation
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use crate::cli::Cli;
+
+/// Arguments for the `mcp` command
+#[derive(Args, Debug)]
+pub struct McpArgs {
+    #[command(subcommand)]
+    pub command: McpCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum McpCommands {
+    /// Run a Model Context Protocol server over stdio, exposing the
+    /// engine as tools for an AI assistant
+    Serve,
+}
+
+pub async fn execute(args: McpArgs, cli: &Cli) -> Result<()> {
+    match args.command {
+        McpCommands::Serve => crate::mcp::server::serve(cli).await,
+    }
+}
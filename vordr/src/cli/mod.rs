@@ -2,14 +2,28 @@
 //! Command-line interface for Vordr
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
+pub mod build;
+pub mod compose;
+pub mod completion;
+pub mod doctor;
 pub mod exec;
+pub mod explain;
 pub mod images;
 pub mod inspect;
+pub mod manager;
+pub mod mcp;
 pub mod network;
+pub mod ports;
+pub mod profile;
 pub mod ps;
+pub mod pull;
 pub mod run;
+pub mod serve;
+pub mod state;
+pub mod system;
+pub mod template;
 pub mod volume;
 
 /// Vordr - High-Assurance Daemonless Container Engine
@@ -35,6 +49,17 @@ pub struct Cli {
     )]
     pub db_path: String,
 
+    /// Storage backend for image metadata (sqlite, postgres). A postgres
+    /// backend reads `db_path` as a connection URL and requires the crate
+    /// to be built with the `postgres` feature.
+    #[arg(
+        long,
+        global = true,
+        default_value = "sqlite",
+        env = "VORDR_DB_BACKEND"
+    )]
+    pub db_backend: String,
+
     /// Container runtime path (youki or runc)
     #[arg(
         long,
@@ -44,6 +69,20 @@ pub struct Cli {
     )]
     pub runtime: String,
 
+    /// Output format. `json` also applies to command failures, which are
+    /// then printed to stdout as `{"error": "...", "code": ...}` instead of
+    /// an unstructured message, so scripts parsing JSON don't break on the
+    /// error path.
+    #[arg(long, global = true, default_value = "table")]
+    pub format: OutputFormat,
+
+    /// Target a `vordr manager` listening elsewhere instead of operating on
+    /// local state, e.g. `vordr:///var/lib/vordr/vordr.sock`. Only a subset
+    /// of commands can be relayed so far; see
+    /// [`crate::remote::SUPPORTED_COMMANDS`].
+    #[arg(long, global = true, env = "VORDR_HOST")]
+    pub host: Option<String>,
+
     /// Root directory for container state
     #[arg(
         long,
@@ -57,8 +96,22 @@ pub struct Cli {
     pub command: Commands,
 }
 
+/// Output format shared by every subcommand.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Fixed-width tables and human-readable summaries (default).
+    #[default]
+    Table,
+    /// Structured JSON - arrays of objects for list commands, a single
+    /// object for inspect commands, and (on failure) a JSON error object.
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
+    /// Build an image from a Dockerfile
+    Build(build::BuildArgs),
+
     /// Run a container from an image
     Run(run::RunArgs),
 
@@ -71,6 +124,12 @@ pub enum Commands {
     /// Display detailed information on a container
     Inspect(inspect::InspectArgs),
 
+    /// Display the OCI runtime-spec state document for a container
+    State(state::StateArgs),
+
+    /// Explain why a policy blocked an action
+    Explain(explain::ExplainArgs),
+
     /// Start a stopped container
     Start {
         /// Container ID or name
@@ -97,6 +156,22 @@ pub enum Commands {
         force: bool,
     },
 
+    /// Manage multi-container applications from a compose file
+    Compose(compose::ComposeArgs),
+
+    /// Check system prerequisites and configuration
+    Doctor(doctor::DoctorArgs),
+
+    /// Run a long-lived listener that executes commands on behalf of
+    /// remote clients using `--host`
+    Manager(manager::ManagerArgs),
+
+    /// Run a Docker-compatible HTTP REST gateway onto this engine
+    Serve(serve::ServeArgs),
+
+    /// Run a Model Context Protocol server for AI-assisted container management
+    Mcp(mcp::McpArgs),
+
     /// Manage images
     #[command(subcommand)]
     Image(images::ImageCommands),
@@ -105,10 +180,16 @@ pub enum Commands {
     #[command(subcommand)]
     Network(network::NetworkCommands),
 
+    /// Manage security profiles
+    Profile(profile::ProfileArgs),
+
     /// Manage volumes
     #[command(subcommand)]
     Volume(volume::VolumeCommands),
 
+    /// Disk usage, pruning, quotas, repair, and metrics
+    System(system::SystemArgs),
+
     /// Pull an image from a registry
     Pull {
         /// Image reference (e.g., alpine:latest)
@@ -120,24 +201,160 @@ pub enum Commands {
 
     /// Show Vordr version
     Version,
+
+    /// Generate shell completion scripts
+    Completion(completion::CompletionArgs),
+
+    /// Print live completion candidates for a given kind (container, image,
+    /// network); shelled out to by the dynamic completion scripts
+    /// `vordr completion` generates
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        kind: String,
+        #[arg(default_value = "")]
+        prefix: String,
+    },
+
+    /// Run the body of a container shim. `vordr` re-execs itself into this
+    /// right after [`crate::runtime::shim::ShimProcess::spawn`]'s
+    /// daemonizing double-fork, so the shim's FIFO/socket/runtime-invoking
+    /// work runs in a fresh, single-threaded process image instead of a
+    /// forked child of this process's multithreaded Tokio runtime.
+    #[command(name = "__shim-exec", hide = true)]
+    ShimExec {
+        /// Path to the JSON [`crate::runtime::shim::ShimSpec`] describing
+        /// the container to supervise
+        spec: std::path::PathBuf,
+    },
 }
 
 /// Execute a CLI command
 pub async fn execute(cli: Cli) -> Result<()> {
+    let format = cli.format;
+    if let Err(err) = dispatch(cli).await {
+        if format == OutputFormat::Json {
+            let body = serde_json::json!({ "error": err.to_string(), "code": 1 });
+            println!("{}", body);
+            std::process::exit(1);
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+async fn dispatch(cli: Cli) -> Result<()> {
+    if let Some(host) = cli.host.clone() {
+        return dispatch_remote(&host, cli).await;
+    }
+
     match cli.command {
+        Commands::Build(args) => build::execute(args, &cli).await,
         Commands::Run(args) => run::execute(args, &cli).await,
         Commands::Exec(args) => exec::execute(args, &cli).await,
         Commands::Ps(args) => ps::execute(args, &cli).await,
         Commands::Inspect(args) => inspect::execute(args, &cli).await,
+        Commands::State(args) => state::execute(args, &cli).await,
+        Commands::Explain(args) => explain::execute(args, &cli).await,
         Commands::Start { container } => start_container(&container, &cli).await,
         Commands::Stop { container, timeout } => stop_container(&container, timeout, &cli).await,
         Commands::Rm { container, force } => remove_container(&container, force, &cli).await,
+        Commands::Compose(args) => compose::execute(args, &cli).await,
+        Commands::Doctor(args) => doctor::execute(args, &cli).await,
+        Commands::Manager(args) => manager::execute(args, &cli).await,
+        Commands::Serve(args) => serve::execute(args, &cli).await,
+        Commands::Mcp(args) => mcp::execute(args, &cli).await,
         Commands::Image(cmd) => images::execute(cmd, &cli).await,
         Commands::Network(cmd) => network::execute(cmd, &cli).await,
+        Commands::Profile(args) => profile::execute(args, &cli).await,
         Commands::Volume(cmd) => volume::execute(cmd, &cli).await,
+        Commands::System(args) => system::execute(args, &cli).await,
         Commands::Pull { image } => pull_image(&image, &cli).await,
         Commands::Info => show_info(&cli).await,
         Commands::Version => show_version(),
+        Commands::Completion(args) => completion::execute(args),
+        Commands::Complete { kind, prefix } => completion::execute_complete(&kind, &prefix, &cli),
+        Commands::ShimExec { spec } => {
+            tokio::task::spawn_blocking(move || crate::runtime::shim::run_shim_exec(&spec)).await??;
+            Ok(())
+        }
+    }
+}
+
+/// Relays a command to the `vordr manager` at `host` instead of running it
+/// against local state. Only [`crate::remote::SUPPORTED_COMMANDS`] can be
+/// relayed today; everything else fails fast with a clear message rather
+/// than silently falling back to local execution.
+async fn dispatch_remote(host: &str, cli: Cli) -> Result<()> {
+    let addr = crate::remote::RemoteAddr::parse(host)?;
+
+    match &cli.command {
+        Commands::Ps(_) => {
+            let mut client = crate::remote::RemoteClient::connect(&addr).await?;
+            let payload = client.call("ps", serde_json::Value::Null).await?;
+            print_remote_containers(&payload, cli.format)
+        }
+        other => anyhow::bail!(
+            "remote execution via --host is only implemented for `ps` right now; \
+             `{}` must run against local state",
+            command_name(other)
+        ),
+    }
+}
+
+fn print_remote_containers(payload: &serde_json::Value, format: OutputFormat) -> Result<()> {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(payload)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:<20} {:<20} {:<20}",
+        "CONTAINER ID", "NAME", "STATUS", "IMAGE"
+    );
+    let entries = payload.as_array().cloned().unwrap_or_default();
+    for entry in &entries {
+        let field = |key: &str| entry.get(key).and_then(|v| v.as_str()).unwrap_or("");
+        let truncated_id: String = field("Id").chars().take(12).collect();
+        let truncated_image: String = field("Image").chars().take(12).collect();
+        println!(
+            "{:<20} {:<20} {:<20} {:<20}",
+            truncated_id,
+            field("Name"),
+            field("Status"),
+            truncated_image
+        );
+    }
+    Ok(())
+}
+
+fn command_name(cmd: &Commands) -> &'static str {
+    match cmd {
+        Commands::Build(_) => "build",
+        Commands::Run(_) => "run",
+        Commands::Exec(_) => "exec",
+        Commands::Ps(_) => "ps",
+        Commands::Inspect(_) => "inspect",
+        Commands::State(_) => "state",
+        Commands::Explain(_) => "explain",
+        Commands::Start { .. } => "start",
+        Commands::Stop { .. } => "stop",
+        Commands::Rm { .. } => "rm",
+        Commands::Compose(_) => "compose",
+        Commands::Doctor(_) => "doctor",
+        Commands::Manager(_) => "manager",
+        Commands::Serve(_) => "serve",
+        Commands::Mcp(_) => "mcp",
+        Commands::Image(_) => "image",
+        Commands::Network(_) => "network",
+        Commands::Profile(_) => "profile",
+        Commands::Volume(_) => "volume",
+        Commands::System(_) => "system",
+        Commands::Pull { .. } => "pull",
+        Commands::Info => "info",
+        Commands::Version => "version",
+        Commands::Completion(_) => "completion",
+        Commands::Complete { .. } => "__complete",
+        Commands::ShimExec { .. } => "__shim-exec",
     }
 }
 
@@ -162,6 +379,7 @@ async fn stop_container(container: &str, timeout: u32, cli: &Cli) -> Result<()>
 
     println!("Stopping container: {} (timeout: {}s)", info.name, timeout);
     // TODO: Implement actual stop via runtime
+    crate::network::portforward::teardown(&ports::published_ports(&info));
     Ok(())
 }
 
@@ -178,13 +396,18 @@ async fn remove_container(container: &str, force: bool, cli: &Cli) -> Result<()>
         println!("Removing container: {}", info.name);
     }
 
+    // `container_volumes` rows for this container are cascade-deleted as
+    // part of `delete_container`; recompute so every volume it had mounted
+    // drops its refcount accordingly.
     state.delete_container(&info.id)?;
+    state.recompute_volume_refcounts()?;
     Ok(())
 }
 
-async fn pull_image(image: &str, _cli: &Cli) -> Result<()> {
+async fn pull_image(image: &str, cli: &Cli) -> Result<()> {
     println!("Pulling image: {}", image);
-    // TODO: Implement registry client
+    let pulled = pull::pull_image(cli, image).await?;
+    println!("{}", pulled.id);
     Ok(())
 }
 
@@ -3,10 +3,13 @@
 
 use anyhow::{Context, Result};
 use clap::Subcommand;
+use std::collections::HashMap;
 use std::path::Path;
 
 use crate::cli::Cli;
-use crate::engine::StateManager;
+use crate::engine::{
+    build_network_driver, ipam, NetworkAttachContext, NetworkCreateRequest, NetworkInfo, StateManager,
+};
 
 #[derive(Subcommand, Debug)]
 pub enum NetworkCommands {
@@ -26,6 +29,16 @@ pub enum NetworkCommands {
         /// Gateway IP address
         #[arg(long)]
         gateway: Option<String>,
+
+        /// Restrict the network to internal (east-west only) traffic, with
+        /// no route to the host or the internet
+        #[arg(long)]
+        internal: bool,
+
+        /// Set driver options (key=value), e.g. `--opt parent=eth0` for
+        /// `macvlan`/`ipvlan`
+        #[arg(short, long, action = clap::ArgAction::Append)]
+        opt: Vec<String>,
     },
 
     /// List networks
@@ -88,7 +101,20 @@ pub async fn execute(cmd: NetworkCommands, cli: &Cli) -> Result<()> {
             driver,
             subnet,
             gateway,
-        } => create_network(&name, &driver, subnet.as_deref(), gateway.as_deref(), cli).await,
+            internal,
+            opt,
+        } => {
+            create_network(
+                &name,
+                &driver,
+                subnet.as_deref(),
+                gateway.as_deref(),
+                internal,
+                &opt,
+                cli,
+            )
+            .await
+        }
         NetworkCommands::Ls { quiet } => list_networks(quiet, cli).await,
         NetworkCommands::Rm { network } => remove_network(&network, cli).await,
         NetworkCommands::Inspect { network } => inspect_network(&network, cli).await,
@@ -110,6 +136,8 @@ async fn create_network(
     driver: &str,
     subnet: Option<&str>,
     gateway: Option<&str>,
+    internal: bool,
+    opt: &[String],
     cli: &Cli,
 ) -> Result<()> {
     let db_path = Path::new(&cli.db_path);
@@ -120,8 +148,51 @@ async fn create_network(
     let state = StateManager::open(db_path).context("Failed to open state database")?;
 
     let network_id = uuid::Uuid::new_v4().to_string();
+    let options_map = parse_kv_pairs(opt);
 
-    state.create_network(&network_id, name, driver, subnet, gateway, None)?;
+    let backend = build_network_driver(driver).with_context(|| format!("unsupported network driver {:?}", driver))?;
+    backend
+        .validate_create(&NetworkCreateRequest {
+            subnet,
+            gateway,
+            internal,
+            options: &options_map,
+        })
+        .with_context(|| format!("invalid options for network driver {:?}", driver))?;
+
+    // `host`/`none` borrow or forgo connectivity entirely, so they never
+    // get a subnet of their own. Everyone else gets the explicit
+    // `--subnet`, or the first free /24 out of the IPAM pool so
+    // `vordr network create`/`run --network` stay collision-free without
+    // requiring the caller to track addressing by hand.
+    let (subnet, gateway) = if !backend.has_own_address_space() {
+        (None, None)
+    } else {
+        match subnet {
+            Some(subnet) => (Some(subnet.to_string()), gateway.map(str::to_string)),
+            None => {
+                let existing = state.list_networks()?;
+                let (subnet, gateway) = ipam::allocate_subnet(&existing).context("failed to allocate a subnet")?;
+                (Some(subnet), Some(gateway))
+            }
+        }
+    };
+
+    let options_json = if options_map.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&options_map).unwrap())
+    };
+
+    state.create_network(
+        &network_id,
+        name,
+        driver,
+        subnet.as_deref(),
+        gateway.as_deref(),
+        options_json.as_deref(),
+        internal,
+    )?;
 
     println!("{}", network_id);
     Ok(())
@@ -182,6 +253,35 @@ async fn inspect_network(network_id: &str, cli: &Cli) -> Result<()> {
         StateManager::open(Path::new(&cli.db_path)).context("Failed to open state database")?;
 
     let network = state.get_network(network_id)?;
+    let dns_records: Vec<serde_json::Value> = state
+        .network_dns_records(&network.id)?
+        .into_iter()
+        .map(|record| {
+            serde_json::json!({
+                "Name": record.container_name,
+                "Aliases": record.aliases,
+                "IPAddress": record.ip_address,
+            })
+        })
+        .collect();
+
+    // Mirrors `docker network inspect`'s `Containers` map (keyed by
+    // container ID), unlike `DnsRecords` above which only covers endpoints
+    // with a resolvable address.
+    let endpoints: serde_json::Map<String, serde_json::Value> = state
+        .network_endpoints(&network.id)?
+        .into_iter()
+        .map(|endpoint| {
+            (
+                endpoint.container_id,
+                serde_json::json!({
+                    "IPv4Address": endpoint.ip_address,
+                    "MacAddress": endpoint.mac_address,
+                    "Aliases": endpoint.aliases,
+                }),
+            )
+        })
+        .collect();
 
     let output = serde_json::json!({
         "Id": network.id,
@@ -191,6 +291,10 @@ async fn inspect_network(network_id: &str, cli: &Cli) -> Result<()> {
         "Gateway": network.gateway,
         "Created": network.created_at,
         "Scope": "local",
+        "Internal": network.internal,
+        "Options": parse_stored_options(network.options.as_deref()),
+        "DnsRecords": dns_records,
+        "Containers": endpoints,
     });
 
     println!("{}", serde_json::to_string_pretty(&output)?);
@@ -211,13 +315,51 @@ async fn connect_network(
     let container = state.get_container(container_id)?;
 
     let aliases = alias.map(|a| vec![a.to_string()]).unwrap_or_default();
+    let assigned_ip = attach_container(&state, &network, &container.id, &aliases, ip)?;
 
-    state.connect_container_network(&container.id, &network.id, ip, None, &aliases)?;
-
-    println!("Connected {} to {}", container.name, network.name);
+    match assigned_ip {
+        Some(ip) => println!("Connected {} to {} ({})", container.name, network.name, ip),
+        None => println!("Connected {} to {}", container.name, network.name),
+    }
     Ok(())
 }
 
+/// Allocates an address (if the driver has one) and attaches `container_id`
+/// to `network`, then runs the driver's netns configuration hook. Shared by
+/// `network connect` and `run --network`.
+pub(crate) fn attach_container(
+    state: &StateManager,
+    network: &NetworkInfo,
+    container_id: &str,
+    aliases: &[String],
+    ip: Option<&str>,
+) -> Result<Option<String>> {
+    let driver = build_network_driver(&network.driver).context("network has an unknown driver")?;
+
+    let assigned_ip = if driver.has_own_address_space() {
+        Some(ipam::allocate_address(state, network, ip).context("failed to allocate an address")?)
+    } else if ip.is_some() {
+        anyhow::bail!(
+            "network driver {:?} has no address space of its own - --ip is not supported",
+            network.driver
+        );
+    } else {
+        None
+    };
+
+    state.connect_container_network(container_id, &network.id, assigned_ip.as_deref(), None, aliases)?;
+
+    let options_map = parse_stored_options(network.options.as_deref());
+    driver.configure(&NetworkAttachContext {
+        container_id,
+        network_name: &network.name,
+        ip_address: assigned_ip.as_deref(),
+        options: &options_map,
+    });
+
+    Ok(assigned_ip)
+}
+
 async fn disconnect_network(network_id: &str, container_id: &str, cli: &Cli) -> Result<()> {
     let state =
         StateManager::open(Path::new(&cli.db_path)).context("Failed to open state database")?;
@@ -236,6 +378,24 @@ async fn prune_networks(_cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+fn parse_kv_pairs(pairs: &[String]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .filter_map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next()?;
+            let value = parts.next()?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn parse_stored_options(options: Option<&str>) -> HashMap<String, String> {
+    options
+        .and_then(|o| serde_json::from_str(o).ok())
+        .unwrap_or_default()
+}
+
 fn truncate(s: &str, max: usize) -> String {
     if s.len() <= max {
         s.to_string()
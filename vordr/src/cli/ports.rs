@@ -0,0 +1,208 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Parses, validates, and records `-p`/`--publish` port mappings
+//!
+//! `-p` specs were previously stored verbatim in a container's
+//! `config_json` and never otherwise acted on. [`parse_specs`] turns them
+//! into normalized, single-port [`PortMapping`]s; [`validate_privileged_ports`]
+//! and [`check_collisions`] are the two checks `run::execute` runs before a
+//! container is allowed to claim them.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::engine::{ContainerInfo, ContainerState, StateManager};
+
+#[derive(Error, Debug)]
+pub enum PortError {
+    #[error(
+        "invalid port spec '{0}': expected container, host:container, or ip:host:container \
+         (each side may be a range), optionally suffixed with /tcp or /udp"
+    )]
+    InvalidSpec(String),
+    #[error("invalid port range in '{0}': host and container ranges must be the same length")]
+    MismatchedRange(String),
+    #[error("host port {host_port}/{protocol} is privileged (<1024) and requires --privileged")]
+    PrivilegedPort { host_port: u16, protocol: Protocol },
+    #[error("host port {host_ip}:{host_port}/{protocol} is already published by container {container}")]
+    PortInUse {
+        host_ip: String,
+        host_port: u16,
+        protocol: Protocol,
+        container: String,
+    },
+    #[error("failed to check for port collisions: {0}")]
+    State(#[from] crate::engine::StateError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        })
+    }
+}
+
+/// One normalized `-p` mapping - always a single host port to a single
+/// container port, even when it was parsed out of a range spec.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PortMapping {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_ip: Option<String>,
+    pub host_port: u16,
+    pub container_port: u16,
+    pub protocol: Protocol,
+}
+
+impl PortMapping {
+    pub fn host_ip(&self) -> &str {
+        self.host_ip.as_deref().unwrap_or("0.0.0.0")
+    }
+}
+
+/// Parses every `-p`/`--publish` spec into normalized, single-port
+/// mappings.
+pub fn parse_specs(raw: &[String]) -> Result<Vec<PortMapping>, PortError> {
+    let mut mappings = Vec::new();
+    for spec in raw {
+        mappings.extend(parse_spec(spec)?);
+    }
+    Ok(mappings)
+}
+
+/// Parses one spec: `container`, `host:container`, or `ip:host:container`,
+/// each side optionally a `start-end` range (both sides must then be
+/// equal-length ranges, mapped pairwise in order), optionally suffixed
+/// with `/tcp` or `/udp` (default `tcp`).
+fn parse_spec(spec: &str) -> Result<Vec<PortMapping>, PortError> {
+    let (body, protocol) = match spec.rsplit_once('/') {
+        Some((body, "tcp")) => (body, Protocol::Tcp),
+        Some((body, "udp")) => (body, Protocol::Udp),
+        Some(_) => return Err(PortError::InvalidSpec(spec.to_string())),
+        None => (spec, Protocol::Tcp),
+    };
+
+    let parts: Vec<&str> = body.split(':').collect();
+    let (host_ip, host_range, container_range) = match parts.as_slice() {
+        [container] => (None, None, *container),
+        [host, container] => (None, Some(*host), *container),
+        [ip, host, container] => (Some(*ip), Some(*host), *container),
+        _ => return Err(PortError::InvalidSpec(spec.to_string())),
+    };
+
+    let to_invalid = |_| PortError::InvalidSpec(spec.to_string());
+    let container_ports = parse_port_range(container_range).map_err(to_invalid)?;
+    let host_ports = match host_range {
+        Some(range) => parse_port_range(range).map_err(to_invalid)?,
+        None => container_ports.clone(),
+    };
+
+    if host_ports.len() != container_ports.len() {
+        return Err(PortError::MismatchedRange(spec.to_string()));
+    }
+
+    Ok(host_ports
+        .into_iter()
+        .zip(container_ports)
+        .map(|(host_port, container_port)| PortMapping {
+            host_ip: host_ip.map(str::to_string),
+            host_port,
+            container_port,
+            protocol,
+        })
+        .collect())
+}
+
+fn parse_port_range(range: &str) -> Result<Vec<u16>, ()> {
+    match range.split_once('-') {
+        Some((start, end)) => {
+            let start: u16 = start.parse().map_err(|_| ())?;
+            let end: u16 = end.parse().map_err(|_| ())?;
+            if start == 0 || end < start {
+                return Err(());
+            }
+            Ok((start..=end).collect())
+        }
+        None => {
+            let port: u16 = range.parse().map_err(|_| ())?;
+            if port == 0 {
+                return Err(());
+            }
+            Ok(vec![port])
+        }
+    }
+}
+
+/// Rejects any mapping that binds a privileged host port (<1024) unless
+/// the container is `--privileged` - the same rule the kernel itself
+/// enforces on an unprivileged process calling `bind()`.
+pub fn validate_privileged_ports(mappings: &[PortMapping], privileged: bool) -> Result<(), PortError> {
+    if privileged {
+        return Ok(());
+    }
+    for mapping in mappings {
+        if mapping.host_port < 1024 {
+            return Err(PortError::PrivilegedPort {
+                host_port: mapping.host_port,
+                protocol: mapping.protocol,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Rejects any mapping whose host ip:port:protocol is already published
+/// by another non-stopped container.
+pub fn check_collisions(state: &StateManager, mappings: &[PortMapping]) -> Result<(), PortError> {
+    for container in state.list_containers(None)? {
+        if container.state == ContainerState::Stopped {
+            continue;
+        }
+        for existing in published_ports(&container) {
+            for mapping in mappings {
+                if ports_collide(&existing, mapping) {
+                    return Err(PortError::PortInUse {
+                        host_ip: existing.host_ip().to_string(),
+                        host_port: existing.host_port,
+                        protocol: existing.protocol,
+                        container: container.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn ports_collide(a: &PortMapping, b: &PortMapping) -> bool {
+    if a.protocol != b.protocol || a.host_port != b.host_port {
+        return false;
+    }
+    // A mapping bound to 0.0.0.0 (the default) collides with any other
+    // host ip on the same port; two mappings each bound to a distinct,
+    // non-wildcard interface can coexist.
+    a.host_ip() == "0.0.0.0" || b.host_ip() == "0.0.0.0" || a.host_ip() == b.host_ip()
+}
+
+/// Reads back the normalized `port_mappings` a container's `config_json`
+/// was stored with, if any.
+pub fn published_ports(container: &ContainerInfo) -> Vec<PortMapping> {
+    let Some(config) = &container.config else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(config) else {
+        return Vec::new();
+    };
+    value
+        .get("port_mappings")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
@@ -31,6 +31,11 @@ pub enum ProfileCommands {
     Show {
         /// Profile name
         name: String,
+
+        /// Preview the effective profile after applying the most specific
+        /// `[overrides."<image-or-glob>"]` table matching this image
+        #[arg(long = "for")]
+        for_image: Option<String>,
     },
 
     /// Compare two profiles
@@ -59,6 +64,28 @@ pub enum ProfileCommands {
         #[arg(long)]
         from: Option<String>,
     },
+
+    /// Translate a resolved profile into a runtime-consumable security
+    /// config: OCI runtime-spec fragment, or a docker/podman flag string
+    Export {
+        /// Profile name
+        name: String,
+
+        /// Target format: oci, docker, or podman
+        #[arg(long, default_value = "docker")]
+        format: String,
+    },
+
+    /// Validate a resolved profile's capabilities and flag contradictory
+    /// security options. Exits non-zero on errors, so it can gate CI.
+    Lint {
+        /// Profile name
+        name: String,
+
+        /// Output format (table, json)
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
 }
 
 /// Security profile definition
@@ -67,11 +94,80 @@ pub struct SecurityProfile {
     pub name: String,
     pub description: String,
     pub security_level: SecurityLevel,
+    #[serde(default)]
     pub capabilities: CapabilityConfig,
+    #[serde(default)]
     pub security: SecurityConfig,
+    #[serde(default)]
     pub seccomp: SeccompConfig,
+    #[serde(default)]
     pub network: NetworkConfig,
+    #[serde(default)]
     pub resources: ResourceConfig,
+    /// Name of a parent profile to inherit from, Cargo-profile-style.
+    /// Scalar fields take the most-derived (child) value; `capabilities`
+    /// merges additively - see [`resolve_profile`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<String>,
+    /// Per-image/workload tightening or relaxing of specific fields,
+    /// keyed by an exact image reference or a single-`*`-wildcard glob
+    /// (e.g. `"nginx:*"`). See [`resolve_profile_for_image`] for the
+    /// matching precedence (exact name > glob, most-specific glob wins).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub overrides: HashMap<String, ProfileOverride>,
+}
+
+/// A sparse set of field overrides applied on top of a resolved
+/// [`SecurityProfile`] for images matching the override's key. Every field
+/// is optional - an omitted field leaves the base profile's value alone,
+/// except `capabilities`, which merges additively just like
+/// [`merge_capabilities`] does for inheritance.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProfileOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_level: Option<SecurityLevel>,
+    #[serde(default)]
+    pub capabilities: CapabilityConfig,
+    #[serde(default)]
+    pub security: SecurityOverride,
+    #[serde(default)]
+    pub seccomp: SeccompOverride,
+    #[serde(default)]
+    pub network: NetworkOverride,
+    #[serde(default)]
+    pub resources: ResourceOverride,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SecurityOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privileged: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_new_privileges: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_rootfs: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_namespace: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SeccompOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NetworkOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ResourceOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pids_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -126,11 +222,13 @@ struct ProfileRow {
 pub async fn execute(args: ProfileArgs, cli: &Cli) -> Result<()> {
     match args.command {
         ProfileCommands::Ls { format } => list_profiles(&format).await,
-        ProfileCommands::Show { name } => show_profile(&name).await,
+        ProfileCommands::Show { name, for_image } => show_profile(&name, for_image.as_deref()).await,
         ProfileCommands::Diff { profile1, profile2 } => diff_profiles(&profile1, &profile2).await,
         ProfileCommands::SetDefault { name } => set_default(&name, cli).await,
         ProfileCommands::GetDefault => get_default(cli).await,
         ProfileCommands::Create { name, from } => create_profile(&name, from.as_deref()).await,
+        ProfileCommands::Export { name, format } => export_profile(&name, &format).await,
+        ProfileCommands::Lint { name, format } => lint_profile_command(&name, &format).await,
     }
 }
 
@@ -165,6 +263,8 @@ pub fn builtin_profiles() -> HashMap<String, SecurityProfile> {
                 pids_limit: 100,
                 memory_limit: "512M".to_string(),
             },
+            inherits: None,
+            overrides: HashMap::new(),
         },
     );
 
@@ -201,6 +301,8 @@ pub fn builtin_profiles() -> HashMap<String, SecurityProfile> {
                 pids_limit: 500,
                 memory_limit: "2G".to_string(),
             },
+            inherits: None,
+            overrides: HashMap::new(),
         },
     );
 
@@ -238,18 +340,262 @@ pub fn builtin_profiles() -> HashMap<String, SecurityProfile> {
                 pids_limit: 0, // Unlimited
                 memory_limit: String::new(), // Unlimited
             },
+            inherits: None,
+            overrides: HashMap::new(),
         },
     );
 
     profiles
 }
 
+/// Directory holding on-disk custom profiles, `<config_dir>/vordr/profiles`.
+fn profiles_dir() -> Result<PathBuf> {
+    Ok(get_config_path()?.parent().unwrap().join("profiles"))
+}
+
+/// Load every custom profile serialized under [`profiles_dir`], keyed by
+/// its filename stem. Missing directory is not an error - it just means no
+/// custom profiles exist yet.
+fn load_custom_profiles() -> Result<HashMap<String, SecurityProfile>> {
+    let dir = profiles_dir()?;
+    let mut profiles = HashMap::new();
+
+    if !dir.exists() {
+        return Ok(profiles);
+    }
+
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Invalid profile filename: {}", path.display()))?
+            .to_string();
+
+        let content = std::fs::read_to_string(&path)?;
+        let profile: SecurityProfile = toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse profile '{}': {}", name, e))?;
+
+        profiles.insert(name, profile);
+    }
+
+    Ok(profiles)
+}
+
+/// The full set of profiles available to `ls`/`show`/`diff`/`set-default`:
+/// the three built-ins, overlaid with every on-disk custom profile. A
+/// custom profile shadows a built-in of the same name; [`create_profile`]
+/// is what actually prevents that name collision from being created in the
+/// first place.
+fn all_profiles() -> Result<HashMap<String, SecurityProfile>> {
+    let mut profiles = builtin_profiles();
+    profiles.extend(load_custom_profiles()?);
+    Ok(profiles)
+}
+
+/// Look up `name` and fully resolve its `inherits` chain (if any) into a
+/// single flattened profile, Cargo-profile-style. The returned profile's
+/// own `inherits` is always `None` - it has nothing left to resolve.
 fn get_profile(name: &str) -> Result<SecurityProfile> {
-    let profiles = builtin_profiles();
-    profiles
+    let profiles = all_profiles()?;
+    let mut chain = Vec::new();
+    resolve_profile(name, &profiles, &mut chain)
+}
+
+/// Walks the `inherits` chain from `name` down to its root, overlaying each
+/// descendant's fields onto its ancestor's. `chain` tracks the names
+/// visited so far on this path and is used to detect cycles.
+fn resolve_profile(
+    name: &str,
+    profiles: &HashMap<String, SecurityProfile>,
+    chain: &mut Vec<String>,
+) -> Result<SecurityProfile> {
+    if chain.iter().any(|visited| visited == name) {
+        chain.push(name.to_string());
+        bail!("profile inheritance cycle detected: {}", chain.join(" -> "));
+    }
+    chain.push(name.to_string());
+
+    let profile = profiles
         .get(name)
         .cloned()
-        .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))
+        .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+
+    let resolved = match profile.inherits.clone() {
+        Some(ref parent) => {
+            let base = resolve_profile(parent, profiles, chain)?;
+            overlay_profile(base, profile)
+        }
+        None => profile,
+    };
+
+    chain.pop();
+    Ok(resolved)
+}
+
+/// Applies `child` on top of `base`. Scalar fields take the most-derived
+/// (child) value; capabilities merge additively - see
+/// [`merge_capabilities`]. The result is fully resolved, so `inherits` is
+/// cleared rather than carried forward.
+fn overlay_profile(base: SecurityProfile, child: SecurityProfile) -> SecurityProfile {
+    let mut overrides = base.overrides;
+    overrides.extend(child.overrides);
+
+    SecurityProfile {
+        name: child.name,
+        description: child.description,
+        security_level: child.security_level,
+        capabilities: merge_capabilities(base.capabilities, child.capabilities),
+        security: child.security,
+        seccomp: child.seccomp,
+        network: child.network,
+        resources: child.resources,
+        inherits: None,
+        overrides,
+    }
+}
+
+/// Additively merges a child's capability directives onto its base's: the
+/// union of both sides' `add`/`drop`, except the child's own directives
+/// win on conflict - a `drop` in `child` cancels an inherited `add` for the
+/// same capability, and an `add` in `child` cancels an inherited `drop`.
+/// `BTreeSet` keeps the result order (and therefore the merge) deterministic
+/// regardless of how the ancestors listed their capabilities.
+fn merge_capabilities(base: CapabilityConfig, child: CapabilityConfig) -> CapabilityConfig {
+    use std::collections::BTreeSet;
+
+    let mut add: BTreeSet<String> = base.add.into_iter().collect();
+    let mut drop: BTreeSet<String> = base.drop.into_iter().collect();
+
+    for cap in child.drop {
+        add.remove(&cap);
+        drop.insert(cap);
+    }
+    for cap in child.add {
+        drop.remove(&cap);
+        add.insert(cap);
+    }
+
+    CapabilityConfig {
+        add: add.into_iter().collect(),
+        drop: drop.into_iter().collect(),
+    }
+}
+
+/// Resolves `name`'s inheritance chain, then applies the most specific
+/// `[overrides."<pattern>"]` table matching `image` on top of it.
+///
+/// Precedence: an exact name match always outranks a glob; among globs,
+/// the one with more literal (non-`*`) characters wins. Two matches at
+/// equal specificity is a profile authoring mistake, not a fatal error -
+/// one is kept deterministically (first by pattern name) and a warning is
+/// logged so the conflict gets noticed.
+pub fn resolve_profile_for_image(name: &str, image: &str) -> Result<SecurityProfile> {
+    let base = get_profile(name)?;
+
+    let mut best: Option<(&String, usize)> = None;
+    for pattern in base.overrides.keys() {
+        if !glob_match(pattern, image) {
+            continue;
+        }
+        let specificity = pattern_specificity(pattern);
+        best = Some(match best {
+            None => (pattern, specificity),
+            Some((_, best_specificity)) if specificity > best_specificity => {
+                (pattern, specificity)
+            }
+            Some((best_pattern, best_specificity)) if specificity == best_specificity => {
+                let (kept, other) = if best_pattern <= pattern {
+                    (best_pattern, pattern)
+                } else {
+                    (pattern, best_pattern)
+                };
+                tracing::warn!(
+                    "profile '{}': overrides '{}' and '{}' both match image '{}' with equal \
+                     specificity; using '{}'",
+                    name, kept, other, image, kept
+                );
+                (kept, best_specificity)
+            }
+            Some(current) => current,
+        });
+    }
+
+    Ok(match best {
+        Some((pattern, _)) => {
+            let ov = base.overrides[pattern].clone();
+            apply_override(base, &ov)
+        }
+        None => base,
+    })
+}
+
+/// Matches `image` against `pattern`, which may contain at most one `*`
+/// wildcard (e.g. `"nginx:*"`, `"*:latest"`). A pattern without `*` must
+/// match `image` exactly.
+fn glob_match(pattern: &str, image: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == image,
+        Some((prefix, suffix)) => {
+            image.len() >= prefix.len() + suffix.len()
+                && image.starts_with(prefix)
+                && image.ends_with(suffix)
+        }
+    }
+}
+
+/// An exact (wildcard-free) pattern is always more specific than any glob;
+/// among globs, more literal characters means a narrower, more specific
+/// match.
+fn pattern_specificity(pattern: &str) -> usize {
+    if pattern.contains('*') {
+        pattern.chars().filter(|&c| c != '*').count()
+    } else {
+        usize::MAX
+    }
+}
+
+/// Applies a single [`ProfileOverride`] on top of a resolved profile. Only
+/// fields the override actually sets are changed; `capabilities` merges
+/// additively like [`merge_capabilities`].
+fn apply_override(mut profile: SecurityProfile, ov: &ProfileOverride) -> SecurityProfile {
+    if let Some(level) = ov.security_level {
+        profile.security_level = level;
+    }
+    if !ov.capabilities.drop.is_empty() || !ov.capabilities.add.is_empty() {
+        profile.capabilities = merge_capabilities(profile.capabilities, ov.capabilities.clone());
+    }
+    if let Some(v) = ov.security.privileged {
+        profile.security.privileged = v;
+    }
+    if let Some(v) = ov.security.no_new_privileges {
+        profile.security.no_new_privileges = v;
+    }
+    if let Some(v) = ov.security.read_only_rootfs {
+        profile.security.read_only_rootfs = v;
+    }
+    if let Some(v) = ov.security.user_namespace {
+        profile.security.user_namespace = v;
+    }
+    if let Some(ref v) = ov.seccomp.profile {
+        profile.seccomp.profile = v.clone();
+    }
+    if let Some(ref v) = ov.network.mode {
+        profile.network.mode = v.clone();
+    }
+    if let Some(v) = ov.resources.pids_limit {
+        profile.resources.pids_limit = v;
+    }
+    if let Some(ref v) = ov.resources.memory_limit {
+        profile.resources.memory_limit = v.clone();
+    }
+    profile.overrides = HashMap::new();
+    profile
 }
 
 fn security_bar(level: SecurityLevel) -> String {
@@ -272,7 +618,7 @@ fn security_bar(level: SecurityLevel) -> String {
 }
 
 async fn list_profiles(format: &str) -> Result<()> {
-    let profiles = builtin_profiles();
+    let profiles = all_profiles()?;
 
     match format {
         "json" => {
@@ -304,10 +650,16 @@ async fn list_profiles(format: &str) -> Result<()> {
     Ok(())
 }
 
-async fn show_profile(name: &str) -> Result<()> {
-    let profile = get_profile(name)?;
+async fn show_profile(name: &str, for_image: Option<&str>) -> Result<()> {
+    let profile = match for_image {
+        Some(image) => resolve_profile_for_image(name, image)?,
+        None => get_profile(name)?,
+    };
 
     println!("{}: {}", style("Profile").bold(), profile.name);
+    if let Some(image) = for_image {
+        println!("{}: {}", style("Effective for").bold(), image);
+    }
     println!();
     println!("{}: {}", style("Description").bold(), profile.description);
     println!(
@@ -554,8 +906,11 @@ async fn set_default(name: &str, _cli: &Cli) -> Result<()> {
     // Verify profile exists
     let _ = get_profile(name)?;
 
-    // Would save to config file
     let config_path = get_config_path()?;
+    let mut config = read_config(&config_path)?;
+    config.default_profile = Some(name.to_string());
+    write_config(&config_path, &config)?;
+
     println!("Default profile set to: {}", style(name).green());
     println!("Saved to: {}", config_path.display());
 
@@ -563,9 +918,8 @@ async fn set_default(name: &str, _cli: &Cli) -> Result<()> {
 }
 
 async fn get_default(_cli: &Cli) -> Result<()> {
-    // Would read from config file
-    // For now, return balanced as default
-    println!("balanced");
+    let config = read_config(&get_config_path()?)?;
+    println!("{}", config.default_profile.unwrap_or_else(|| "balanced".to_string()));
     Ok(())
 }
 
@@ -585,7 +939,7 @@ async fn create_profile(name: &str, from: Option<&str>) -> Result<()> {
         get_profile("balanced")?
     };
 
-    let config_dir = get_config_path()?.parent().unwrap().join("profiles");
+    let config_dir = profiles_dir()?;
     std::fs::create_dir_all(&config_dir)?;
 
     let profile_path = config_dir.join(format!("{}.toml", name));
@@ -594,7 +948,10 @@ async fn create_profile(name: &str, from: Option<&str>) -> Result<()> {
     new_profile.name = name.to_string();
     new_profile.description = format!("Custom profile based on {}", from.unwrap_or("balanced"));
 
-    // Would serialize to TOML and save
+    let toml_content = toml::to_string_pretty(&new_profile)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize profile '{}': {}", name, e))?;
+    std::fs::write(&profile_path, toml_content)?;
+
     println!(
         "Created profile '{}' at {}",
         style(name).green(),
@@ -607,6 +964,433 @@ async fn create_profile(name: &str, from: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Capabilities a container engine grants by default (runc/Docker's
+/// well-known default set) absent any `drop`/`add` directives at all.
+/// [`effective_capabilities`] starts from this set unless `drop` contains
+/// `"ALL"`.
+const DEFAULT_CAPABILITIES: &[&str] = &[
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_FSETID",
+    "CAP_FOWNER",
+    "CAP_MKNOD",
+    "CAP_NET_RAW",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETFCAP",
+    "CAP_SETPCAP",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_SYS_CHROOT",
+    "CAP_KILL",
+    "CAP_AUDIT_WRITE",
+];
+
+fn normalize_capability(cap: &str) -> String {
+    if cap.eq_ignore_ascii_case("ALL") {
+        "ALL".to_string()
+    } else if cap.starts_with("CAP_") {
+        cap.to_string()
+    } else {
+        format!("CAP_{}", cap.to_uppercase())
+    }
+}
+
+/// Resolves a profile's `drop`/`add` directives into the concrete
+/// capability set a runtime should actually grant, the same way
+/// runc/Docker apply them: start from [`DEFAULT_CAPABILITIES`] (or nothing,
+/// if `drop` contains `"ALL"`), remove anything `drop` names, then add
+/// back anything `add` names.
+fn effective_capabilities(caps: &CapabilityConfig) -> Vec<String> {
+    use std::collections::BTreeSet;
+
+    let drop_all = caps.drop.iter().any(|c| c.eq_ignore_ascii_case("ALL"));
+    let mut set: BTreeSet<String> = if drop_all {
+        BTreeSet::new()
+    } else {
+        DEFAULT_CAPABILITIES.iter().map(|c| c.to_string()).collect()
+    };
+
+    for cap in &caps.drop {
+        set.remove(&normalize_capability(cap));
+    }
+    for cap in &caps.add {
+        set.insert(normalize_capability(cap));
+    }
+
+    set.into_iter().collect()
+}
+
+/// Parses a profile's human `memory_limit` (`"512M"`, `"2G"`, `""` for
+/// unlimited) into bytes for the OCI runtime spec.
+fn parse_memory_limit_bytes(limit: &str) -> Option<i64> {
+    if limit.is_empty() {
+        return None;
+    }
+
+    let (digits, multiplier) = if let Some(n) = limit.strip_suffix(['G', 'g']) {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = limit.strip_suffix(['M', 'm']) {
+        (n, 1024 * 1024)
+    } else if let Some(n) = limit.strip_suffix(['K', 'k']) {
+        (n, 1024)
+    } else {
+        (limit, 1)
+    };
+
+    digits.trim().parse::<i64>().ok().map(|n| n * multiplier)
+}
+
+/// Translates a resolved profile into the relevant fragment of an OCI
+/// `config.json` runtime spec: capability sets, a seccomp stub, root/paths
+/// readonly flags, and pids/memory resource limits.
+fn export_oci(profile: &SecurityProfile) -> serde_json::Value {
+    let caps = effective_capabilities(&profile.capabilities);
+    let default_seccomp_action = if profile.seccomp.profile == "unconfined" {
+        "SCMP_ACT_ALLOW"
+    } else {
+        "SCMP_ACT_ERRNO"
+    };
+
+    serde_json::json!({
+        "process": {
+            "noNewPrivileges": profile.security.no_new_privileges,
+            "capabilities": {
+                "bounding": caps,
+                "effective": caps,
+                "permitted": caps,
+                "inheritable": [],
+                "ambient": [],
+            },
+        },
+        "root": {
+            "readonly": profile.security.read_only_rootfs,
+        },
+        "readonlyPaths": if profile.security.read_only_rootfs {
+            serde_json::json!(["/proc/sys", "/proc/sysrq-trigger", "/proc/irq", "/proc/bus"])
+        } else {
+            serde_json::json!([])
+        },
+        "linux": {
+            "seccomp": {
+                "defaultAction": default_seccomp_action,
+                "profile": profile.seccomp.profile,
+            },
+            "resources": {
+                "pids": {
+                    "limit": profile.resources.pids_limit,
+                },
+                "memory": {
+                    "limit": parse_memory_limit_bytes(&profile.resources.memory_limit),
+                },
+            },
+        },
+    })
+}
+
+/// Translates a resolved profile into the flag string `docker run`/
+/// `podman run` accept - the two CLIs share this flag surface, so the
+/// same translation serves both.
+fn export_docker_flags(profile: &SecurityProfile) -> String {
+    let mut flags = Vec::new();
+
+    for cap in &profile.capabilities.drop {
+        flags.push(format!("--cap-drop={}", cap));
+    }
+    for cap in &profile.capabilities.add {
+        flags.push(format!("--cap-add={}", cap));
+    }
+    if profile.security.no_new_privileges {
+        flags.push("--security-opt=no-new-privileges".to_string());
+    }
+    if profile.seccomp.profile == "unconfined" {
+        flags.push("--security-opt=seccomp=unconfined".to_string());
+    } else if !profile.seccomp.profile.is_empty() {
+        flags.push(format!("--security-opt=seccomp={}", profile.seccomp.profile));
+    }
+    if profile.security.read_only_rootfs {
+        flags.push("--read-only".to_string());
+    }
+    if profile.resources.pids_limit > 0 {
+        flags.push(format!("--pids-limit={}", profile.resources.pids_limit));
+    }
+    if !profile.resources.memory_limit.is_empty() {
+        flags.push(format!("--memory={}", profile.resources.memory_limit));
+    }
+
+    flags.join(" ")
+}
+
+async fn export_profile(name: &str, format: &str) -> Result<()> {
+    let profile = get_profile(name)?;
+
+    match format {
+        "oci" => println!("{}", serde_json::to_string_pretty(&export_oci(&profile))?),
+        "docker" | "podman" => println!("{}", export_docker_flags(&profile)),
+        other => bail!(
+            "Unknown export format '{}' (expected oci, docker, or podman)",
+            other
+        ),
+    }
+
+    Ok(())
+}
+
+/// Every Linux capability known as of kernel 5.8 (`CAP_CHECKPOINT_RESTORE`),
+/// bare-named per this crate's convention (no `CAP_` prefix). Used by
+/// [`lint_profile`] to reject unknown/misspelled capability names.
+const KNOWN_CAPABILITIES: &[&str] = &[
+    "CHOWN",
+    "DAC_OVERRIDE",
+    "DAC_READ_SEARCH",
+    "FOWNER",
+    "FSETID",
+    "KILL",
+    "SETGID",
+    "SETUID",
+    "SETPCAP",
+    "LINUX_IMMUTABLE",
+    "NET_BIND_SERVICE",
+    "NET_BROADCAST",
+    "NET_ADMIN",
+    "NET_RAW",
+    "IPC_LOCK",
+    "IPC_OWNER",
+    "SYS_MODULE",
+    "SYS_RAWIO",
+    "SYS_CHROOT",
+    "SYS_PTRACE",
+    "SYS_PACCT",
+    "SYS_ADMIN",
+    "SYS_BOOT",
+    "SYS_NICE",
+    "SYS_RESOURCE",
+    "SYS_TIME",
+    "SYS_TTY_CONFIG",
+    "MKNOD",
+    "LEASE",
+    "AUDIT_WRITE",
+    "AUDIT_CONTROL",
+    "SETFCAP",
+    "MAC_OVERRIDE",
+    "MAC_ADMIN",
+    "SYSLOG",
+    "WAKE_ALARM",
+    "BLOCK_SUSPEND",
+    "AUDIT_READ",
+    "PERFMON",
+    "BPF",
+    "CHECKPOINT_RESTORE",
+];
+
+#[derive(Debug, Clone, Serialize)]
+struct LintFinding {
+    severity: String,
+    message: String,
+}
+
+impl LintFinding {
+    fn error(message: impl Into<String>) -> Self {
+        LintFinding {
+            severity: "error".to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(message: impl Into<String>) -> Self {
+        LintFinding {
+            severity: "warning".to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn info(message: impl Into<String>) -> Self {
+        LintFinding {
+            severity: "info".to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Levenshtein edit distance, used to suggest the likely-intended
+/// capability name behind a typo (e.g. `NET_BIND_SERVIC` -> `NET_BIND_SERVICE`).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut prev_diag = row[0];
+        row[0] = i;
+        for j in 1..=b.len() {
+            let prev_row_j = row[j];
+            row[j] = if a[i - 1] == b[j - 1] {
+                prev_diag
+            } else {
+                1 + prev_diag.min(row[j]).min(row[j - 1])
+            };
+            prev_diag = prev_row_j;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Closest entry in [`KNOWN_CAPABILITIES`] to `name`, if within a distance
+/// small enough to be a plausible typo rather than an unrelated word.
+fn suggest_capability(name: &str) -> Option<&'static str> {
+    KNOWN_CAPABILITIES
+        .iter()
+        .map(|&known| (known, edit_distance(name, known)))
+        .min_by_key(|&(_, dist)| dist)
+        .filter(|&(_, dist)| dist <= 3)
+        .map(|(known, _)| known)
+}
+
+/// Validates a resolved profile's capabilities and flags contradictory
+/// security options, SELinux-policy-checker-style. Unknown capability
+/// names are errors (non-zero exit); everything else is a warning or an
+/// informational note about an idiom that is probably intentional.
+fn lint_profile(profile: &SecurityProfile) -> Vec<LintFinding> {
+    use std::collections::HashSet;
+
+    let mut findings = Vec::new();
+
+    let check_known = |cap: &str, findings: &mut Vec<LintFinding>| {
+        if cap.eq_ignore_ascii_case("ALL") {
+            return;
+        }
+        let normalized = cap.trim_start_matches("CAP_").to_uppercase();
+        if !KNOWN_CAPABILITIES.contains(&normalized.as_str()) {
+            findings.push(LintFinding::error(match suggest_capability(&normalized) {
+                Some(suggestion) => format!(
+                    "unknown capability '{}' (did you mean '{}'?)",
+                    cap, suggestion
+                ),
+                None => format!("unknown capability '{}'", cap),
+            }));
+        }
+    };
+
+    for cap in &profile.capabilities.drop {
+        check_known(cap, &mut findings);
+    }
+    for cap in &profile.capabilities.add {
+        check_known(cap, &mut findings);
+    }
+
+    let drop_set: HashSet<String> = profile
+        .capabilities
+        .drop
+        .iter()
+        .map(|c| c.to_uppercase())
+        .collect();
+    let drops_all = drop_set.contains("ALL");
+
+    for cap in &profile.capabilities.add {
+        let upper = cap.to_uppercase();
+        if drop_set.contains(&upper) {
+            findings.push(LintFinding::warning(format!(
+                "capability '{}' appears in both `add` and `drop`",
+                cap
+            )));
+        } else if drops_all {
+            findings.push(LintFinding::info(format!(
+                "`add` re-grants '{}', which `drop: [\"ALL\"]` would otherwise remove \
+                 (assuming this is intentional)",
+                cap
+            )));
+        }
+    }
+
+    if profile.security.privileged {
+        if profile.security.read_only_rootfs {
+            findings.push(LintFinding::warning(
+                "privileged = true together with read_only_rootfs = true is contradictory: \
+                 a privileged container can remount its root writable anyway"
+                    .to_string(),
+            ));
+        }
+        if profile.security.no_new_privileges {
+            findings.push(LintFinding::warning(
+                "privileged = true together with no_new_privileges = true is contradictory: \
+                 privileged already grants every capability no_new_privileges is meant to contain"
+                    .to_string(),
+            ));
+        }
+    }
+
+    if profile.network.mode == "none" {
+        let has_net_bind = profile
+            .capabilities
+            .add
+            .iter()
+            .any(|c| c.to_uppercase().trim_start_matches("CAP_") == "NET_BIND_SERVICE");
+        if has_net_bind {
+            findings.push(LintFinding::warning(
+                "network.mode = \"none\" together with capability NET_BIND_SERVICE has no \
+                 effect: there is no network to bind a privileged port on"
+                    .to_string(),
+            ));
+        }
+    }
+
+    findings
+}
+
+async fn lint_profile_command(name: &str, format: &str) -> Result<()> {
+    let profile = get_profile(name)?;
+    let findings = lint_profile(&profile);
+    let has_errors = findings.iter().any(|f| f.severity == "error");
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&findings)?),
+        _ => {
+            if findings.is_empty() {
+                println!("{} {}", style("OK").green().bold(), name);
+            } else {
+                for finding in &findings {
+                    let label = match finding.severity.as_str() {
+                        "error" => style("ERROR").red().bold(),
+                        "warning" => style("WARN").yellow().bold(),
+                        _ => style("INFO").cyan().bold(),
+                    };
+                    println!("{}: {}", label, finding.message);
+                }
+            }
+        }
+    }
+
+    if has_errors {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// On-disk `config.toml` contents. New fields should default to `None`/
+/// skip serialization so older config files keep round-tripping cleanly.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_profile: Option<String>,
+}
+
+fn read_config(path: &PathBuf) -> Result<Config> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    toml::from_str(&content).map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn write_config(path: &PathBuf, config: &Config) -> Result<()> {
+    let content = toml::to_string_pretty(config)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize config: {}", e))?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
 fn get_config_path() -> Result<PathBuf> {
     let config_dir = dirs::config_dir()
         .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?
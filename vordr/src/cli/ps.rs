@@ -3,9 +3,10 @@
 
 use anyhow::{Context, Result};
 use clap::Args;
+use serde_json::json;
 use std::path::Path;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, OutputFormat};
 use crate::engine::{ContainerState, StateManager};
 
 /// Arguments for the `ps` command
@@ -34,10 +35,11 @@ pub async fn execute(args: PsArgs, cli: &Cli) -> Result<()> {
 
     // If database doesn't exist yet, show empty list
     if !db_path.exists() {
-        if args.quiet {
-            return Ok(());
+        if cli.format == OutputFormat::Json {
+            println!("[]");
+        } else if !args.quiet {
+            println!("CONTAINER ID        NAME                STATUS              IMAGE");
         }
-        println!("CONTAINER ID        NAME                STATUS              IMAGE");
         return Ok(());
     }
 
@@ -56,20 +58,19 @@ pub async fn execute(args: PsArgs, cli: &Cli) -> Result<()> {
     // Get containers
     let containers = state.list_containers(state_filter)?;
 
-    if args.quiet {
+    if cli.format == OutputFormat::Json {
+        let entries: Vec<serde_json::Value> = containers.iter().map(container_row).collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if args.quiet {
         // Only print IDs
         for container in &containers {
             println!("{}", &container.id[..12]);
         }
     } else if let Some(ref format) = args.format {
-        // Custom format
         for container in &containers {
-            let output = format
-                .replace("{{.ID}}", &container.id[..12])
-                .replace("{{.Name}}", &container.name)
-                .replace("{{.Status}}", container.state.as_str())
-                .replace("{{.Image}}", &container.image_id[..12]);
-            println!("{}", output);
+            let rendered = crate::cli::template::render(format, &container_row(container))
+                .context("failed to render --format template")?;
+            println!("{}", rendered);
         }
     } else {
         // Default table format
@@ -89,6 +90,23 @@ pub async fn execute(args: PsArgs, cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// Builds the per-container JSON row used both for `--format json` and as the
+/// data fed to `--format '{{...}}'` templates. `ID`/`ImageID` are truncated
+/// the way the default table is; `Id`/`Image` carry the full ids.
+fn container_row(container: &crate::engine::ContainerInfo) -> serde_json::Value {
+    json!({
+        "Id": container.id,
+        "ID": &container.id[..12.min(container.id.len())],
+        "Name": container.name,
+        "Status": format_status(container),
+        "Image": container.image_id,
+        "ImageID": &container.image_id[..12.min(container.image_id.len())],
+        "Pid": container.pid,
+        "ExitCode": container.exit_code,
+        "Ports": crate::cli::ports::published_ports(container),
+    })
+}
+
 fn format_status(container: &crate::engine::ContainerInfo) -> String {
     match container.state {
         ContainerState::Running => {
@@ -98,6 +116,7 @@ fn format_status(container: &crate::engine::ContainerInfo) -> String {
                 "Up".to_string()
             }
         }
+        ContainerState::Creating => "Creating".to_string(),
         ContainerState::Created => "Created".to_string(),
         ContainerState::Paused => "Paused".to_string(),
         ContainerState::Stopped => {
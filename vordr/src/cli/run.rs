@@ -6,9 +6,12 @@ use clap::Args;
 use std::path::Path;
 use tracing::info;
 
+use crate::cli::ports;
+use crate::cli::pull;
 use crate::cli::Cli;
-use crate::engine::{ContainerState, StateManager};
+use crate::engine::{ContainerState, ResolveMode, StateManager};
 use crate::ffi::{ConfigValidator, NetworkMode};
+use crate::registry::LayerStore;
 
 /// Arguments for the `run` command
 #[derive(Args, Debug)]
@@ -88,9 +91,32 @@ pub async fn execute(args: RunArgs, cli: &Cli) -> Result<()> {
         1000 // Default non-root user
     };
 
+    // Ensure database directory exists
+    let db_path = Path::new(&cli.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .context("Failed to create database directory")?;
+    }
+
+    // Open state database
+    let state = StateManager::open(db_path)
+        .context("Failed to open state database")?;
+
+    // An `--internal` network has no route to the host or the internet, so
+    // anything attached to one is forced into NetworkMode::Restricted
+    // regardless of --privileged/--no-network.
+    let attached_network = args
+        .network
+        .as_deref()
+        .map(|name| state.get_network(name).context("network not found"))
+        .transpose()?;
+    let network_is_internal = attached_network.as_ref().is_some_and(|n| n.internal);
+
     // Build and validate configuration through the gatekeeper
     let network_mode = if args.no_network {
         NetworkMode::Unprivileged
+    } else if network_is_internal {
+        NetworkMode::Restricted
     } else if args.privileged {
         NetworkMode::Admin
     } else {
@@ -115,6 +141,13 @@ pub async fn execute(args: RunArgs, cli: &Cli) -> Result<()> {
 
     info!("Configuration validated by gatekeeper");
 
+    // Parse, validate, and reserve the published ports before anything
+    // else is created, so a bad -p spec or a collision fails fast.
+    let port_mappings = ports::parse_specs(&args.ports).context("Invalid --publish spec")?;
+    ports::validate_privileged_ports(&port_mappings, validated_config.privileged)
+        .context("Port validation failed")?;
+    ports::check_collisions(&state, &port_mappings).context("Port validation failed")?;
+
     // Generate container ID and name
     let container_id = generate_container_id();
     let container_name = args.name.unwrap_or_else(|| generate_container_name());
@@ -124,36 +157,29 @@ pub async fn execute(args: RunArgs, cli: &Cli) -> Result<()> {
     std::fs::create_dir_all(root_path)
         .context("Failed to create root directory")?;
 
-    // Ensure database directory exists
-    let db_path = Path::new(&cli.db_path);
-    if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent)
-            .context("Failed to create database directory")?;
-    }
-
-    // Open state database
-    let state = StateManager::open(db_path)
-        .context("Failed to open state database")?;
-
     // Create bundle directory
     let bundle_path = root_path.join("containers").join(&container_id);
     std::fs::create_dir_all(&bundle_path)
         .context("Failed to create bundle directory")?;
 
-    // TODO: Pull image if not present
-    // For now, use a placeholder image ID
-    let image_id = format!("sha256:{}", &container_id[..12]);
-
-    // Check if image exists, create placeholder if not
-    if state.get_image(&image_id).is_err() {
-        state.create_image(
-            &image_id,
-            &format!("sha256:{}", hex::encode(&container_id.as_bytes()[..16])),
-            Some(&args.image),
-            &[args.image.clone()],
-            0,
-        )?;
-    }
+    // Resolve the image, pulling it from its registry only if state
+    // doesn't already have a matching tag/digest.
+    let image = pull::ensure_image(cli, &args.image)
+        .await
+        .context("Failed to resolve image")?;
+    let image_id = image.id.clone();
+
+    // Unpack the image's layers (already downloaded and digest-verified
+    // into the local layer store by the pull above) into this
+    // container's own bundle rootfs, in the manifest's layering order.
+    let layer_store = LayerStore::open(root_path.join("layers")).context("failed to open local layer store")?;
+    let layer_paths = state
+        .image_layers(&image_id)?
+        .into_iter()
+        .map(|layer| layer_store.blob_path(&layer.digest))
+        .collect::<Vec<_>>();
+    crate::unpack::unpack_layers(&layer_paths, &bundle_path.join("rootfs"))
+        .context("Failed to unpack image layers")?;
 
     // Create container record
     let config_json = serde_json::json!({
@@ -162,6 +188,7 @@ pub async fn execute(args: RunArgs, cli: &Cli) -> Result<()> {
         "env": args.env,
         "volumes": args.volumes,
         "ports": args.ports,
+        "port_mappings": port_mappings,
         "privileged": validated_config.privileged,
         "user": user_id,
         "userns": validated_config.user_namespace,
@@ -173,8 +200,42 @@ pub async fn execute(args: RunArgs, cli: &Cli) -> Result<()> {
         &image_id,
         bundle_path.to_str().unwrap(),
         Some(&config_json.to_string()),
+        ResolveMode::Default,
     ).context("Failed to create container record")?;
 
+    // Install the actual host -> container forwarding rules. This engine
+    // doesn't attach a real network namespace to a container yet (`start`
+    // is still simulated - see the runtime TODO), so there is no veth/netns
+    // for a DNAT rule to target; this call is the seam that will own that
+    // once one exists, and is a deliberate no-op until then.
+    crate::network::portforward::install(&port_mappings);
+
+    // Attach to the requested network (if any) the same way `network
+    // connect` does, so the driver - bridge, host, none, macvlan/ipvlan -
+    // gets a chance to configure the container's netns once `start` attaches
+    // a real one.
+    if let Some(network) = &attached_network {
+        let aliases = vec![container_name.clone()];
+        crate::cli::network::attach_container(&state, network, &container_id, &aliases, None)
+            .context("Failed to attach container to network")?;
+    }
+
+    // `-v name:/path[:ro|rw]` entries that name an existing volume are
+    // mounted and bump its refcount; entries that don't resolve to a
+    // known volume are treated as host bind mounts, outside this
+    // bookkeeping.
+    for spec in &args.volumes {
+        let mut parts = spec.splitn(3, ':');
+        let name = parts.next().unwrap_or_default();
+        let Some(mount_path) = parts.next() else {
+            continue;
+        };
+
+        if let Ok(volume) = state.get_volume(name) {
+            state.mount_volume(&container_id, &volume.id, mount_path)?;
+        }
+    }
+
     info!("Created container {} ({})", container_name, container_id);
 
     if args.detach {
@@ -194,7 +255,7 @@ pub async fn execute(args: RunArgs, cli: &Cli) -> Result<()> {
 }
 
 /// Generate a unique container ID (64 hex characters)
-fn generate_container_id() -> String {
+pub(crate) fn generate_container_id() -> String {
     use sha2::{Sha256, Digest};
 
     let mut hasher = Sha256::new();
@@ -209,7 +270,7 @@ fn generate_container_id() -> String {
 }
 
 /// Generate a random container name (adjective_noun format)
-fn generate_container_name() -> String {
+pub(crate) fn generate_container_name() -> String {
     let adjectives = [
         "brave", "calm", "eager", "fair", "gentle",
         "happy", "jolly", "kind", "lively", "merry",
@@ -0,0 +1,747 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! `vordr serve --http <addr>` / `--unix <path>` - Docker-compatible HTTP
+//! REST gateway
+//!
+//! Exposes the same container/image/volume/network operations available
+//! through the CLI subcommands as a JSON REST API, modeled on the Docker
+//! Engine API v1.40 endpoint shapes (`/containers/json`,
+//! `/containers/{id}/start`, `/volumes`, `/images/create`, ...) so existing
+//! ecosystem tooling - including the `docker` CLI itself, via
+//! `docker -H unix://...` - can talk to a running engine unmodified. Every
+//! route is a thin dispatch onto [`StateManager`], the same state the CLI
+//! reads and writes, so CLI and HTTP behave identically; container creation
+//! runs through the same [`ConfigValidator`] gatekeeper path as `vordr run`.
+//!
+//! There's no HTTP crate in this build, so the gateway hand-rolls just
+//! enough HTTP/1.1 (request line, headers, a fixed-length body) to serve
+//! JSON request/response bodies - the same approach already used for the
+//! hand-rolled ttrpc and remote-control wire protocols elsewhere in this
+//! crate, rather than reimplementing a general-purpose HTTP stack.
+
+use anyhow::{Context, Result};
+use clap::Args;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, UnixListener};
+use tracing::{info, warn};
+
+use crate::cli::Cli;
+use crate::engine::{build_driver, ContainerState, ResolveMode, StateManager, VolumeContext};
+use crate::ffi::{ConfigValidator, NetworkMode};
+
+/// Arguments for the `serve` command. Exactly one of `--http`/`--unix` must
+/// be given - matching the Docker daemon's own `-H tcp://...` /
+/// `-H unix://...` split, where a single running gateway answers on one
+/// transport at a time.
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Address to bind the HTTP gateway to, e.g. 127.0.0.1:2375
+    #[arg(long)]
+    pub http: Option<String>,
+
+    /// Unix socket path to bind the HTTP gateway to, e.g. /var/run/vordr.sock
+    /// - what `docker -H unix://...` expects to find. Created mode 0600
+    /// (owner only), since this endpoint grants unauthenticated control
+    /// over every container.
+    #[arg(long)]
+    pub unix: Option<String>,
+}
+
+pub async fn execute(args: ServeArgs, cli: &Cli) -> Result<()> {
+    let db_path = PathBuf::from(&cli.db_path);
+    let root = PathBuf::from(&cli.root);
+
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create database directory")?;
+    }
+    // Make sure the schema exists and refcounts are recomputed before any
+    // request is served.
+    StateManager::open(&db_path).context("Failed to open state database")?;
+
+    match (&args.http, &args.unix) {
+        (Some(_), Some(_)) => anyhow::bail!("only one of --http or --unix may be given"),
+        (None, None) => anyhow::bail!("one of --http or --unix is required"),
+        (Some(addr), None) => serve_tcp(addr, &db_path, &root).await,
+        (None, Some(path)) => serve_unix(path, &db_path, &root).await,
+    }
+}
+
+async fn serve_tcp(addr: &str, db_path: &Path, root: &Path) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind HTTP gateway to {}", addr))?;
+
+    info!("HTTP gateway listening on {}", addr);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let db_path = db_path.to_path_buf();
+        let root = root.to_path_buf();
+
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, &db_path, &root).await {
+                warn!("HTTP gateway connection from {} failed: {}", peer, err);
+            }
+        });
+    }
+}
+
+async fn serve_unix(path: &str, db_path: &Path, root: &Path) -> Result<()> {
+    // A stale socket file left over from a previous run (e.g. after a
+    // crash) makes bind fail with "address already in use" even though
+    // nothing is listening - remove it first, same as `vordr manager` does
+    // for its own socket.
+    if Path::new(path).exists() {
+        std::fs::remove_file(path).context("failed to remove stale gateway socket")?;
+    }
+
+    let listener = UnixListener::bind(path).with_context(|| format!("Failed to bind HTTP gateway to {}", path))?;
+
+    // `UnixListener::bind` creates the socket file with whatever the
+    // process umask leaves it at, which on a lot of systems is
+    // group/world-accessible - and this gateway grants full,
+    // unauthenticated control over every container, same as the Docker
+    // daemon socket it mimics. Lock it down to the owner only.
+    #[cfg(unix)]
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("failed to set permissions on {}", path))?;
+
+    info!("HTTP gateway listening on unix:{}", path);
+
+    loop {
+        let (stream, _peer) = listener.accept().await?;
+        let db_path = db_path.to_path_buf();
+        let root = root.to_path_buf();
+
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, &db_path, &root).await {
+                warn!("HTTP gateway connection on unix socket failed: {}", err);
+            }
+        });
+    }
+}
+
+async fn handle_connection<S>(stream: S, db_path: &Path, root: &Path) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).await? == 0 {
+        return Ok(());
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("").to_string();
+
+    let mut content_length: usize = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    if content_length > 0 {
+        reader.read_exact(&mut body).await?;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target.as_str(), ""));
+
+    let (status, body_json) = match route(&method, path, query, &body, db_path, root) {
+        Ok(response) => response,
+        Err(err) => error_response(&err),
+    };
+
+    write_response(&mut writer, status, &body_json).await
+}
+
+async fn write_response<W>(writer: &mut W, status: (u16, &'static str), body: &serde_json::Value) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let payload = serde_json::to_vec(body)?;
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status.0,
+        status.1,
+        payload.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(&payload).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+type Status = (u16, &'static str);
+
+fn error_response(err: &anyhow::Error) -> (Status, serde_json::Value) {
+    let message = err.to_string();
+    let status = if message.to_lowercase().contains("not found") {
+        (404, "Not Found")
+    } else {
+        (500, "Internal Server Error")
+    };
+    (status, serde_json::json!({ "message": message }))
+}
+
+/// Routes one request to its handler. Modeled on the Docker Engine API's
+/// path shapes so existing ecosystem tooling needs no translation layer.
+fn route(
+    method: &str,
+    path: &str,
+    query: &str,
+    body: &[u8],
+    db_path: &Path,
+    root: &Path,
+) -> Result<(Status, serde_json::Value)> {
+    let state = StateManager::open(db_path).context("Failed to open state database")?;
+    let segments: Vec<&str> = path
+        .trim_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["containers", "json"]) => list_containers(&state, query),
+        ("GET", ["containers", id, "json"]) => inspect_container(&state, id),
+        ("GET", ["containers", id, "logs"]) => container_logs(&state, id),
+        ("POST", ["containers", "create"]) => create_container(&state, root, query, body),
+        ("POST", ["containers", id, "start"]) => start_container(&state, id),
+        ("POST", ["containers", id, "stop"]) => stop_container(&state, id),
+        ("DELETE", ["containers", id]) => remove_container(&state, id, query),
+        ("POST", ["containers", _, "exec"]) | ("POST", ["exec", _, "start"]) => {
+            Ok(not_implemented(
+                "streaming exec/attach over HTTP is not implemented yet; use the `vordr exec` CLI command",
+            ))
+        }
+        ("GET", ["images", "json"]) => list_images(&state),
+        ("POST", ["images", "create"]) => create_image(&state, query),
+        ("GET", ["volumes"]) => list_volumes(&state),
+        ("POST", ["volumes", "create"]) => create_volume(&state, root, body),
+        ("DELETE", ["volumes", name]) => remove_volume(&state, name),
+        ("GET", ["networks"]) => list_networks(&state),
+        ("POST", ["networks", "create"]) => create_network(&state, body),
+        ("DELETE", ["networks", id]) => remove_network(&state, id),
+        _ => Ok((
+            (404, "Not Found"),
+            serde_json::json!({ "message": format!("no such route: {} {}", method, path) }),
+        )),
+    }
+}
+
+fn not_implemented(message: &str) -> (Status, serde_json::Value) {
+    ((501, "Not Implemented"), serde_json::json!({ "message": message }))
+}
+
+fn query_param(query: &str, key: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (k, v) = pair.split_once('=')?;
+        (k == key).then(|| v.to_string())
+    })
+}
+
+fn parse_json_body(body: &[u8]) -> Result<serde_json::Value> {
+    if body.is_empty() {
+        Ok(serde_json::json!({}))
+    } else {
+        serde_json::from_slice(body).context("request body is not valid JSON")
+    }
+}
+
+// === CONTAINERS ===
+
+fn list_containers(state: &StateManager, query: &str) -> Result<(Status, serde_json::Value)> {
+    let state_filter = if query_param(query, "all").as_deref() == Some("true") {
+        None
+    } else {
+        Some(ContainerState::Running)
+    };
+
+    let containers = state.list_containers(state_filter)?;
+    let filters = filter_param(query, "filters");
+
+    let entries: Vec<serde_json::Value> = containers
+        .iter()
+        .filter(|c| matches_filters(c, &filters))
+        .map(|c| {
+            serde_json::json!({
+                "Id": c.id,
+                "Names": [format!("/{}", c.name)],
+                "Image": c.image_id,
+                "State": c.state.as_str(),
+                "Status": container_status(c),
+                "Ports": ports_json(c),
+                "Mounts": [],
+            })
+        })
+        .collect();
+
+    Ok(((200, "OK"), serde_json::Value::Array(entries)))
+}
+
+/// Parses the Docker Engine API's `filters` query parameter - a JSON object
+/// mapping filter name to a list of acceptable values - into an easy-to-`get`
+/// form. An absent or unparseable value means "no filters", same as Docker's
+/// own behavior for a malformed query.
+fn filter_param(query: &str, key: &str) -> std::collections::HashMap<String, Vec<String>> {
+    query_param(query, key)
+        .and_then(|raw| urlencoded_json(&raw))
+        .unwrap_or_default()
+}
+
+/// The `filters` value arrives percent-encoded JSON; `query_param` only
+/// splits on `&`/`=`, so undo the encoding this hand-rolled parser doesn't.
+fn urlencoded_json(raw: &str) -> Option<std::collections::HashMap<String, Vec<String>>> {
+    let decoded = percent_decode(raw);
+    serde_json::from_str(&decoded).ok()
+}
+
+fn percent_decode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(byte) => out.push(byte as char),
+                    Err(_) => out.push_str(&hex),
+                }
+            }
+            '+' => out.push(' '),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Only `status` is a meaningful filter over what [`crate::engine::ContainerInfo`]
+/// tracks today; any other filter name is ignored rather than rejected, same
+/// as Docker does for filters a given API version doesn't support.
+fn matches_filters(container: &crate::engine::ContainerInfo, filters: &std::collections::HashMap<String, Vec<String>>) -> bool {
+    match filters.get("status") {
+        Some(statuses) => statuses.iter().any(|s| s == container.state.as_str()),
+        None => true,
+    }
+}
+
+/// Maps a container's published `-p` mappings onto the Docker Engine API's
+/// `Ports` array shape.
+fn ports_json(container: &crate::engine::ContainerInfo) -> Vec<serde_json::Value> {
+    crate::cli::ports::published_ports(container)
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "IP": p.host_ip(),
+                "PrivatePort": p.container_port,
+                "PublicPort": p.host_port,
+                "Type": p.protocol.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn inspect_container(state: &StateManager, id: &str) -> Result<(Status, serde_json::Value)> {
+    let container = state.get_container(id)?;
+    Ok((
+        (200, "OK"),
+        serde_json::json!({
+            "Id": container.id,
+            "Name": format!("/{}", container.name),
+            "Image": container.image_id,
+            "State": {
+                "Status": container.state.as_str(),
+                "Pid": container.pid,
+                "ExitCode": container.exit_code,
+            },
+            "Created": container.created_at,
+            "Mounts": [],
+            "NetworkSettings": { "Ports": ports_json(&container) },
+        }),
+    ))
+}
+
+/// `POST /containers/create` - builds and validates configuration through
+/// the gatekeeper exactly like `vordr run`, then records the container.
+/// Unlike `vordr run`, creation and starting are separate steps here,
+/// matching the Docker Engine API split.
+fn create_container(
+    state: &StateManager,
+    root: &Path,
+    query: &str,
+    body: &[u8],
+) -> Result<(Status, serde_json::Value)> {
+    crate::engine::quota::enforce(state, root, crate::engine::quota::QuotaResource::Containers)
+        .context("container quota check failed")?;
+
+    let payload = parse_json_body(body)?;
+
+    let image = payload
+        .get("Image")
+        .and_then(|v| v.as_str())
+        .context("request body must include an \"Image\" field")?
+        .to_string();
+
+    let host_config = payload.get("HostConfig").cloned().unwrap_or_default();
+    let privileged = host_config
+        .get("Privileged")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let readonly_rootfs = host_config
+        .get("ReadonlyRootfs")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let network_mode = if privileged {
+        NetworkMode::Admin
+    } else {
+        NetworkMode::Restricted
+    };
+
+    let validated = ConfigValidator::new()
+        .privileged(privileged)
+        .network_mode(network_mode)
+        .readonly_rootfs(readonly_rootfs)
+        .validate()
+        .context("security validation failed")?;
+
+    let container_id = uuid::Uuid::new_v4().simple().to_string();
+    let name = query_param(query, "name").unwrap_or_else(|| format!("vordr-{}", &container_id[..8]));
+
+    let bundle_path = root.join("containers").join(&container_id);
+    std::fs::create_dir_all(&bundle_path)
+        .context("Failed to create bundle directory")?;
+
+    // TODO: Pull the real image; see images/create below for the same gap.
+    let image_id = format!("sha256:{}", &container_id[..12]);
+    if state.get_image(&image_id).is_err() {
+        state.create_image(&image_id, &image_id, Some(&image), &[image.clone()], 0, &[], None)?;
+    }
+
+    let cmd: Vec<String> = payload
+        .get("Cmd")
+        .and_then(|v| v.as_array())
+        .map(|values| values.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+
+    let config_json = serde_json::json!({
+        "image": image,
+        "command": cmd,
+        "privileged": validated.privileged,
+        "userns": validated.user_namespace,
+    });
+
+    state.create_container(
+        &container_id,
+        &name,
+        &image_id,
+        bundle_path.to_str().unwrap(),
+        Some(&config_json.to_string()),
+        ResolveMode::Default,
+    )?;
+
+    Ok((
+        (201, "Created"),
+        serde_json::json!({ "Id": container_id, "Warnings": [] }),
+    ))
+}
+
+fn start_container(state: &StateManager, id: &str) -> Result<(Status, serde_json::Value)> {
+    let container = state.get_container(id)?;
+    // TODO: Actually start the container via the runtime shim; `vordr
+    // start` has the same gap today.
+    state.set_container_state(&container.id, ContainerState::Running, Some(std::process::id() as i32))?;
+    Ok(((204, "No Content"), serde_json::Value::Null))
+}
+
+fn stop_container(state: &StateManager, id: &str) -> Result<(Status, serde_json::Value)> {
+    // Confirms the container exists; actually signaling the runtime is the
+    // same not-yet-implemented step `vordr stop` has today.
+    state.get_container(id)?;
+    Ok(((204, "No Content"), serde_json::Value::Null))
+}
+
+fn remove_container(state: &StateManager, id: &str, query: &str) -> Result<(Status, serde_json::Value)> {
+    let _force = query_param(query, "force").as_deref() == Some("true");
+    let container = state.get_container(id)?;
+
+    state.delete_container(&container.id)?;
+    // `container_volumes` rows for this container are cascade-deleted as
+    // part of the delete; recompute so any volume it had mounted drops its
+    // refcount accordingly.
+    state.recompute_volume_refcounts()?;
+
+    Ok(((204, "No Content"), serde_json::Value::Null))
+}
+
+fn container_status(container: &crate::engine::ContainerInfo) -> String {
+    match container.state {
+        ContainerState::Running => container
+            .pid
+            .map(|pid| format!("Up (PID {})", pid))
+            .unwrap_or_else(|| "Up".to_string()),
+        ContainerState::Creating => "Creating".to_string(),
+        ContainerState::Created => "Created".to_string(),
+        ContainerState::Paused => "Paused".to_string(),
+        ContainerState::Stopped => container
+            .exit_code
+            .map(|code| format!("Exited ({})", code))
+            .unwrap_or_else(|| "Exited".to_string()),
+    }
+}
+
+/// `GET /containers/{id}/logs` - confirms the container exists, matching
+/// the 404 Docker would give for an unknown id, but there's nowhere to read
+/// captured stdout/stderr from yet - no CLI command or runtime path
+/// collects it either (see `cli::exec`, which has the same runtime-shim
+/// gap).
+fn container_logs(state: &StateManager, id: &str) -> Result<(Status, serde_json::Value)> {
+    state.get_container(id)?;
+    Ok(not_implemented(
+        "log capture is not implemented yet; no runtime path records container stdout/stderr",
+    ))
+}
+
+// === IMAGES ===
+
+fn list_images(state: &StateManager) -> Result<(Status, serde_json::Value)> {
+    let images = state.list_images()?;
+    let entries: Vec<serde_json::Value> = images
+        .iter()
+        .map(|i| {
+            let repo_tags: Vec<String> = match &i.repository {
+                Some(repository) if !i.tags.is_empty() => {
+                    i.tags.iter().map(|tag| format!("{}:{}", repository, tag)).collect()
+                }
+                _ => vec!["<none>:<none>".to_string()],
+            };
+            serde_json::json!({
+                "Id": i.id,
+                "RepoTags": repo_tags,
+                "RepoDigests": [format!("{}@{}", i.repository.as_deref().unwrap_or("<none>"), i.digest)],
+                "Size": i.size,
+                "Created": i.created_at,
+            })
+        })
+        .collect();
+
+    Ok(((200, "OK"), serde_json::Value::Array(entries)))
+}
+
+/// `POST /images/create?fromImage=<ref>` - registers a placeholder image
+/// record without contacting a registry, unlike `vordr pull` (see
+/// `cli::pull::pull_image`).
+fn create_image(state: &StateManager, query: &str) -> Result<(Status, serde_json::Value)> {
+    let reference = query_param(query, "fromImage").context("missing fromImage query parameter")?;
+
+    let image_id = format!("sha256:{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+    state.upsert_image(&image_id, &image_id, Some(&reference), &[reference.clone()], 0, None)?;
+
+    Ok(((200, "OK"), serde_json::json!({ "status": format!("Pulled {}", reference) })))
+}
+
+// === VOLUMES ===
+
+fn list_volumes(state: &StateManager) -> Result<(Status, serde_json::Value)> {
+    let volumes = state.list_volumes()?;
+    let entries: Vec<serde_json::Value> = volumes
+        .iter()
+        .map(|v| {
+            serde_json::json!({
+                "Name": v.name,
+                "Driver": v.driver,
+                "Mountpoint": v.mountpoint,
+            })
+        })
+        .collect();
+
+    Ok(((200, "OK"), serde_json::json!({ "Volumes": entries, "Warnings": [] })))
+}
+
+fn create_volume(state: &StateManager, root: &Path, body: &[u8]) -> Result<(Status, serde_json::Value)> {
+    crate::engine::quota::enforce(state, root, crate::engine::quota::QuotaResource::Volumes)
+        .context("volume quota check failed")?;
+
+    let payload = parse_json_body(body)?;
+
+    let name = payload
+        .get("Name")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+    let driver = payload
+        .get("Driver")
+        .and_then(|v| v.as_str())
+        .unwrap_or("local")
+        .to_string();
+
+    let options: std::collections::HashMap<String, String> = payload
+        .get("DriverOpts")
+        .and_then(|v| v.as_object())
+        .map(|m| {
+            m.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mountpoint = root.join("volumes").join(&name);
+
+    let backend = build_driver(&driver).with_context(|| format!("unsupported volume driver {:?}", driver))?;
+    backend
+        .create(&VolumeContext {
+            name: name.clone(),
+            mountpoint: mountpoint.clone(),
+            options: options.clone(),
+        })
+        .with_context(|| format!("Failed to create volume with driver {:?}", driver))?;
+
+    let options_json = if options.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&options).unwrap())
+    };
+
+    let volume_id = uuid::Uuid::new_v4().to_string();
+    state.create_volume(&volume_id, &name, &driver, mountpoint.to_str().unwrap(), options_json.as_deref(), None)?;
+
+    Ok((
+        (201, "Created"),
+        serde_json::json!({ "Name": name, "Driver": driver, "Mountpoint": mountpoint.to_string_lossy() }),
+    ))
+}
+
+fn remove_volume(state: &StateManager, name: &str) -> Result<(Status, serde_json::Value)> {
+    let volume = state.get_volume(name)?;
+    let options: std::collections::HashMap<String, String> = volume
+        .options
+        .as_deref()
+        .and_then(|o| serde_json::from_str(o).ok())
+        .unwrap_or_default();
+
+    let backend = build_driver(&volume.driver)
+        .with_context(|| format!("unsupported volume driver {:?}", volume.driver))?;
+    backend
+        .remove(&VolumeContext {
+            name: volume.name.clone(),
+            mountpoint: PathBuf::from(&volume.mountpoint),
+            options,
+        })
+        .with_context(|| format!("Failed to remove volume with driver {:?}", volume.driver))?;
+
+    state.delete_volume(&volume.id)?;
+    Ok(((204, "No Content"), serde_json::Value::Null))
+}
+
+// === NETWORKS ===
+
+fn list_networks(state: &StateManager) -> Result<(Status, serde_json::Value)> {
+    let networks = state.list_networks()?;
+    let entries: Vec<serde_json::Value> = networks
+        .iter()
+        .map(|n| {
+            serde_json::json!({
+                "Id": n.id,
+                "Name": n.name,
+                "Driver": n.driver,
+                "Internal": n.internal,
+                "Options": n.options.as_deref().and_then(|o| serde_json::from_str::<serde_json::Value>(o).ok()).unwrap_or_default(),
+                "IPAM": { "Config": [{ "Subnet": n.subnet, "Gateway": n.gateway }] },
+            })
+        })
+        .collect();
+
+    Ok(((200, "OK"), serde_json::Value::Array(entries)))
+}
+
+fn create_network(state: &StateManager, body: &[u8]) -> Result<(Status, serde_json::Value)> {
+    let payload = parse_json_body(body)?;
+
+    let name = payload
+        .get("Name")
+        .and_then(|v| v.as_str())
+        .context("request body must include a \"Name\" field")?
+        .to_string();
+    let driver = payload
+        .get("Driver")
+        .and_then(|v| v.as_str())
+        .unwrap_or("bridge")
+        .to_string();
+
+    let ipam_config = payload
+        .get("IPAM")
+        .and_then(|v| v.get("Config"))
+        .and_then(|v| v.as_array())
+        .and_then(|a| a.first());
+    let subnet = ipam_config.and_then(|c| c.get("Subnet")).and_then(|v| v.as_str());
+    let gateway = ipam_config.and_then(|c| c.get("Gateway")).and_then(|v| v.as_str());
+    let internal = payload.get("Internal").and_then(|v| v.as_bool()).unwrap_or(false);
+    let options_map: std::collections::HashMap<String, String> = payload
+        .get("Options")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+
+    let backend = crate::engine::build_network_driver(&driver)
+        .with_context(|| format!("unsupported network driver {:?}", driver))?;
+    backend
+        .validate_create(&crate::engine::NetworkCreateRequest {
+            subnet,
+            gateway,
+            internal,
+            options: &options_map,
+        })
+        .with_context(|| format!("invalid options for network driver {:?}", driver))?;
+
+    let network_id = uuid::Uuid::new_v4().to_string();
+    let (subnet, gateway) = if !backend.has_own_address_space() {
+        (None, None)
+    } else {
+        match subnet {
+            Some(subnet) => (Some(subnet.to_string()), gateway.map(str::to_string)),
+            None => {
+                let existing = state.list_networks()?;
+                let (subnet, gateway) =
+                    crate::engine::ipam::allocate_subnet(&existing).context("failed to allocate a subnet")?;
+                (Some(subnet), Some(gateway))
+            }
+        }
+    };
+    let options_json = if options_map.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&options_map).unwrap())
+    };
+    state.create_network(
+        &network_id,
+        &name,
+        &driver,
+        subnet.as_deref(),
+        gateway.as_deref(),
+        options_json.as_deref(),
+        internal,
+    )?;
+
+    Ok(((201, "Created"), serde_json::json!({ "Id": network_id, "Warning": "" })))
+}
+
+fn remove_network(state: &StateManager, id: &str) -> Result<(Status, serde_json::Value)> {
+    let network = state.get_network(id)?;
+    state.delete_network(&network.id)?;
+    Ok(((204, "No Content"), serde_json::Value::Null))
+}
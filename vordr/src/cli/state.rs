@@ -0,0 +1,35 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! `vordr state` command implementation
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::Path;
+
+use crate::cli::Cli;
+use crate::engine::ContainerLifecycle;
+
+/// Arguments for the `state` command
+#[derive(Args, Debug)]
+pub struct StateArgs {
+    /// Container ID or name
+    pub container: String,
+}
+
+/// Prints the OCI runtime-spec `state` document for a container, so
+/// external tooling that speaks that format (e.g. `runtime-tools`
+/// validation) can query svalinn the same way it would runc or youki.
+pub async fn execute(args: StateArgs, cli: &Cli) -> Result<()> {
+    let lifecycle = ContainerLifecycle::new(
+        Path::new(&cli.db_path),
+        Path::new(&cli.root),
+        &cli.runtime,
+    )
+    .context("Failed to open container lifecycle")?;
+
+    let state = lifecycle
+        .oci_state(&args.container)
+        .context("Failed to read container state")?;
+
+    println!("{}", serde_json::to_string_pretty(&state)?);
+    Ok(())
+}
@@ -1,15 +1,17 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
-//! System management commands (df, prune, info, reset)
+//! System management commands (df, prune, info, metrics, reset)
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use bytesize::ByteSize;
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand, ValueEnum};
 use dialoguer::Confirm;
 use std::path::Path;
 use tabled::{Table, Tabled};
+use tracing::warn;
 
 use crate::cli::Cli;
-use crate::engine::StateManager;
+use crate::engine::{self, ContainerInfo, StateManager};
 
 /// System management commands
 #[derive(Parser, Debug)]
@@ -29,6 +31,10 @@ pub enum SystemCommands {
         /// Output format
         #[arg(long, value_enum, default_value = "table")]
         format: OutputFormat,
+
+        /// Force a full re-scan instead of using cached usage counters
+        #[arg(long)]
+        refresh: bool,
     },
 
     /// Remove unused data
@@ -57,6 +63,34 @@ pub enum SystemCommands {
     /// Display system information
     Info,
 
+    /// Emit engine statistics in Prometheus text exposition format
+    Metrics {
+        /// Serve metrics over HTTP at this address instead of printing once
+        /// and exiting, e.g. 127.0.0.1:9477. Every request, regardless of
+        /// method or path, gets a fresh `text/plain; version=0.0.4` scrape
+        /// response.
+        #[arg(long, value_name = "ADDR")]
+        listen: Option<String>,
+    },
+
+    /// Offline reconciliation pass between the state DB and `cli.root`
+    Repair {
+        /// List discrepancies only; this is the default if neither
+        /// `--dry-run` nor `--fix` is given
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Apply corrections instead of just reporting them
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Manage per-resource-type disk quotas
+    Quota {
+        #[command(subcommand)]
+        command: QuotaCommands,
+    },
+
     /// Reset all Vordr data (dangerous!)
     Reset {
         /// Don't prompt for confirmation
@@ -69,6 +103,40 @@ pub enum SystemCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum QuotaCommands {
+    /// Set (or update) the max bytes allowed for a resource type
+    Set {
+        /// Resource type to cap
+        #[arg(long = "type", value_enum)]
+        resource: QuotaResourceArg,
+
+        /// Max size, e.g. 10GB, 500MB
+        #[arg(long)]
+        max: String,
+    },
+
+    /// List configured quotas alongside current usage
+    Ls,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum QuotaResourceArg {
+    Containers,
+    Volumes,
+    Images,
+}
+
+impl QuotaResourceArg {
+    fn to_engine(self) -> engine::QuotaResource {
+        match self {
+            QuotaResourceArg::Containers => engine::QuotaResource::Containers,
+            QuotaResourceArg::Volumes => engine::QuotaResource::Volumes,
+            QuotaResourceArg::Images => engine::QuotaResource::Images,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
 pub enum OutputFormat {
     #[default]
@@ -90,23 +158,6 @@ struct DiskUsageRow {
     reclaimable: String,
 }
 
-#[derive(serde::Serialize)]
-struct DiskUsage {
-    images: ResourceUsage,
-    containers: ResourceUsage,
-    volumes: ResourceUsage,
-    total_size: u64,
-    reclaimable: u64,
-}
-
-#[derive(serde::Serialize)]
-struct ResourceUsage {
-    total: u64,
-    active: u64,
-    size: u64,
-    reclaimable: u64,
-}
-
 #[derive(serde::Serialize)]
 struct PruneResult {
     containers_deleted: u64,
@@ -119,7 +170,11 @@ struct PruneResult {
 /// Execute system command
 pub async fn execute(args: SystemArgs, cli: &Cli) -> Result<()> {
     match args.command {
-        SystemCommands::Df { verbose, format } => df(cli, verbose, format).await,
+        SystemCommands::Df {
+            verbose,
+            format,
+            refresh,
+        } => df(cli, verbose, format, refresh).await,
         SystemCommands::Prune {
             all,
             volumes,
@@ -128,6 +183,9 @@ pub async fn execute(args: SystemArgs, cli: &Cli) -> Result<()> {
             filter,
         } => prune(cli, all, volumes, force, dry_run, filter).await,
         SystemCommands::Info => info(cli).await,
+        SystemCommands::Metrics { listen } => metrics(cli, listen).await,
+        SystemCommands::Quota { command } => quota(cli, command).await,
+        SystemCommands::Repair { dry_run, fix } => repair(cli, dry_run, fix).await,
         SystemCommands::Reset {
             force,
             include_config,
@@ -135,36 +193,11 @@ pub async fn execute(args: SystemArgs, cli: &Cli) -> Result<()> {
     }
 }
 
-async fn df(cli: &Cli, verbose: bool, format: OutputFormat) -> Result<()> {
+async fn df(cli: &Cli, verbose: bool, format: OutputFormat, refresh: bool) -> Result<()> {
     let state = StateManager::open(Path::new(&cli.db_path))?;
-
-    // Get counts and sizes from database
     let containers = state.list_containers(None)?;
-    let running_count = containers.iter().filter(|c| c.state == crate::engine::ContainerState::Running).count();
-
-    // Calculate disk usage (simplified - actual implementation would scan directories)
-    let usage = DiskUsage {
-        images: ResourceUsage {
-            total: 0, // Would query images table
-            active: 0,
-            size: 0,
-            reclaimable: 0,
-        },
-        containers: ResourceUsage {
-            total: containers.len() as u64,
-            active: running_count as u64,
-            size: 0, // Would calculate from container directories
-            reclaimable: 0,
-        },
-        volumes: ResourceUsage {
-            total: 0,
-            active: 0,
-            size: 0,
-            reclaimable: 0,
-        },
-        total_size: 0,
-        reclaimable: 0,
-    };
+
+    let usage = engine::disk_usage(&state, Path::new(&cli.root), refresh)?;
 
     match format {
         OutputFormat::Json => {
@@ -252,21 +285,147 @@ async fn df(cli: &Cli, verbose: bool, format: OutputFormat) -> Result<()> {
     Ok(())
 }
 
+/// A single parsed `--filter` predicate. Multiple filters AND-combine.
+#[derive(Debug, Clone)]
+enum PruneFilter {
+    /// `until=<duration|timestamp>` - only resources created before this
+    /// instant match.
+    Until(DateTime<Utc>),
+    /// `label=<key>` or `label=<key>=<value>`.
+    Label { key: String, value: Option<String> },
+    /// `dangling=true|false` - images only; untagged containers don't
+    /// exist as a concept, so this predicate is a no-op for containers.
+    Dangling(bool),
+}
+
+fn parse_prune_filters(filter: &[String]) -> Result<Vec<PruneFilter>> {
+    filter.iter().map(|raw| parse_prune_filter(raw)).collect()
+}
+
+fn parse_prune_filter(raw: &str) -> Result<PruneFilter> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("Invalid filter '{}' (expected key=value)", raw))?;
+
+    match key {
+        "until" => Ok(PruneFilter::Until(parse_until(value)?)),
+        "label" => Ok(match value.split_once('=') {
+            Some((k, v)) => PruneFilter::Label {
+                key: k.to_string(),
+                value: Some(v.to_string()),
+            },
+            None => PruneFilter::Label {
+                key: value.to_string(),
+                value: None,
+            },
+        }),
+        "dangling" => {
+            let dangling: bool = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid dangling value '{}' (expected true or false)", value))?;
+            Ok(PruneFilter::Dangling(dangling))
+        }
+        other => bail!("Unknown filter key '{}' (expected until, label, or dangling)", other),
+    }
+}
+
+/// Parses an `until=` value: a relative window (`24h`, `7d`, `30m`) or an
+/// absolute RFC3339 timestamp, returning the resulting cutoff instant.
+fn parse_until(value: &str) -> Result<DateTime<Utc>> {
+    if let Some(hours) = value.strip_suffix('h') {
+        let hours: i64 = hours.parse().with_context(|| format!("Invalid duration '{}'", value))?;
+        return Ok(Utc::now() - chrono::Duration::hours(hours));
+    }
+    if let Some(days) = value.strip_suffix('d') {
+        let days: i64 = days.parse().with_context(|| format!("Invalid duration '{}'", value))?;
+        return Ok(Utc::now() - chrono::Duration::days(days));
+    }
+    if let Some(minutes) = value.strip_suffix('m') {
+        let minutes: i64 = minutes.parse().with_context(|| format!("Invalid duration '{}'", value))?;
+        return Ok(Utc::now() - chrono::Duration::minutes(minutes));
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .with_context(|| {
+            format!(
+                "Invalid until= value '{}' (expected a duration like 24h/7d/30m or an RFC3339 timestamp)",
+                value
+            )
+        })
+}
+
+/// Parses a `created_at` column value, which is either SQLite's
+/// `CURRENT_TIMESTAMP` format or already RFC3339.
+fn parse_created_at(created_at: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(created_at) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    chrono::NaiveDateTime::parse_from_str(created_at, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|naive| DateTime::from_naive_utc_and_offset(naive, Utc))
+}
+
+/// Reads the `labels` object out of a container's stored config JSON blob,
+/// if it has one. No command currently writes labels, so this is empty in
+/// practice until `vordr run --label` lands - but the predicate is real
+/// and ready for it.
+fn container_labels(container: &ContainerInfo) -> std::collections::HashMap<String, String> {
+    let Some(config) = &container.config else {
+        return std::collections::HashMap::new();
+    };
+    let Ok(parsed) = serde_json::from_str::<serde_json::Value>(config) else {
+        return std::collections::HashMap::new();
+    };
+
+    parsed
+        .get("labels")
+        .and_then(|v| v.as_object())
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `container` satisfies every predicate in `filters` (AND).
+fn container_matches_filters(container: &ContainerInfo, filters: &[PruneFilter]) -> bool {
+    filters.iter().all(|filter| match filter {
+        PruneFilter::Until(cutoff) => match parse_created_at(&container.created_at) {
+            Some(created) => created < *cutoff,
+            None => false,
+        },
+        PruneFilter::Label { key, value } => {
+            let labels = container_labels(container);
+            match value {
+                Some(v) => labels.get(key) == Some(v),
+                None => labels.contains_key(key),
+            }
+        }
+        PruneFilter::Dangling(_) => true,
+    })
+}
+
 async fn prune(
     cli: &Cli,
     all: bool,
     volumes: bool,
     force: bool,
     dry_run: bool,
-    _filter: Vec<String>,
+    filter: Vec<String>,
 ) -> Result<()> {
     let state = StateManager::open(Path::new(&cli.db_path))?;
+    let filters = parse_prune_filters(&filter)?;
 
     // Find resources to prune
     let containers = state.list_containers(None)?;
     let stopped_containers: Vec<_> = containers
         .iter()
         .filter(|c| c.state != crate::engine::ContainerState::Running)
+        .filter(|c| container_matches_filters(c, &filters))
         .collect();
 
     // Calculate what would be pruned
@@ -280,6 +439,19 @@ async fn prune(
 
     if dry_run {
         println!("DRY RUN - No changes will be made\n");
+
+        for filter in &filters {
+            match filter {
+                PruneFilter::Until(cutoff) => println!("Filter: until (cutoff {})", cutoff.to_rfc3339()),
+                PruneFilter::Label { key, value: Some(v) } => println!("Filter: label {}={}", key, v),
+                PruneFilter::Label { key, value: None } => println!("Filter: label {}", key),
+                PruneFilter::Dangling(want) => println!("Filter: dangling={}", want),
+            }
+        }
+        if !filters.is_empty() {
+            println!();
+        }
+
         println!("Would remove:");
 
         if !stopped_containers.is_empty() {
@@ -395,6 +567,267 @@ async fn info(cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// `vordr system metrics` - Prometheus text-exposition snapshot of engine
+/// state, for scraping without shelling out to `df`/`ps`. With `--listen`
+/// it serves the same snapshot fresh on every request instead of printing
+/// once; the minimal HTTP loop mirrors the hand-rolled one in `cli::serve`
+/// since there's no HTTP crate in this build.
+async fn metrics(cli: &Cli, listen: Option<String>) -> Result<()> {
+    match listen {
+        Some(addr) => serve_metrics(cli, &addr).await,
+        None => {
+            let state = StateManager::open(Path::new(&cli.db_path))?;
+            print!("{}", render_metrics(cli, &state)?);
+            Ok(())
+        }
+    }
+}
+
+async fn serve_metrics(cli: &Cli, addr: &str) -> Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind metrics listener to {}", addr))?;
+    println!("Metrics listening on {}", addr);
+
+    let db_path = cli.db_path.clone();
+    let runtime = cli.runtime.clone();
+    let root = cli.root.clone();
+
+    loop {
+        let (mut stream, peer) = listener.accept().await?;
+        let db_path = db_path.clone();
+        let runtime = runtime.clone();
+        let root = root.clone();
+
+        tokio::spawn(async move {
+            // Drain and discard the request; every scrape gets the same
+            // response regardless of method or path.
+            let mut discard = [0u8; 1024];
+            let _ = stream.read(&mut discard).await;
+
+            let body = match StateManager::open(Path::new(&db_path))
+                .map_err(anyhow::Error::from)
+                .and_then(|state| render_metrics_raw(&db_path, &runtime, &root, &state))
+            {
+                Ok(body) => body,
+                Err(err) => format!("# failed to collect metrics: {}\n", err),
+            };
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            if let Err(err) = stream.write_all(response.as_bytes()).await {
+                warn!("Metrics connection from {} failed: {}", peer, err);
+            }
+            let _ = stream.flush().await;
+        });
+    }
+}
+
+fn render_metrics(cli: &Cli, state: &StateManager) -> Result<String> {
+    render_metrics_raw(&cli.db_path, &cli.runtime, &cli.root, state)
+}
+
+fn render_metrics_raw(_db_path: &str, _runtime: &str, root: &str, state: &StateManager) -> Result<String> {
+    let containers = state.list_containers(None)?;
+    let images = state.list_images()?;
+    let volumes = state.list_volumes()?;
+    let usage = engine::disk_usage(state, Path::new(root), false)?;
+
+    let mut out = String::new();
+
+    out.push_str("# HELP vordr_containers_total Number of containers by state\n");
+    out.push_str("# TYPE vordr_containers_total gauge\n");
+    for label in ["running", "stopped", "paused", "created", "creating"] {
+        let count = containers.iter().filter(|c| c.state.as_str() == label).count();
+        out.push_str(&format!(
+            "vordr_containers_total{{state=\"{}\"}} {}\n",
+            label, count
+        ));
+    }
+
+    out.push_str("# HELP vordr_images_total Number of images known to the engine\n");
+    out.push_str("# TYPE vordr_images_total gauge\n");
+    out.push_str(&format!("vordr_images_total {}\n", images.len()));
+
+    out.push_str("# HELP vordr_volumes_total Number of volumes known to the engine\n");
+    out.push_str("# TYPE vordr_volumes_total gauge\n");
+    out.push_str(&format!("vordr_volumes_total {}\n", volumes.len()));
+
+    out.push_str("# HELP vordr_disk_bytes On-disk bytes used per resource type\n");
+    out.push_str("# TYPE vordr_disk_bytes gauge\n");
+    out.push_str(&format!("vordr_disk_bytes{{type=\"images\"}} {}\n", usage.images.size));
+    out.push_str(&format!(
+        "vordr_disk_bytes{{type=\"containers\"}} {}\n",
+        usage.containers.size
+    ));
+    out.push_str(&format!("vordr_disk_bytes{{type=\"volumes\"}} {}\n", usage.volumes.size));
+
+    out.push_str("# HELP vordr_reclaimable_bytes Bytes reclaimable by `system prune`\n");
+    out.push_str("# TYPE vordr_reclaimable_bytes gauge\n");
+    out.push_str(&format!("vordr_reclaimable_bytes {}\n", usage.reclaimable));
+
+    out.push_str("# HELP vordr_build_info Build metadata; value is always 1\n");
+    out.push_str("# TYPE vordr_build_info gauge\n");
+    out.push_str(&format!(
+        "vordr_build_info{{version=\"{}\",gatekeeper=\"{}\"}} 1\n",
+        env!("CARGO_PKG_VERSION"),
+        crate::ffi::gatekeeper_version()
+    ));
+
+    Ok(out)
+}
+
+/// `vordr system quota` - set or list per-resource-type disk quotas,
+/// enforced at container/volume creation time via `engine::quota::enforce`.
+async fn quota(cli: &Cli, command: QuotaCommands) -> Result<()> {
+    let state = StateManager::open(Path::new(&cli.db_path))?;
+
+    match command {
+        QuotaCommands::Set { resource, max } => {
+            let max_bytes: ByteSize = max
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid size {:?} (expected e.g. 10GB, 500MB)", max))?;
+            let resource = resource.to_engine();
+            state.set_quota(resource.as_str(), max_bytes.0)?;
+            println!("Quota for {} set to {}", resource.as_str(), max_bytes);
+        }
+        QuotaCommands::Ls => {
+            let quotas = state.list_quotas()?;
+            if quotas.is_empty() {
+                println!("No quotas configured");
+                return Ok(());
+            }
+
+            let usage = engine::disk_usage(&state, Path::new(&cli.root), false)?;
+            println!("{:<12} {:>12} {:>12} {:>6}", "TYPE", "USED", "LIMIT", "%");
+            for (resource_type, max_bytes) in quotas {
+                let used = match resource_type.as_str() {
+                    "containers" => usage.containers.size,
+                    "volumes" => usage.volumes.size,
+                    "images" => usage.images.size,
+                    _ => 0,
+                };
+                let pct = if max_bytes > 0 { used * 100 / max_bytes } else { 0 };
+                println!(
+                    "{:<12} {:>12} {:>12} {:>5}%",
+                    resource_type,
+                    ByteSize(used).to_string(),
+                    ByteSize(max_bytes).to_string(),
+                    pct
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `vordr system repair` - offline reconciliation between the state DB
+/// and `cli.root`, for healing after an unclean shutdown. Three
+/// categories are checked: containers whose recorded PID is no longer
+/// alive (stale `running` state), bundle directories on disk with no
+/// matching DB row (orphans), and DB container rows whose bundle
+/// directory is missing. Usage counters are always rebuilt from the
+/// `system df` accounting subsystem when applying fixes, since a repair
+/// pass is exactly the kind of event that should invalidate any cache.
+async fn repair(cli: &Cli, dry_run: bool, fix: bool) -> Result<()> {
+    let apply = fix && !dry_run;
+    if !apply {
+        println!("DRY RUN - No changes will be made\n");
+    }
+
+    let state = StateManager::open(Path::new(&cli.db_path))?;
+    let root = Path::new(&cli.root);
+    let containers_dir = root.join("containers");
+
+    let containers = state.list_containers(None)?;
+
+    let stale_running: Vec<_> = containers
+        .iter()
+        .filter(|c| {
+            c.state == crate::engine::ContainerState::Running
+                && c.pid.is_some_and(|pid| !StateManager::process_is_alive(pid))
+        })
+        .collect();
+
+    let missing_bundle: Vec<_> = containers
+        .iter()
+        .filter(|c| !Path::new(&c.bundle_path).exists())
+        .collect();
+
+    let known_ids: std::collections::HashSet<&str> = containers.iter().map(|c| c.id.as_str()).collect();
+    let mut orphan_dirs = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(&containers_dir) {
+        for entry in entries.flatten() {
+            if let Some(name) = entry.file_name().to_str() {
+                if entry.path().is_dir() && !known_ids.contains(name) {
+                    orphan_dirs.push(entry.path());
+                }
+            }
+        }
+    }
+
+    println!("Stale running states (PID no longer alive): {}", stale_running.len());
+    for container in &stale_running {
+        println!(
+            "  - {} ({}, pid {:?})",
+            &container.id[..12.min(container.id.len())],
+            container.name,
+            container.pid
+        );
+    }
+
+    println!("Container rows with no bundle directory: {}", missing_bundle.len());
+    for container in &missing_bundle {
+        println!(
+            "  - {} ({}) -> {}",
+            &container.id[..12.min(container.id.len())],
+            container.name,
+            container.bundle_path
+        );
+    }
+
+    println!("Orphaned bundle directories with no DB row: {}", orphan_dirs.len());
+    for path in &orphan_dirs {
+        println!("  - {}", path.display());
+    }
+
+    if !apply {
+        println!("\nRun with --fix to apply corrections.");
+        return Ok(());
+    }
+
+    for container in &stale_running {
+        state.set_container_state(&container.id, crate::engine::ContainerState::Stopped, None)?;
+    }
+
+    let mut removed_dirs = 0u64;
+    for path in &orphan_dirs {
+        match std::fs::remove_dir_all(path) {
+            Ok(()) => removed_dirs += 1,
+            Err(err) => warn!("Failed to remove orphaned bundle directory {}: {}", path.display(), err),
+        }
+    }
+
+    // A repair pass is exactly the situation cached usage counters should
+    // not be trusted through, so force a full rescan.
+    engine::disk_usage(&state, root, true)?;
+
+    println!(
+        "\nRepaired: {} stale state(s), {} orphaned directory/directories removed, usage counters rebuilt",
+        stale_running.len(),
+        removed_dirs
+    );
+
+    Ok(())
+}
+
 async fn reset(cli: &Cli, force: bool, include_config: bool) -> Result<()> {
     println!("WARNING: This will delete ALL Vordr data:");
     println!("  - All containers (running and stopped)");
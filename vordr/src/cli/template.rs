@@ -0,0 +1,263 @@
+//! Minimal Go `text/template`-compatible evaluator for `--format` strings.
+//!
+//! `docker`/`podman`-style CLIs let users pass `--format '{{.Name}}'` and expect
+//! dotted field access, a handful of helper functions, and `{{range}}...{{end}}`
+//! loops over arrays. There's no template crate in this build, so this hand-rolls
+//! just enough of that subset: a tokenizer that splits on `{{`/`}}`, a recursive
+//! descent parser over the resulting actions, and an evaluator over `serde_json::Value`.
+
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("unterminated action: missing \"}}}}\"")]
+    UnterminatedAction,
+    #[error("unterminated {{range}}: missing matching {{end}}")]
+    UnterminatedRange,
+    #[error("unexpected {{end}} with no matching {{range}}")]
+    UnmatchedEnd,
+    #[error("unknown function {0:?}")]
+    UnknownFunction(String),
+    #[error("{0:?} is not an array, cannot range over it")]
+    NotAnArray(String),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Expr(Expr),
+    Range(Expr, Vec<Node>),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Field(Vec<String>),
+    Str(String),
+    Call(String, Vec<Expr>),
+}
+
+enum RawToken<'a> {
+    Text(&'a str),
+    Action(&'a str),
+}
+
+/// Renders `template` against `data`, supporting dotted field paths (`.State.Pid`),
+/// the `json`/`println`/`printf` functions, and `{{range}}...{{end}}` loops that
+/// rebind `.` to each element of the ranged-over array.
+pub fn render(template: &str, data: &Value) -> Result<String, TemplateError> {
+    let tokens = tokenize(template)?;
+    let (nodes, rest) = parse_nodes(&tokens, false)?;
+    if !rest.is_empty() {
+        return Err(TemplateError::UnmatchedEnd);
+    }
+    let mut out = String::new();
+    eval_nodes(&nodes, data, &mut out)?;
+    Ok(out)
+}
+
+fn tokenize(template: &str) -> Result<Vec<RawToken<'_>>, TemplateError> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(RawToken::Text(&rest[..start]));
+        }
+        let after_open = &rest[start + 2..];
+        let end = after_open.find("}}").ok_or(TemplateError::UnterminatedAction)?;
+        tokens.push(RawToken::Action(after_open[..end].trim()));
+        rest = &after_open[end + 2..];
+    }
+    if !rest.is_empty() {
+        tokens.push(RawToken::Text(rest));
+    }
+    Ok(tokens)
+}
+
+fn parse_nodes<'a>(
+    tokens: &'a [RawToken<'a>],
+    in_range: bool,
+) -> Result<(Vec<Node>, &'a [RawToken<'a>]), TemplateError> {
+    let mut nodes = Vec::new();
+    let mut rest = tokens;
+    loop {
+        match rest.first() {
+            None => {
+                if in_range {
+                    return Err(TemplateError::UnterminatedRange);
+                }
+                return Ok((nodes, rest));
+            }
+            Some(RawToken::Text(text)) => {
+                nodes.push(Node::Text((*text).to_string()));
+                rest = &rest[1..];
+            }
+            Some(RawToken::Action(action)) => {
+                if *action == "end" {
+                    if !in_range {
+                        return Err(TemplateError::UnmatchedEnd);
+                    }
+                    return Ok((nodes, &rest[1..]));
+                }
+                if let Some(range_expr) = action.strip_prefix("range ") {
+                    let expr = parse_expr(range_expr.trim())?;
+                    let (body, after) = parse_nodes(&rest[1..], true)?;
+                    nodes.push(Node::Range(expr, body));
+                    rest = after;
+                } else {
+                    nodes.push(Node::Expr(parse_expr(action)?));
+                    rest = &rest[1..];
+                }
+            }
+        }
+    }
+}
+
+fn parse_expr(action: &str) -> Result<Expr, TemplateError> {
+    let parts = split_args(action);
+    if parts.is_empty() {
+        return Ok(Expr::Field(Vec::new()));
+    }
+    if parts.len() == 1 && !matches!(parts[0].as_str(), "json" | "println" | "printf") {
+        return Ok(parse_arg(&parts[0]));
+    }
+    let (name, rest) = parts.split_first().expect("checked non-empty above");
+    let args = rest.iter().map(|a| parse_arg(a)).collect();
+    Ok(Expr::Call(name.clone(), args))
+}
+
+fn parse_arg(token: &str) -> Expr {
+    if let Some(stripped) = token.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Expr::Str(stripped.to_string());
+    }
+    Expr::Field(parse_field_path(token))
+}
+
+fn parse_field_path(token: &str) -> Vec<String> {
+    token
+        .trim_start_matches('.')
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Splits a template action into whitespace-separated arguments, keeping
+/// double-quoted strings (which may contain spaces) intact.
+fn split_args(action: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = action.chars().peekable();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+fn eval_nodes(nodes: &[Node], ctx: &Value, out: &mut String) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Expr(expr) => out.push_str(&display_value(&eval(expr, ctx)?)),
+            Node::Range(expr, body) => {
+                let value = eval(expr, ctx)?;
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| TemplateError::NotAnArray(value.to_string()))?;
+                for item in items {
+                    eval_nodes(body, item, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn eval(expr: &Expr, ctx: &Value) -> Result<Value, TemplateError> {
+    match expr {
+        Expr::Field(path) => Ok(resolve_field(ctx, path)),
+        Expr::Str(s) => Ok(Value::String(s.clone())),
+        Expr::Call(name, args) => {
+            let values: Vec<Value> = args.iter().map(|a| eval(a, ctx)).collect::<Result<_, _>>()?;
+            match name.as_str() {
+                "json" => {
+                    let arg = values.first().cloned().unwrap_or(Value::Null);
+                    Ok(Value::String(serde_json::to_string(&arg).unwrap_or_default()))
+                }
+                "println" => {
+                    let line = values.iter().map(display_value).collect::<Vec<_>>().join(" ");
+                    Ok(Value::String(format!("{}\n", line)))
+                }
+                "printf" => Ok(Value::String(printf(&values))),
+                other => Err(TemplateError::UnknownFunction(other.to_string())),
+            }
+        }
+    }
+}
+
+fn resolve_field(ctx: &Value, path: &[String]) -> Value {
+    let mut current = ctx;
+    for key in path {
+        match current.get(key) {
+            Some(next) => current = next,
+            None => return Value::Null,
+        }
+    }
+    current.clone()
+}
+
+fn printf(values: &[Value]) -> String {
+    let Some(fmt) = values.first().and_then(Value::as_str) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    let mut args = values[1..].iter();
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => out.push('%'),
+            Some('s') | Some('v') | Some('d') => {
+                if let Some(arg) = args.next() {
+                    out.push_str(&display_value(arg));
+                }
+            }
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// Stringifies a `Value` the way Go's `text/template` would: plain strings
+/// unquoted, scalars via their natural display form, and missing/null as empty.
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
@@ -3,10 +3,12 @@
 
 use anyhow::{Context, Result};
 use clap::Subcommand;
-use std::path::Path;
+use dialoguer::Confirm;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use crate::cli::Cli;
-use crate::engine::StateManager;
+use crate::cli::{Cli, OutputFormat};
+use crate::engine::{build_driver, quota, StateManager, VolumeContext};
 
 #[derive(Subcommand, Debug)]
 pub enum VolumeCommands {
@@ -57,13 +59,17 @@ pub enum VolumeCommands {
 
     /// Remove unused volumes
     Prune {
-        /// Remove all unused volumes, not just anonymous ones
+        /// Remove all unused volumes, not just anonymous (unlabeled) ones
         #[arg(short, long)]
         all: bool,
 
         /// Do not prompt for confirmation
         #[arg(short, long)]
         force: bool,
+
+        /// Filter volumes (e.g. driver=local, label=<key>[=<value>])
+        #[arg(long)]
+        filter: Option<String>,
     },
 }
 
@@ -75,10 +81,10 @@ pub async fn execute(cmd: VolumeCommands, cli: &Cli) -> Result<()> {
             label,
             opt,
         } => create_volume(&name, &driver, &label, &opt, cli).await,
-        VolumeCommands::Ls { quiet, filter: _ } => list_volumes(quiet, cli).await,
+        VolumeCommands::Ls { quiet, filter } => list_volumes(quiet, filter, cli).await,
         VolumeCommands::Rm { volume, force: _ } => remove_volume(&volume, cli).await,
         VolumeCommands::Inspect { volume } => inspect_volume(&volume, cli).await,
-        VolumeCommands::Prune { all: _, force: _ } => prune_volumes(cli).await,
+        VolumeCommands::Prune { all, force, filter } => prune_volumes(all, force, filter, cli).await,
     }
 }
 
@@ -96,46 +102,38 @@ async fn create_volume(
 
     let state = StateManager::open(db_path).context("Failed to open state database")?;
 
+    quota::enforce(&state, Path::new(&cli.root), quota::QuotaResource::Volumes)
+        .context("volume quota check failed")?;
+
     let volume_id = uuid::Uuid::new_v4().to_string();
 
-    // Create mountpoint
+    // Mountpoint used by the `local` driver; other drivers may ignore it
+    // until the volume is actually mounted into a container.
     let root_path = Path::new(&cli.root);
     let mountpoint = root_path.join("volumes").join(name);
-    std::fs::create_dir_all(&mountpoint).context("Failed to create volume mountpoint")?;
+
+    let options_map = parse_kv_pairs(options);
+    let backend = build_driver(driver).with_context(|| format!("unsupported volume driver {:?}", driver))?;
+    backend
+        .create(&VolumeContext {
+            name: name.to_string(),
+            mountpoint: mountpoint.clone(),
+            options: options_map.clone(),
+        })
+        .with_context(|| format!("Failed to create volume with driver {:?}", driver))?;
 
     // Parse labels and options to JSON
     let labels_json = if labels.is_empty() {
         None
     } else {
-        let map: std::collections::HashMap<String, String> = labels
-            .iter()
-            .filter_map(|l| {
-                let parts: Vec<&str> = l.splitn(2, '=').collect();
-                if parts.len() == 2 {
-                    Some((parts[0].to_string(), parts[1].to_string()))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let map = parse_kv_pairs(labels);
         Some(serde_json::to_string(&map).unwrap())
     };
 
-    let options_json = if options.is_empty() {
+    let options_json = if options_map.is_empty() {
         None
     } else {
-        let map: std::collections::HashMap<String, String> = options
-            .iter()
-            .filter_map(|o| {
-                let parts: Vec<&str> = o.splitn(2, '=').collect();
-                if parts.len() == 2 {
-                    Some((parts[0].to_string(), parts[1].to_string()))
-                } else {
-                    None
-                }
-            })
-            .collect();
-        Some(serde_json::to_string(&map).unwrap())
+        Some(serde_json::to_string(&options_map).unwrap())
     };
 
     state.create_volume(
@@ -151,22 +149,40 @@ async fn create_volume(
     Ok(())
 }
 
-async fn list_volumes(quiet: bool, cli: &Cli) -> Result<()> {
+async fn list_volumes(quiet: bool, filter: Option<String>, cli: &Cli) -> Result<()> {
     let db_path = Path::new(&cli.db_path);
 
     if !db_path.exists() {
-        if quiet {
-            return Ok(());
+        if cli.format == OutputFormat::Json {
+            println!("[]");
+        } else if !quiet {
+            println!("DRIVER              VOLUME NAME");
         }
-        println!("DRIVER              VOLUME NAME");
         return Ok(());
     }
 
     let state = StateManager::open(db_path).context("Failed to open state database")?;
 
-    let volumes = state.list_volumes()?;
+    let filters = parse_volume_filters(filter.as_deref())?;
+    let volumes: Vec<_> = state
+        .list_volumes()?
+        .into_iter()
+        .filter(|volume| volume_matches_filters(volume, &filters))
+        .collect();
 
-    if quiet {
+    if cli.format == OutputFormat::Json {
+        let entries: Vec<serde_json::Value> = volumes
+            .iter()
+            .map(|volume| {
+                serde_json::json!({
+                    "Name": volume.name,
+                    "Driver": volume.driver,
+                    "Mountpoint": volume.mountpoint,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if quiet {
         for volume in &volumes {
             println!("{}", volume.name);
         }
@@ -186,12 +202,17 @@ async fn remove_volume(volume_name: &str, cli: &Cli) -> Result<()> {
         StateManager::open(Path::new(&cli.db_path)).context("Failed to open state database")?;
 
     let volume = state.get_volume(volume_name)?;
-
-    // Remove mountpoint
-    let mountpoint = Path::new(&volume.mountpoint);
-    if mountpoint.exists() {
-        std::fs::remove_dir_all(mountpoint).context("Failed to remove volume mountpoint")?;
-    }
+    let options_map = parse_stored_options(volume.options.as_deref());
+
+    let backend = build_driver(&volume.driver)
+        .with_context(|| format!("unsupported volume driver {:?}", volume.driver))?;
+    backend
+        .remove(&VolumeContext {
+            name: volume.name.clone(),
+            mountpoint: PathBuf::from(&volume.mountpoint),
+            options: options_map,
+        })
+        .with_context(|| format!("Failed to remove volume with driver {:?}", volume.driver))?;
 
     state.delete_volume(&volume.id)?;
 
@@ -211,27 +232,251 @@ async fn inspect_volume(volume_name: &str, cli: &Cli) -> Result<()> {
         .and_then(|l| serde_json::from_str(l).ok())
         .unwrap_or(serde_json::json!({}));
 
-    let options: serde_json::Value = volume
-        .options
-        .as_ref()
-        .and_then(|o| serde_json::from_str(o).ok())
-        .unwrap_or(serde_json::json!({}));
+    let options_map = parse_stored_options(volume.options.as_deref());
+
+    let backend = build_driver(&volume.driver)
+        .with_context(|| format!("unsupported volume driver {:?}", volume.driver))?;
+    let driver_details = backend.inspect(&VolumeContext {
+        name: volume.name.clone(),
+        mountpoint: PathBuf::from(&volume.mountpoint),
+        options: options_map.clone(),
+    });
 
     let output = serde_json::json!({
         "Name": volume.name,
         "Driver": volume.driver,
         "Mountpoint": volume.mountpoint,
         "Labels": labels,
-        "Options": options,
+        "Options": options_map,
+        "DriverOpts": driver_details,
         "CreatedAt": volume.created_at,
         "Scope": "local",
     });
 
-    println!("{}", serde_json::to_string_pretty(&output)?);
+    if cli.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Name:       {}", volume.name);
+        println!("Driver:     {}", volume.driver);
+        println!("Mountpoint: {}", volume.mountpoint);
+        println!("Created:    {}", volume.created_at);
+    }
     Ok(())
 }
 
-async fn prune_volumes(_cli: &Cli) -> Result<()> {
-    println!("Volume pruning not yet implemented");
+/// Removes volumes with a refcount of zero (not currently mounted into any
+/// container). Without `--all`, only volumes with no labels are considered
+/// "anonymous" and eligible; `--all` also removes labeled, named volumes
+/// that simply aren't in use.
+async fn prune_volumes(all: bool, force: bool, filter: Option<String>, cli: &Cli) -> Result<()> {
+    let db_path = Path::new(&cli.db_path);
+
+    if !db_path.exists() {
+        println!("Total reclaimed space: {}", format_size(0));
+        return Ok(());
+    }
+
+    let state = StateManager::open(db_path).context("Failed to open state database")?;
+    // Startup already recomputed refcounts from live mounts, but a prune
+    // run can follow a long-lived process - recompute again so a container
+    // torn down moments ago is reflected before we decide what's unused.
+    state.recompute_volume_refcounts()?;
+
+    let filters = parse_volume_filters(filter.as_deref())?;
+
+    let candidates: Vec<_> = state
+        .list_volumes()?
+        .into_iter()
+        .filter(|volume| volume.refcount <= 0)
+        .filter(|volume| all || !has_labels(volume))
+        .filter(|volume| volume_matches_filters(volume, &filters))
+        .collect();
+
+    if candidates.is_empty() {
+        println!("Total reclaimed space: {}", format_size(0));
+        return Ok(());
+    }
+
+    if !force {
+        println!("WARNING! This will remove {} volume(s).", candidates.len());
+        let confirmed = Confirm::new()
+            .with_prompt("Are you sure you want to continue?")
+            .default(false)
+            .interact()?;
+
+        if !confirmed {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    let mut reclaimed: i64 = 0;
+    for volume in &candidates {
+        let options_map = parse_stored_options(volume.options.as_deref());
+        let backend = build_driver(&volume.driver)
+            .with_context(|| format!("unsupported volume driver {:?}", volume.driver))?;
+        // Measured before `remove`, which deletes a local mountpoint.
+        let size = dir_size(Path::new(&volume.mountpoint));
+        backend
+            .remove(&VolumeContext {
+                name: volume.name.clone(),
+                mountpoint: PathBuf::from(&volume.mountpoint),
+                options: options_map,
+            })
+            .with_context(|| format!("Failed to remove volume with driver {:?}", volume.driver))?;
+
+        reclaimed += size;
+        state.delete_volume(&volume.id)?;
+        println!("Deleted: {}", volume.name);
+    }
+
+    println!("Total reclaimed space: {}", format_size(reclaimed));
     Ok(())
 }
+
+/// Parses `volume ls`/`volume prune --filter`, a comma-separated list of
+/// `key=value` predicates: `dangling=true`/`dangling=false` (refcount
+/// zero or not), `driver=<name>`, and `label=<key>[=<value>]`.
+#[derive(Default)]
+struct VolumeFilters {
+    dangling: Option<bool>,
+    driver: Option<String>,
+    labels: Vec<(String, Option<String>)>,
+}
+
+fn parse_volume_filters(filter: Option<&str>) -> Result<VolumeFilters> {
+    let mut filters = VolumeFilters::default();
+
+    let Some(filter) = filter else {
+        return Ok(filters);
+    };
+
+    for entry in filter.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        if let Some(value) = entry.strip_prefix("dangling=") {
+            filters.dangling = Some(value.parse().with_context(|| {
+                format!("Invalid filter '{}' (expected dangling=true or dangling=false)", entry)
+            })?);
+        } else if let Some(value) = entry.strip_prefix("driver=") {
+            filters.driver = Some(value.to_string());
+        } else if let Some(value) = entry.strip_prefix("label=") {
+            filters.labels.push(match value.split_once('=') {
+                Some((k, v)) => (k.to_string(), Some(v.to_string())),
+                None => (value.to_string(), None),
+            });
+        } else {
+            anyhow::bail!(
+                "Unsupported filter '{}' (expected dangling, driver, or label)",
+                entry
+            );
+        }
+    }
+
+    Ok(filters)
+}
+
+fn volume_matches_filters(volume: &crate::engine::VolumeInfo, filters: &VolumeFilters) -> bool {
+    if let Some(dangling) = filters.dangling {
+        if (volume.refcount <= 0) != dangling {
+            return false;
+        }
+    }
+
+    if let Some(driver) = &filters.driver {
+        if &volume.driver != driver {
+            return false;
+        }
+    }
+
+    if !filters.labels.is_empty() {
+        let labels = volume_labels(volume);
+        for (key, expected) in &filters.labels {
+            let actual = labels.get(key).and_then(|v| v.as_str());
+            let matched = match (expected, actual) {
+                (Some(expected), Some(actual)) => expected == actual,
+                (None, Some(_)) => true,
+                _ => false,
+            };
+            if !matched {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn volume_labels(volume: &crate::engine::VolumeInfo) -> serde_json::Value {
+    volume
+        .labels
+        .as_deref()
+        .and_then(|l| serde_json::from_str(l).ok())
+        .unwrap_or_else(|| serde_json::json!({}))
+}
+
+fn has_labels(volume: &crate::engine::VolumeInfo) -> bool {
+    volume_labels(volume)
+        .as_object()
+        .map(|o| !o.is_empty())
+        .unwrap_or(false)
+}
+
+/// Recursively sums file sizes under `path`, used to report reclaimed
+/// space for the `local` driver. Best-effort: unreadable entries (a
+/// remote-backed driver's mountpoint not populated locally, permission
+/// errors, ...) are skipped rather than failing the whole prune.
+fn dir_size(path: &Path) -> i64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+
+    if metadata.is_file() {
+        return metadata.len() as i64;
+    }
+
+    if !metadata.is_dir() {
+        return 0;
+    }
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| dir_size(&entry.path()))
+        .sum()
+}
+
+fn format_size(bytes: i64) -> String {
+    const KB: i64 = 1024;
+    const MB: i64 = KB * 1024;
+    const GB: i64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.1}GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1}MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1}KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+fn parse_kv_pairs(pairs: &[String]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .filter_map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next()?;
+            let value = parts.next()?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn parse_stored_options(options: Option<&str>) -> HashMap<String, String> {
+    options
+        .and_then(|o| serde_json::from_str(o).ok())
+        .unwrap_or_default()
+}
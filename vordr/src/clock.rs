@@ -0,0 +1,252 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Injectable time source
+//!
+//! Code that embeds timestamps (tokens, audit records, nonces) should take
+//! a `&dyn Clock` rather than calling `SystemTime::now()` directly, so
+//! tests can freeze time to a known instant instead of racing the wall
+//! clock.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+pub enum ClockError {
+    #[error("clock reads {actual}s since epoch, before the configured floor of {floor}s")]
+    BeforeFloor { actual: i64, floor: i64 },
+}
+
+/// Signed Unix epoch seconds: positive after 1970-01-01, negative if the
+/// clock has skewed before it. A bare `u64` can't represent that and
+/// `duration_since(UNIX_EPOCH).unwrap_or_default()` silently collapses a
+/// backward clock to `0` with no signal - a dangerous failure mode for
+/// anything security-relevant (tokens, nonces, audit timestamps).
+/// Saturates at `i64::MAX`/`i64::MIN` rather than overflowing.
+pub fn epoch_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => i64::try_from(duration.as_secs()).unwrap_or(i64::MAX),
+        Err(e) => i64::try_from(e.duration().as_secs())
+            .map(|secs| -secs)
+            .unwrap_or(i64::MIN),
+    }
+}
+
+/// Like [`epoch_seconds`] but rejects any timestamp older than `floor`
+/// (signed Unix seconds). Replay-sensitive paths should use this instead
+/// so a misconfigured or skewed clock fails loudly rather than accepting a
+/// suspiciously old timestamp.
+pub fn epoch_seconds_checked(time: SystemTime, floor: i64) -> Result<i64, ClockError> {
+    let actual = epoch_seconds(time);
+    if actual < floor {
+        Err(ClockError::BeforeFloor { actual, floor })
+    } else {
+        Ok(actual)
+    }
+}
+
+/// Wraps `SystemTime::now()` - the clock used outside of tests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Always returns the instant it was constructed with, for deterministic
+/// unit tests and simulations.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// Signed Unix epoch milliseconds, mirroring [`epoch_seconds`] at finer
+/// resolution - negative if `time` precedes 1970-01-01.
+pub fn epoch_millis(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => i64::try_from(duration.as_millis()).unwrap_or(i64::MAX),
+        Err(e) => i64::try_from(e.duration().as_millis())
+            .map(|millis| -millis)
+            .unwrap_or(i64::MIN),
+    }
+}
+
+/// One reading from a [`MonotonicClock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonotonicTick {
+    /// What the wrapped clock actually reported, in epoch milliseconds.
+    pub raw_millis: i64,
+    /// What was handed out. Equal to `raw_millis` unless a correction was
+    /// needed to keep the sequence strictly increasing.
+    pub issued_millis: i64,
+    /// Whether `issued_millis` had to be pulled forward of `raw_millis` -
+    /// signals a clock anomaly (backward NTP step, or two calls within the
+    /// same millisecond) worth logging.
+    pub corrected: bool,
+}
+
+/// Wraps a [`Clock`] to guarantee a strictly increasing sequence of
+/// millisecond epoch values, even across backward wall-clock adjustments.
+///
+/// Anything that assumes monotonic issuance (nonces, sequence-tagged audit
+/// entries, rotating keys) should read timestamps through
+/// [`MonotonicClock::next_millis`] instead of the wrapped clock directly -
+/// an NTP step backward would otherwise hand out a timestamp smaller than
+/// one already issued.
+pub struct MonotonicClock<C> {
+    inner: C,
+    last_issued_millis: std::sync::Mutex<i64>,
+}
+
+impl<C: Clock> MonotonicClock<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            last_issued_millis: std::sync::Mutex::new(i64::MIN),
+        }
+    }
+
+    /// Returns a tick whose `issued_millis` is strictly greater than every
+    /// `issued_millis` this instance has previously returned, correcting
+    /// forward when the wrapped clock goes backward or repeats.
+    pub fn next_millis(&self) -> MonotonicTick {
+        let raw_millis = epoch_millis(self.inner.now());
+        let mut last = self.last_issued_millis.lock().unwrap();
+        let floor = last.saturating_add(1);
+        let issued_millis = raw_millis.max(floor);
+        let corrected = issued_millis != raw_millis;
+        *last = issued_millis;
+
+        MonotonicTick {
+            raw_millis,
+            issued_millis,
+            corrected,
+        }
+    }
+}
+
+impl<C: Clock> Clock for MonotonicClock<C> {
+    fn now(&self) -> SystemTime {
+        let issued = self.next_millis().issued_millis;
+        if issued >= 0 {
+            UNIX_EPOCH + std::time::Duration::from_millis(issued as u64)
+        } else {
+            UNIX_EPOCH - std::time::Duration::from_millis((-issued) as u64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn fixed_clock_returns_set_instant() {
+        let instant = UNIX_EPOCH + Duration::from_secs(1_707_553_108);
+        let clock = FixedClock(instant);
+        assert_eq!(clock.now(), instant);
+        assert_eq!(clock.now(), instant); // repeated calls don't advance
+    }
+
+    #[test]
+    fn system_clock_does_not_go_backwards() {
+        let clock = SystemClock;
+        let first = clock.now();
+        let second = clock.now();
+        assert!(second >= first);
+    }
+
+    #[test]
+    fn epoch_seconds_is_positive_after_epoch() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_707_553_108);
+        assert_eq!(epoch_seconds(time), 1_707_553_108);
+    }
+
+    #[test]
+    fn epoch_seconds_is_negative_before_epoch() {
+        let time = UNIX_EPOCH - Duration::from_secs(3600);
+        assert_eq!(epoch_seconds(time), -3600);
+    }
+
+    #[test]
+    fn epoch_seconds_checked_rejects_clock_before_floor() {
+        let time = UNIX_EPOCH - Duration::from_secs(3600);
+        let err = epoch_seconds_checked(time, 0).unwrap_err();
+        assert!(matches!(err, ClockError::BeforeFloor { actual: -3600, floor: 0 }));
+    }
+
+    #[test]
+    fn epoch_seconds_checked_accepts_time_at_or_after_floor() {
+        let time = UNIX_EPOCH + Duration::from_secs(100);
+        assert_eq!(epoch_seconds_checked(time, 100).unwrap(), 100);
+    }
+
+    #[derive(Clone)]
+    struct SteppingClock(std::sync::Arc<std::sync::Mutex<SystemTime>>);
+
+    impl SteppingClock {
+        fn new(time: SystemTime) -> Self {
+            Self(std::sync::Arc::new(std::sync::Mutex::new(time)))
+        }
+
+        fn set(&self, time: SystemTime) {
+            *self.0.lock().unwrap() = time;
+        }
+    }
+
+    impl Clock for SteppingClock {
+        fn now(&self) -> SystemTime {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn monotonic_clock_passes_through_when_clock_advances() {
+        let stepping = SteppingClock::new(UNIX_EPOCH + Duration::from_millis(1_000));
+        let mono = MonotonicClock::new(stepping);
+
+        let first = mono.next_millis();
+        assert_eq!(first.raw_millis, 1_000);
+        assert_eq!(first.issued_millis, 1_000);
+        assert!(!first.corrected);
+    }
+
+    #[test]
+    fn monotonic_clock_corrects_backward_jump() {
+        let stepping = SteppingClock::new(UNIX_EPOCH + Duration::from_millis(2_000));
+        let mono = MonotonicClock::new(stepping.clone());
+
+        let first = mono.next_millis();
+        assert_eq!(first.issued_millis, 2_000);
+        assert!(!first.corrected);
+
+        stepping.set(UNIX_EPOCH + Duration::from_millis(1_000));
+        let second = mono.next_millis();
+        assert_eq!(second.raw_millis, 1_000);
+        assert_eq!(second.issued_millis, 2_001);
+        assert!(second.corrected);
+    }
+
+    #[test]
+    fn monotonic_clock_is_strictly_increasing_for_repeated_calls() {
+        let stepping = SteppingClock::new(UNIX_EPOCH + Duration::from_millis(500));
+        let mono = MonotonicClock::new(stepping);
+
+        let a = mono.next_millis();
+        let b = mono.next_millis();
+        let c = mono.next_millis();
+        assert!(a.issued_millis < b.issued_millis);
+        assert!(b.issued_millis < c.issued_millis);
+        assert!(b.corrected);
+        assert!(c.corrected);
+    }
+}
@@ -1,6 +1,7 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
 //! OCI runtime configuration generation
 
+use caps::Capability;
 use oci_spec::runtime::{
     LinuxBuilder, LinuxCapabilitiesBuilder, LinuxNamespace, LinuxNamespaceBuilder,
     LinuxNamespaceType, MountBuilder, ProcessBuilder, RootBuilder, Spec, SpecBuilder, UserBuilder,
@@ -45,6 +46,10 @@ pub struct OciConfigBuilder {
     cap_add: Vec<String>,
     /// Capabilities to drop
     cap_drop: Vec<String>,
+    /// Set by `keep_capabilities`: when present, the capability set starts
+    /// from this list instead of the privileged/unprivileged defaults, and
+    /// `cap_drop` plays no part (there's nothing left to drop from).
+    keep_caps: Option<Vec<String>>,
     /// Additional mounts
     mounts: Vec<MountSpec>,
     /// Namespaces to create
@@ -53,6 +58,8 @@ pub struct OciConfigBuilder {
     privileged: bool,
     /// No new privileges
     no_new_privileges: bool,
+    /// Healthcheck probe, if one was configured
+    healthcheck: Option<HealthCheckSpec>,
 }
 
 /// Mount specification
@@ -64,12 +71,73 @@ pub struct MountSpec {
     pub options: Vec<String>,
 }
 
+/// The annotation key [`OciConfigBuilder::healthcheck`] stores its spec
+/// under in the generated bundle `config.json`. The OCI runtime spec has no
+/// native concept of a healthcheck, so it travels as an annotation, the
+/// same way Docker's own (non-standard) `HEALTHCHECK` is carried outside
+/// the process/root/mounts fields - see [`crate::engine::lifecycle`] for the
+/// code that reads it back out to drive probing.
+pub const HEALTHCHECK_ANNOTATION: &str = "com.vordr.healthcheck";
+
+/// A container healthcheck probe, modeled on Docker's `HEALTHCHECK`
+/// instruction: run `test` inside the container on a fixed `interval`,
+/// give it up to `timeout` to finish, and don't start judging it
+/// unhealthy until `start_period` has elapsed (so a slow-starting service
+/// isn't marked unhealthy before it's even had a chance to come up).
+/// `retries` consecutive failures (after the start period) are required
+/// before the rolling status flips to `Unhealthy` - see
+/// [`crate::engine::state::HealthStatus`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HealthCheckSpec {
+    /// Command to run inside the container to probe health
+    pub test: Vec<String>,
+    /// How often to run the probe, in seconds
+    pub interval_secs: u64,
+    /// How long to let a single probe run before treating it as a
+    /// failure, in seconds
+    pub timeout_secs: u64,
+    /// Consecutive failures required to transition to `Unhealthy`
+    pub retries: u32,
+    /// Grace period after container start during which failures don't
+    /// count against `retries`, in seconds
+    pub start_period_secs: u64,
+}
+
+impl HealthCheckSpec {
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.interval_secs)
+    }
+
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_secs)
+    }
+
+    pub fn start_period(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.start_period_secs)
+    }
+}
+
 impl Default for OciConfigBuilder {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Normalizes `cap` to its `CAP_`-prefixed form and checks it against the
+/// `caps` crate's `Capability` enum, so a typo (or a name the running
+/// kernel's headers don't know about) is rejected here instead of
+/// silently producing an OCI spec with a bogus capability string in it.
+fn validate_capability(cap: &str) -> Result<String, ConfigError> {
+    let name = if cap.starts_with("CAP_") {
+        cap.to_uppercase()
+    } else {
+        format!("CAP_{}", cap.to_uppercase())
+    };
+    name.parse::<Capability>()
+        .map_err(|_| ConfigError::Invalid(format!("unknown capability: {}", name)))?;
+    Ok(name)
+}
+
 impl OciConfigBuilder {
     /// Create a new OCI config builder with secure defaults
     pub fn new() -> Self {
@@ -88,6 +156,7 @@ impl OciConfigBuilder {
             readonly_rootfs: false,
             cap_add: Vec::new(),
             cap_drop: Vec::new(),
+            keep_caps: None,
             mounts: Vec::new(),
             namespaces: vec![
                 LinuxNamespaceType::Pid,
@@ -98,6 +167,7 @@ impl OciConfigBuilder {
             ],
             privileged: false,
             no_new_privileges: true,
+            healthcheck: None,
         }
     }
 
@@ -176,16 +246,34 @@ impl OciConfigBuilder {
         self
     }
 
-    /// Add a capability
-    pub fn add_capability(mut self, cap: impl Into<String>) -> Self {
-        self.cap_add.push(cap.into());
-        self
+    /// Add a capability. Fails if `cap` isn't a capability name the `caps`
+    /// crate recognizes.
+    pub fn add_capability(mut self, cap: impl Into<String>) -> Result<Self, ConfigError> {
+        let name = validate_capability(&cap.into())?;
+        self.cap_add.push(name);
+        Ok(self)
     }
 
-    /// Drop a capability
-    pub fn drop_capability(mut self, cap: impl Into<String>) -> Self {
-        self.cap_drop.push(cap.into());
-        self
+    /// Drop a capability. Fails if `cap` isn't a capability name the `caps`
+    /// crate recognizes.
+    pub fn drop_capability(mut self, cap: impl Into<String>) -> Result<Self, ConfigError> {
+        let name = validate_capability(&cap.into())?;
+        self.cap_drop.push(name);
+        Ok(self)
+    }
+
+    /// Starts the capability set from empty instead of the
+    /// privileged/unprivileged defaults, adding only `caps` - the inverse
+    /// of the default drop-based model, for building least-privilege
+    /// containers. `cap_add` still applies on top of this set; `cap_drop`
+    /// is ignored once this is set.
+    pub fn keep_capabilities(mut self, caps: Vec<String>) -> Result<Self, ConfigError> {
+        let mut kept = Vec::with_capacity(caps.len());
+        for cap in caps {
+            kept.push(validate_capability(&cap)?);
+        }
+        self.keep_caps = Some(kept);
+        Ok(self)
     }
 
     /// Add a mount
@@ -194,6 +282,14 @@ impl OciConfigBuilder {
         self
     }
 
+    /// Configure a healthcheck probe, carried through to the bundle
+    /// `config.json` as a [`HEALTHCHECK_ANNOTATION`] annotation for
+    /// [`crate::engine::lifecycle::ContainerLifecycle`] to read back.
+    pub fn healthcheck(mut self, healthcheck: HealthCheckSpec) -> Self {
+        self.healthcheck = Some(healthcheck);
+        self
+    }
+
     /// Set privileged mode
     pub fn privileged(mut self, privileged: bool) -> Self {
         self.privileged = privileged;
@@ -220,13 +316,27 @@ impl OciConfigBuilder {
             .map_err(|e| ConfigError::OciSpec(e.to_string()))?;
 
         // Build capabilities
-        let default_caps = self.get_default_capabilities();
+        let default_caps = self.get_default_capabilities()?;
+        let process_bounding = caps::read(None, caps::CapSet::Bounding)
+            .map_err(|e| ConfigError::Invalid(format!("failed to read process bounding capability set: {}", e)))?;
+        // Effective/permitted can never exceed what this process itself
+        // holds in its bounding set, no matter what was requested.
+        let grantable: Vec<String> = default_caps
+            .iter()
+            .filter(|name| {
+                name.parse::<Capability>()
+                    .map(|cap| process_bounding.contains(&cap))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+
         let capabilities = LinuxCapabilitiesBuilder::default()
             .bounding(default_caps.clone())
-            .effective(default_caps.clone())
-            .inheritable(default_caps.clone())
-            .permitted(default_caps.clone())
-            .ambient(default_caps)
+            .effective(grantable.clone())
+            .inheritable(default_caps)
+            .permitted(grantable.clone())
+            .ambient(grantable)
             .build()
             .map_err(|e| ConfigError::OciSpec(e.to_string()))?;
 
@@ -296,6 +406,14 @@ impl OciConfigBuilder {
             spec_builder.hostname(hostname);
         }
 
+        if let Some(healthcheck) = self.healthcheck {
+            let encoded = serde_json::to_string(&healthcheck)
+                .map_err(|e| ConfigError::OciSpec(e.to_string()))?;
+            let mut annotations = std::collections::HashMap::new();
+            annotations.insert(HEALTHCHECK_ANNOTATION.to_string(), encoded);
+            spec_builder.annotations(annotations);
+        }
+
         spec_builder
             .build()
             .map_err(|e| ConfigError::OciSpec(e.to_string()))
@@ -310,7 +428,37 @@ impl OciConfigBuilder {
         Ok(())
     }
 
-    fn get_default_capabilities(&self) -> Vec<String> {
+    fn get_default_capabilities(&self) -> Result<Vec<String>, ConfigError> {
+        let mut caps = self.base_capabilities();
+
+        // Add requested capabilities
+        for cap in &self.cap_add {
+            let cap_name = validate_capability(cap)?;
+            if !caps.contains(&cap_name) {
+                caps.push(cap_name);
+            }
+        }
+
+        // Remove dropped capabilities (meaningless once `keep_capabilities`
+        // already started the set from empty and built it back up)
+        if self.keep_caps.is_none() {
+            for cap in &self.cap_drop {
+                let cap_name = validate_capability(cap)?;
+                caps.retain(|c| c != &cap_name);
+            }
+        }
+
+        Ok(caps)
+    }
+
+    /// The capability set before `cap_add`/`cap_drop` are applied: the
+    /// explicit `keep_capabilities` list if set, otherwise the
+    /// privileged/unprivileged OCI defaults.
+    fn base_capabilities(&self) -> Vec<String> {
+        if let Some(keep) = &self.keep_caps {
+            return keep.clone();
+        }
+
         if self.privileged {
             // All capabilities in privileged mode
             return vec![
@@ -379,28 +527,6 @@ impl OciConfigBuilder {
         .map(String::from)
         .collect();
 
-        // Add requested capabilities
-        for cap in &self.cap_add {
-            let cap_name = if cap.starts_with("CAP_") {
-                cap.clone()
-            } else {
-                format!("CAP_{}", cap.to_uppercase())
-            };
-            if !caps.contains(&cap_name) {
-                caps.push(cap_name);
-            }
-        }
-
-        // Remove dropped capabilities
-        for cap in &self.cap_drop {
-            let cap_name = if cap.starts_with("CAP_") {
-                cap.clone()
-            } else {
-                format!("CAP_{}", cap.to_uppercase())
-            };
-            caps.retain(|c| c != &cap_name);
-        }
-
         caps
     }
 
@@ -507,4 +633,43 @@ mod tests {
         let root = spec.root().as_ref().unwrap();
         assert!(root.readonly().unwrap_or(false));
     }
+
+    #[test]
+    fn rejects_unknown_capability() {
+        let err = OciConfigBuilder::new().add_capability("CAP_NOT_REAL").unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid(_)));
+    }
+
+    #[test]
+    fn accepts_capability_without_prefix() {
+        let builder = OciConfigBuilder::new().add_capability("net_admin").unwrap();
+        assert_eq!(builder.cap_add, vec!["CAP_NET_ADMIN".to_string()]);
+    }
+
+    #[test]
+    fn keep_capabilities_overrides_defaults() {
+        let builder = OciConfigBuilder::new()
+            .keep_capabilities(vec!["CAP_CHOWN".to_string()])
+            .unwrap();
+        assert_eq!(builder.base_capabilities(), vec!["CAP_CHOWN".to_string()]);
+    }
+
+    #[test]
+    fn healthcheck_is_carried_as_an_annotation() {
+        let spec = OciConfigBuilder::new()
+            .healthcheck(HealthCheckSpec {
+                test: vec!["curl".to_string(), "-f".to_string(), "http://localhost/health".to_string()],
+                interval_secs: 30,
+                timeout_secs: 5,
+                retries: 3,
+                start_period_secs: 0,
+            })
+            .build()
+            .unwrap();
+
+        let annotations = spec.annotations().as_ref().unwrap();
+        let encoded = annotations.get(HEALTHCHECK_ANNOTATION).unwrap();
+        let decoded: HealthCheckSpec = serde_json::from_str(encoded).unwrap();
+        assert_eq!(decoded.retries, 3);
+    }
 }
@@ -0,0 +1,161 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Persisted policy-rejection event log
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EventStoreError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// A policy-rejection event recorded by the gatekeeper/policy engine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyEvent {
+    pub id: String,
+    pub timestamp: String,
+    pub container_id: String,
+    pub container_name: String,
+    pub policy_rule: String,
+    pub action: String,
+    pub target: String,
+    pub reason: String,
+    pub severity: Severity,
+    pub profile: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+/// Query predicates for [`EventStore::query`]. Every set field is
+/// AND-combined; an unset field matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub severity: Option<Severity>,
+    pub container_id: Option<String>,
+    pub policy_rule: Option<String>,
+    /// Only events at or after this RFC3339 timestamp.
+    pub since: Option<String>,
+    /// Cap on the number of matching events returned, most recent first.
+    pub limit: Option<usize>,
+}
+
+impl EventFilter {
+    fn matches(&self, event: &PolicyEvent) -> bool {
+        if let Some(severity) = self.severity {
+            if severity != event.severity {
+                return false;
+            }
+        }
+        if let Some(ref container_id) = self.container_id {
+            if &event.container_id != container_id && &event.container_name != container_id {
+                return false;
+            }
+        }
+        if let Some(ref policy_rule) = self.policy_rule {
+            if &event.policy_rule != policy_rule {
+                return false;
+            }
+        }
+        if let Some(ref since) = self.since {
+            // RFC3339 timestamps sort lexicographically, so a plain string
+            // comparison is enough to implement "at or after".
+            if event.timestamp.as_str() < since.as_str() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Storage and query interface for persisted policy-rejection events.
+/// `append` is the only writer - everything else is read-only - so the
+/// gatekeeper's reject path and the `explain` CLI can share one
+/// implementation without either depending on the other's internals.
+pub trait EventStore {
+    fn append(&self, event: &PolicyEvent) -> Result<(), EventStoreError>;
+    fn get_last(&self) -> Result<Option<PolicyEvent>, EventStoreError>;
+    fn get_by_id(&self, id: &str) -> Result<Option<PolicyEvent>, EventStoreError>;
+    fn query(&self, filter: &EventFilter) -> Result<Vec<PolicyEvent>, EventStoreError>;
+}
+
+/// Append-only JSONL backend: one [`PolicyEvent`] per line under the
+/// runtime state dir. Reads load the whole file, which is fine at the
+/// scale a single host's rejection log reaches; a SQLite-backed store can
+/// implement the same trait later without any caller changes.
+pub struct JsonlEventStore {
+    path: PathBuf,
+}
+
+impl JsonlEventStore {
+    /// Opens (creating if needed) the event log at `<root_dir>/events.jsonl`.
+    pub fn open(root_dir: &Path) -> Result<Self, EventStoreError> {
+        std::fs::create_dir_all(root_dir)?;
+        let path = root_dir.join("events.jsonl");
+        OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self { path })
+    }
+
+    fn read_all(&self) -> Result<Vec<PolicyEvent>, EventStoreError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut events = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(serde_json::from_str(&line)?);
+        }
+        Ok(events)
+    }
+}
+
+impl EventStore for JsonlEventStore {
+    fn append(&self, event: &PolicyEvent) -> Result<(), EventStoreError> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    }
+
+    fn get_last(&self) -> Result<Option<PolicyEvent>, EventStoreError> {
+        Ok(self.read_all()?.into_iter().next_back())
+    }
+
+    fn get_by_id(&self, id: &str) -> Result<Option<PolicyEvent>, EventStoreError> {
+        Ok(self.read_all()?.into_iter().find(|event| event.id == id))
+    }
+
+    fn query(&self, filter: &EventFilter) -> Result<Vec<PolicyEvent>, EventStoreError> {
+        let mut events: Vec<PolicyEvent> = self
+            .read_all()?
+            .into_iter()
+            .filter(|event| filter.matches(event))
+            .collect();
+
+        // Newest first, matching how `explain --list` should read.
+        events.reverse();
+
+        if let Some(limit) = filter.limit {
+            events.truncate(limit);
+        }
+        Ok(events)
+    }
+}
@@ -0,0 +1,227 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Automatic subnet and address allocation for `vordr network create` / `connect`.
+//!
+//! Mirrors how Docker's default bridge IPAM works: networks that don't pin a
+//! subnet get the first free `/24` out of a fixed pool of private ranges, and
+//! containers that don't pin an address get the lowest unused host address in
+//! their network's subnet. Everything here is pure arithmetic over 32-bit
+//! addresses plus whatever `list_networks()`/`allocated_ips()` already report,
+//! so there's no separate allocation table to keep in sync.
+
+use std::net::Ipv4Addr;
+
+use thiserror::Error;
+
+use super::state::{NetworkInfo, StateError, StateManager};
+
+#[derive(Error, Debug)]
+pub enum IpamError {
+    #[error("state error: {0}")]
+    State(#[from] StateError),
+    #[error("invalid CIDR {0:?}: {1}")]
+    InvalidCidr(String, String),
+    #[error("invalid IP address {0:?}: {1}")]
+    InvalidAddress(String, String),
+    #[error("no free /24 subnet left in the IPAM pool")]
+    PoolExhausted,
+    #[error("network {0} has no allocated subnet")]
+    NoSubnet(String),
+    #[error("address {ip} is not inside subnet {subnet}")]
+    OutOfRange { ip: String, subnet: String },
+    #[error("address {0} is already assigned on this network")]
+    AlreadyAssigned(String),
+}
+
+/// Candidate `/16` ranges carved into `/24` blocks, tried in order: the first
+/// two `/16`s of the 172.18-31 block Docker's default bridge IPAM draws from
+/// (172.17.0.0/16 is left alone since that's the well-known default bridge
+/// subnet), then 10.89.0.0/16 as a fallback once those are exhausted.
+const CANDIDATE_POOLS: &[&str] = &["172.18.0.0/16", "172.19.0.0/16", "10.89.0.0/16"];
+
+/// Picks the first unused `/24` out of [`CANDIDATE_POOLS`] that doesn't
+/// overlap any subnet in `existing`, returning its CIDR and `.1` gateway.
+pub fn allocate_subnet(existing: &[NetworkInfo]) -> Result<(String, String), IpamError> {
+    let taken: Vec<(u32, u8)> = existing
+        .iter()
+        .filter_map(|n| n.subnet.as_deref())
+        .filter_map(|cidr| parse_cidr(cidr).ok())
+        .collect();
+
+    for pool in CANDIDATE_POOLS {
+        let (pool_net, pool_prefix) = parse_cidr(pool)?;
+        debug_assert!(pool_prefix <= 24, "candidate pool must be at least a /24");
+
+        let subnet_count = 1u32 << (24 - pool_prefix);
+        for i in 0..subnet_count {
+            let candidate_net = pool_net + (i << 8);
+            if taken.iter().any(|&(net, prefix)| overlaps(candidate_net, 24, net, prefix)) {
+                continue;
+            }
+            let gateway = candidate_net + 1;
+            return Ok((cidr_to_string(candidate_net, 24), u32_to_ip(gateway).to_string()));
+        }
+    }
+
+    Err(IpamError::PoolExhausted)
+}
+
+/// Resolves the address a container should use on `network`: `requested` if
+/// given (validated against the subnet and existing allocations), otherwise
+/// the lowest unused host address.
+pub fn allocate_address(
+    state: &StateManager,
+    network: &NetworkInfo,
+    requested: Option<&str>,
+) -> Result<String, IpamError> {
+    let subnet = network
+        .subnet
+        .as_deref()
+        .ok_or_else(|| IpamError::NoSubnet(network.name.clone()))?;
+    let (net, prefix) = parse_cidr(subnet)?;
+    let used = state.allocated_ips(&network.id)?;
+
+    if let Some(requested) = requested {
+        let ip = parse_ip(requested)?;
+        if !in_subnet(ip, net, prefix) {
+            return Err(IpamError::OutOfRange {
+                ip: requested.to_string(),
+                subnet: subnet.to_string(),
+            });
+        }
+        if used.iter().any(|u| u == requested) {
+            return Err(IpamError::AlreadyAssigned(requested.to_string()));
+        }
+        return Ok(requested.to_string());
+    }
+
+    let gateway = network.gateway.as_deref().and_then(|g| parse_ip(g).ok());
+    let host_count = 1u32 << (32 - prefix);
+    // Skip the network address (offset 0), the gateway (usually offset 1),
+    // and the broadcast address (the last offset in the block).
+    for offset in 1..host_count.saturating_sub(1) {
+        let candidate = u32_to_ip(net + offset);
+        if Some(candidate) == gateway {
+            continue;
+        }
+        let candidate = candidate.to_string();
+        if !used.iter().any(|u| *u == candidate) {
+            return Ok(candidate);
+        }
+    }
+
+    Err(IpamError::OutOfRange {
+        ip: "<any>".to_string(),
+        subnet: subnet.to_string(),
+    })
+}
+
+fn parse_cidr(cidr: &str) -> Result<(u32, u8), IpamError> {
+    let (addr, prefix) = cidr
+        .split_once('/')
+        .ok_or_else(|| IpamError::InvalidCidr(cidr.to_string(), "missing /prefix".to_string()))?;
+    let prefix: u8 = prefix
+        .parse()
+        .map_err(|_| IpamError::InvalidCidr(cidr.to_string(), "prefix is not a number".to_string()))?;
+    if prefix > 32 {
+        return Err(IpamError::InvalidCidr(cidr.to_string(), "prefix must be <= 32".to_string()));
+    }
+    let ip = parse_ip(addr).map_err(|_| IpamError::InvalidCidr(cidr.to_string(), "bad address".to_string()))?;
+    let mask = network_mask(prefix);
+    Ok((u32::from(ip) & mask, prefix))
+}
+
+fn parse_ip(addr: &str) -> Result<Ipv4Addr, IpamError> {
+    addr.parse()
+        .map_err(|e: std::net::AddrParseError| IpamError::InvalidAddress(addr.to_string(), e.to_string()))
+}
+
+fn network_mask(prefix: u8) -> u32 {
+    if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix)
+    }
+}
+
+fn overlaps(a_net: u32, a_prefix: u8, b_net: u32, b_prefix: u8) -> bool {
+    let (a_start, a_end) = range(a_net, a_prefix);
+    let (b_start, b_end) = range(b_net, b_prefix);
+    a_start <= b_end && b_start <= a_end
+}
+
+fn range(net: u32, prefix: u8) -> (u32, u32) {
+    let mask = network_mask(prefix);
+    let start = net & mask;
+    let end = start | !mask;
+    (start, end)
+}
+
+fn in_subnet(ip: Ipv4Addr, net: u32, prefix: u8) -> bool {
+    let (start, end) = range(net, prefix);
+    let addr = u32::from(ip);
+    addr >= start && addr <= end
+}
+
+fn u32_to_ip(addr: u32) -> Ipv4Addr {
+    Ipv4Addr::from(addr)
+}
+
+fn cidr_to_string(net: u32, prefix: u8) -> String {
+    format!("{}/{}", u32_to_ip(net), prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(subnet: &str) -> NetworkInfo {
+        NetworkInfo {
+            id: "net1".to_string(),
+            name: "net1".to_string(),
+            driver: "bridge".to_string(),
+            subnet: Some(subnet.to_string()),
+            gateway: Some(u32_to_ip(parse_cidr(subnet).unwrap().0 + 1).to_string()),
+            options: None,
+            internal: false,
+            created_at: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn allocates_first_pool_subnet_when_nothing_taken() {
+        let (subnet, gateway) = allocate_subnet(&[]).unwrap();
+        assert_eq!(subnet, "172.18.0.0/24");
+        assert_eq!(gateway, "172.18.0.1");
+    }
+
+    #[test]
+    fn skips_overlapping_subnets() {
+        let existing = vec![network("172.18.0.0/24")];
+        let (subnet, _) = allocate_subnet(&existing).unwrap();
+        assert_eq!(subnet, "172.18.1.0/24");
+    }
+
+    #[test]
+    fn rejects_address_outside_subnet() {
+        let net = network("172.18.5.0/24");
+        let err = allocate_address_pure(&net, Some("10.0.0.5"));
+        assert!(matches!(err, Err(IpamError::OutOfRange { .. })));
+    }
+
+    // allocate_address() needs a StateManager for `used`; exercise the pure
+    // validation path it shares by inlining the same checks here instead of
+    // standing up a database just for this assertion.
+    fn allocate_address_pure(network: &NetworkInfo, requested: Option<&str>) -> Result<String, IpamError> {
+        let subnet = network.subnet.as_deref().unwrap();
+        let (net, prefix) = parse_cidr(subnet).unwrap();
+        let requested = requested.unwrap();
+        let ip = parse_ip(requested)?;
+        if !in_subnet(ip, net, prefix) {
+            return Err(IpamError::OutOfRange {
+                ip: requested.to_string(),
+                subnet: subnet.to_string(),
+            });
+        }
+        Ok(requested.to_string())
+    }
+}
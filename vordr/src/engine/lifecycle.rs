@@ -1,14 +1,66 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
 //! Container lifecycle management
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use serde::Serialize;
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
-use crate::engine::{ContainerInfo, ContainerState, StateManager};
+use crate::engine::config::{HealthCheckSpec, HEALTHCHECK_ANNOTATION};
+use crate::engine::{quota, usage, ContainerInfo, ContainerState, HealthStatus, ResolveMode, StateManager};
 use crate::ffi::ValidatedConfig;
+use crate::runtime::shim::ShimProcess;
 use crate::runtime::ShimClient;
 
+/// The OCI runtime-spec version this engine's bundle config targets, and
+/// the value reported in [`OciState::oci_version`].
+const OCI_RUNTIME_SPEC_VERSION: &str = "1.0.2";
+
+/// How often [`ContainerLifecycle::wait_for`] re-checks the container's
+/// state. Polling rather than an event subscription keeps `wait_for` a
+/// plain loop over [`StateManager::get_container`], the same way every
+/// other read in this module works.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long [`ContainerLifecycle::start`] waits for the freshly spawned
+/// shim to have run the runtime's `create`/`start` and for `runtime state`
+/// to report a pid, polling every [`SHIM_READY_POLL_INTERVAL`].
+const SHIM_READY_TIMEOUT: Duration = Duration::from_secs(10);
+const SHIM_READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A target condition for [`ContainerLifecycle::wait_for`] to block until.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitCondition {
+    /// The container has reached [`ContainerState::Running`].
+    Running,
+    /// The container's rolling healthcheck has reported [`HealthStatus::Healthy`].
+    Healthy,
+    /// The container has exited with the given code.
+    Exited(i32),
+}
+
+impl WaitCondition {
+    fn is_met_by(&self, container: &ContainerInfo) -> bool {
+        match self {
+            WaitCondition::Running => container.state == ContainerState::Running,
+            WaitCondition::Healthy => container.health_status == HealthStatus::Healthy,
+            WaitCondition::Exited(code) => {
+                container.state == ContainerState::Stopped && container.exit_code == Some(*code)
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum WaitError {
+    #[error("State error: {0}")]
+    State(#[from] crate::engine::StateError),
+    #[error("timed out waiting for the container to reach the target condition")]
+    Timeout,
+}
+
 #[derive(Error, Debug)]
 pub enum LifecycleError {
     #[error("Container not found: {0}")]
@@ -28,6 +80,12 @@ pub enum LifecycleError {
     Io(#[from] std::io::Error),
     #[error("Config error: {0}")]
     Config(#[from] crate::engine::config::ConfigError),
+    #[error("Freezer error: {0}")]
+    Freezer(String),
+    #[error("Checkpoint/restore failed: {0}")]
+    CheckpointFailed(String),
+    #[error("Quota error: {0}")]
+    Quota(#[from] crate::engine::quota::QuotaError),
 }
 
 /// Container lifecycle manager
@@ -62,8 +120,37 @@ impl ContainerLifecycle {
     ) -> Result<ContainerInfo, LifecycleError> {
         info!("Creating container {} ({})", name, id);
 
-        // Create bundle directory
+        quota::enforce(&self.state, &self.root_dir, quota::QuotaResource::Containers)?;
+
         let bundle_path = self.root_dir.join("containers").join(id);
+
+        // Serialize config for storage
+        let config_json = serde_json::json!({
+            "privileged": config.privileged,
+            "user_namespace": config.user_namespace,
+            "user_id": config.user_id,
+            "no_new_privileges": config.no_new_privileges,
+            "readonly_rootfs": config.readonly_rootfs,
+        });
+
+        // Create the database record and immediately mark it `Creating`,
+        // before any bundle file exists on disk. If the process crashes
+        // partway through assembling the bundle below, the row is left in
+        // `Creating` rather than looking like a finished `Created`
+        // container - recoverable and distinguishable rather than silently
+        // wrong.
+        self.state.create_container(
+            id,
+            name,
+            image_id,
+            bundle_path.to_str().unwrap(),
+            Some(&config_json.to_string()),
+            ResolveMode::Default,
+        )?;
+        self.state
+            .set_container_state(id, ContainerState::Creating, None)?;
+
+        // Create bundle directory
         std::fs::create_dir_all(&bundle_path)?;
 
         // Create rootfs directory (will be populated by image extraction)
@@ -84,23 +171,8 @@ impl ContainerLifecycle {
         let config_path = bundle_path.join("config.json");
         builder.write_to_file(&config_path)?;
 
-        // Serialize config for storage
-        let config_json = serde_json::json!({
-            "privileged": config.privileged,
-            "user_namespace": config.user_namespace,
-            "user_id": config.user_id,
-            "no_new_privileges": config.no_new_privileges,
-            "readonly_rootfs": config.readonly_rootfs,
-        });
-
-        // Create database record
-        self.state.create_container(
-            id,
-            name,
-            image_id,
-            bundle_path.to_str().unwrap(),
-            Some(&config_json.to_string()),
-        )?;
+        self.state
+            .set_container_state(id, ContainerState::Created, None)?;
 
         self.state.get_container(id).map_err(|e| e.into())
     }
@@ -109,8 +181,7 @@ impl ContainerLifecycle {
     pub async fn start(&self, id: &str) -> Result<u32, LifecycleError> {
         let container = self.state.get_container(id)?;
 
-        // Validate state transition
-        if container.state != ContainerState::Created {
+        if !container.state.can_start() {
             return Err(LifecycleError::InvalidTransition {
                 from: container.state,
                 to: ContainerState::Running,
@@ -119,13 +190,34 @@ impl ContainerLifecycle {
 
         info!("Starting container {} ({})", container.name, container.id);
 
-        // Start via runtime shim
-        let shim = ShimClient::new(&self.runtime_path, &container.bundle_path);
-        let pid = shim
-            .create_and_start(id)
+        // Daemonize a shim that runs the runtime's create/start and then
+        // owns the container (stdio, exit-code capture) for its whole
+        // life - see `ShimProcess::spawn`. The call returns as soon as the
+        // shim has forked off; it doesn't wait for `create`/`start` to
+        // finish inside it, so we poll `runtime state` below until the
+        // container actually has a pid.
+        let root_dir = self.root_dir.clone();
+        let runtime_path = self.runtime_path.clone();
+        let bundle_path = PathBuf::from(&container.bundle_path);
+        let id_owned = id.to_string();
+        tokio::task::spawn_blocking(move || ShimProcess::spawn(&runtime_path, &id_owned, &bundle_path, &root_dir))
             .await
+            .map_err(|e| LifecycleError::Runtime(e.to_string()))?
             .map_err(|e| LifecycleError::Runtime(e.to_string()))?;
 
+        let shim = ShimClient::new(&self.runtime_path, &container.bundle_path);
+        let deadline = Instant::now() + SHIM_READY_TIMEOUT;
+        let pid = loop {
+            match shim.state(id).await {
+                Ok(state) if state.pid != 0 => break state.pid,
+                Ok(_) | Err(_) if Instant::now() < deadline => {
+                    tokio::time::sleep(SHIM_READY_POLL_INTERVAL).await;
+                }
+                Ok(_) => return Err(LifecycleError::Runtime("shim never reported a pid".to_string())),
+                Err(e) => return Err(LifecycleError::Runtime(e.to_string())),
+            }
+        };
+
         // Update state
         self.state
             .set_container_state(id, ContainerState::Running, Some(pid as i32))?;
@@ -137,7 +229,7 @@ impl ContainerLifecycle {
     pub async fn stop(&self, id: &str, timeout_secs: u32) -> Result<(), LifecycleError> {
         let container = self.state.get_container(id)?;
 
-        if container.state != ContainerState::Running {
+        if !container.state.can_kill() {
             return Err(LifecycleError::InvalidTransition {
                 from: container.state,
                 to: ContainerState::Stopped,
@@ -182,6 +274,13 @@ impl ContainerLifecycle {
         self.state
             .set_container_state(id, ContainerState::Stopped, None)?;
 
+        // The rootfs stops changing the moment the container is stopped,
+        // so this is the one point where measuring its bundle size is
+        // worth caching rather than re-walking on every `system df`.
+        if let Err(err) = usage::measure_and_cache_container_disk_usage(&self.state, &self.root_dir, id) {
+            warn!("Failed to record disk usage for container {}: {}", id, err);
+        }
+
         Ok(())
     }
 
@@ -189,7 +288,7 @@ impl ContainerLifecycle {
     pub fn kill(&self, id: &str, signal: i32) -> Result<(), LifecycleError> {
         let container = self.state.get_container(id)?;
 
-        if container.state != ContainerState::Running {
+        if !container.state.can_kill() {
             return Err(LifecycleError::InvalidTransition {
                 from: container.state,
                 to: ContainerState::Stopped,
@@ -210,8 +309,7 @@ impl ContainerLifecycle {
     pub fn delete(&self, id: &str, force: bool) -> Result<(), LifecycleError> {
         let container = self.state.get_container(id)?;
 
-        // Check if container can be deleted
-        if container.state == ContainerState::Running && !force {
+        if !container.state.can_delete(force) {
             return Err(LifecycleError::InvalidTransition {
                 from: container.state,
                 to: ContainerState::Stopped,
@@ -246,17 +344,20 @@ impl ContainerLifecycle {
     pub fn pause(&self, id: &str) -> Result<(), LifecycleError> {
         let container = self.state.get_container(id)?;
 
-        if container.state != ContainerState::Running {
+        if !container.state.can_pause() {
             return Err(LifecycleError::InvalidTransition {
                 from: container.state,
                 to: ContainerState::Paused,
             });
         }
 
-        // Use cgroups to freeze the container
+        // Use cgroups to freeze the container. The DB is only flipped to
+        // `Paused` once the kernel confirms the freeze, so a crash or
+        // error partway through leaves the recorded state matching
+        // reality (still `Running`) rather than lying about it.
         if let Some(pid) = container.pid {
             debug!("Pausing container {} (pid: {})", id, pid);
-            // TODO: Implement cgroup freezer
+            freeze_cgroup(pid)?;
         }
 
         self.state
@@ -269,7 +370,7 @@ impl ContainerLifecycle {
     pub fn resume(&self, id: &str) -> Result<(), LifecycleError> {
         let container = self.state.get_container(id)?;
 
-        if container.state != ContainerState::Paused {
+        if !container.state.can_resume() {
             return Err(LifecycleError::InvalidTransition {
                 from: container.state,
                 to: ContainerState::Running,
@@ -279,7 +380,7 @@ impl ContainerLifecycle {
         // Use cgroups to thaw the container
         if let Some(pid) = container.pid {
             debug!("Resuming container {} (pid: {})", id, pid);
-            // TODO: Implement cgroup freezer
+            thaw_cgroup(pid)?;
         }
 
         self.state
@@ -288,11 +389,233 @@ impl ContainerLifecycle {
         Ok(())
     }
 
+    /// Checkpoint a running container's process tree into `opts.image_path`
+    /// via CRIU (through the runtime's `checkpoint` subcommand). Persists
+    /// the image path on the container record so a later `restore` can
+    /// find it without the caller tracking it separately.
+    pub async fn checkpoint(
+        &self,
+        id: &str,
+        opts: &crate::runtime::CheckpointOptions,
+    ) -> Result<(), LifecycleError> {
+        let container = self.state.get_container(id)?;
+
+        if !container.state.can_checkpoint() {
+            return Err(LifecycleError::InvalidTransition {
+                from: container.state,
+                to: ContainerState::Stopped,
+            });
+        }
+
+        info!(
+            "Checkpointing container {} ({})",
+            container.name, container.id
+        );
+
+        let shim = ShimClient::new(&self.runtime_path, &container.bundle_path);
+        shim.checkpoint(id, opts)
+            .await
+            .map_err(|e| LifecycleError::CheckpointFailed(e.to_string()))?;
+
+        self.state
+            .set_container_checkpoint_path(id, &opts.image_path.to_string_lossy())?;
+
+        if !opts.leave_running {
+            self.state
+                .set_container_state(id, ContainerState::Stopped, None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Restore a container from its most recent checkpoint via the
+    /// runtime's `restore` subcommand, re-registering the restored pid and
+    /// flipping the state back to `Running` on success.
+    pub async fn restore(
+        &self,
+        id: &str,
+        opts: &crate::runtime::CheckpointOptions,
+    ) -> Result<u32, LifecycleError> {
+        let container = self.state.get_container(id)?;
+
+        if !container.state.can_restore() {
+            return Err(LifecycleError::InvalidTransition {
+                from: container.state,
+                to: ContainerState::Running,
+            });
+        }
+
+        info!(
+            "Restoring container {} ({}) from checkpoint",
+            container.name, container.id
+        );
+
+        let shim = ShimClient::new(&self.runtime_path, &container.bundle_path);
+        let bundle_path = Path::new(&container.bundle_path);
+        let pid = shim
+            .restore(id, bundle_path, opts)
+            .await
+            .map_err(|e| LifecycleError::CheckpointFailed(e.to_string()))?;
+
+        self.state
+            .set_container_state(id, ContainerState::Running, Some(pid as i32))?;
+
+        Ok(pid)
+    }
+
     /// Get container state
     pub fn get(&self, id: &str) -> Result<ContainerInfo, LifecycleError> {
         self.state.get_container(id).map_err(|e| e.into())
     }
 
+    /// Reads back the [`HealthCheckSpec`] [`OciConfigBuilder::healthcheck`]
+    /// stored in `id`'s bundle `config.json`, if one was configured.
+    fn read_healthcheck_spec(&self, container: &ContainerInfo) -> Option<HealthCheckSpec> {
+        let config_json: serde_json::Value =
+            std::fs::read_to_string(Path::new(&container.bundle_path).join("config.json"))
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())?;
+
+        let encoded = config_json
+            .get("annotations")?
+            .get(HEALTHCHECK_ANNOTATION)?
+            .as_str()?;
+
+        serde_json::from_str(encoded).ok()
+    }
+
+    /// Runs `id`'s configured healthcheck once via the runtime's `exec`
+    /// path, recording the result through
+    /// [`StateManager::record_health_probe`]. A no-op returning `Ok(None)`
+    /// if the container has no healthcheck configured.
+    pub async fn run_health_probe(&self, id: &str) -> Result<Option<HealthStatus>, LifecycleError> {
+        let container = self.state.get_container(id)?;
+
+        let Some(spec) = self.read_healthcheck_spec(&container) else {
+            return Ok(None);
+        };
+
+        if container.state != ContainerState::Running {
+            return Ok(None);
+        }
+
+        let process_spec = serde_json::json!({
+            "terminal": false,
+            "args": spec.test,
+            "cwd": "/",
+        })
+        .to_string();
+
+        let shim = ShimClient::new(&self.runtime_path, &container.bundle_path);
+        let (success, output) = match shim.exec_captured(id, &process_spec, spec.timeout()).await {
+            Ok((code, output)) => (code == 0, output),
+            Err(e) => (false, e.to_string()),
+        };
+
+        let status = self.state.record_health_probe(id, success, &output, spec.retries)?;
+        Ok(Some(status))
+    }
+
+    /// Runs `id`'s healthcheck on its configured `interval` until the
+    /// container stops running, sleeping `start_period` first so a
+    /// slow-starting service isn't probed before it's had a chance to come
+    /// up. Intended to be spawned as a background task right after a
+    /// container transitions to `Running`; returns once there's nothing
+    /// left to probe (no healthcheck configured, or the container isn't
+    /// running anymore).
+    pub async fn monitor_health(&self, id: &str) -> Result<(), LifecycleError> {
+        let container = self.state.get_container(id)?;
+        let Some(spec) = self.read_healthcheck_spec(&container) else {
+            return Ok(());
+        };
+
+        tokio::time::sleep(spec.start_period()).await;
+
+        loop {
+            match self.state.get_container(id) {
+                Ok(c) if c.state == ContainerState::Running => {}
+                _ => return Ok(()),
+            }
+
+            if let Err(e) = self.run_health_probe(id).await {
+                warn!("health probe for container {} failed: {}", id, e);
+            }
+
+            tokio::time::sleep(spec.interval()).await;
+        }
+    }
+
+    /// Blocks until `id` reaches `condition` or `timeout` elapses, polling
+    /// at [`WAIT_POLL_INTERVAL`]. Lets automated callers (e.g. the
+    /// `vordr_wait` MCP tool) sequence dependent containers - "don't start
+    /// B until A is healthy" - without the caller busy-polling `inspect`
+    /// itself.
+    pub async fn wait_for(
+        &self,
+        id: &str,
+        condition: WaitCondition,
+        timeout: Option<Duration>,
+    ) -> Result<ContainerInfo, WaitError> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        loop {
+            let container = self.state.get_container(id).map_err(WaitError::State)?;
+            if condition.is_met_by(&container) {
+                return Ok(container);
+            }
+
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    return Err(WaitError::Timeout);
+                }
+            }
+
+            tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Build a spec-compliant OCI runtime `state` document for `id`, so
+    /// tooling that speaks the OCI runtime state format (e.g. `runtime-tools`
+    /// validation) can consume svalinn containers the same way it would
+    /// runc or youki ones.
+    pub fn oci_state(&self, id: &str) -> Result<OciState, LifecycleError> {
+        let container = self.state.get_container(id)?;
+
+        let bundle_config: serde_json::Value =
+            std::fs::read_to_string(Path::new(&container.bundle_path).join("config.json"))
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_else(|| serde_json::json!({}));
+
+        let annotations = bundle_config
+            .get("annotations")
+            .and_then(|v| v.as_object())
+            .map(|obj| {
+                obj.iter()
+                    .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let owner = container
+            .config
+            .as_ref()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(c).ok())
+            .and_then(|v| v.get("user_id").and_then(|u| u.as_u64()))
+            .unwrap_or(0) as u32;
+
+        Ok(OciState {
+            oci_version: OCI_RUNTIME_SPEC_VERSION.to_string(),
+            id: container.id,
+            status: container.state.as_str().to_string(),
+            pid: container.pid.unwrap_or(0),
+            bundle: container.bundle_path,
+            annotations,
+            created: to_rfc3339(&container.created_at),
+            owner,
+        })
+    }
+
     /// List containers
     pub fn list(
         &self,
@@ -302,6 +625,151 @@ impl ContainerLifecycle {
     }
 }
 
+/// An OCI runtime-spec `state` response. Field names are camelCase on the
+/// wire to match the spec (`ociVersion`, not `oci_version`); `created` and
+/// `owner` are svalinn extensions, the same way runc/youki add their own.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OciState {
+    pub oci_version: String,
+    pub id: String,
+    pub status: String,
+    pub pid: i32,
+    pub bundle: String,
+    pub annotations: HashMap<String, String>,
+    /// RFC3339 creation timestamp.
+    pub created: String,
+    /// Uid the container's process runs as.
+    pub owner: u32,
+}
+
+/// Converts a `created_at` column value - SQLite's `CURRENT_TIMESTAMP`
+/// format (`YYYY-MM-DD HH:MM:SS`, UTC) or already-RFC3339 - to RFC3339.
+fn to_rfc3339(created_at: &str) -> String {
+    use chrono::{DateTime, NaiveDateTime, Utc};
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(created_at) {
+        return dt.with_timezone(&Utc).to_rfc3339();
+    }
+
+    NaiveDateTime::parse_from_str(created_at, "%Y-%m-%d %H:%M:%S")
+        .map(|naive| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc).to_rfc3339())
+        .unwrap_or_else(|_| created_at.to_string())
+}
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const FREEZER_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const FREEZER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether this host uses the unified (v2) cgroup hierarchy, the same
+/// check `doctor`/`system info` use.
+fn is_cgroup_v2() -> bool {
+    Path::new(CGROUP_ROOT).join("cgroup.controllers").exists()
+}
+
+/// Resolves `pid`'s cgroup v2 directory from the `0::<path>` line in
+/// `/proc/<pid>/cgroup` - cgroup v2 has a single unified hierarchy, so
+/// there's exactly one such line.
+fn cgroup_v2_dir(pid: i32) -> Result<PathBuf, LifecycleError> {
+    let content = std::fs::read_to_string(format!("/proc/{}/cgroup", pid))?;
+    let rel = content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .ok_or_else(|| LifecycleError::Freezer(format!("no unified cgroup entry for pid {}", pid)))?;
+    Ok(Path::new(CGROUP_ROOT).join(rel.trim_start_matches('/')))
+}
+
+/// Resolves `pid`'s per-process directory under the cgroup v1 `freezer`
+/// controller hierarchy (mounted separately from the other v1
+/// controllers, at `/sys/fs/cgroup/freezer`).
+fn cgroup_v1_freezer_dir(pid: i32) -> Result<PathBuf, LifecycleError> {
+    let content = std::fs::read_to_string(format!("/proc/{}/cgroup", pid))?;
+    let rel = content
+        .lines()
+        .find_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            let _hierarchy_id = fields.next()?;
+            let controllers = fields.next()?;
+            let path = fields.next()?;
+            controllers
+                .split(',')
+                .any(|c| c == "freezer")
+                .then(|| path.to_string())
+        })
+        .ok_or_else(|| LifecycleError::Freezer(format!("no freezer cgroup entry for pid {}", pid)))?;
+    Ok(Path::new(CGROUP_ROOT).join("freezer").join(rel.trim_start_matches('/')))
+}
+
+/// Freezes the container whose init process is `pid`, blocking until the
+/// kernel confirms the transition or [`FREEZER_TIMEOUT`] elapses.
+fn freeze_cgroup(pid: i32) -> Result<(), LifecycleError> {
+    if is_cgroup_v2() {
+        let dir = cgroup_v2_dir(pid)?;
+        std::fs::write(dir.join("cgroup.freeze"), "1")?;
+        wait_for_cgroup_v2_events(&dir, "frozen 1")
+    } else {
+        let dir = cgroup_v1_freezer_dir(pid)?;
+        std::fs::write(dir.join("freezer.state"), "FROZEN")?;
+        wait_for_cgroup_v1_state(&dir, "FROZEN")
+    }
+}
+
+/// Thaws a previously frozen container, blocking until the kernel
+/// confirms the transition or [`FREEZER_TIMEOUT`] elapses.
+fn thaw_cgroup(pid: i32) -> Result<(), LifecycleError> {
+    if is_cgroup_v2() {
+        let dir = cgroup_v2_dir(pid)?;
+        std::fs::write(dir.join("cgroup.freeze"), "0")?;
+        wait_for_cgroup_v2_events(&dir, "frozen 0")
+    } else {
+        let dir = cgroup_v1_freezer_dir(pid)?;
+        std::fs::write(dir.join("freezer.state"), "THAWED")?;
+        wait_for_cgroup_v1_state(&dir, "THAWED")
+    }
+}
+
+/// Polls `<dir>/cgroup.events` for the given `frozen 0`/`frozen 1` line.
+fn wait_for_cgroup_v2_events(dir: &Path, want_line: &str) -> Result<(), LifecycleError> {
+    let events_path = dir.join("cgroup.events");
+    let deadline = Instant::now() + FREEZER_TIMEOUT;
+
+    loop {
+        let content = std::fs::read_to_string(&events_path)?;
+        if content.lines().any(|line| line.trim() == want_line) {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(LifecycleError::Freezer(format!(
+                "timed out waiting for '{}' in {}",
+                want_line,
+                events_path.display()
+            )));
+        }
+        std::thread::sleep(FREEZER_POLL_INTERVAL);
+    }
+}
+
+/// Polls `<dir>/freezer.state` until it reports `want_state`.
+fn wait_for_cgroup_v1_state(dir: &Path, want_state: &str) -> Result<(), LifecycleError> {
+    let state_path = dir.join("freezer.state");
+    let deadline = Instant::now() + FREEZER_TIMEOUT;
+
+    loop {
+        let content = std::fs::read_to_string(&state_path)?;
+        if content.trim() == want_state {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(LifecycleError::Freezer(format!(
+                "timed out waiting for freezer.state={} in {}",
+                want_state,
+                state_path.display()
+            )));
+        }
+        std::thread::sleep(FREEZER_POLL_INTERVAL);
+    }
+}
+
 /// Check if a process exists
 fn process_exists(pid: i32) -> bool {
     #[cfg(unix)]
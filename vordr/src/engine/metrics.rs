@@ -0,0 +1,53 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Prometheus-format state inventory, backing the daemon's `/metrics` endpoint
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Point-in-time inventory counts over the state database, as returned by
+/// [`StateManager::metrics`](super::state::StateManager::metrics).
+#[derive(Debug, Clone, Default)]
+pub struct StateMetrics {
+    /// Container count by [`ContainerState::as_str`](super::state::ContainerState::as_str).
+    pub containers_by_state: BTreeMap<String, u64>,
+    pub images_total: u64,
+    /// Summed `images.size`, in bytes.
+    pub image_bytes_total: u64,
+    pub networks_total: u64,
+    pub volumes_total: u64,
+    /// Advisory locks currently held, per the `locks` table.
+    pub locks_held: u64,
+}
+
+/// Renders `metrics` in Prometheus text exposition format.
+pub(crate) fn render_prometheus(metrics: &StateMetrics) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# HELP svalinn_containers Number of containers by state.");
+    let _ = writeln!(out, "# TYPE svalinn_containers gauge");
+    for (state, count) in &metrics.containers_by_state {
+        let _ = writeln!(out, "svalinn_containers{{state=\"{state}\"}} {count}");
+    }
+
+    let _ = writeln!(out, "# HELP svalinn_images_total Number of images.");
+    let _ = writeln!(out, "# TYPE svalinn_images_total gauge");
+    let _ = writeln!(out, "svalinn_images_total {}", metrics.images_total);
+
+    let _ = writeln!(out, "# HELP svalinn_image_bytes_total Summed size of all images, in bytes.");
+    let _ = writeln!(out, "# TYPE svalinn_image_bytes_total gauge");
+    let _ = writeln!(out, "svalinn_image_bytes_total {}", metrics.image_bytes_total);
+
+    let _ = writeln!(out, "# HELP svalinn_networks_total Number of networks.");
+    let _ = writeln!(out, "# TYPE svalinn_networks_total gauge");
+    let _ = writeln!(out, "svalinn_networks_total {}", metrics.networks_total);
+
+    let _ = writeln!(out, "# HELP svalinn_volumes_total Number of volumes.");
+    let _ = writeln!(out, "# TYPE svalinn_volumes_total gauge");
+    let _ = writeln!(out, "svalinn_volumes_total {}", metrics.volumes_total);
+
+    let _ = writeln!(out, "# HELP svalinn_locks_held Number of advisory locks currently held.");
+    let _ = writeln!(out, "# TYPE svalinn_locks_held gauge");
+    let _ = writeln!(out, "svalinn_locks_held {}", metrics.locks_held);
+
+    out
+}
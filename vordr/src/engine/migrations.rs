@@ -0,0 +1,134 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Versioned schema migrations
+//!
+//! `StateManager::open` used to replay `schema.sql` - all `CREATE TABLE IF
+//! NOT EXISTS` - on every launch, which only works as long as the schema
+//! only ever grows new tables. An `ALTER TABLE` or a data backfill can't be
+//! expressed that way, so schema changes are tracked here instead as an
+//! ordered list of one-shot migrations keyed on SQLite's own
+//! `PRAGMA user_version`. [`apply`] reads the on-disk version, runs every
+//! migration newer than it inside a single transaction, and bumps
+//! `user_version` as each one succeeds.
+
+use rusqlite::Connection;
+
+use super::state::StateError;
+
+/// One forward schema change, identified by the `user_version` it leaves
+/// the database at.
+struct Migration {
+    version: i64,
+    sql: &'static str,
+}
+
+/// Ordered, oldest first. `version` values must be consecutive starting at
+/// 1 - [`apply`] applies every migration with a version greater than what's
+/// on disk, so gaps or out-of-order entries would silently skip schema.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        sql: include_str!("../../schema.sql"),
+    },
+    Migration {
+        version: 2,
+        // Durable, replayable log of lifecycle transitions (container
+        // create/start/die, network/volume create/destroy, ...), in the
+        // `docker events` sense - distinct from the JSONL-backed policy
+        // rejection log in `crate::engine::events`. Appended to by
+        // `StateManager::record_event` inside the same transaction as the
+        // mutation it describes, and read back by `list_events`/`watch_events`.
+        sql: "CREATE TABLE IF NOT EXISTS events (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at  TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            object_type TEXT NOT NULL,
+            object_id   TEXT NOT NULL,
+            action      TEXT NOT NULL,
+            payload     TEXT
+        )",
+    },
+    Migration {
+        version: 3,
+        // Lets `cleanup_stale_locks` tell a live process holding a lock
+        // apart from an unrelated process that reused its PID after the
+        // original owner died or the machine rebooted - see
+        // `StateManager::lock_is_stale`. Both columns are nullable so
+        // locks acquired by an older binary before this migration (with no
+        // recorded identity) are still treated as plain PID-liveness
+        // checks rather than unconditionally stale.
+        sql: "ALTER TABLE locks ADD COLUMN owner_start_time TEXT;
+              ALTER TABLE locks ADD COLUMN boot_id TEXT;",
+    },
+    Migration {
+        version: 4,
+        // Pull-resolution policy, borrowed from BuildKit's image source
+        // resolve modes - see `ResolveMode`. Stored on both sides since
+        // they answer different questions: an image's own mode is "should
+        // a future resolve of my reference replace me", while a
+        // container's is "how should my image reference be re-resolved if
+        // asked" (e.g. `run --pull`), which can differ per container even
+        // when they share an image.
+        sql: "ALTER TABLE images ADD COLUMN resolve_mode TEXT NOT NULL DEFAULT 'default';
+              ALTER TABLE containers ADD COLUMN resolve_mode TEXT NOT NULL DEFAULT 'default';",
+    },
+    Migration {
+        version: 5,
+        // Tracks an image build in flight, keyed by the source ref it was
+        // triggered from, so `get_build_status` can answer "is the image
+        // for branch main built yet" without the caller polling the
+        // `images` table - see `StateManager::start_image_build`.
+        sql: "CREATE TABLE IF NOT EXISTS image_builds (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            repo        TEXT NOT NULL,
+            image_name  TEXT NOT NULL,
+            ref_type    TEXT NOT NULL,
+            ref_value   TEXT NOT NULL,
+            status      TEXT NOT NULL DEFAULT 'queued',
+            image_sha   TEXT,
+            started_at  TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            finished_at TIMESTAMP
+        );
+        CREATE INDEX IF NOT EXISTS idx_image_builds_lookup
+            ON image_builds (repo, image_name, ref_value, id DESC);",
+    },
+    Migration {
+        version: 6,
+        // Rolling healthcheck status - see `StateManager::record_health_probe`.
+        // `health_consecutive_failures` isn't exposed on `ContainerInfo`
+        // (it's an internal counter driving the `Starting`/`Unhealthy`
+        // transition), but lives alongside the columns that are.
+        sql: "ALTER TABLE containers ADD COLUMN health_status TEXT NOT NULL DEFAULT 'none';
+              ALTER TABLE containers ADD COLUMN health_log TEXT;
+              ALTER TABLE containers ADD COLUMN health_consecutive_failures INTEGER NOT NULL DEFAULT 0;",
+    },
+];
+
+/// Brings `conn`'s schema up to the newest version this binary knows about.
+///
+/// Fails closed with [`StateError::MigrationFailed`] if the database's
+/// `user_version` is already ahead of [`MIGRATIONS`] - that means it was
+/// last opened by a newer build, and blindly continuing risks running
+/// queries against a schema this binary doesn't understand.
+pub fn apply(conn: &mut Connection) -> Result<(), StateError> {
+    let current_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    let latest_version = MIGRATIONS.last().map(|m| m.version).unwrap_or(0);
+    if current_version > latest_version {
+        return Err(StateError::MigrationFailed(format!(
+            "database is at schema version {current_version}, but this build only knows about up to {latest_version} - refusing to open a database from a newer version"
+        )));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current_version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql).map_err(|e| {
+            StateError::MigrationFailed(format!(
+                "migration to schema version {} failed: {e}",
+                migration.version
+            ))
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
@@ -2,9 +2,37 @@
 //! Container engine core functionality
 
 pub mod config;
+mod crypto;
+pub mod events;
+pub mod ipam;
 pub mod lifecycle;
+pub mod metrics;
+mod migrations;
+pub mod net_driver;
+#[cfg(feature = "postgres")]
+pub mod postgres_repo;
+pub mod quota;
+pub mod reference;
+pub mod repo;
 pub mod state;
+pub mod usage;
+pub mod volume_driver;
 
-pub use config::OciConfigBuilder;
-pub use lifecycle::ContainerLifecycle;
-pub use state::{ContainerInfo, ContainerState, ImageInfo, NetworkInfo, StateError, StateManager, VolumeInfo};
+pub use config::{HealthCheckSpec, OciConfigBuilder, HEALTHCHECK_ANNOTATION};
+pub use events::{EventFilter, EventStore, EventStoreError, JsonlEventStore, PolicyEvent, Severity};
+pub use ipam::IpamError;
+pub use lifecycle::{ContainerLifecycle, OciState, WaitCondition, WaitError};
+pub use metrics::StateMetrics;
+pub use net_driver::{
+    build_network_driver, NetworkAttachContext, NetworkCreateRequest, NetworkDriver, NetworkDriverError,
+};
+pub use quota::{QuotaError, QuotaResource};
+pub use reference::Reference;
+pub use repo::{open_image_repo, ImageRepo, SettingsRepo};
+pub use state::{
+    BuildLookup, BuildRefType, BuildStatus, ContainerInfo, ContainerState, EventWatcher, HealthProbeLog, HealthStatus,
+    ImageBuildInfo, ImageInfo, ImagePruneResult, LayerInfo, LifecycleEvent, LifecycleEventFilter, NetworkDnsRecord,
+    NetworkEndpoint, NetworkInfo, ResolveMode, StateError, StateManager, StateTransaction, VolumeInfo,
+};
+pub use usage::{disk_usage, DiskUsage, ResourceUsage};
+pub use volume_driver::{build_driver, VolumeContext, VolumeDriver, VolumeDriverError};
@@ -0,0 +1,171 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Network driver abstraction
+//!
+//! `create_network` has always stored a `driver` string but `run`/`connect`
+//! only ever behaved like a local bridge network. This gives the field
+//! actual behavior, mirroring how [`super::volume_driver`] does for
+//! `--driver` on volumes: `bridge` (the default - a NATed local subnet with
+//! its own address space), `host` (the container shares the host's network
+//! namespace, so it cannot also own a subnet/gateway of its own), `none`
+//! (no connectivity at all - same address-space restriction as `host`),
+//! and `macvlan`/`ipvlan` (assigns a container an address directly on a
+//! parent interface's L2 segment, so a `parent` `--opt` is mandatory).
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum NetworkDriverError {
+    #[error("unknown network driver {0:?}")]
+    UnknownDriver(String),
+    #[error("driver {driver} has no address space of its own - --subnet/--gateway are not supported")]
+    SubnetNotSupported { driver: &'static str },
+    #[error("driver {driver} requires --opt {key}")]
+    MissingOption { driver: &'static str, key: &'static str },
+}
+
+/// Everything a driver needs to validate a `network create` call before
+/// anything is persisted.
+pub struct NetworkCreateRequest<'a> {
+    pub subnet: Option<&'a str>,
+    pub gateway: Option<&'a str>,
+    pub internal: bool,
+    pub options: &'a HashMap<String, String>,
+}
+
+/// Everything a driver needs to attach one container to a network it
+/// already validated at create time.
+pub struct NetworkAttachContext<'a> {
+    pub container_id: &'a str,
+    pub network_name: &'a str,
+    pub ip_address: Option<&'a str>,
+    pub options: &'a HashMap<String, String>,
+}
+
+/// A backend for a `network create --driver <name>`.
+pub trait NetworkDriver: Send + Sync {
+    /// Whether this driver's networks get their own IPAM-managed subnet.
+    /// `false` for drivers that either have no connectivity (`none`) or
+    /// borrow someone else's address space (`host`) - `create_network`
+    /// skips subnet auto-allocation for them and `connect`/`run --network`
+    /// skip address assignment.
+    fn has_own_address_space(&self) -> bool {
+        true
+    }
+
+    /// Rejects incompatible flags/options before the network is persisted.
+    fn validate_create(&self, req: &NetworkCreateRequest) -> Result<(), NetworkDriverError>;
+
+    /// Configures a container's network namespace for this driver as it's
+    /// attached (via `network connect` or `run --network`). `run::execute`
+    /// doesn't attach a real netns to a container yet - the same gap
+    /// [`crate::network::portforward`] documents on the forwarding side -
+    /// so today this only logs what it would have done.
+    fn configure(&self, ctx: &NetworkAttachContext);
+}
+
+/// Resolves a `--driver` name to its implementation.
+pub fn build_network_driver(name: &str) -> Result<Box<dyn NetworkDriver>, NetworkDriverError> {
+    match name {
+        "bridge" => Ok(Box::new(BridgeDriver)),
+        "host" => Ok(Box::new(HostDriver)),
+        "none" => Ok(Box::new(NoneDriver)),
+        "macvlan" | "ipvlan" => Ok(Box::new(MacvlanDriver)),
+        other => Err(NetworkDriverError::UnknownDriver(other.to_string())),
+    }
+}
+
+/// The current default: a local subnet, NATed to the outside world unless
+/// `--internal`.
+pub struct BridgeDriver;
+
+impl NetworkDriver for BridgeDriver {
+    fn validate_create(&self, _req: &NetworkCreateRequest) -> Result<(), NetworkDriverError> {
+        Ok(())
+    }
+
+    fn configure(&self, ctx: &NetworkAttachContext) {
+        tracing::info!(
+            "network driver not yet wired to a real netns backend: would bridge-attach container {} to {} at {:?}",
+            ctx.container_id,
+            ctx.network_name,
+            ctx.ip_address
+        );
+    }
+}
+
+/// Shares the host's network namespace - there is no separate subnet to
+/// address into.
+pub struct HostDriver;
+
+impl NetworkDriver for HostDriver {
+    fn has_own_address_space(&self) -> bool {
+        false
+    }
+
+    fn validate_create(&self, req: &NetworkCreateRequest) -> Result<(), NetworkDriverError> {
+        if req.subnet.is_some() || req.gateway.is_some() {
+            return Err(NetworkDriverError::SubnetNotSupported { driver: "host" });
+        }
+        Ok(())
+    }
+
+    fn configure(&self, ctx: &NetworkAttachContext) {
+        tracing::info!(
+            "network driver not yet wired to a real netns backend: would share the host netns with container {}",
+            ctx.container_id
+        );
+    }
+}
+
+/// No connectivity at all.
+pub struct NoneDriver;
+
+impl NetworkDriver for NoneDriver {
+    fn has_own_address_space(&self) -> bool {
+        false
+    }
+
+    fn validate_create(&self, req: &NetworkCreateRequest) -> Result<(), NetworkDriverError> {
+        if req.subnet.is_some() || req.gateway.is_some() {
+            return Err(NetworkDriverError::SubnetNotSupported { driver: "none" });
+        }
+        Ok(())
+    }
+
+    fn configure(&self, ctx: &NetworkAttachContext) {
+        tracing::info!(
+            "network driver not yet wired to a real netns backend: would leave container {} with no network connectivity",
+            ctx.container_id
+        );
+    }
+}
+
+/// Assigns a container an address directly on a parent interface's L2
+/// segment. `ipvlan` is treated the same way here - the distinction
+/// between the two (shared vs. per-container MAC) only matters once
+/// there's a real netns to configure.
+pub struct MacvlanDriver;
+
+impl NetworkDriver for MacvlanDriver {
+    fn validate_create(&self, req: &NetworkCreateRequest) -> Result<(), NetworkDriverError> {
+        if !req.options.contains_key("parent") {
+            return Err(NetworkDriverError::MissingOption {
+                driver: "macvlan",
+                key: "parent",
+            });
+        }
+        Ok(())
+    }
+
+    fn configure(&self, ctx: &NetworkAttachContext) {
+        let parent = ctx.options.get("parent").map(String::as_str).unwrap_or("?");
+        tracing::info!(
+            "network driver not yet wired to a real netns backend: would assign container {} an address on parent interface {} at {:?}",
+            ctx.container_id,
+            parent,
+            ctx.ip_address
+        );
+    }
+}
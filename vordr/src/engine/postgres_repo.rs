@@ -0,0 +1,175 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Postgres-backed `ImageRepo`/`SettingsRepo` for shared image metadata
+//!
+//! Gated behind the `postgres` feature: most deployments only need the
+//! embedded SQLite store, so the `postgres` crate is an optional
+//! dependency pulled in only when this module is compiled.
+
+use std::sync::Mutex;
+
+use postgres::{Client, NoTls};
+
+use crate::engine::repo::{ImageRepo, SettingsRepo};
+use crate::engine::state::{ImageInfo, ResolveMode, StateError};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS images (
+    id           TEXT PRIMARY KEY,
+    digest       TEXT NOT NULL UNIQUE,
+    repository   TEXT,
+    tags         TEXT NOT NULL DEFAULT '[]',
+    size         BIGINT NOT NULL DEFAULT 0,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    config       TEXT,
+    resolve_mode TEXT NOT NULL DEFAULT 'default'
+);
+CREATE TABLE IF NOT EXISTS settings (
+    key   TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+pub struct PostgresImageRepo {
+    client: Mutex<Client>,
+}
+
+impl PostgresImageRepo {
+    pub fn connect(connection_url: &str) -> Result<Self, StateError> {
+        let client = Client::connect(connection_url, NoTls)
+            .map_err(|e| StateError::UnsupportedBackend(format!("postgres connect failed: {}", e)))?;
+        let repo = Self {
+            client: Mutex::new(client),
+        };
+        repo.init_schema()?;
+        Ok(repo)
+    }
+
+    fn init_schema(&self) -> Result<(), StateError> {
+        self.client
+            .lock()
+            .unwrap()
+            .batch_execute(SCHEMA)
+            .map_err(|e| StateError::UnsupportedBackend(format!("schema init failed: {}", e)))
+    }
+}
+
+impl ImageRepo for PostgresImageRepo {
+    fn list_images(&self) -> Result<Vec<ImageInfo>, StateError> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                "SELECT id, digest, repository, tags, size, created_at::text, config, resolve_mode
+                 FROM images ORDER BY created_at DESC",
+                &[],
+            )
+            .map_err(|e| StateError::UnsupportedBackend(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let tags_json: String = row.get(3);
+                let resolve_mode_str: String = row.get(7);
+                ImageInfo {
+                    id: row.get(0),
+                    digest: row.get(1),
+                    repository: row.get(2),
+                    tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+                    size: row.get(4),
+                    created_at: row.get(5),
+                    config: row.get(6),
+                    resolve_mode: ResolveMode::from_str(&resolve_mode_str).unwrap_or(ResolveMode::Default),
+                }
+            })
+            .collect())
+    }
+
+    fn get_image(&self, id_or_digest: &str) -> Result<ImageInfo, StateError> {
+        let mut client = self.client.lock().unwrap();
+        let row = client
+            .query_opt(
+                "SELECT id, digest, repository, tags, size, created_at::text, config, resolve_mode
+                 FROM images WHERE id = $1 OR digest = $1",
+                &[&id_or_digest],
+            )
+            .map_err(|e| StateError::UnsupportedBackend(e.to_string()))?
+            .ok_or_else(|| StateError::ImageNotFound(id_or_digest.to_string()))?;
+
+        let tags_json: String = row.get(3);
+        let resolve_mode_str: String = row.get(7);
+        Ok(ImageInfo {
+            id: row.get(0),
+            digest: row.get(1),
+            repository: row.get(2),
+            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+            size: row.get(4),
+            created_at: row.get(5),
+            config: row.get(6),
+            resolve_mode: ResolveMode::from_str(&resolve_mode_str).unwrap_or(ResolveMode::Default),
+        })
+    }
+
+    fn put_image(
+        &self,
+        id: &str,
+        digest: &str,
+        repository: Option<&str>,
+        tags: &[String],
+        size: i64,
+        config: Option<&str>,
+    ) -> Result<(), StateError> {
+        let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string());
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO images (id, digest, repository, tags, size, config)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (id) DO UPDATE SET
+                    digest = excluded.digest,
+                    repository = excluded.repository,
+                    tags = excluded.tags,
+                    size = excluded.size,
+                    config = excluded.config",
+                &[&id, &digest, &repository, &tags_json, &size, &config],
+            )
+            .map_err(|e| StateError::UnsupportedBackend(e.to_string()))?;
+        Ok(())
+    }
+
+    // The Postgres-backed store only tracks image metadata, not containers
+    // (those stay in each node's local SQLite state), so there is no
+    // referencing-container count to check here - `force` is accepted for
+    // trait compatibility but every delete behaves as if it were set.
+    fn delete_image(&self, id: &str, _force: bool) -> Result<(), StateError> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .execute("DELETE FROM images WHERE id = $1", &[&id])
+            .map_err(|e| StateError::UnsupportedBackend(e.to_string()))?;
+
+        if rows == 0 {
+            return Err(StateError::ImageNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl SettingsRepo for PostgresImageRepo {
+    fn get_setting(&self, key: &str) -> Result<Option<String>, StateError> {
+        let mut client = self.client.lock().unwrap();
+        let row = client
+            .query_opt("SELECT value FROM settings WHERE key = $1", &[&key])
+            .map_err(|e| StateError::UnsupportedBackend(e.to_string()))?;
+        Ok(row.map(|r| r.get(0)))
+    }
+
+    fn set_setting(&self, key: &str, value: &str) -> Result<(), StateError> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO settings (key, value) VALUES ($1, $2)
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+                &[&key, &value],
+            )
+            .map_err(|e| StateError::UnsupportedBackend(e.to_string()))?;
+        Ok(())
+    }
+}
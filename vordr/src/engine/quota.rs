@@ -0,0 +1,72 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Per-resource-type disk quotas, enforced at allocation time
+
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::engine::usage::disk_usage;
+use crate::engine::{StateError, StateManager};
+
+#[derive(Error, Debug)]
+pub enum QuotaError {
+    #[error("State error: {0}")]
+    State(#[from] StateError),
+    #[error(
+        "{resource} quota exceeded: {used} bytes used, {max} byte limit (pass `system quota set --type {resource} --max <size>` to raise it)"
+    )]
+    Exceeded {
+        resource: String,
+        used: u64,
+        max: u64,
+    },
+}
+
+/// The resource types a quota can be set on. Matches the `resource_type`
+/// strings stored in the `quotas` table and reported by `system df`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaResource {
+    Containers,
+    Volumes,
+    Images,
+}
+
+impl QuotaResource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaResource::Containers => "containers",
+            QuotaResource::Volumes => "volumes",
+            QuotaResource::Images => "images",
+        }
+    }
+}
+
+/// Rejects an allocating operation (container or volume creation) if the
+/// resource type it belongs to is already at or over its configured
+/// quota. Resource types with no quota configured always pass. Uses the
+/// same `engine::usage::disk_usage` accounting `system df` reports, so a
+/// quota and the dashboard it's checked against never disagree; this
+/// reads cached counters rather than forcing a fresh scan on every
+/// allocation, matching how `system df` itself defaults to cached data.
+pub fn enforce(state: &StateManager, root_dir: &Path, resource: QuotaResource) -> Result<(), QuotaError> {
+    let Some(max_bytes) = state.get_quota(resource.as_str())? else {
+        return Ok(());
+    };
+
+    let usage = disk_usage(state, root_dir, false)?;
+    let used = match resource {
+        QuotaResource::Containers => usage.containers.size,
+        QuotaResource::Volumes => usage.volumes.size,
+        QuotaResource::Images => usage.images.size,
+    };
+
+    if used >= max_bytes {
+        return Err(QuotaError::Exceeded {
+            resource: resource.as_str().to_string(),
+            used,
+            max: max_bytes,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Canonical image reference parsing, following containerd's `reference`
+//! package rules.
+//!
+//! `create_image`/`get_image` used to treat `repository` and `tags` as
+//! opaque strings, so `alpine`, `docker.io/library/alpine:latest`, and
+//! `alpine@sha256:...` were all distinct as far as the store was concerned.
+//! [`Reference::parse`] normalizes any of those spellings to the same
+//! `{domain, path, tag, digest}`, and [`Reference::canonical`] renders the
+//! fully-qualified `domain/path:tag` (or `domain/path@digest`) form.
+//! `create_image` stores the `domain/path` part as the repository so
+//! lookups stay unambiguous.
+
+/// A normalized image reference, e.g. `docker.io/library/alpine:latest` or
+/// `ghcr.io/owner/repo@sha256:...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub domain: String,
+    pub path: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl Reference {
+    /// Parses a user-supplied reference string.
+    ///
+    /// - The first slash-delimited component is the registry domain if it
+    ///   contains a `.` or `:`, or is exactly `localhost`; otherwise the
+    ///   domain defaults to `docker.io` and, if the path would otherwise be
+    ///   a single component, it's prefixed with `library/`.
+    /// - A trailing `:tag` is only treated as a tag when it matches
+    ///   `:[\w][\w.-]+$` at the very end of the (digest-stripped) string -
+    ///   this is what keeps a bare `host:5000/image` port from being
+    ///   misread as a tag.
+    /// - A trailing `@sha256:...` is the digest, and is stripped before the
+    ///   tag is looked for.
+    pub fn parse(reference: &str) -> Self {
+        let reference = reference.trim();
+
+        let (rest, digest) = match reference.rfind('@') {
+            Some(pos) => (&reference[..pos], Some(reference[pos + 1..].to_string())),
+            None => (reference, None),
+        };
+
+        let (rest, tag) = match rest.rfind(':') {
+            Some(pos) if is_tag(&rest[pos + 1..]) => (&rest[..pos], Some(rest[pos + 1..].to_string())),
+            _ => (rest, None),
+        };
+
+        let (domain, path) = match rest.split_once('/') {
+            Some((first, remainder)) if is_domain(first) => (first.to_string(), remainder.to_string()),
+            Some(_) => ("docker.io".to_string(), rest.to_string()),
+            None => ("docker.io".to_string(), format!("library/{rest}")),
+        };
+
+        Reference { domain, path, tag, digest }
+    }
+
+    /// Renders the fully-qualified `domain/path:tag` form, or
+    /// `domain/path@digest` when a digest is set; a reference with
+    /// neither renders with `:latest`. Two references that normalize the
+    /// same way render identically regardless of the caller's spelling.
+    pub fn canonical(&self) -> String {
+        let mut out = format!("{}/{}", self.domain, self.path);
+        match (&self.digest, &self.tag) {
+            (Some(digest), _) => {
+                out.push('@');
+                out.push_str(digest);
+            }
+            (None, Some(tag)) => {
+                out.push(':');
+                out.push_str(tag);
+            }
+            (None, None) => {
+                out.push_str(":latest");
+            }
+        }
+        out
+    }
+}
+
+/// Whether `component` (the part of the reference before the first `/`)
+/// looks like a registry domain rather than the first path segment of a
+/// Docker Hub repository.
+fn is_domain(component: &str) -> bool {
+    component.contains('.') || component.contains(':') || component == "localhost"
+}
+
+/// Whether `candidate` (the text after the last `:`) is a valid tag rather
+/// than, say, the port number of a `host:5000/image` reference with no tag
+/// at all.
+fn is_tag(candidate: &str) -> bool {
+    let mut chars = candidate.chars();
+    let Some(first) = chars.next() else { return false };
+    if !(first.is_ascii_alphanumeric() || first == '_') {
+        return false;
+    }
+    let rest = chars.as_str();
+    !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_name_gets_docker_io_and_library_prefix() {
+        let r = Reference::parse("alpine");
+        assert_eq!(r.domain, "docker.io");
+        assert_eq!(r.path, "library/alpine");
+        assert_eq!(r.tag, None);
+        assert_eq!(r.canonical(), "docker.io/library/alpine:latest");
+    }
+
+    #[test]
+    fn fully_qualified_reference_round_trips() {
+        let r = Reference::parse("docker.io/library/alpine:latest");
+        assert_eq!(r.domain, "docker.io");
+        assert_eq!(r.path, "library/alpine");
+        assert_eq!(r.tag.as_deref(), Some("latest"));
+        assert_eq!(r.canonical(), "docker.io/library/alpine:latest");
+    }
+
+    #[test]
+    fn digest_reference_drops_the_default_tag() {
+        let r = Reference::parse("alpine@sha256:abc123");
+        assert_eq!(r.path, "library/alpine");
+        assert_eq!(r.tag, None);
+        assert_eq!(r.digest.as_deref(), Some("sha256:abc123"));
+        assert_eq!(r.canonical(), "docker.io/library/alpine@sha256:abc123");
+    }
+
+    #[test]
+    fn custom_registry_with_port_is_not_mistaken_for_a_tag() {
+        let r = Reference::parse("localhost:5000/myimage");
+        assert_eq!(r.domain, "localhost:5000");
+        assert_eq!(r.path, "myimage");
+        assert_eq!(r.tag, None);
+    }
+
+    #[test]
+    fn custom_registry_with_port_and_tag() {
+        let r = Reference::parse("registry.example.com:5000/org/myimage:v1.2.3");
+        assert_eq!(r.domain, "registry.example.com:5000");
+        assert_eq!(r.path, "org/myimage");
+        assert_eq!(r.tag.as_deref(), Some("v1.2.3"));
+    }
+
+    #[test]
+    fn docker_hub_org_repository_is_not_prefixed() {
+        let r = Reference::parse("library/alpine:3.18");
+        assert_eq!(r.domain, "docker.io");
+        assert_eq!(r.path, "library/alpine");
+        assert_eq!(r.tag.as_deref(), Some("3.18"));
+    }
+}
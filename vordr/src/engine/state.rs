@@ -1,10 +1,17 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
 //! SQLite state management with WAL mode for concurrent access
 
-use rusqlite::{params, Connection, OpenFlags};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use std::path::Path;
+use std::sync::Mutex;
 use thiserror::Error;
 
+use super::crypto::{self, FieldCipher};
+use super::metrics::StateMetrics;
+use super::reference::Reference;
+
 #[derive(Error, Debug)]
 pub enum StateError {
     #[error("Database error: {0}")]
@@ -17,17 +24,37 @@ pub enum StateError {
     ImageNotFound(String),
     #[error("Image already exists: {0}")]
     ImageAlreadyExists(String),
+    #[error("Image still in use by a container: {0}")]
+    ImageInUse(String),
+    #[error("Layer not found or still referenced: {0}")]
+    LayerNotFound(String),
     #[error("Network not found: {0}")]
     NetworkNotFound(String),
     #[error("Volume not found: {0}")]
     VolumeNotFound(String),
+    #[error("Image build not found: {0}")]
+    BuildNotFound(String),
     #[error("Lock acquisition failed: {0}")]
     LockFailed(String),
+    #[error("Unsupported storage backend: {0}")]
+    UnsupportedBackend(String),
+    #[error("Failed to initialize connection pool: {0}")]
+    PoolInit(String),
+    #[error("Schema migration failed: {0}")]
+    MigrationFailed(String),
+    #[error("failed to decrypt column - wrong key or corrupt data")]
+    DecryptionFailed,
 }
 
-/// Container state as stored in database
+/// Container state as stored in database, modeled on the OCI runtime
+/// lifecycle (`creating`, `created`, `running`, `stopped`), plus `paused`
+/// as a Linux-specific extension for the cgroup freezer.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ContainerState {
+    /// The bundle is still being assembled; not yet safe to start, and not
+    /// the same as `Created` so a crash mid-build is distinguishable from a
+    /// finished container.
+    Creating,
     Created,
     Running,
     Paused,
@@ -37,6 +64,7 @@ pub enum ContainerState {
 impl ContainerState {
     pub fn as_str(&self) -> &'static str {
         match self {
+            ContainerState::Creating => "creating",
             ContainerState::Created => "created",
             ContainerState::Running => "running",
             ContainerState::Paused => "paused",
@@ -46,6 +74,7 @@ impl ContainerState {
 
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
+            "creating" => Some(ContainerState::Creating),
             "created" => Some(ContainerState::Created),
             "running" => Some(ContainerState::Running),
             "paused" => Some(ContainerState::Paused),
@@ -53,6 +82,89 @@ impl ContainerState {
             _ => None,
         }
     }
+
+    /// The `docker events`-style action name [`StateManager::set_container_state`]
+    /// records for a transition into this state.
+    fn as_event_action(&self) -> &'static str {
+        match self {
+            ContainerState::Creating => "creating",
+            ContainerState::Created => "create",
+            ContainerState::Running => "start",
+            ContainerState::Paused => "pause",
+            ContainerState::Stopped => "die",
+        }
+    }
+
+    /// Only a fully-assembled container can be started.
+    pub fn can_start(&self) -> bool {
+        matches!(self, ContainerState::Created)
+    }
+
+    /// Signal delivery (`kill`, and `stop`'s SIGTERM/SIGKILL) only makes
+    /// sense while the container is actually running.
+    pub fn can_kill(&self) -> bool {
+        matches!(self, ContainerState::Running)
+    }
+
+    /// Without `force`, only a `Stopped` container can be deleted; `force`
+    /// overrides this and allows deleting out of any state.
+    pub fn can_delete(&self, force: bool) -> bool {
+        force || matches!(self, ContainerState::Stopped)
+    }
+
+    /// Only a running container can be frozen.
+    pub fn can_pause(&self) -> bool {
+        matches!(self, ContainerState::Running)
+    }
+
+    /// Only a frozen container can be thawed.
+    pub fn can_resume(&self) -> bool {
+        matches!(self, ContainerState::Paused)
+    }
+
+    /// Only a running container has live process state worth snapshotting.
+    pub fn can_checkpoint(&self) -> bool {
+        matches!(self, ContainerState::Running)
+    }
+
+    /// Restore targets a freshly-created, not-yet-started container.
+    pub fn can_restore(&self) -> bool {
+        matches!(self, ContainerState::Created)
+    }
+}
+
+/// How a pull/run path should reconcile a requested image reference
+/// against what's already local, borrowed from BuildKit's image source
+/// resolve modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveMode {
+    /// Reuse a locally-cached image by digest when one exists; otherwise
+    /// pull.
+    Default,
+    /// Always re-pull, ignoring any local image with a matching reference.
+    ForcePull,
+    /// Never pull if any local image with a matching digest exists, even
+    /// one resolved from a different tag.
+    PreferLocal,
+}
+
+impl ResolveMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResolveMode::Default => "default",
+            ResolveMode::ForcePull => "force-pull",
+            ResolveMode::PreferLocal => "prefer-local",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "default" => Some(ResolveMode::Default),
+            "force-pull" => Some(ResolveMode::ForcePull),
+            "prefer-local" => Some(ResolveMode::PreferLocal),
+            _ => None,
+        }
+    }
 }
 
 /// Container information
@@ -69,6 +181,72 @@ pub struct ContainerInfo {
     pub started_at: Option<String>,
     pub finished_at: Option<String>,
     pub config: Option<String>,
+    /// Directory holding this container's most recent CRIU checkpoint
+    /// image, if it has ever been checkpointed.
+    pub checkpoint_image_path: Option<String>,
+    /// How this container's image reference should be reconciled against
+    /// local state on a future re-resolve (e.g. `run --pull`), as recorded
+    /// at `create_container` time.
+    pub resolve_mode: ResolveMode,
+    /// Rolling healthcheck status, updated by
+    /// [`StateManager::record_health_probe`]. `None` if the container has
+    /// no healthcheck configured.
+    pub health_status: HealthStatus,
+    /// The last few healthcheck probe results, most recent last. Bounded
+    /// to [`HEALTH_LOG_LIMIT`] entries.
+    pub health_log: Vec<HealthProbeLog>,
+}
+
+/// How many consecutive health probes are worth keeping in
+/// [`ContainerInfo::health_log`] - enough to see a flapping pattern without
+/// the column growing unboundedly over a long-running container's life.
+const HEALTH_LOG_LIMIT: usize = 5;
+
+/// A container's rolling healthcheck status, mirroring Docker's
+/// `Starting`/`healthy`/`unhealthy` states plus `None` for containers with
+/// no healthcheck configured at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// No healthcheck is configured for this container.
+    None,
+    /// A healthcheck is configured but hasn't run enough probes yet to
+    /// judge (still inside `start_period`, or fewer than `retries`
+    /// consecutive results are in).
+    Starting,
+    Healthy,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthStatus::None => "none",
+            HealthStatus::Starting => "starting",
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Unhealthy => "unhealthy",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(HealthStatus::None),
+            "starting" => Some(HealthStatus::Starting),
+            "healthy" => Some(HealthStatus::Healthy),
+            "unhealthy" => Some(HealthStatus::Unhealthy),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded healthcheck probe result, as stored (JSON-encoded, newest
+/// last) in the `containers.health_log` column.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HealthProbeLog {
+    pub at: String,
+    pub success: bool,
+    /// Combined stdout/stderr from the probe command, truncated by the
+    /// caller if needed before it's recorded.
+    pub output: String,
 }
 
 /// Image information
@@ -80,6 +258,140 @@ pub struct ImageInfo {
     pub tags: Vec<String>,
     pub size: i64,
     pub created_at: String,
+    /// JSON-encoded OCI image config (architecture, os, entrypoint/cmd,
+    /// env, labels), when known.
+    pub config: Option<String>,
+    /// How a future pull resolving this image's reference should treat
+    /// this local copy. Set via [`StateManager::set_image_resolve_mode`].
+    pub resolve_mode: ResolveMode,
+}
+
+/// Result of [`StateManager::prune_images`]: the ids actually removed, and
+/// the sum of their stored `size` - not a real measurement of reclaimed
+/// disk space, since layers can be shared across images and aren't swept
+/// here (see [`StateManager::unreferenced_layers`] for that).
+#[derive(Debug, Clone, Default)]
+pub struct ImagePruneResult {
+    pub removed_ids: Vec<String>,
+    pub reclaimed_bytes: i64,
+}
+
+/// Where an [`ImageBuildInfo`] sourced the content it's building, mirroring
+/// how a CI system triggers a build off a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildRefType {
+    Branch,
+    Commit,
+    Tag,
+}
+
+impl BuildRefType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuildRefType::Branch => "branch",
+            BuildRefType::Commit => "commit",
+            BuildRefType::Tag => "tag",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "branch" => Some(BuildRefType::Branch),
+            "commit" => Some(BuildRefType::Commit),
+            "tag" => Some(BuildRefType::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// Where an image build currently stands, as tracked by
+/// [`StateManager::start_image_build`]/[`StateManager::update_build_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildStatus {
+    Queued,
+    Building,
+    Built,
+    Failed,
+}
+
+impl BuildStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuildStatus::Queued => "queued",
+            BuildStatus::Building => "building",
+            BuildStatus::Built => "built",
+            BuildStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "queued" => Some(BuildStatus::Queued),
+            "building" => Some(BuildStatus::Building),
+            "built" => Some(BuildStatus::Built),
+            "failed" => Some(BuildStatus::Failed),
+            _ => None,
+        }
+    }
+
+    /// Whether this status is a terminal one - no further transition is
+    /// expected without starting a new build.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, BuildStatus::Built | BuildStatus::Failed)
+    }
+}
+
+/// One row of the `image_builds` table: an image that is being produced
+/// rather than already present, keyed by the source ref that triggered it
+/// (e.g. "is the image for branch main built yet"). Returned as-is while a
+/// build is in flight or has failed; once [`BuildStatus::Built`],
+/// [`StateManager::get_build_status`] resolves it to the finished
+/// [`ImageInfo`] instead so callers don't need a second lookup.
+#[derive(Debug, Clone)]
+pub struct ImageBuildInfo {
+    pub id: i64,
+    pub repo: String,
+    pub image_name: String,
+    pub ref_type: BuildRefType,
+    pub ref_value: String,
+    pub status: BuildStatus,
+    pub image_sha: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+impl FromRow for ImageBuildInfo {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let ref_type_str: String = row.get(3)?;
+        let status_str: String = row.get(5)?;
+        Ok(ImageBuildInfo {
+            id: row.get(0)?,
+            repo: row.get(1)?,
+            image_name: row.get(2)?,
+            ref_type: BuildRefType::from_str(&ref_type_str).unwrap_or(BuildRefType::Branch),
+            ref_value: row.get(4)?,
+            status: BuildStatus::from_str(&status_str).unwrap_or(BuildStatus::Queued),
+            image_sha: row.get(6)?,
+            started_at: row.get(7)?,
+            finished_at: row.get(8)?,
+        })
+    }
+}
+
+/// Answer to [`StateManager::get_build_status`]: either the build is still
+/// in flight (or failed) and the raw row is all there is, or it finished
+/// and the caller gets the resolved image straight away.
+#[derive(Debug, Clone)]
+pub enum BuildLookup {
+    /// No build has ever been recorded for this repo/image/ref.
+    NotFound,
+    /// The build has no resolvable image: it is queued, building or
+    /// failed, or it reached [`BuildStatus::Built`] but its image is no
+    /// longer in the `images` table.
+    InProgress(ImageBuildInfo),
+    /// The build finished and its image is still present in the `images`
+    /// table.
+    Complete(ImageInfo),
 }
 
 /// Network information
@@ -91,9 +403,41 @@ pub struct NetworkInfo {
     pub subnet: Option<String>,
     pub gateway: Option<String>,
     pub options: Option<String>,
+    /// No external connectivity - containers attached to this network are
+    /// forced into `NetworkMode::Restricted` by the run path.
+    pub internal: bool,
     pub created_at: String,
 }
 
+/// One container's name/aliases/address on a network, as needed to answer a
+/// DNS lookup for it. Returned by [`StateManager::network_dns_records`].
+#[derive(Debug, Clone)]
+pub struct NetworkDnsRecord {
+    pub container_name: String,
+    pub aliases: Vec<String>,
+    pub ip_address: String,
+}
+
+/// A container's attachment to a network - the `docker network inspect`
+/// `Containers` entry shape. Returned by [`StateManager::network_endpoints`];
+/// a superset of [`NetworkDnsRecord`] keyed by container ID instead of name
+/// and including the allocated MAC address.
+#[derive(Debug, Clone)]
+pub struct NetworkEndpoint {
+    pub container_id: String,
+    pub ip_address: Option<String>,
+    pub mac_address: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+/// Content-addressed image layer, shared across images.
+#[derive(Debug, Clone)]
+pub struct LayerInfo {
+    pub digest: String,
+    pub size: i64,
+    pub refcount: i64,
+}
+
 /// Volume information
 #[derive(Debug, Clone)]
 pub struct VolumeInfo {
@@ -103,25 +447,213 @@ pub struct VolumeInfo {
     pub mountpoint: String,
     pub options: Option<String>,
     pub labels: Option<String>,
+    /// Number of containers currently mounting this volume. Recomputed from
+    /// `container_volumes` on every [`StateManager::open`], never trusted
+    /// across a crash.
+    pub refcount: i64,
+    pub created_at: String,
+}
+
+/// One row of the durable lifecycle event log - a container/network/volume
+/// transition in the `docker events` sense, appended by
+/// [`StateManager::record_event`] and read back by
+/// [`StateManager::list_events`]/[`StateManager::watch_events`]. Distinct
+/// from [`crate::engine::events::PolicyEvent`], which is the JSONL-backed
+/// gatekeeper rejection log.
+#[derive(Debug, Clone)]
+pub struct LifecycleEvent {
+    pub id: i64,
     pub created_at: String,
+    pub object_type: String,
+    pub object_id: String,
+    pub action: String,
+    pub payload: Option<String>,
+}
+
+/// Filter predicates for [`StateManager::list_events`]/[`StateManager::watch_events`].
+/// Every set field is AND-combined with the others; an unset field matches
+/// everything. Mirrors [`crate::engine::events::EventFilter`]'s
+/// filter-in-Rust approach rather than building dynamic SQL.
+#[derive(Debug, Clone, Default)]
+pub struct LifecycleEventFilter {
+    pub object_type: Option<String>,
+    pub object_id: Option<String>,
+    pub action: Option<String>,
+}
+
+impl LifecycleEventFilter {
+    fn matches(&self, event: &LifecycleEvent) -> bool {
+        if let Some(ref object_type) = self.object_type {
+            if &event.object_type != object_type {
+                return false;
+            }
+        }
+        if let Some(ref object_id) = self.object_id {
+            if &event.object_id != object_id {
+                return false;
+            }
+        }
+        if let Some(ref action) = self.action {
+            if &event.action != action {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Maps one `rusqlite::Row` to `Self`. Every `*Info` struct implements this
+/// so `get_*`/`list_*` methods can share [`StateManager::query_one`] and
+/// [`StateManager::query_all`] instead of each repeating its own
+/// near-identical mapping closure - and so a future table gains the same
+/// typed query surface for free.
+pub trait FromRow: Sized {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self>;
+}
+
+impl FromRow for ImageInfo {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let tags_json: String = row.get(3)?;
+        let resolve_mode_str: String = row.get(7)?;
+        Ok(ImageInfo {
+            id: row.get(0)?,
+            digest: row.get(1)?,
+            repository: row.get(2)?,
+            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+            size: row.get(4)?,
+            created_at: row.get(5)?,
+            config: row.get(6)?,
+            resolve_mode: ResolveMode::from_str(&resolve_mode_str).unwrap_or(ResolveMode::Default),
+        })
+    }
+}
+
+impl FromRow for ContainerInfo {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let state_str: String = row.get(4)?;
+        let state = ContainerState::from_str(&state_str).unwrap_or(ContainerState::Created);
+
+        let resolve_mode_str: String = row.get(12)?;
+        let health_status_str: String = row.get(13)?;
+        let health_log_json: Option<String> = row.get(14)?;
+
+        Ok(ContainerInfo {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            image_id: row.get(2)?,
+            bundle_path: row.get(3)?,
+            state,
+            pid: row.get(5)?,
+            exit_code: row.get(6)?,
+            created_at: row.get(7)?,
+            started_at: row.get(8)?,
+            finished_at: row.get(9)?,
+            config: row.get(10)?,
+            checkpoint_image_path: row.get(11)?,
+            resolve_mode: ResolveMode::from_str(&resolve_mode_str).unwrap_or(ResolveMode::Default),
+            health_status: HealthStatus::from_str(&health_status_str).unwrap_or(HealthStatus::None),
+            health_log: health_log_json
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl FromRow for NetworkInfo {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(NetworkInfo {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            driver: row.get(2)?,
+            subnet: row.get(3)?,
+            gateway: row.get(4)?,
+            options: row.get(5)?,
+            internal: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+impl FromRow for VolumeInfo {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(VolumeInfo {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            driver: row.get(2)?,
+            mountpoint: row.get(3)?,
+            options: row.get(4)?,
+            labels: row.get(5)?,
+            refcount: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+impl FromRow for LifecycleEvent {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(LifecycleEvent {
+            id: row.get(0)?,
+            created_at: row.get(1)?,
+            object_type: row.get(2)?,
+            object_id: row.get(3)?,
+            action: row.get(4)?,
+            payload: row.get(5)?,
+        })
+    }
 }
 
+/// Default interval [`EventWatcher`] sleeps between polls when nothing new
+/// has appeared yet.
+const EVENT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Number of pooled reader connections opened by [`StateManager::open`] when
+/// the caller doesn't request a specific size.
+const DEFAULT_POOL_SIZE: u32 = 8;
+
 pub struct StateManager {
-    conn: Connection,
+    /// Multiple connections for concurrent readers - WAL mode allows these
+    /// to run alongside each other and alongside the writer without
+    /// blocking, unlike the single `SQLITE_OPEN_FULL_MUTEX` connection this
+    /// struct used to serialize everything through.
+    read_pool: Pool<SqliteConnectionManager>,
+    /// A single dedicated connection for writes, serialized behind a
+    /// mutex the same way [`super::postgres_repo::PostgresImageRepo`]
+    /// serializes its one client connection - SQLite only ever allows one
+    /// writer at a time regardless, so a pool of writers would just queue
+    /// on the database's own lock instead of this one.
+    writer: Mutex<Connection>,
+    /// When set, encrypts `containers.config`/`volumes.labels` at rest -
+    /// see [`crate::engine::crypto`]. `None` for every caller that hasn't
+    /// opted into [`Self::open_with_key`].
+    cipher: Option<FieldCipher>,
 }
 
 impl StateManager {
-    /// Open or create the state database.
-    /// Automatically detects filesystem type and configures journal mode.
+    /// Open or create the state database with [`DEFAULT_POOL_SIZE`] pooled
+    /// reader connections. Automatically detects filesystem type and
+    /// configures journal mode.
     pub fn open(db_path: &Path) -> Result<Self, StateError> {
-        let conn = Connection::open_with_flags(
-            db_path,
-            OpenFlags::SQLITE_OPEN_READ_WRITE
-                | OpenFlags::SQLITE_OPEN_CREATE
-                | OpenFlags::SQLITE_OPEN_FULL_MUTEX,
-        )?;
+        Self::open_internal(db_path, DEFAULT_POOL_SIZE, None)
+    }
+
+    /// Same as [`Self::open`], but with an explicit number of pooled reader
+    /// connections - a multi-threaded daemon fielding heavy concurrent
+    /// `ps`/`inspect` traffic may want more than the default.
+    pub fn open_with_pool_size(db_path: &Path, pool_size: u32) -> Result<Self, StateError> {
+        Self::open_internal(db_path, pool_size, None)
+    }
 
-        // Detect filesystem and configure journal mode
+    /// Same as [`Self::open`], but encrypts `containers.config` and
+    /// `volumes.labels` at rest under `key` with AES-256-GCM before they
+    /// ever reach SQLite - see [`crate::engine::crypto`]. `key` is never
+    /// persisted; opening an existing encrypted database with the wrong
+    /// key surfaces [`StateError::DecryptionFailed`] the first time an
+    /// encrypted row is read, not on open.
+    pub fn open_with_key(db_path: &Path, key: &[u8; 32]) -> Result<Self, StateError> {
+        Self::open_internal(db_path, DEFAULT_POOL_SIZE, Some(FieldCipher::new(key)))
+    }
+
+    fn open_internal(db_path: &Path, pool_size: u32, cipher: Option<FieldCipher>) -> Result<Self, StateError> {
         let journal_mode = if Self::supports_wal(db_path) {
             "WAL"
         } else {
@@ -129,23 +661,159 @@ impl StateManager {
             "DELETE"
         };
 
+        // The writer connection owns schema setup; every reader pool
+        // connection opens against the same on-disk file afterward and
+        // only needs its own pragma setup, not another migration pass.
+        let mut writer = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+                | OpenFlags::SQLITE_OPEN_CREATE
+                | OpenFlags::SQLITE_OPEN_FULL_MUTEX,
+        )?;
+        Self::configure_connection(&writer, journal_mode)?;
+        super::migrations::apply(&mut writer)?;
+
+        let read_pool = Self::build_read_pool(
+            SqliteConnectionManager::file(db_path)
+                .with_flags(OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_FULL_MUTEX),
+            journal_mode,
+            pool_size,
+        )?;
+
+        let manager = Self {
+            read_pool,
+            writer: Mutex::new(writer),
+            cipher,
+        };
+        manager.recompute_volume_refcounts()?;
+        Ok(manager)
+    }
+
+    /// Open an in-memory database (for testing). Uses a uniquely-named
+    /// shared-cache `file::memory:` database rather than a plain
+    /// `:memory:` connection, since a real pool of independent `:memory:`
+    /// connections would each see their own empty database; shared-cache
+    /// mode lets the reader pool see the same data as the writer for as
+    /// long as the writer connection stays open.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, StateError> {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let uri = format!("file:vordr-test-{}?mode=memory&cache=shared", id);
+
+        let mut writer = Connection::open_with_flags(
+            &uri,
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+                | OpenFlags::SQLITE_OPEN_CREATE
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_FULL_MUTEX,
+        )?;
+        writer.pragma_update(None, "foreign_keys", "ON")?;
+        super::migrations::apply(&mut writer)?;
+
+        let read_pool = Self::build_read_pool(
+            SqliteConnectionManager::file(&uri).with_flags(
+                OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_FULL_MUTEX,
+            ),
+            "WAL",
+            4,
+        )?;
+
+        let manager = Self {
+            read_pool,
+            writer: Mutex::new(writer),
+            cipher: None,
+        };
+        manager.recompute_volume_refcounts()?;
+        Ok(manager)
+    }
+
+    fn build_read_pool(
+        manager: SqliteConnectionManager,
+        journal_mode: &str,
+        pool_size: u32,
+    ) -> Result<Pool<SqliteConnectionManager>, StateError> {
+        let journal_mode = journal_mode.to_string();
+        Pool::builder()
+            .max_size(pool_size.max(1))
+            .build(manager.with_init(move |conn| Self::configure_connection(conn, &journal_mode)))
+            .map_err(|e| StateError::PoolInit(e.to_string()))
+    }
+
+    /// Applies the pragma setup every connection - writer or pooled reader -
+    /// needs on checkout: journal mode, relaxed (but still WAL-safe) sync,
+    /// and foreign key enforcement.
+    fn configure_connection(conn: &Connection, journal_mode: &str) -> rusqlite::Result<()> {
         conn.pragma_update(None, "journal_mode", journal_mode)?;
         conn.pragma_update(None, "synchronous", "NORMAL")?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
+        Ok(())
+    }
 
-        // Initialise schema
-        conn.execute_batch(include_str!("../../schema.sql"))?;
+    /// Checks out a pooled reader connection for a query.
+    fn read(&self) -> Result<r2d2::PooledConnection<SqliteConnectionManager>, StateError> {
+        self.read_pool.get().map_err(|e| StateError::PoolInit(e.to_string()))
+    }
 
-        Ok(Self { conn })
+    /// Locks the dedicated writer connection for a mutation.
+    fn write(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.writer.lock().unwrap()
     }
 
-    /// Open an in-memory database (for testing)
-    #[cfg(test)]
-    pub fn open_in_memory() -> Result<Self, StateError> {
-        let conn = Connection::open_in_memory()?;
-        conn.pragma_update(None, "foreign_keys", "ON")?;
-        conn.execute_batch(include_str!("../../schema.sql"))?;
-        Ok(Self { conn })
+    /// Runs `sql` against a pooled reader connection and maps every
+    /// resulting row via [`FromRow`].
+    fn query_all<T: FromRow, P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<Vec<T>, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt
+            .query_map(params, |row| T::from_row(row))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Runs `sql` against a pooled reader connection and maps the single
+    /// expected row via [`FromRow`], calling `not_found` instead of
+    /// returning a generic "no rows" error when it doesn't exist.
+    fn query_one<T: FromRow, P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        params: P,
+        not_found: impl FnOnce() -> StateError,
+    ) -> Result<T, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare(sql)?;
+        stmt.query_row(params, |row| T::from_row(row)).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => not_found(),
+            _ => StateError::Database(e),
+        })
+    }
+
+    /// Appends one row to the `events` table. Takes a `&Connection` rather
+    /// than locking the writer itself, so a mutating method can run this as
+    /// part of the same `rusqlite::Transaction` as the mutation it
+    /// describes - a reader should never observe one without the other.
+    fn record_event(
+        conn: &Connection,
+        object_type: &str,
+        object_id: &str,
+        action: &str,
+        payload: Option<&str>,
+    ) -> rusqlite::Result<()> {
+        conn.execute(
+            "INSERT INTO events (object_type, object_id, action, payload) VALUES (?1, ?2, ?3, ?4)",
+            params![object_type, object_id, action, payload],
+        )?;
+        Ok(())
+    }
+
+    /// Starts an atomic batch of heterogeneous create/connect/mount calls -
+    /// see [`StateTransaction`]. Holds the writer lock for the returned
+    /// guard's entire lifetime, so keep it short-lived: calling any other
+    /// `StateManager` write method before it commits or drops deadlocks on
+    /// the same mutex.
+    pub fn transaction(&self) -> Result<StateTransaction<'_>, StateError> {
+        StateTransaction::begin(self.write(), self.cipher.as_ref())
     }
 
     /// Check if the filesystem supports WAL mode (shared memory).
@@ -172,7 +840,9 @@ impl StateManager {
 
     // === IMAGE OPERATIONS ===
 
-    /// Create a new image record.
+    /// Create a new image record, registering the layer digests it is
+    /// composed of. Layers already known to the store have their refcount
+    /// incremented rather than being duplicated.
     pub fn create_image(
         &self,
         id: &str,
@@ -180,14 +850,23 @@ impl StateManager {
         repository: Option<&str>,
         tags: &[String],
         size: i64,
+        layers: &[(String, i64)],
+        config: Option<&str>,
     ) -> Result<(), StateError> {
+        // Canonicalize to `domain/path` (e.g. `alpine` and `library/alpine`
+        // both become `docker.io/library/alpine`) so `get_image` can resolve
+        // any spelling of the same repository to this one row.
+        let repository = repository.map(|r| {
+            let reference = Reference::parse(r);
+            format!("{}/{}", reference.domain, reference.path)
+        });
         let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string());
+        let conn = self.write();
 
-        self.conn
-            .execute(
-                "INSERT INTO images (id, digest, repository, tags, size)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![id, digest, repository, tags_json, size],
+        conn.execute(
+                "INSERT INTO images (id, digest, repository, tags, size, config)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![id, digest, repository, tags_json, size, config],
             )
             .map_err(|e| {
                 if let rusqlite::Error::SqliteFailure(ref err, _) = e {
@@ -197,137 +876,463 @@ impl StateManager {
                 }
                 StateError::Database(e)
             })?;
+
+        // `image_layers` has a PRIMARY KEY of (image_id, layer_digest) and
+        // is populated with INSERT OR IGNORE, so a digest repeated within
+        // `layers` - two manifest layers sharing a byte-identical empty
+        // diff, say - only ever gets one row there. The refcount below
+        // has to dedupe the same way, or a repeated digest inflates it
+        // past what `delete_image`'s one-decrement-per-row loop can ever
+        // undo, leaving the layer permanently unreclaimable.
+        let mut counted = std::collections::HashSet::new();
+        for (position, (layer_digest, layer_size)) in layers.iter().enumerate() {
+            if counted.insert(layer_digest) {
+                conn.execute(
+                    "INSERT INTO layers (digest, size, refcount) VALUES (?1, ?2, 1)
+                     ON CONFLICT(digest) DO UPDATE SET refcount = refcount + 1",
+                    params![layer_digest, layer_size],
+                )?;
+            }
+            conn.execute(
+                "INSERT OR IGNORE INTO image_layers (image_id, layer_digest, position)
+                 VALUES (?1, ?2, ?3)",
+                params![id, layer_digest, position as i64],
+            )?;
+        }
+
         Ok(())
     }
 
-    /// Get an image by ID or digest.
+    /// Get an image by ID, digest, or any spelling of a `name[:tag]` or
+    /// `name@digest` reference that [`Reference::parse`] would normalize to
+    /// the same repository - so `alpine`, `docker.io/library/alpine:latest`,
+    /// and `library/alpine` all resolve to the image `create_image` stored
+    /// under its canonical repository.
     pub fn get_image(&self, id_or_digest: &str) -> Result<ImageInfo, StateError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, digest, repository, tags, size, created_at
-             FROM images WHERE id = ?1 OR digest = ?1",
-        )?;
-
-        stmt.query_row([id_or_digest], |row| {
-            let tags_json: String = row.get(3)?;
-            let tags: Vec<String> =
-                serde_json::from_str(&tags_json).unwrap_or_default();
-
-            Ok(ImageInfo {
-                id: row.get(0)?,
-                digest: row.get(1)?,
-                repository: row.get(2)?,
-                tags,
-                size: row.get(4)?,
-                created_at: row.get(5)?,
+        let direct = {
+            let conn = self.read()?;
+            let mut stmt = conn.prepare(
+                "SELECT id, digest, repository, tags, size, created_at, config, resolve_mode
+                 FROM images WHERE id = ?1 OR digest = ?1",
+            )?;
+            stmt.query_row([id_or_digest], |row| ImageInfo::from_row(row)).optional()?
+        };
+        if let Some(image) = direct {
+            return Ok(image);
+        }
+
+        let reference = Reference::parse(id_or_digest);
+        let canonical_repository = format!("{}/{}", reference.domain, reference.path);
+        let tag = reference.tag.as_deref().unwrap_or("latest");
+
+        self.list_images()?
+            .into_iter()
+            .find(|image| match &reference.digest {
+                Some(digest) => &image.digest == digest,
+                None => {
+                    image.repository.as_deref() == Some(canonical_repository.as_str())
+                        && image.tags.iter().any(|t| t == tag)
+                }
             })
-        })
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => {
-                StateError::ImageNotFound(id_or_digest.to_string())
-            }
-            _ => StateError::Database(e),
-        })
+            .ok_or_else(|| StateError::ImageNotFound(id_or_digest.to_string()))
     }
 
     /// List all images.
     pub fn list_images(&self) -> Result<Vec<ImageInfo>, StateError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, digest, repository, tags, size, created_at FROM images
+        self.query_all(
+            "SELECT id, digest, repository, tags, size, created_at, config, resolve_mode FROM images
              ORDER BY created_at DESC",
-        )?;
-
-        let images = stmt
-            .query_map([], |row| {
-                let tags_json: String = row.get(3)?;
-                let tags: Vec<String> =
-                    serde_json::from_str(&tags_json).unwrap_or_default();
-
-                Ok(ImageInfo {
-                    id: row.get(0)?,
-                    digest: row.get(1)?,
-                    repository: row.get(2)?,
-                    tags,
-                    size: row.get(4)?,
-                    created_at: row.get(5)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(images)
+            [],
+        )
     }
 
-    /// Delete an image.
-    pub fn delete_image(&self, id: &str) -> Result<(), StateError> {
-        let rows = self
-            .conn
-            .execute("DELETE FROM images WHERE id = ?1", params![id])?;
-
+    /// Sets the pull-resolution policy an image's reference should be
+    /// reconciled with on a future re-resolve. See [`ResolveMode`].
+    pub fn set_image_resolve_mode(&self, id: &str, mode: ResolveMode) -> Result<(), StateError> {
+        let rows = self.write().execute(
+            "UPDATE images SET resolve_mode = ?1 WHERE id = ?2",
+            params![mode.as_str(), id],
+        )?;
         if rows == 0 {
             return Err(StateError::ImageNotFound(id.to_string()));
         }
         Ok(())
     }
 
-    // === CONTAINER OPERATIONS ===
+    /// Whether any local image already has this exact digest - the check a
+    /// [`ResolveMode::PreferLocal`] decision needs before deciding to pull,
+    /// since a digest match is valid regardless of which tag originally
+    /// resolved to it.
+    pub fn has_local_digest(&self, digest: &str) -> Result<bool, StateError> {
+        let conn = self.read()?;
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM images WHERE digest = ?1)",
+            [digest],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
 
-    /// Create a new container record.
-    pub fn create_container(
+    /// Create an image record if it does not already exist, or update its
+    /// metadata in place if it does. Used by [`crate::engine::repo::ImageRepo`]
+    /// implementations where callers don't track whether the image is new.
+    pub fn upsert_image(
         &self,
         id: &str,
-        name: &str,
-        image_id: &str,
-        bundle_path: &str,
-        config: Option<&str>,
-    ) -> Result<(), StateError> {
-        self.conn
-            .execute(
-                "INSERT INTO containers (id, name, image_id, bundle_path, state, config)
-             VALUES (?1, ?2, ?3, ?4, 'created', ?5)",
-                params![id, name, image_id, bundle_path, config],
-            )
-            .map_err(|e| {
-                if let rusqlite::Error::SqliteFailure(ref err, _) = e {
-                    if err.code == rusqlite::ffi::ErrorCode::ConstraintViolation {
-                        return StateError::ContainerAlreadyExists(name.to_string());
-                    }
+        digest: &str,
+        repository: Option<&str>,
+        tags: &[String],
+        size: i64,
+        config: Option<&str>,
+    ) -> Result<(), StateError> {
+        let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string());
+
+        self.write().execute(
+            "INSERT INTO images (id, digest, repository, tags, size, config)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET
+                digest = excluded.digest,
+                repository = excluded.repository,
+                tags = excluded.tags,
+                size = excluded.size,
+                config = excluded.config",
+            params![id, digest, repository, tags_json, size, config],
+        )?;
+        Ok(())
+    }
+
+    /// Delete an image, decrementing the refcount of every layer it
+    /// referenced. A layer whose refcount reaches zero becomes eligible for
+    /// collection by [`StateManager::unreferenced_layers`].
+    ///
+    /// Refuses with [`StateError::ImageInUse`] if any container still
+    /// references this image, unless `force` is set.
+    pub fn delete_image(&self, id: &str, force: bool) -> Result<(), StateError> {
+        let conn = self.write();
+
+        if !force {
+            let referencing: i64 =
+                conn.query_row("SELECT COUNT(*) FROM containers WHERE image_id = ?1", params![id], |row| {
+                    row.get(0)
+                })?;
+            if referencing > 0 {
+                return Err(StateError::ImageInUse(id.to_string()));
+            }
+        }
+
+        let layer_digests: Vec<String> = {
+            let mut stmt = conn.prepare("SELECT layer_digest FROM image_layers WHERE image_id = ?1")?;
+            stmt.query_map([id], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let rows = conn.execute("DELETE FROM images WHERE id = ?1", params![id])?;
+
+        if rows == 0 {
+            return Err(StateError::ImageNotFound(id.to_string()));
+        }
+
+        for digest in layer_digests {
+            conn.execute(
+                "UPDATE layers SET refcount = refcount - 1 WHERE digest = ?1",
+                params![digest],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Images with zero referencing containers - the candidate set for
+    /// [`Self::prune_images`] - optionally narrowed to dangling ones (no
+    /// repository or tags pointing at them, the `docker image prune`
+    /// default before `--all`).
+    pub fn unreferenced_images(&self, dangling_only: bool) -> Result<Vec<ImageInfo>, StateError> {
+        let images = self.list_images()?;
+        let in_use: std::collections::HashSet<String> =
+            self.list_containers(None)?.into_iter().map(|c| c.image_id).collect();
+
+        Ok(images
+            .into_iter()
+            .filter(|image| !in_use.contains(&image.id))
+            .filter(|image| !dangling_only || (image.repository.is_none() && image.tags.is_empty()))
+            .collect())
+    }
+
+    /// Deletes every image [`Self::unreferenced_images`] reports for
+    /// `dangling_only`, returning the ids actually removed and the summed
+    /// `size` reclaimed.
+    pub fn prune_images(&self, dangling_only: bool) -> Result<ImagePruneResult, StateError> {
+        let candidates = self.unreferenced_images(dangling_only)?;
+
+        let mut result = ImagePruneResult::default();
+        for image in candidates {
+            // Already known unreferenced above, so force past the check
+            // delete_image would otherwise redo against a slightly staler
+            // view of the containers table.
+            self.delete_image(&image.id, true)?;
+            result.reclaimed_bytes += image.size;
+            result.removed_ids.push(image.id);
+        }
+
+        Ok(result)
+    }
+
+    // === LAYER OPERATIONS ===
+
+    /// List all known layers with their current refcount.
+    pub fn list_layers(&self) -> Result<Vec<LayerInfo>, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare("SELECT digest, size, refcount FROM layers ORDER BY digest")?;
+
+        let layers = stmt
+            .query_map([], |row| {
+                Ok(LayerInfo {
+                    digest: row.get(0)?,
+                    size: row.get(1)?,
+                    refcount: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(layers)
+    }
+
+    /// List the layers that make up a single image, in the order they were
+    /// registered by [`StateManager::create_image`].
+    pub fn image_layers(&self, image_id: &str) -> Result<Vec<LayerInfo>, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare(
+            "SELECT l.digest, l.size, l.refcount
+             FROM image_layers il
+             JOIN layers l ON l.digest = il.layer_digest
+             WHERE il.image_id = ?1
+             ORDER BY il.position",
+        )?;
+
+        let layers = stmt
+            .query_map([image_id], |row| {
+                Ok(LayerInfo {
+                    digest: row.get(0)?,
+                    size: row.get(1)?,
+                    refcount: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(layers)
+    }
+
+    /// List layers with a refcount of zero or less (the "mark" phase of the
+    /// layer GC: the live set is everything still referenced by an image).
+    pub fn unreferenced_layers(&self) -> Result<Vec<LayerInfo>, StateError> {
+        Ok(self
+            .list_layers()?
+            .into_iter()
+            .filter(|l| l.refcount <= 0)
+            .collect())
+    }
+
+    /// Digests currently reserved by an in-flight pull, keyed via the
+    /// advisory lock table so a concurrent pull's blob is never swept as
+    /// dead even before its owning image is committed.
+    pub fn reserved_layer_digests(&self) -> Result<std::collections::HashSet<String>, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare("SELECT resource_id FROM locks WHERE resource_type = 'layer_pull'")?;
+
+        let digests = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<std::collections::HashSet<String>, _>>()?;
+
+        Ok(digests)
+    }
+
+    /// Remove a layer's bookkeeping row once its on-disk blob has been
+    /// swept. Only valid for layers with a non-positive refcount.
+    pub fn delete_layer_record(&self, digest: &str) -> Result<(), StateError> {
+        let rows = self.write().execute(
+            "DELETE FROM layers WHERE digest = ?1 AND refcount <= 0",
+            params![digest],
+        )?;
+
+        if rows == 0 {
+            return Err(StateError::LayerNotFound(digest.to_string()));
+        }
+        Ok(())
+    }
+
+    // === IMAGE BUILD OPERATIONS ===
+
+    /// Records the start of a new build, identified by the `repo`/`image_name`
+    /// it will produce and the source ref that triggered it. Returns the new
+    /// row's id for a later [`Self::update_build_status`] call.
+    pub fn start_image_build(
+        &self,
+        repo: &str,
+        image_name: &str,
+        ref_type: BuildRefType,
+        ref_value: &str,
+    ) -> Result<i64, StateError> {
+        let conn = self.write();
+        conn.execute(
+            "INSERT INTO image_builds (repo, image_name, ref_type, ref_value, status)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![repo, image_name, ref_type.as_str(), ref_value, BuildStatus::Queued.as_str()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Advances a build to `status`, optionally recording the finished
+    /// image's id/digest. `finished_at` is stamped automatically the first
+    /// time `status` becomes terminal (see [`BuildStatus::is_terminal`]).
+    pub fn update_build_status(
+        &self,
+        build_id: i64,
+        status: BuildStatus,
+        image_sha: Option<&str>,
+    ) -> Result<(), StateError> {
+        let conn = self.write();
+        let rows = if status.is_terminal() {
+            conn.execute(
+                "UPDATE image_builds SET status = ?1, image_sha = ?2, finished_at = CURRENT_TIMESTAMP
+                 WHERE id = ?3",
+                params![status.as_str(), image_sha, build_id],
+            )?
+        } else {
+            conn.execute(
+                "UPDATE image_builds SET status = ?1, image_sha = ?2 WHERE id = ?3",
+                params![status.as_str(), image_sha, build_id],
+            )?
+        };
+
+        if rows == 0 {
+            return Err(StateError::BuildNotFound(build_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Answers "is the image for this repo/image/ref built yet, and what is
+    /// its SHA" without polling [`Self::list_images`]: looks up the most
+    /// recent build matching `repo`/`image_name`/`ref` (any [`BuildRefType`]),
+    /// resolving it to the finished [`ImageInfo`] once [`BuildStatus::Built`]
+    /// rather than handing back a dangling `image_sha`.
+    pub fn get_build_status(&self, repo: &str, image_name: &str, ref_value: &str) -> Result<BuildLookup, StateError> {
+        let build: Option<ImageBuildInfo> = self
+            .query_all(
+                "SELECT id, repo, image_name, ref_type, ref_value, status, image_sha, started_at, finished_at
+                 FROM image_builds
+                 WHERE repo = ?1 AND image_name = ?2 AND ref_value = ?3
+                 ORDER BY id DESC LIMIT 1",
+                params![repo, image_name, ref_value],
+            )?
+            .into_iter()
+            .next();
+
+        let Some(build) = build else {
+            return Ok(BuildLookup::NotFound);
+        };
+
+        if build.status == BuildStatus::Built {
+            if let Some(ref image_sha) = build.image_sha {
+                if let Ok(image) = self.get_image(image_sha) {
+                    return Ok(BuildLookup::Complete(image));
                 }
-                StateError::Database(e)
-            })?;
+            }
+        }
+
+        Ok(BuildLookup::InProgress(build))
+    }
+
+    // === CONTAINER OPERATIONS ===
+
+    /// Create a new container record. `resolve_mode` records how this
+    /// container's image reference should be reconciled against local state
+    /// on a future re-resolve (e.g. `run --pull`); it does not affect the
+    /// image record itself, which tracks its own mode via
+    /// [`Self::set_image_resolve_mode`].
+    pub fn create_container(
+        &self,
+        id: &str,
+        name: &str,
+        image_id: &str,
+        bundle_path: &str,
+        config: Option<&str>,
+        resolve_mode: ResolveMode,
+    ) -> Result<(), StateError> {
+        let config = crypto::encrypt_field(self.cipher.as_ref(), config);
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO containers (id, name, image_id, bundle_path, state, config, resolve_mode)
+             VALUES (?1, ?2, ?3, ?4, 'created', ?5, ?6)",
+            params![id, name, image_id, bundle_path, config, resolve_mode.as_str()],
+        )
+        .map_err(|e| {
+            if let rusqlite::Error::SqliteFailure(ref err, _) = e {
+                if err.code == rusqlite::ffi::ErrorCode::ConstraintViolation {
+                    return StateError::ContainerAlreadyExists(name.to_string());
+                }
+            }
+            StateError::Database(e)
+        })?;
+        Self::record_event(&tx, "container", id, "create", None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
     /// Get a container by ID or name.
     pub fn get_container(&self, id_or_name: &str) -> Result<ContainerInfo, StateError> {
-        let mut stmt = self.conn.prepare(
+        let mut container: ContainerInfo = self.query_one(
             "SELECT id, name, image_id, bundle_path, state, pid, exit_code,
-                    created_at, started_at, finished_at, config
+                    created_at, started_at, finished_at, config, checkpoint_image_path, resolve_mode,
+                    health_status, health_log
              FROM containers WHERE id = ?1 OR name = ?1",
+            [id_or_name],
+            || StateError::ContainerNotFound(id_or_name.to_string()),
         )?;
+        container.config = crypto::decrypt_field(self.cipher.as_ref(), container.config)?;
+        Ok(container)
+    }
 
-        stmt.query_row([id_or_name], |row| {
-            let state_str: String = row.get(4)?;
-            let state = ContainerState::from_str(&state_str).unwrap_or(ContainerState::Created);
-
-            Ok(ContainerInfo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                image_id: row.get(2)?,
-                bundle_path: row.get(3)?,
-                state,
-                pid: row.get(5)?,
-                exit_code: row.get(6)?,
-                created_at: row.get(7)?,
-                started_at: row.get(8)?,
-                finished_at: row.get(9)?,
-                config: row.get(10)?,
-            })
-        })
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => {
-                StateError::ContainerNotFound(id_or_name.to_string())
-            }
-            _ => StateError::Database(e),
-        })
+    /// Record the checkpoint image path for a successful checkpoint.
+    pub fn set_container_checkpoint_path(&self, id: &str, image_path: &str) -> Result<(), StateError> {
+        let rows = self.write().execute(
+            "UPDATE containers SET checkpoint_image_path = ?1 WHERE id = ?2",
+            params![image_path, id],
+        )?;
+
+        if rows == 0 {
+            return Err(StateError::ContainerNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Cheap lookup for a stopped container's last-measured bundle size,
+    /// recorded by [`Self::set_container_disk_usage`]. `None` means no
+    /// measurement has been taken yet (a full scan is needed).
+    pub fn cached_container_disk_usage(&self, id: &str) -> Result<Option<u64>, StateError> {
+        self.read()?
+            .query_row(
+                "SELECT bytes FROM container_disk_usage WHERE container_id = ?1",
+                params![id],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?
+            .map(|bytes| Ok(bytes.max(0) as u64))
+            .transpose()
+    }
+
+    /// Records the on-disk byte count last measured for `id`'s bundle
+    /// directory, overwriting any previous measurement.
+    pub fn set_container_disk_usage(&self, id: &str, bytes: u64) -> Result<(), StateError> {
+        self.write().execute(
+            "INSERT INTO container_disk_usage (container_id, bytes, updated_at)
+             VALUES (?1, ?2, CURRENT_TIMESTAMP)
+             ON CONFLICT(container_id) DO UPDATE SET bytes = excluded.bytes, updated_at = excluded.updated_at",
+            params![id, bytes as i64],
+        )?;
+        Ok(())
     }
 
     /// Update container state.
@@ -337,7 +1342,10 @@ impl StateManager {
         state: ContainerState,
         pid: Option<i32>,
     ) -> Result<(), StateError> {
-        let rows = self.conn.execute(
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        let rows = tx.execute(
             "UPDATE containers SET state = ?1, pid = ?2,
              started_at = CASE WHEN ?1 = 'running' THEN CURRENT_TIMESTAMP ELSE started_at END,
              finished_at = CASE WHEN ?1 = 'stopped' THEN CURRENT_TIMESTAMP ELSE finished_at END
@@ -348,12 +1356,18 @@ impl StateManager {
         if rows == 0 {
             return Err(StateError::ContainerNotFound(id.to_string()));
         }
+        Self::record_event(&tx, "container", id, state.as_event_action(), None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
     /// Set container exit code.
     pub fn set_container_exit_code(&self, id: &str, exit_code: i32) -> Result<(), StateError> {
-        let rows = self.conn.execute(
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        let rows = tx.execute(
             "UPDATE containers SET exit_code = ?1, state = 'stopped',
              finished_at = CURRENT_TIMESTAMP WHERE id = ?2",
             params![exit_code, id],
@@ -362,66 +1376,118 @@ impl StateManager {
         if rows == 0 {
             return Err(StateError::ContainerNotFound(id.to_string()));
         }
+        Self::record_event(&tx, "container", id, "die", Some(&exit_code.to_string()))?;
+
+        tx.commit()?;
         Ok(())
     }
 
+    /// Records one healthcheck probe result, updating the rolling
+    /// [`HealthStatus`] and appending to the bounded probe log.
+    ///
+    /// The status only ever flips to [`HealthStatus::Unhealthy`] after
+    /// `retries` consecutive failures - a single bad probe just keeps the
+    /// container `Starting`/`Healthy` as it was, matching Docker's own
+    /// debounced behavior so one slow response doesn't flap the status.
+    pub fn record_health_probe(
+        &self,
+        id: &str,
+        success: bool,
+        output: &str,
+        retries: u32,
+    ) -> Result<HealthStatus, StateError> {
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        let (health_log_json, consecutive_failures, previous_status): (Option<String>, i64, Option<String>) = tx.query_row(
+            "SELECT health_log, health_consecutive_failures, health_status FROM containers WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).optional()?.ok_or_else(|| StateError::ContainerNotFound(id.to_string()))?;
+
+        let mut log: Vec<HealthProbeLog> = health_log_json
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        log.push(HealthProbeLog {
+            at: chrono::Utc::now().to_rfc3339(),
+            success,
+            output: output.to_string(),
+        });
+        if log.len() > HEALTH_LOG_LIMIT {
+            let drop = log.len() - HEALTH_LOG_LIMIT;
+            log.drain(0..drop);
+        }
+
+        let consecutive_failures = if success { 0 } else { consecutive_failures + 1 };
+        let status = if success {
+            HealthStatus::Healthy
+        } else if consecutive_failures >= retries as i64 {
+            HealthStatus::Unhealthy
+        } else if previous_status.as_deref().and_then(HealthStatus::from_str) == Some(HealthStatus::Healthy) {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Starting
+        };
+
+        let log_json = serde_json::to_string(&log)
+            .map_err(|e| StateError::MigrationFailed(format!("failed to encode health log: {e}")))?;
+
+        tx.execute(
+            "UPDATE containers SET health_status = ?1, health_log = ?2, health_consecutive_failures = ?3
+             WHERE id = ?4",
+            params![status.as_str(), log_json, consecutive_failures, id],
+        )?;
+        Self::record_event(&tx, "container", id, "health_status", Some(status.as_str()))?;
+
+        tx.commit()?;
+        Ok(status)
+    }
+
     /// List containers with optional state filter.
     pub fn list_containers(
         &self,
         state_filter: Option<ContainerState>,
     ) -> Result<Vec<ContainerInfo>, StateError> {
-        let containers = match state_filter {
-            Some(state) => {
-                let mut stmt = self.conn.prepare(
-                    "SELECT id, name, image_id, bundle_path, state, pid, exit_code,
-                            created_at, started_at, finished_at, config
-                     FROM containers WHERE state = ?1
-                     ORDER BY created_at DESC",
-                )?;
-                stmt.query_map([state.as_str()], Self::row_to_container_info)?
-                    .collect::<Result<Vec<_>, _>>()?
-            }
-            None => {
-                let mut stmt = self.conn.prepare(
-                    "SELECT id, name, image_id, bundle_path, state, pid, exit_code,
-                            created_at, started_at, finished_at, config
-                     FROM containers ORDER BY created_at DESC",
-                )?;
-                stmt.query_map([], Self::row_to_container_info)?
-                    .collect::<Result<Vec<_>, _>>()?
-            }
-        };
-        Ok(containers)
-    }
-
-    fn row_to_container_info(row: &rusqlite::Row) -> Result<ContainerInfo, rusqlite::Error> {
-        let state_str: String = row.get(4)?;
-        let state = ContainerState::from_str(&state_str).unwrap_or(ContainerState::Created);
-
-        Ok(ContainerInfo {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            image_id: row.get(2)?,
-            bundle_path: row.get(3)?,
-            state,
-            pid: row.get(5)?,
-            exit_code: row.get(6)?,
-            created_at: row.get(7)?,
-            started_at: row.get(8)?,
-            finished_at: row.get(9)?,
-            config: row.get(10)?,
-        })
+        let containers: Vec<ContainerInfo> = match state_filter {
+            Some(state) => self.query_all(
+                "SELECT id, name, image_id, bundle_path, state, pid, exit_code,
+                        created_at, started_at, finished_at, config, checkpoint_image_path, resolve_mode,
+                        health_status, health_log
+                 FROM containers WHERE state = ?1
+                 ORDER BY created_at DESC",
+                [state.as_str()],
+            ),
+            None => self.query_all(
+                "SELECT id, name, image_id, bundle_path, state, pid, exit_code,
+                        created_at, started_at, finished_at, config, checkpoint_image_path, resolve_mode,
+                        health_status, health_log
+                 FROM containers ORDER BY created_at DESC",
+                [],
+            ),
+        }?;
+
+        containers
+            .into_iter()
+            .map(|mut container| {
+                container.config = crypto::decrypt_field(self.cipher.as_ref(), container.config)?;
+                Ok(container)
+            })
+            .collect()
     }
 
     /// Delete a container.
     pub fn delete_container(&self, id: &str) -> Result<(), StateError> {
-        let rows = self
-            .conn
-            .execute("DELETE FROM containers WHERE id = ?1", params![id])?;
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        let rows = tx.execute("DELETE FROM containers WHERE id = ?1", params![id])?;
 
         if rows == 0 {
             return Err(StateError::ContainerNotFound(id.to_string()));
         }
+        Self::record_event(&tx, "container", id, "destroy", None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
@@ -436,74 +1502,54 @@ impl StateManager {
         subnet: Option<&str>,
         gateway: Option<&str>,
         options: Option<&str>,
+        internal: bool,
     ) -> Result<(), StateError> {
-        self.conn.execute(
-            "INSERT INTO networks (id, name, driver, subnet, gateway, options)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![id, name, driver, subnet, gateway, options],
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO networks (id, name, driver, subnet, gateway, options, internal)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![id, name, driver, subnet, gateway, options, internal],
         )?;
+        Self::record_event(&tx, "network", id, "create", None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
     /// Get a network by ID or name.
     pub fn get_network(&self, id_or_name: &str) -> Result<NetworkInfo, StateError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, driver, subnet, gateway, options, created_at
+        self.query_one(
+            "SELECT id, name, driver, subnet, gateway, options, internal, created_at
              FROM networks WHERE id = ?1 OR name = ?1",
-        )?;
-
-        stmt.query_row([id_or_name], |row| {
-            Ok(NetworkInfo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                driver: row.get(2)?,
-                subnet: row.get(3)?,
-                gateway: row.get(4)?,
-                options: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => {
-                StateError::NetworkNotFound(id_or_name.to_string())
-            }
-            _ => StateError::Database(e),
-        })
+            [id_or_name],
+            || StateError::NetworkNotFound(id_or_name.to_string()),
+        )
     }
 
     /// List all networks.
     pub fn list_networks(&self) -> Result<Vec<NetworkInfo>, StateError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, driver, subnet, gateway, options, created_at
+        self.query_all(
+            "SELECT id, name, driver, subnet, gateway, options, internal, created_at
              FROM networks ORDER BY created_at DESC",
-        )?;
-
-        let networks = stmt
-            .query_map([], |row| {
-                Ok(NetworkInfo {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    driver: row.get(2)?,
-                    subnet: row.get(3)?,
-                    gateway: row.get(4)?,
-                    options: row.get(5)?,
-                    created_at: row.get(6)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(networks)
+            [],
+        )
     }
 
     /// Delete a network.
     pub fn delete_network(&self, id: &str) -> Result<(), StateError> {
-        let rows = self
-            .conn
-            .execute("DELETE FROM networks WHERE id = ?1", params![id])?;
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        let rows = tx.execute("DELETE FROM networks WHERE id = ?1", params![id])?;
 
         if rows == 0 {
             return Err(StateError::NetworkNotFound(id.to_string()));
         }
+        Self::record_event(&tx, "network", id, "destroy", None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
@@ -518,7 +1564,7 @@ impl StateManager {
     ) -> Result<(), StateError> {
         let aliases_json = serde_json::to_string(aliases).unwrap_or_else(|_| "[]".to_string());
 
-        self.conn.execute(
+        self.write().execute(
             "INSERT INTO container_networks (container_id, network_id, ip_address, mac_address, aliases)
              VALUES (?1, ?2, ?3, ?4, ?5)",
             params![container_id, network_id, ip_address, mac_address, aliases_json],
@@ -532,13 +1578,91 @@ impl StateManager {
         container_id: &str,
         network_id: &str,
     ) -> Result<(), StateError> {
-        self.conn.execute(
+        self.write().execute(
             "DELETE FROM container_networks WHERE container_id = ?1 AND network_id = ?2",
             params![container_id, network_id],
         )?;
         Ok(())
     }
 
+    /// IP addresses already handed out to containers on `network_id`, used
+    /// by [`crate::engine::ipam`] to find the next free host address.
+    pub fn allocated_ips(&self, network_id: &str) -> Result<Vec<String>, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare(
+            "SELECT ip_address FROM container_networks
+             WHERE network_id = ?1 AND ip_address IS NOT NULL",
+        )?;
+
+        let ips = stmt
+            .query_map(params![network_id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ips)
+    }
+
+    /// Every container currently attached to `network_id` with a resolvable
+    /// address, for the embedded DNS resolver ([`crate::network::sql_dns`])
+    /// and `network inspect` to read live off the database - there's no
+    /// separate push-maintained name table, so a lookup is only ever as
+    /// stale as the last `connect`/`disconnect`.
+    pub fn network_dns_records(&self, network_id: &str) -> Result<Vec<NetworkDnsRecord>, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare(
+            "SELECT c.name, cn.aliases, cn.ip_address
+             FROM container_networks cn
+             JOIN containers c ON c.id = cn.container_id
+             WHERE cn.network_id = ?1 AND cn.ip_address IS NOT NULL",
+        )?;
+
+        let records = stmt
+            .query_map(params![network_id], |row| {
+                let container_name: String = row.get(0)?;
+                let aliases_json: String = row.get(1)?;
+                let ip_address: String = row.get(2)?;
+                let aliases: Vec<String> = serde_json::from_str(&aliases_json).unwrap_or_default();
+                Ok(NetworkDnsRecord {
+                    container_name,
+                    aliases,
+                    ip_address,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// Every container attached to `network_id`, regardless of whether it
+    /// has an address yet - the full `docker network inspect` endpoint
+    /// list, including the allocated MAC address. Use
+    /// [`Self::network_dns_records`] instead when only resolvable
+    /// (container name, IP) pairs are needed.
+    pub fn network_endpoints(&self, network_id: &str) -> Result<Vec<NetworkEndpoint>, StateError> {
+        let conn = self.read()?;
+        let mut stmt = conn.prepare(
+            "SELECT container_id, ip_address, mac_address, aliases
+             FROM container_networks WHERE network_id = ?1",
+        )?;
+
+        let endpoints = stmt
+            .query_map(params![network_id], |row| {
+                let container_id: String = row.get(0)?;
+                let ip_address: Option<String> = row.get(1)?;
+                let mac_address: Option<String> = row.get(2)?;
+                let aliases_json: String = row.get(3)?;
+                let aliases: Vec<String> = serde_json::from_str(&aliases_json).unwrap_or_default();
+                Ok(NetworkEndpoint {
+                    container_id,
+                    ip_address,
+                    mac_address,
+                    aliases,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(endpoints)
+    }
+
     // === VOLUME OPERATIONS ===
 
     /// Create a new volume.
@@ -551,73 +1675,119 @@ impl StateManager {
         options: Option<&str>,
         labels: Option<&str>,
     ) -> Result<(), StateError> {
-        self.conn.execute(
+        let labels = crypto::encrypt_field(self.cipher.as_ref(), labels);
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        tx.execute(
             "INSERT INTO volumes (id, name, driver, mountpoint, options, labels)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![id, name, driver, mountpoint, options, labels],
         )?;
+        Self::record_event(&tx, "volume", id, "create", None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
     /// Get a volume by ID or name.
     pub fn get_volume(&self, id_or_name: &str) -> Result<VolumeInfo, StateError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, driver, mountpoint, options, labels, created_at
+        let mut volume: VolumeInfo = self.query_one(
+            "SELECT id, name, driver, mountpoint, options, labels, refcount, created_at
              FROM volumes WHERE id = ?1 OR name = ?1",
+            [id_or_name],
+            || StateError::VolumeNotFound(id_or_name.to_string()),
         )?;
-
-        stmt.query_row([id_or_name], |row| {
-            Ok(VolumeInfo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                driver: row.get(2)?,
-                mountpoint: row.get(3)?,
-                options: row.get(4)?,
-                labels: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => {
-                StateError::VolumeNotFound(id_or_name.to_string())
-            }
-            _ => StateError::Database(e),
-        })
+        volume.labels = crypto::decrypt_field(self.cipher.as_ref(), volume.labels)?;
+        Ok(volume)
     }
 
     /// List all volumes.
     pub fn list_volumes(&self) -> Result<Vec<VolumeInfo>, StateError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, driver, mountpoint, options, labels, created_at
+        let volumes: Vec<VolumeInfo> = self.query_all(
+            "SELECT id, name, driver, mountpoint, options, labels, refcount, created_at
              FROM volumes ORDER BY created_at DESC",
+            [],
         )?;
 
-        let volumes = stmt
-            .query_map([], |row| {
-                Ok(VolumeInfo {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    driver: row.get(2)?,
-                    mountpoint: row.get(3)?,
-                    options: row.get(4)?,
-                    labels: row.get(5)?,
-                    created_at: row.get(6)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(volumes)
+        volumes
+            .into_iter()
+            .map(|mut volume| {
+                volume.labels = crypto::decrypt_field(self.cipher.as_ref(), volume.labels)?;
+                Ok(volume)
+            })
+            .collect()
     }
 
     /// Delete a volume.
     pub fn delete_volume(&self, id: &str) -> Result<(), StateError> {
-        let rows = self
-            .conn
-            .execute("DELETE FROM volumes WHERE id = ?1", params![id])?;
+        let mut conn = self.write();
+        let tx = conn.transaction()?;
+
+        let rows = tx.execute("DELETE FROM volumes WHERE id = ?1", params![id])?;
 
         if rows == 0 {
             return Err(StateError::VolumeNotFound(id.to_string()));
         }
+        Self::record_event(&tx, "volume", id, "destroy", None)?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Record that `container_id` has mounted `volume_id` at `mount_path`,
+    /// incrementing the volume's refcount. A no-op if this container/volume
+    /// pair is already mounted.
+    pub fn mount_volume(
+        &self,
+        container_id: &str,
+        volume_id: &str,
+        mount_path: &str,
+    ) -> Result<(), StateError> {
+        let inserted = self.write().execute(
+            "INSERT OR IGNORE INTO container_volumes (container_id, volume_id, mount_path)
+             VALUES (?1, ?2, ?3)",
+            params![container_id, volume_id, mount_path],
+        )?;
+
+        if inserted > 0 {
+            self.write().execute(
+                "UPDATE volumes SET refcount = refcount + 1 WHERE id = ?1",
+                params![volume_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Record that `container_id` has released `volume_id`, decrementing
+    /// the volume's refcount. A no-op if the pair wasn't mounted.
+    pub fn unmount_volume(&self, container_id: &str, volume_id: &str) -> Result<(), StateError> {
+        let deleted = self.write().execute(
+            "DELETE FROM container_volumes WHERE container_id = ?1 AND volume_id = ?2",
+            params![container_id, volume_id],
+        )?;
+
+        if deleted > 0 {
+            self.write().execute(
+                "UPDATE volumes SET refcount = refcount - 1 WHERE id = ?1",
+                params![volume_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Recompute every volume's refcount from the live `container_volumes`
+    /// rows instead of trusting the persisted counter. Run on every
+    /// [`StateManager::open`] so a process killed mid-mount/unmount can
+    /// never leave a volume wrongly pinned or wrongly eligible for
+    /// `volume prune`.
+    pub fn recompute_volume_refcounts(&self) -> Result<(), StateError> {
+        self.write().execute_batch(
+            "UPDATE volumes SET refcount = (
+                 SELECT COUNT(*) FROM container_volumes
+                 WHERE container_volumes.volume_id = volumes.id
+             )",
+        )?;
         Ok(())
     }
 
@@ -626,15 +1796,18 @@ impl StateManager {
     /// Acquire an advisory lock.
     pub fn acquire_lock(&self, resource_type: &str, resource_id: &str) -> Result<(), StateError> {
         let pid = std::process::id() as i32;
+        let (start_time, boot_id) = Self::current_lock_identity(pid);
 
-        // First, clean up stale locks from dead processes
+        // First, clean up stale locks from dead processes. This runs in its
+        // own write-lock critical section, released before we take the lock
+        // again below - `Mutex` isn't reentrant.
         self.cleanup_stale_locks()?;
 
-        self.conn
+        self.write()
             .execute(
-                "INSERT INTO locks (resource_type, resource_id, owner_pid)
-             VALUES (?1, ?2, ?3)",
-                params![resource_type, resource_id, pid],
+                "INSERT INTO locks (resource_type, resource_id, owner_pid, owner_start_time, boot_id)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![resource_type, resource_id, pid, start_time, boot_id],
             )
             .map_err(|e| {
                 if let rusqlite::Error::SqliteFailure(ref err, _) = e {
@@ -654,7 +1827,7 @@ impl StateManager {
     pub fn release_lock(&self, resource_type: &str, resource_id: &str) -> Result<(), StateError> {
         let pid = std::process::id() as i32;
 
-        self.conn.execute(
+        self.write().execute(
             "DELETE FROM locks WHERE resource_type = ?1 AND resource_id = ?2 AND owner_pid = ?3",
             params![resource_type, resource_id, pid],
         )?;
@@ -663,19 +1836,21 @@ impl StateManager {
 
     /// Clean up locks from dead processes.
     fn cleanup_stale_locks(&self) -> Result<(), StateError> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT resource_type, resource_id, owner_pid FROM locks")?;
-
-        let locks: Vec<(String, String, i32)> = stmt
-            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        let locks: Vec<(String, String, i32, Option<String>, Option<String>)> = {
+            let conn = self.read()?;
+            let mut stmt = conn.prepare(
+                "SELECT resource_type, resource_id, owner_pid, owner_start_time, boot_id FROM locks",
+            )?;
+            stmt.query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })?
             .filter_map(Result::ok)
-            .collect();
+            .collect()
+        };
 
-        for (resource_type, resource_id, pid) in locks {
-            // Check if process is still alive
-            if !Self::process_exists(pid) {
-                self.conn.execute(
+        for (resource_type, resource_id, pid, start_time, boot_id) in locks {
+            if Self::lock_is_stale(pid, start_time.as_deref(), boot_id.as_deref()) {
+                self.write().execute(
                     "DELETE FROM locks WHERE resource_type = ?1 AND resource_id = ?2",
                     params![resource_type, resource_id],
                 )?;
@@ -685,6 +1860,233 @@ impl StateManager {
         Ok(())
     }
 
+    /// True if the lock recorded for `pid`/`start_time`/`boot_id` no longer
+    /// reflects the process that acquired it - either the PID is dead, or
+    /// (on Linux, where `start_time`/`boot_id` are populated) it's alive
+    /// but isn't the same process: its current start time doesn't match
+    /// what [`Self::acquire_lock`] recorded, or the machine has rebooted
+    /// since, and PIDs get reused across a reboot. A lock recorded by a
+    /// pre-migration binary (both fields `None`) falls back to a plain PID
+    /// liveness check, same as non-Linux.
+    fn lock_is_stale(pid: i32, start_time: Option<&str>, boot_id: Option<&str>) -> bool {
+        if !Self::process_exists(pid) {
+            return true;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if boot_id.is_some() && Self::read_boot_id().as_deref() != boot_id {
+                return true;
+            }
+            if start_time.is_some() && Self::read_process_start_time(pid).as_deref() != start_time {
+                return true;
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (start_time, boot_id);
+        }
+
+        false
+    }
+
+    /// The identity to record alongside a newly acquired lock: `pid`'s
+    /// start time (clock ticks since boot) and the running kernel's boot
+    /// id, both `None` outside Linux where `/proc` isn't available and
+    /// staleness falls back to [`Self::process_exists`] alone.
+    #[cfg(target_os = "linux")]
+    fn current_lock_identity(pid: i32) -> (Option<String>, Option<String>) {
+        (Self::read_process_start_time(pid), Self::read_boot_id())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn current_lock_identity(_pid: i32) -> (Option<String>, Option<String>) {
+        (None, None)
+    }
+
+    /// Reads field 22 (`starttime`, clock ticks since boot) of
+    /// `/proc/{pid}/stat`. The `comm` field (2) can itself contain spaces
+    /// or parentheses, so fields are located relative to the *last* `)` in
+    /// the line rather than by naive whitespace splitting.
+    #[cfg(target_os = "linux")]
+    fn read_process_start_time(pid: i32) -> Option<String> {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        let after_comm = stat.rsplit_once(')')?.1;
+        after_comm.split_whitespace().nth(19).map(str::to_string)
+    }
+
+    /// Reads the running kernel's boot id, a fresh random value on every
+    /// boot - used to invalidate every lock from a prior boot at once,
+    /// since PIDs (and their start times) reset across a reboot.
+    #[cfg(target_os = "linux")]
+    fn read_boot_id() -> Option<String> {
+        std::fs::read_to_string("/proc/sys/kernel/random/boot_id")
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
+
+    // === QUOTA OPERATIONS ===
+
+    /// Sets (or replaces) the max-bytes quota for a resource type
+    /// (`"containers"`, `"volumes"`, or `"images"`).
+    pub fn set_quota(&self, resource_type: &str, max_bytes: u64) -> Result<(), StateError> {
+        self.write().execute(
+            "INSERT INTO quotas (resource_type, max_bytes) VALUES (?1, ?2)
+             ON CONFLICT(resource_type) DO UPDATE SET max_bytes = excluded.max_bytes",
+            params![resource_type, max_bytes as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Looks up the configured quota for a resource type, if any.
+    pub fn get_quota(&self, resource_type: &str) -> Result<Option<u64>, StateError> {
+        self.read()?
+            .query_row(
+                "SELECT max_bytes FROM quotas WHERE resource_type = ?1",
+                params![resource_type],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?
+            .map(|bytes| Ok(bytes.max(0) as u64))
+            .transpose()
+    }
+
+    /// Lists every configured quota, ordered by resource type.
+    pub fn list_quotas(&self) -> Result<Vec<(String, u64)>, StateError> {
+        let conn = self.read()?;
+        let mut stmt =
+            conn.prepare("SELECT resource_type, max_bytes FROM quotas ORDER BY resource_type")?;
+        let rows = stmt
+            .query_map([], |row| {
+                let max_bytes: i64 = row.get(1)?;
+                Ok((row.get::<_, String>(0)?, max_bytes.max(0) as u64))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    // === SETTINGS OPERATIONS ===
+
+    /// Get a free-form key/value setting.
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>, StateError> {
+        self.read()?
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                _ => Err(StateError::Database(e)),
+            })
+    }
+
+    /// Set a free-form key/value setting.
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<(), StateError> {
+        self.write().execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    // === EVENT OPERATIONS ===
+
+    /// List recorded lifecycle events, oldest first, optionally restricted
+    /// to those at or after `since` (an RFC 3339 / SQLite `TIMESTAMP`
+    /// string comparable with `created_at`) and matching `filter`.
+    pub fn list_events(
+        &self,
+        since: Option<&str>,
+        filter: &LifecycleEventFilter,
+    ) -> Result<Vec<LifecycleEvent>, StateError> {
+        let events: Vec<LifecycleEvent> = match since {
+            Some(since) => self.query_all(
+                "SELECT id, created_at, object_type, object_id, action, payload
+                 FROM events WHERE created_at >= ?1 ORDER BY id",
+                [since],
+            )?,
+            None => self.query_all(
+                "SELECT id, created_at, object_type, object_id, action, payload
+                 FROM events ORDER BY id",
+                [],
+            )?,
+        };
+
+        Ok(events.into_iter().filter(|e| filter.matches(e)).collect())
+    }
+
+    /// Starts watching for new lifecycle events matching `filter`, in the
+    /// `docker events --since ... --follow` sense. `since` is interpreted
+    /// as in [`Self::list_events`]; if unset, watching starts from the
+    /// newest event already recorded, so only events appended from now on
+    /// are yielded.
+    pub fn watch_events(
+        &self,
+        since: Option<&str>,
+        filter: LifecycleEventFilter,
+    ) -> Result<EventWatcher<'_>, StateError> {
+        let last_id: i64 = match since {
+            Some(since) => {
+                let before: Vec<LifecycleEvent> = self.query_all(
+                    "SELECT id, created_at, object_type, object_id, action, payload
+                     FROM events WHERE created_at < ?1 ORDER BY id DESC LIMIT 1",
+                    [since],
+                )?;
+                before.into_iter().next().map(|e| e.id).unwrap_or(0)
+            }
+            None => self
+                .read()?
+                .query_row("SELECT COALESCE(MAX(id), 0) FROM events", [], |row| row.get(0))?,
+        };
+
+        Ok(EventWatcher {
+            state: self,
+            filter,
+            last_id,
+            poll_interval: EVENT_POLL_INTERVAL,
+        })
+    }
+
+    // === METRICS OPERATIONS ===
+
+    /// Point-in-time inventory counts over the state database - container
+    /// counts by state, image/network/volume totals, summed image size,
+    /// and currently-held locks - for the daemon's Prometheus `/metrics`
+    /// endpoint. See [`Self::render_prometheus`] for the text exposition
+    /// format, or use the counts directly.
+    pub fn metrics(&self) -> Result<StateMetrics, StateError> {
+        let mut containers_by_state: std::collections::BTreeMap<String, u64> = std::collections::BTreeMap::new();
+        for container in self.list_containers(None)? {
+            *containers_by_state.entry(container.state.as_str().to_string()).or_default() += 1;
+        }
+
+        let images = self.list_images()?;
+        let image_bytes_total = images.iter().map(|i| i.size.max(0) as u64).sum();
+
+        let locks_held: i64 = self.read()?.query_row("SELECT COUNT(*) FROM locks", [], |row| row.get(0))?;
+
+        Ok(StateMetrics {
+            containers_by_state,
+            images_total: images.len() as u64,
+            image_bytes_total,
+            networks_total: self.list_networks()?.len() as u64,
+            volumes_total: self.list_volumes()?.len() as u64,
+            locks_held: locks_held.max(0) as u64,
+        })
+    }
+
+    /// Renders [`Self::metrics`] in Prometheus text exposition format, for
+    /// the daemon's `/metrics` endpoint to serve directly.
+    pub fn render_prometheus(&self) -> String {
+        match self.metrics() {
+            Ok(metrics) => super::metrics::render_prometheus(&metrics),
+            Err(e) => format!("# error collecting state metrics: {e}\n"),
+        }
+    }
+
     /// Check if a process exists.
     fn process_exists(pid: i32) -> bool {
         #[cfg(unix)]
@@ -698,6 +2100,177 @@ impl StateManager {
             true
         }
     }
+
+    /// Public entry point for [`Self::process_exists`], for callers
+    /// outside this module that need the same liveness check (e.g.
+    /// `system repair` detecting containers whose recorded PID is dead).
+    pub fn process_is_alive(pid: i32) -> bool {
+        Self::process_exists(pid)
+    }
+}
+
+/// Blocking iterator returned by [`StateManager::watch_events`]. Each call
+/// to `next()` polls the `events` table for rows newer than the last one
+/// seen, sleeping briefly between polls when there's nothing new yet - the
+/// `docker events --since ... --follow` poll strategy, chosen over a
+/// `rusqlite` update-hook + condvar pairing since every mutation already
+/// goes through a single mutex-guarded writer connection, making "is there
+/// a newer row yet" a cheap, uncontended read off the pool rather than a
+/// cross-thread signal worth wiring up.
+pub struct EventWatcher<'a> {
+    state: &'a StateManager,
+    filter: LifecycleEventFilter,
+    last_id: i64,
+    poll_interval: std::time::Duration,
+}
+
+impl<'a> Iterator for EventWatcher<'a> {
+    type Item = Result<LifecycleEvent, StateError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let row: Result<Vec<LifecycleEvent>, StateError> = self.state.query_all(
+                "SELECT id, created_at, object_type, object_id, action, payload
+                 FROM events WHERE id > ?1 ORDER BY id LIMIT 1",
+                [self.last_id],
+            );
+
+            match row {
+                Ok(mut rows) => {
+                    if let Some(event) = rows.pop() {
+                        self.last_id = event.id;
+                        if self.filter.matches(&event) {
+                            return Some(Ok(event));
+                        }
+                        continue;
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+
+            std::thread::sleep(self.poll_interval);
+        }
+    }
+}
+
+/// Guard returned by [`StateManager::transaction`] for bundling
+/// heterogeneous create/connect/mount calls - e.g. "create container +
+/// connect it to a network + record a volume mount" - into a single
+/// atomic commit, instead of several independent calls any of which can
+/// fail halfway and leave orphaned `container_networks`/
+/// `container_volumes` rows.
+///
+/// Issues its own `BEGIN`/`COMMIT`/`ROLLBACK` rather than wrapping a
+/// `rusqlite::Transaction`, since the latter borrows from the
+/// `MutexGuard` it would otherwise have to live alongside in the same
+/// struct. Rolls back on drop unless [`Self::commit`] was called.
+pub struct StateTransaction<'a> {
+    conn: std::sync::MutexGuard<'a, Connection>,
+    cipher: Option<&'a FieldCipher>,
+    committed: bool,
+}
+
+impl<'a> StateTransaction<'a> {
+    fn begin(conn: std::sync::MutexGuard<'a, Connection>, cipher: Option<&'a FieldCipher>) -> Result<Self, StateError> {
+        conn.execute_batch("BEGIN")?;
+        Ok(Self {
+            conn,
+            cipher,
+            committed: false,
+        })
+    }
+
+    /// Create a container record as part of this transaction. Mirrors
+    /// [`StateManager::create_container`].
+    pub fn create_container(
+        &self,
+        id: &str,
+        name: &str,
+        image_id: &str,
+        bundle_path: &str,
+        config: Option<&str>,
+        resolve_mode: ResolveMode,
+    ) -> Result<(), StateError> {
+        let config = crypto::encrypt_field(self.cipher, config);
+        self.conn
+            .execute(
+                "INSERT INTO containers (id, name, image_id, bundle_path, state, config, resolve_mode)
+                 VALUES (?1, ?2, ?3, ?4, 'created', ?5, ?6)",
+                params![id, name, image_id, bundle_path, config, resolve_mode.as_str()],
+            )
+            .map_err(|e| {
+                if let rusqlite::Error::SqliteFailure(ref err, _) = e {
+                    if err.code == rusqlite::ffi::ErrorCode::ConstraintViolation {
+                        return StateError::ContainerAlreadyExists(name.to_string());
+                    }
+                }
+                StateError::Database(e)
+            })?;
+        StateManager::record_event(&self.conn, "container", id, "create", None)?;
+        Ok(())
+    }
+
+    /// Connect a container to a network as part of this transaction.
+    /// Mirrors [`StateManager::connect_container_network`].
+    pub fn connect_container_network(
+        &self,
+        container_id: &str,
+        network_id: &str,
+        ip_address: Option<&str>,
+        mac_address: Option<&str>,
+        aliases: &[String],
+    ) -> Result<(), StateError> {
+        let aliases_json = serde_json::to_string(aliases).unwrap_or_else(|_| "[]".to_string());
+
+        self.conn.execute(
+            "INSERT INTO container_networks (container_id, network_id, ip_address, mac_address, aliases)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![container_id, network_id, ip_address, mac_address, aliases_json],
+        )?;
+        Ok(())
+    }
+
+    /// Record that `container_id` has mounted `volume_id` at `mount_path`
+    /// as part of this transaction, incrementing the volume's refcount.
+    /// Mirrors [`StateManager::mount_volume`].
+    pub fn mount_volume(&self, container_id: &str, volume_id: &str, mount_path: &str) -> Result<(), StateError> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO container_volumes (container_id, volume_id, mount_path)
+             VALUES (?1, ?2, ?3)",
+            params![container_id, volume_id, mount_path],
+        )?;
+
+        if inserted > 0 {
+            self.conn.execute(
+                "UPDATE volumes SET refcount = refcount + 1 WHERE id = ?1",
+                params![volume_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Commits every operation issued through this transaction atomically.
+    pub fn commit(mut self) -> Result<(), StateError> {
+        self.conn.execute_batch("COMMIT")?;
+        self.committed = true;
+        Ok(())
+    }
+
+    /// Explicitly rolls back. Equivalent to dropping the transaction
+    /// without calling [`Self::commit`].
+    pub fn rollback(mut self) -> Result<(), StateError> {
+        self.conn.execute_batch("ROLLBACK")?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl<'a> Drop for StateTransaction<'a> {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+    }
 }
 
 #[cfg(test)]
@@ -716,12 +2289,21 @@ mod tests {
                 Some("alpine"),
                 &["latest".to_string()],
                 1024,
+                &[],
+                None,
             )
             .unwrap();
 
         // Create container
         state
-            .create_container("ctr-456", "my-container", "img-123", "/bundles/ctr-456", None)
+            .create_container(
+                "ctr-456",
+                "my-container",
+                "img-123",
+                "/bundles/ctr-456",
+                None,
+                ResolveMode::Default,
+            )
             .unwrap();
 
         // Get container
@@ -764,6 +2346,7 @@ mod tests {
                 Some("172.28.0.0/16"),
                 Some("172.28.0.1"),
                 None,
+                false,
             )
             .unwrap();
 
@@ -793,6 +2376,8 @@ mod tests {
                 Some("alpine"),
                 &["latest".to_string(), "3.19".to_string()],
                 5 * 1024 * 1024,
+                &[("sha256:layer1".to_string(), 1024), ("sha256:layer2".to_string(), 2048)],
+                Some(r#"{"architecture":"arm64","os":"linux"}"#),
             )
             .unwrap();
 
@@ -800,6 +2385,17 @@ mod tests {
         let image = state.get_image("img-123").unwrap();
         assert_eq!(image.repository, Some("alpine".to_string()));
         assert_eq!(image.tags.len(), 2);
+        assert!(image.config.unwrap().contains("arm64"));
+
+        // Layers are registered with a refcount of 1, in insertion order
+        let layers = state.list_layers().unwrap();
+        assert_eq!(layers.len(), 2);
+        assert!(layers.iter().all(|l| l.refcount == 1));
+
+        let image_layers = state.image_layers("img-123").unwrap();
+        assert_eq!(image_layers.len(), 2);
+        assert_eq!(image_layers[0].digest, "sha256:layer1");
+        assert_eq!(image_layers[1].digest, "sha256:layer2");
 
         // Get by digest
         let image = state.get_image("sha256:abc123def456").unwrap();
@@ -808,5 +2404,200 @@ mod tests {
         // List images
         let images = state.list_images().unwrap();
         assert_eq!(images.len(), 1);
+
+        // Deleting the image drops every layer's refcount to zero
+        state.delete_image("img-123", false).unwrap();
+        let unreferenced = state.unreferenced_layers().unwrap();
+        assert_eq!(unreferenced.len(), 2);
+    }
+
+    #[test]
+    fn test_create_image_with_duplicate_layer_digest_keeps_refcount_in_sync() {
+        let state = StateManager::open_in_memory().unwrap();
+
+        // Two manifest layers sharing a byte-identical (e.g. empty) diff
+        // end up with the same digest listed twice.
+        state
+            .create_image(
+                "img-dup",
+                "sha256:dup123",
+                Some("alpine"),
+                &["latest".to_string()],
+                2048,
+                &[
+                    ("sha256:layer1".to_string(), 1024),
+                    ("sha256:layer1".to_string(), 1024),
+                    ("sha256:layer2".to_string(), 1024),
+                ],
+                None,
+            )
+            .unwrap();
+
+        // image_layers dedupes on (image_id, layer_digest), so the
+        // repeated digest only shows up once...
+        let image_layers = state.image_layers("img-dup").unwrap();
+        assert_eq!(image_layers.len(), 2);
+
+        // ...and refcount must be incremented the same number of times,
+        // not once per raw entry in the input slice.
+        let layers = state.list_layers().unwrap();
+        assert!(layers.iter().all(|l| l.refcount == 1));
+
+        // So deleting the one image referencing it clears both layers.
+        state.delete_image("img-dup", false).unwrap();
+        let unreferenced = state.unreferenced_layers().unwrap();
+        assert_eq!(unreferenced.len(), 2);
+    }
+
+    #[test]
+    fn test_image_resolve_mode() {
+        let state = StateManager::open_in_memory().unwrap();
+
+        state
+            .create_image("img-123", "sha256:abc123", Some("alpine"), &["latest".to_string()], 0, &[], None)
+            .unwrap();
+
+        // Defaults to ResolveMode::Default until set otherwise
+        assert_eq!(state.get_image("img-123").unwrap().resolve_mode, ResolveMode::Default);
+        assert!(state.has_local_digest("sha256:abc123").unwrap());
+        assert!(!state.has_local_digest("sha256:nonexistent").unwrap());
+
+        state.set_image_resolve_mode("img-123", ResolveMode::PreferLocal).unwrap();
+        assert_eq!(state.get_image("img-123").unwrap().resolve_mode, ResolveMode::PreferLocal);
+
+        assert!(matches!(
+            state.set_image_resolve_mode("img-missing", ResolveMode::ForcePull),
+            Err(StateError::ImageNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_image_build_status() {
+        let state = StateManager::open_in_memory().unwrap();
+
+        // No build recorded yet for this ref
+        assert!(matches!(
+            state.get_build_status("acme/widget", "widget", "main").unwrap(),
+            BuildLookup::NotFound
+        ));
+
+        let build_id = state
+            .start_image_build("acme/widget", "widget", BuildRefType::Branch, "main")
+            .unwrap();
+
+        match state.get_build_status("acme/widget", "widget", "main").unwrap() {
+            BuildLookup::InProgress(build) => {
+                assert_eq!(build.id, build_id);
+                assert_eq!(build.status, BuildStatus::Queued);
+                assert!(build.finished_at.is_none());
+            }
+            other => panic!("expected InProgress, got {other:?}"),
+        }
+
+        state.update_build_status(build_id, BuildStatus::Building, None).unwrap();
+        match state.get_build_status("acme/widget", "widget", "main").unwrap() {
+            BuildLookup::InProgress(build) => assert_eq!(build.status, BuildStatus::Building),
+            other => panic!("expected InProgress, got {other:?}"),
+        }
+
+        // Once built, the build resolves straight to the finished image
+        state
+            .create_image("img-widget", "sha256:widget123", Some("acme/widget"), &["main".to_string()], 2048, &[], None)
+            .unwrap();
+        state
+            .update_build_status(build_id, BuildStatus::Built, Some("img-widget"))
+            .unwrap();
+
+        match state.get_build_status("acme/widget", "widget", "main").unwrap() {
+            BuildLookup::Complete(image) => assert_eq!(image.id, "img-widget"),
+            other => panic!("expected Complete, got {other:?}"),
+        }
+
+        assert!(matches!(
+            state.update_build_status(9999, BuildStatus::Failed, None),
+            Err(StateError::BuildNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_image_prune_and_in_use_protection() {
+        let state = StateManager::open_in_memory().unwrap();
+
+        state
+            .create_image("img-tagged", "sha256:tagged", Some("alpine"), &["latest".to_string()], 100, &[], None)
+            .unwrap();
+        state
+            .create_image("img-dangling", "sha256:dangling", None, &[], 200, &[], None)
+            .unwrap();
+        state
+            .create_container("ctr-1", "one", "img-tagged", "/bundles/ctr-1", None, ResolveMode::Default)
+            .unwrap();
+
+        // Still referenced by ctr-1, so an unforced delete is refused
+        assert!(matches!(
+            state.delete_image("img-tagged", false),
+            Err(StateError::ImageInUse(_))
+        ));
+        state.delete_image("img-tagged", true).unwrap();
+        assert!(state.get_image("img-tagged").is_err());
+
+        // Dangling, unreferenced image was never touched above
+        assert!(state.get_image("img-dangling").is_ok());
+
+        // dangling_only=true leaves a tagged-but-unreferenced image alone
+        state
+            .create_image("img-tagged-unused", "sha256:tagged-unused", Some("busybox"), &["latest".to_string()], 50, &[], None)
+            .unwrap();
+        let result = state.prune_images(true).unwrap();
+        assert_eq!(result.removed_ids, vec!["img-dangling".to_string()]);
+        assert_eq!(result.reclaimed_bytes, 200);
+        assert!(state.get_image("img-tagged-unused").is_ok());
+
+        // dangling_only=false sweeps every unreferenced image, tagged or not
+        let result = state.prune_images(false).unwrap();
+        assert_eq!(result.removed_ids, vec!["img-tagged-unused".to_string()]);
+        assert_eq!(result.reclaimed_bytes, 50);
+    }
+
+    #[test]
+    fn test_volume_refcounting() {
+        let state = StateManager::open_in_memory().unwrap();
+
+        state
+            .create_image("img-123", "sha256:abc123", Some("alpine"), &[], 0, &[], None)
+            .unwrap();
+        state
+            .create_container("ctr-1", "one", "img-123", "/bundles/ctr-1", None, ResolveMode::Default)
+            .unwrap();
+        state
+            .create_container("ctr-2", "two", "img-123", "/bundles/ctr-2", None, ResolveMode::Default)
+            .unwrap();
+        state
+            .create_volume("vol-1", "my-volume", "local", "/var/lib/vordr/volumes/my-volume", None, None)
+            .unwrap();
+
+        let volume = state.get_volume("my-volume").unwrap();
+        assert_eq!(volume.refcount, 0);
+
+        // Mounting into two containers brings the refcount to 2
+        state.mount_volume("ctr-1", "vol-1", "/data").unwrap();
+        state.mount_volume("ctr-2", "vol-1", "/data").unwrap();
+        assert_eq!(state.get_volume("vol-1").unwrap().refcount, 2);
+
+        // Mounting the same pair again is a no-op
+        state.mount_volume("ctr-1", "vol-1", "/data").unwrap();
+        assert_eq!(state.get_volume("vol-1").unwrap().refcount, 2);
+
+        // Unmounting drops the refcount back down
+        state.unmount_volume("ctr-1", "vol-1").unwrap();
+        assert_eq!(state.get_volume("vol-1").unwrap().refcount, 1);
+
+        // A crashed process can't corrupt the count: deleting the container
+        // directly (bypassing unmount_volume, as a kill -9 would) cascades
+        // the container_volumes row, and recompute_volume_refcounts derives
+        // the correct value from what's actually left mounted.
+        state.delete_container("ctr-2").unwrap();
+        state.recompute_volume_refcounts().unwrap();
+        assert_eq!(state.get_volume("vol-1").unwrap().refcount, 0);
     }
 }
@@ -0,0 +1,187 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Volume driver abstraction
+//!
+//! `local` backs a volume with a directory on this host - Vordr's original,
+//! single-machine behavior. `s3` backs it with a prefix in an
+//! S3-compatible bucket, syncing with the `aws` CLI's `s3 sync` (the repo
+//! already shells out to external tools to drive the container runtime
+//! itself, see `runtime::shim`, rather than reimplementing their
+//! protocols), so volumes become portable across hosts instead of being
+//! pinned to one machine's filesystem.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum VolumeDriverError {
+    #[error("unknown volume driver {0:?}")]
+    UnknownDriver(String),
+    #[error("driver {driver} requires --opt {key}")]
+    MissingOption { driver: &'static str, key: &'static str },
+    #[error("sync command failed: {0}")]
+    SyncFailed(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Everything a driver needs to act on one volume.
+pub struct VolumeContext {
+    pub name: String,
+    pub mountpoint: PathBuf,
+    pub options: HashMap<String, String>,
+}
+
+/// A backend for volume lifecycle and data placement.
+pub trait VolumeDriver: Send + Sync {
+    /// Prepares whatever the driver needs before the volume can be
+    /// mounted (a local directory, validated remote options, ...).
+    fn create(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError>;
+
+    /// Materializes the volume's data at `ctx.mountpoint` and returns the
+    /// path a container's mount namespace should bind from.
+    fn mount(&self, ctx: &VolumeContext) -> Result<PathBuf, VolumeDriverError>;
+
+    /// Syncs local changes back to the backing store.
+    fn unmount(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError>;
+
+    /// Releases whatever `create` set up. Drivers backed by a remote store
+    /// must not delete remote data here - only the local volume handle is
+    /// removed.
+    fn remove(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError>;
+
+    /// Driver-specific details to merge into `volume inspect` output, with
+    /// any credentials redacted.
+    fn inspect(&self, ctx: &VolumeContext) -> serde_json::Value;
+}
+
+/// Resolves a `--driver` name to its implementation.
+pub fn build_driver(name: &str) -> Result<Box<dyn VolumeDriver>, VolumeDriverError> {
+    match name {
+        "local" => Ok(Box::new(LocalDriver)),
+        "s3" => Ok(Box::new(S3Driver)),
+        other => Err(VolumeDriverError::UnknownDriver(other.to_string())),
+    }
+}
+
+/// Backs a volume with a directory under `<root>/volumes/<name>`.
+pub struct LocalDriver;
+
+impl VolumeDriver for LocalDriver {
+    fn create(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError> {
+        std::fs::create_dir_all(&ctx.mountpoint)?;
+        Ok(())
+    }
+
+    fn mount(&self, ctx: &VolumeContext) -> Result<PathBuf, VolumeDriverError> {
+        std::fs::create_dir_all(&ctx.mountpoint)?;
+        Ok(ctx.mountpoint.clone())
+    }
+
+    fn unmount(&self, _ctx: &VolumeContext) -> Result<(), VolumeDriverError> {
+        Ok(())
+    }
+
+    fn remove(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError> {
+        if ctx.mountpoint.exists() {
+            std::fs::remove_dir_all(&ctx.mountpoint)?;
+        }
+        Ok(())
+    }
+
+    fn inspect(&self, _ctx: &VolumeContext) -> serde_json::Value {
+        serde_json::json!({})
+    }
+}
+
+/// Backs a volume with a prefix in an S3-compatible bucket. `--opt` keys:
+/// `bucket` (required), `prefix`, `endpoint` (for non-AWS S3-compatible
+/// stores), `region`, `access_key_id`, `secret_access_key`.
+pub struct S3Driver;
+
+impl S3Driver {
+    fn require<'a>(
+        &self,
+        opts: &'a HashMap<String, String>,
+        key: &'static str,
+    ) -> Result<&'a str, VolumeDriverError> {
+        opts.get(key)
+            .map(String::as_str)
+            .ok_or(VolumeDriverError::MissingOption { driver: "s3", key })
+    }
+
+    fn bucket_uri(&self, ctx: &VolumeContext) -> Result<String, VolumeDriverError> {
+        let bucket = self.require(&ctx.options, "bucket")?;
+        let prefix = ctx.options.get("prefix").map(String::as_str).unwrap_or("");
+        Ok(if prefix.is_empty() {
+            format!("s3://{}", bucket)
+        } else {
+            format!("s3://{}/{}", bucket, prefix.trim_matches('/'))
+        })
+    }
+
+    fn sync(&self, ctx: &VolumeContext, source: &str, dest: &str) -> Result<(), VolumeDriverError> {
+        let mut cmd = Command::new("aws");
+        cmd.arg("s3").arg("sync").arg(source).arg(dest);
+
+        if let Some(endpoint) = ctx.options.get("endpoint") {
+            cmd.arg("--endpoint-url").arg(endpoint);
+        }
+        if let Some(region) = ctx.options.get("region") {
+            cmd.env("AWS_DEFAULT_REGION", region);
+        }
+        if let Some(key_id) = ctx.options.get("access_key_id") {
+            cmd.env("AWS_ACCESS_KEY_ID", key_id);
+        }
+        if let Some(secret) = ctx.options.get("secret_access_key") {
+            cmd.env("AWS_SECRET_ACCESS_KEY", secret);
+        }
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            return Err(VolumeDriverError::SyncFailed(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl VolumeDriver for S3Driver {
+    fn create(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError> {
+        self.require(&ctx.options, "bucket")?;
+        Ok(())
+    }
+
+    fn mount(&self, ctx: &VolumeContext) -> Result<PathBuf, VolumeDriverError> {
+        std::fs::create_dir_all(&ctx.mountpoint)?;
+        let source = self.bucket_uri(ctx)?;
+        self.sync(ctx, &source, &ctx.mountpoint.to_string_lossy())?;
+        Ok(ctx.mountpoint.clone())
+    }
+
+    fn unmount(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError> {
+        let dest = self.bucket_uri(ctx)?;
+        self.sync(ctx, &ctx.mountpoint.to_string_lossy(), &dest)
+    }
+
+    fn remove(&self, ctx: &VolumeContext) -> Result<(), VolumeDriverError> {
+        // Bucket data outlives the local volume handle - only the local
+        // staging directory is cleaned up here.
+        if ctx.mountpoint.exists() {
+            std::fs::remove_dir_all(&ctx.mountpoint)?;
+        }
+        Ok(())
+    }
+
+    fn inspect(&self, ctx: &VolumeContext) -> serde_json::Value {
+        serde_json::json!({
+            "Endpoint": ctx.options.get("endpoint"),
+            "Bucket": ctx.options.get("bucket"),
+            "Prefix": ctx.options.get("prefix"),
+            "Region": ctx.options.get("region"),
+        })
+    }
+}
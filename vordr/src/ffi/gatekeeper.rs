@@ -6,6 +6,7 @@
 
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Errors that can occur during gatekeeper validation
@@ -34,10 +35,19 @@ pub enum GatekeeperError {
 
     #[error("Gatekeeper not initialized")]
     NotInitialized,
+
+    #[error("Gatekeeper ABI mismatch: Rust bindings expect version {expected}, library reports {found}")]
+    AbiMismatch { expected: c_int, found: c_int },
+
+    #[error("Gatekeeper error {code}: {message}")]
+    Unknown { code: c_int, message: String },
 }
 
 impl GatekeeperError {
-    /// Convert from FFI error code
+    /// Convert from FFI error code. Codes outside the known contract fall
+    /// back to `get_error_message` instead of being collapsed to
+    /// `InternalError`, so new policy failure reasons added on the Ada
+    /// side still surface their description.
     fn from_code(code: c_int) -> Result<(), Self> {
         match code {
             0 => Ok(()),
@@ -46,35 +56,67 @@ impl GatekeeperError {
             3 => Err(GatekeeperError::InvalidNetworkMode),
             4 => Err(GatekeeperError::InvalidPrivilegeEscape),
             5 => Err(GatekeeperError::ParseError),
-            _ => Err(GatekeeperError::InternalError),
+            _ => Err(GatekeeperError::Unknown {
+                code,
+                message: get_error_description(code as i32),
+            }),
         }
     }
 }
 
+/// ABI version the Rust bindings in this module are written against.
+/// Checked against `gatekeeper_abi_version()` at `init()` time; see
+/// [`GatekeeperError::AbiMismatch`].
+const ABI_VERSION: c_int = 1;
+
 // FFI declarations
 extern "C" {
     fn verify_json_config(json: *const c_char) -> c_int;
     fn get_error_message(code: c_int) -> *const c_char;
     fn sanitise_config(json: *const c_char, output: *mut c_char, size: c_int) -> c_int;
     fn gatekeeper_version() -> *const c_char;
+    fn gatekeeper_abi_version() -> c_int;
     fn gatekeeper_init() -> c_int;
 }
 
 /// Global initialization state
 static INIT: std::sync::Once = std::sync::Once::new();
 static mut INIT_RESULT: c_int = -1;
+/// Set during `init()` if the library's ABI version doesn't match
+/// [`ABI_VERSION`]; when set, `init()` refuses to run `gatekeeper_init()`
+/// at all, since the numeric error-code contract can't be trusted.
+static mut ABI_MISMATCH: Option<c_int> = None;
 
 /// Initialize the gatekeeper. Must be called before any validation.
 /// This is safe to call multiple times - subsequent calls are no-ops.
+///
+/// Before running the library's own init, this checks
+/// `gatekeeper_abi_version()` against [`ABI_VERSION`] and refuses to
+/// proceed on a mismatch - running validation against a library built to
+/// a different ABI contract than these bindings expect is undefined
+/// behavior, not a recoverable error.
 pub fn init() -> Result<(), GatekeeperError> {
     INIT.call_once(|| {
         // Safety: This is only called once due to std::sync::Once
         unsafe {
-            INIT_RESULT = gatekeeper_init();
+            let found = gatekeeper_abi_version();
+            if found == ABI_VERSION {
+                INIT_RESULT = gatekeeper_init();
+            } else {
+                ABI_MISMATCH = Some(found);
+            }
         }
     });
 
-    // Safety: INIT_RESULT is only written to once in call_once
+    // Safety: ABI_MISMATCH and INIT_RESULT are only written to once, in
+    // call_once above
+    if let Some(found) = unsafe { ABI_MISMATCH } {
+        return Err(GatekeeperError::AbiMismatch {
+            expected: ABI_VERSION,
+            found,
+        });
+    }
+
     if unsafe { INIT_RESULT } == 0 {
         Ok(())
     } else {
@@ -180,6 +222,75 @@ pub fn version() -> String {
     }
 }
 
+/// The subset of the OCI runtime spec (`config.json`) the gatekeeper
+/// actually inspects, plus the `network_mode` extension Vordr adds on top
+/// of it. [`ConfigValidator::to_json`] serializes this; `from_oci_json`
+/// deserializes it back, which is what lets a real container bundle's
+/// `config.json` round-trip through the builder instead of only the
+/// toy config `to_json` used to hand-build.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct OciSpec {
+    #[serde(default)]
+    process: OciProcess,
+    #[serde(default)]
+    root: OciRoot,
+    #[serde(default)]
+    linux: OciLinux,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct OciProcess {
+    #[serde(default)]
+    user: OciUser,
+    #[serde(rename = "noNewPrivileges", default)]
+    no_new_privileges: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    capabilities: Option<OciCapabilities>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct OciUser {
+    #[serde(default)]
+    uid: u32,
+}
+
+/// `process.capabilities` as the gatekeeper cares about it: the three
+/// sets it actually checks. `inheritable`/`ambient` aren't part of the
+/// policy it validates, so they're left out rather than modeled and
+/// ignored.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct OciCapabilities {
+    #[serde(default)]
+    bounding: Vec<String>,
+    #[serde(default)]
+    effective: Vec<String>,
+    #[serde(default)]
+    permitted: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct OciRoot {
+    #[serde(default)]
+    readonly: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct OciLinux {
+    #[serde(default)]
+    namespaces: Vec<OciNamespace>,
+    /// Not part of the OCI runtime spec proper - a Vordr extension the
+    /// gatekeeper reads to validate capabilities against the claimed
+    /// [`NetworkMode`].
+    #[serde(default)]
+    network_mode: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OciNamespace {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
 /// Builder for constructing validated container configurations.
 #[derive(Debug, Clone)]
 pub struct ConfigValidator {
@@ -263,6 +374,33 @@ impl ConfigValidator {
         self
     }
 
+    /// Build a validator from an existing OCI runtime spec, e.g. a
+    /// container bundle's `config.json`. Round-trips through [`OciSpec`]
+    /// rather than `validate()`'s hand-rolled template, so it carries
+    /// over capabilities, the user namespace, and `network_mode` exactly
+    /// as the spec declares them.
+    pub fn from_oci_json(json: &str) -> Result<Self, GatekeeperError> {
+        let spec: OciSpec = serde_json::from_str(json).map_err(|_| GatekeeperError::ParseError)?;
+
+        let user_namespace = spec.linux.namespaces.iter().any(|ns| ns.kind == "user");
+        let network_mode = match spec.linux.network_mode.as_str() {
+            "restricted" => NetworkMode::Restricted,
+            "admin" => NetworkMode::Admin,
+            _ => NetworkMode::Unprivileged,
+        };
+        let capabilities = spec.process.capabilities.map(|caps| caps.bounding).unwrap_or_default();
+
+        Ok(Self {
+            privileged: false,
+            user_namespace,
+            user_id: spec.process.user.uid,
+            network_mode,
+            capabilities,
+            no_new_privileges: spec.process.no_new_privileges,
+            readonly_rootfs: spec.root.readonly,
+        })
+    }
+
     /// Build and validate the configuration.
     pub fn validate(self) -> Result<ValidatedConfig, GatekeeperError> {
         // Build a minimal OCI config JSON for validation
@@ -289,28 +427,38 @@ impl ConfigValidator {
             NetworkMode::Admin => "admin",
         };
 
-        format!(
-            r#"{{
-                "process": {{
-                    "user": {{ "uid": {} }},
-                    "noNewPrivileges": {}
-                }},
-                "root": {{ "readonly": {} }},
-                "linux": {{
-                    "namespaces": [{}],
-                    "network_mode": "{}"
-                }}
-            }}"#,
-            self.user_id,
-            self.no_new_privileges,
-            self.readonly_rootfs,
-            if self.user_namespace {
-                r#"{"type": "user"}"#
-            } else {
-                ""
+        let capabilities = if self.capabilities.is_empty() {
+            None
+        } else {
+            Some(OciCapabilities {
+                bounding: self.capabilities.clone(),
+                effective: self.capabilities.clone(),
+                permitted: self.capabilities.clone(),
+            })
+        };
+
+        let namespaces = if self.user_namespace {
+            vec![OciNamespace { kind: "user".to_string() }]
+        } else {
+            Vec::new()
+        };
+
+        let spec = OciSpec {
+            process: OciProcess {
+                user: OciUser { uid: self.user_id },
+                no_new_privileges: self.no_new_privileges,
+                capabilities,
             },
-            network_mode
-        )
+            root: OciRoot {
+                readonly: self.readonly_rootfs,
+            },
+            linux: OciLinux {
+                namespaces,
+                network_mode: network_mode.to_string(),
+            },
+        };
+
+        serde_json::to_string(&spec).unwrap_or_default()
     }
 }
 
@@ -377,4 +525,36 @@ mod tests {
         assert!(!desc.is_empty());
         assert!(desc.contains("SYS_ADMIN") || desc.contains("capability"));
     }
+
+    #[test]
+    fn test_to_json_includes_capabilities() {
+        let json = ConfigValidator::new()
+            .add_capability("CAP_NET_BIND_SERVICE")
+            .to_json();
+        assert!(json.contains("CAP_NET_BIND_SERVICE"));
+        assert!(json.contains("\"bounding\""));
+    }
+
+    #[test]
+    fn test_from_oci_json_round_trips_capabilities_and_network_mode() {
+        let original = ConfigValidator::new()
+            .user_id(1000)
+            .network_mode(NetworkMode::Restricted)
+            .add_capability("CAP_NET_ADMIN")
+            .to_json();
+
+        let restored = ConfigValidator::from_oci_json(&original).unwrap();
+        assert_eq!(restored.user_id, 1000);
+        assert_eq!(restored.network_mode, NetworkMode::Restricted);
+        assert_eq!(restored.capabilities, vec!["CAP_NET_ADMIN".to_string()]);
+        assert!(restored.user_namespace);
+    }
+
+    #[test]
+    fn test_from_oci_json_rejects_invalid_json() {
+        assert_eq!(
+            ConfigValidator::from_oci_json("not json"),
+            Err(GatekeeperError::ParseError)
+        );
+    }
 }
@@ -10,11 +10,15 @@ use tracing::info;
 use tracing_subscriber::{fmt, EnvFilter};
 
 mod cli;
+mod clock;
 mod engine;
 mod ffi;
+mod mcp;
 mod network;
 mod registry;
+mod remote;
 mod runtime;
+mod unpack;
 
 use cli::{Cli, Commands};
 
@@ -2,7 +2,10 @@
 //! MCP server for AI-assisted container management
 //!
 //! This module provides Model Context Protocol tool definitions
-//! for integration with AI assistants.
+//! for integration with AI assistants. [`server`] dispatches them over a
+//! JSON-RPC 2.0 stdio transport.
+
+pub mod server;
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -12,12 +15,35 @@ use serde_json::json;
 pub struct McpToolDefinition {
     pub name: String,
     pub description: String,
+    #[serde(rename = "inputSchema")]
     pub input_schema: serde_json::Value,
 }
 
 /// Generate MCP tool definitions for Vordr
 pub fn get_tool_definitions() -> Vec<McpToolDefinition> {
     vec![
+        McpToolDefinition {
+            name: "vordr_build".into(),
+            description: "Build an image from a Dockerfile, executing RUN instructions in ephemeral containers and layering the result.".into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "context": {
+                        "type": "string",
+                        "description": "Build context directory that COPY/ADD sources are resolved against (default: \".\")"
+                    },
+                    "dockerfile": {
+                        "type": "string",
+                        "description": "Path to the Dockerfile, relative to context (default: \"Dockerfile\")"
+                    },
+                    "tag": {
+                        "type": "string",
+                        "description": "Name and tag for the resulting image (name:tag)"
+                    }
+                },
+                "required": ["tag"]
+            }),
+        },
         McpToolDefinition {
             name: "vordr_run".into(),
             description: "Create and start a container from an image. The image will be pulled if not present locally.".into(),
@@ -246,6 +272,72 @@ pub fn get_tool_definitions() -> Vec<McpToolDefinition> {
                 "required": ["name"]
             }),
         },
+        McpToolDefinition {
+            name: "vordr_wait".into(),
+            description: "Block until a container reaches a target condition (running, healthy, or exited), instead of polling inspect in a loop.".into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "container": {
+                        "type": "string",
+                        "description": "Container ID or name"
+                    },
+                    "condition": {
+                        "type": "string",
+                        "enum": ["running", "healthy", "exited"],
+                        "description": "Condition to wait for (default: exited)"
+                    },
+                    "exit_code": {
+                        "type": "integer",
+                        "description": "Exit code to wait for when condition is \"exited\" (default: 0)"
+                    },
+                    "timeout": {
+                        "type": "integer",
+                        "description": "Seconds to wait before giving up (default: wait indefinitely)"
+                    }
+                },
+                "required": ["container"]
+            }),
+        },
+        McpToolDefinition {
+            name: "vordr_compose_up".into(),
+            description: "Bring up a multi-container stack from a compose file. Over MCP this always returns an error directing the caller to run `vordr compose up` directly; only `file`, if given, is checked to exist.".into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "file": {
+                        "type": "string",
+                        "description": "Path to the compose file (default: compose.yaml)"
+                    },
+                    "project": {
+                        "type": "string",
+                        "description": "Project name (default: current directory name)"
+                    },
+                    "services": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Specific services to start (default: all)"
+                    }
+                }
+            }),
+        },
+        McpToolDefinition {
+            name: "vordr_compose_down".into(),
+            description: "Stop and remove every container (and network) belonging to a compose project. Over MCP this always returns an error directing the caller to run `vordr compose down` directly; only `file`, if given, is checked to exist.".into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "file": {
+                        "type": "string",
+                        "description": "Path to the compose file (default: compose.yaml)"
+                    },
+                    "project": {
+                        "type": "string",
+                        "description": "Project name (default: current directory name)"
+                    }
+                }
+            }),
+        },
     ]
 }
 
@@ -0,0 +1,709 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! JSON-RPC 2.0 stdio transport for the tool definitions in [`super`]
+//!
+//! Framing mirrors `crate::remote`'s newline-delimited JSON, just over
+//! stdin/stdout instead of a Unix socket: one JSON-RPC request per line
+//! in, one response per line out. `tools/call` dispatches straight into
+//! `StateManager` and the pure helpers CLI commands already factor out
+//! (`pull::ensure_image`, `run::generate_container_id`, ...) rather than
+//! the CLI `execute()` functions, which `println!` to the same stdout
+//! this server needs as a clean JSON-RPC channel.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::cli::{build, ports, pull, run, Cli};
+use crate::engine::{
+    build_network_driver, ipam, ContainerLifecycle, ContainerState, NetworkCreateRequest, ResolveMode, StateManager,
+    WaitCondition,
+};
+use crate::ffi::{ConfigValidator, NetworkMode};
+use crate::mcp::{get_tool_definitions, McpToolResult};
+use crate::registry::LayerStore;
+
+const JSONRPC_VERSION: &str = "2.0";
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+impl JsonRpcResponse {
+    fn ok(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn err(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+/// Runs the MCP server loop: reads one JSON-RPC request per line from
+/// stdin, writes one response per line to stdout, until stdin closes.
+pub async fn serve(cli: &Cli) -> anyhow::Result<()> {
+    let mut reader = BufReader::new(tokio::io::stdin());
+    let mut stdout = tokio::io::stdout();
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let bytes_read = reader.read_line(&mut line).await?;
+        if bytes_read == 0 {
+            return Ok(());
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<JsonRpcRequest>(trimmed) {
+            Ok(request) => handle_request(request, cli).await,
+            Err(e) => JsonRpcResponse::err(
+                Value::Null,
+                INVALID_PARAMS,
+                format!("malformed JSON-RPC request: {e}"),
+            ),
+        };
+
+        let mut out = serde_json::to_vec(&response)?;
+        out.push(b'\n');
+        stdout.write_all(&out).await?;
+        stdout.flush().await?;
+    }
+}
+
+async fn handle_request(request: JsonRpcRequest, cli: &Cli) -> JsonRpcResponse {
+    match request.method.as_str() {
+        "initialize" => JsonRpcResponse::ok(
+            request.id,
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "vordr", "version": env!("CARGO_PKG_VERSION") },
+            }),
+        ),
+        "tools/list" => JsonRpcResponse::ok(request.id, json!({ "tools": get_tool_definitions() })),
+        "tools/call" => handle_tools_call(request.id, request.params, cli).await,
+        other => JsonRpcResponse::err(request.id, METHOD_NOT_FOUND, format!("unknown method {:?}", other)),
+    }
+}
+
+async fn handle_tools_call(id: Value, params: Value, cli: &Cli) -> JsonRpcResponse {
+    let Some(name) = params.get("name").and_then(Value::as_str) else {
+        return JsonRpcResponse::err(id, INVALID_PARAMS, "tools/call requires a string \"name\"");
+    };
+    let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+    let result = call_tool(name, &arguments, cli).await;
+    JsonRpcResponse::ok(id, to_mcp_content(result))
+}
+
+/// Wraps an [`McpToolResult`] in the standard MCP tool-result content shape.
+fn to_mcp_content(result: McpToolResult) -> Value {
+    let text = if result.success {
+        result.output
+    } else {
+        result.error.unwrap_or_default()
+    };
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": !result.success,
+    })
+}
+
+async fn call_tool(name: &str, args: &Value, cli: &Cli) -> McpToolResult {
+    match name {
+        "vordr_build" => tool_build(args, cli).await,
+        "vordr_run" => tool_run(args, cli).await,
+        "vordr_ps" => tool_ps(args, cli),
+        "vordr_stop" => tool_stop(args, cli),
+        "vordr_rm" => tool_rm(args, cli),
+        "vordr_exec" => tool_exec(args, cli),
+        "vordr_logs" => tool_logs(args, cli),
+        "vordr_inspect" => tool_inspect(args, cli),
+        "vordr_wait" => tool_wait(args, cli).await,
+        "vordr_images" => tool_images(cli),
+        "vordr_pull" => tool_pull(args, cli).await,
+        "vordr_network_ls" => tool_network_ls(cli),
+        "vordr_network_create" => tool_network_create(args, cli),
+        "vordr_compose_up" => tool_compose_unavailable(args),
+        "vordr_compose_down" => tool_compose_unavailable(args),
+        other => McpToolResult::error(format!("unknown tool {:?}", other)),
+    }
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(Value::as_str)
+}
+
+fn str_list_arg(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+fn bool_arg(args: &Value, key: &str, default: bool) -> bool {
+    args.get(key).and_then(Value::as_bool).unwrap_or(default)
+}
+
+async fn tool_run(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(image) = str_arg(args, "image") else {
+        return McpToolResult::error("\"image\" is required");
+    };
+
+    match run_container(args, image, cli).await {
+        Ok(summary) => McpToolResult::success(summary),
+        Err(e) => McpToolResult::error(format!("{e:#}")),
+    }
+}
+
+/// Mirrors `run::execute`'s pipeline (gatekeeper validation, port
+/// reservation, image pull/unpack, container + volume bookkeeping) but
+/// returns a summary instead of printing, since stdout here is the
+/// JSON-RPC channel. Always runs unprivileged/restricted-network,
+/// since the tool schema doesn't expose `--privileged`/`--network`.
+async fn run_container(args: &Value, image: &str, cli: &Cli) -> anyhow::Result<String> {
+    let user_id = str_arg(args, "user")
+        .map(|u| u.parse::<u32>().context("invalid \"user\""))
+        .transpose()?
+        .unwrap_or(1000);
+    let command = str_list_arg(args, "command");
+    let env = str_list_arg(args, "env");
+    let volumes = str_list_arg(args, "volumes");
+    let port_specs = str_list_arg(args, "ports");
+    let detach = bool_arg(args, "detach", true);
+
+    let db_path = Path::new(&cli.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create database directory")?;
+    }
+    let state = StateManager::open(db_path).context("Failed to open state database")?;
+
+    let validated_config = ConfigValidator::new()
+        .privileged(false)
+        .user_namespace(true)
+        .user_id(user_id)
+        .network_mode(NetworkMode::Restricted)
+        .readonly_rootfs(false)
+        .validate()
+        .context("Security validation failed")?;
+
+    let port_mappings = ports::parse_specs(&port_specs).context("Invalid port spec")?;
+    ports::validate_privileged_ports(&port_mappings, validated_config.privileged)
+        .context("Port validation failed")?;
+    ports::check_collisions(&state, &port_mappings).context("Port validation failed")?;
+
+    let container_id = run::generate_container_id();
+    let container_name = str_arg(args, "name")
+        .map(str::to_string)
+        .unwrap_or_else(run::generate_container_name);
+
+    let root_path = Path::new(&cli.root);
+    std::fs::create_dir_all(root_path).context("Failed to create root directory")?;
+    let bundle_path = root_path.join("containers").join(&container_id);
+    std::fs::create_dir_all(&bundle_path).context("Failed to create bundle directory")?;
+
+    let image_info = pull::ensure_image(cli, image).await.context("Failed to resolve image")?;
+    let image_id = image_info.id.clone();
+
+    let layer_store = LayerStore::open(root_path.join("layers")).context("failed to open local layer store")?;
+    let layer_paths = state
+        .image_layers(&image_id)?
+        .into_iter()
+        .map(|layer| layer_store.blob_path(&layer.digest))
+        .collect::<Vec<_>>();
+    crate::unpack::unpack_layers(&layer_paths, &bundle_path.join("rootfs"))
+        .context("Failed to unpack image layers")?;
+
+    let config_json = json!({
+        "image": image,
+        "command": command,
+        "env": env,
+        "volumes": volumes,
+        "ports": port_specs,
+        "port_mappings": port_mappings,
+        "privileged": validated_config.privileged,
+        "user": user_id,
+        "userns": validated_config.user_namespace,
+    });
+
+    state
+        .create_container(
+            &container_id,
+            &container_name,
+            &image_id,
+            bundle_path.to_str().unwrap(),
+            Some(&config_json.to_string()),
+            ResolveMode::Default,
+        )
+        .context("Failed to create container record")?;
+
+    crate::network::portforward::install(&port_mappings);
+
+    for spec in &volumes {
+        let mut parts = spec.splitn(3, ':');
+        let name = parts.next().unwrap_or_default();
+        let Some(mount_path) = parts.next() else {
+            continue;
+        };
+        if let Ok(volume) = state.get_volume(name) {
+            state.mount_volume(&container_id, &volume.id, mount_path)?;
+        }
+    }
+
+    if detach {
+        state.set_container_state(&container_id, ContainerState::Running, Some(std::process::id() as i32))?;
+        Ok(format!("Created and started container {container_name} ({container_id})"))
+    } else {
+        Ok(format!("Created container {container_name} ({container_id})"))
+    }
+}
+
+async fn tool_build(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(tag) = str_arg(args, "tag") else {
+        return McpToolResult::error("\"tag\" is required");
+    };
+    let context = Path::new(str_arg(args, "context").unwrap_or("."));
+    let dockerfile_path = context.join(str_arg(args, "dockerfile").unwrap_or("Dockerfile"));
+
+    let content = match std::fs::read_to_string(&dockerfile_path) {
+        Ok(content) => content,
+        Err(e) => return McpToolResult::error(format!("failed to read {}: {e}", dockerfile_path.display())),
+    };
+
+    match build::build_image(cli, context, &content, tag).await {
+        Ok(image) => McpToolResult::success(
+            json!({
+                "Id": image.id,
+                "Tags": image.tags,
+                "Size": image.size,
+            })
+            .to_string(),
+        ),
+        Err(e) => McpToolResult::error(format!("{e:#}")),
+    }
+}
+
+fn tool_ps(args: &Value, cli: &Cli) -> McpToolResult {
+    let db_path = Path::new(&cli.db_path);
+    if !db_path.exists() {
+        return McpToolResult::success("[]");
+    }
+
+    let state = match StateManager::open(db_path) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    let state_filter = if bool_arg(args, "all", false) {
+        None
+    } else if let Some(filter) = str_arg(args, "filter") {
+        Some(ContainerState::from_str(filter).unwrap_or(ContainerState::Running))
+    } else {
+        Some(ContainerState::Running)
+    };
+
+    let containers = match state.list_containers(state_filter) {
+        Ok(containers) => containers,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    let entries: Vec<Value> = containers
+        .iter()
+        .map(|c| {
+            json!({
+                "Id": c.id,
+                "Name": c.name,
+                "Status": c.state.as_str(),
+                "Image": c.image_id,
+                "Pid": c.pid,
+                "ExitCode": c.exit_code,
+                "Ports": ports::published_ports(c),
+            })
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&entries) {
+        Ok(text) => McpToolResult::success(text),
+        Err(e) => McpToolResult::error(e.to_string()),
+    }
+}
+
+fn tool_stop(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(container) = str_arg(args, "container") else {
+        return McpToolResult::error("\"container\" is required");
+    };
+
+    let state = match StateManager::open(Path::new(&cli.db_path)) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    let info = match state.get_container(container) {
+        Ok(info) => info,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    // No real runtime attachment exists yet (see `run::execute`'s
+    // "simulate starting" comment), so stopping just records the
+    // transition and tears down the port-forward rules `run` installed.
+    if let Err(e) = state.set_container_exit_code(&info.id, 0) {
+        return McpToolResult::error(e.to_string());
+    }
+    crate::network::portforward::teardown(&ports::published_ports(&info));
+
+    McpToolResult::success(format!("Stopped container {} ({})", info.name, info.id))
+}
+
+fn tool_rm(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(container) = str_arg(args, "container") else {
+        return McpToolResult::error("\"container\" is required");
+    };
+
+    let state = match StateManager::open(Path::new(&cli.db_path)) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    let info = match state.get_container(container) {
+        Ok(info) => info,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    if info.state == ContainerState::Running && !bool_arg(args, "force", false) {
+        return McpToolResult::error(format!(
+            "container {} is running - stop it first or pass \"force\": true",
+            info.name
+        ));
+    }
+
+    if let Err(e) = state.delete_container(&info.id) {
+        return McpToolResult::error(e.to_string());
+    }
+    if let Err(e) = state.recompute_volume_refcounts() {
+        return McpToolResult::error(e.to_string());
+    }
+
+    McpToolResult::success(format!("Removed container {} ({})", info.name, info.id))
+}
+
+fn tool_exec(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(container) = str_arg(args, "container") else {
+        return McpToolResult::error("\"container\" is required");
+    };
+    if str_list_arg(args, "command").is_empty() {
+        return McpToolResult::error("\"command\" is required");
+    }
+
+    let state = match StateManager::open(Path::new(&cli.db_path)) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+    let info = match state.get_container(container) {
+        Ok(info) => info,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+    if info.state != ContainerState::Running {
+        return McpToolResult::error(format!(
+            "container {} is not running (state: {})",
+            info.name,
+            info.state.as_str()
+        ));
+    }
+
+    // Mirrors `exec::execute` - no runtime shim exists yet to actually
+    // attach to and run a command inside a live container.
+    McpToolResult::error("exec not yet implemented - container runtime integration pending")
+}
+
+fn tool_logs(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(container) = str_arg(args, "container") else {
+        return McpToolResult::error("\"container\" is required");
+    };
+
+    let state = match StateManager::open(Path::new(&cli.db_path)) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+    if let Err(e) = state.get_container(container) {
+        return McpToolResult::error(e.to_string());
+    }
+
+    // No log-capture infrastructure exists anywhere in the engine yet.
+    McpToolResult::error("logs not yet implemented - no log capture infrastructure exists yet")
+}
+
+fn tool_inspect(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(container) = str_arg(args, "container") else {
+        return McpToolResult::error("\"container\" is required");
+    };
+
+    let state = match StateManager::open(Path::new(&cli.db_path)) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+    let container = match state.get_container(container) {
+        Ok(container) => container,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    let config: Value = container
+        .config
+        .as_ref()
+        .and_then(|c| serde_json::from_str(c).ok())
+        .unwrap_or(json!({}));
+
+    let output = json!({
+        "Id": container.id,
+        "Name": container.name,
+        "Created": container.created_at,
+        "State": {
+            "Status": container.state.as_str(),
+            "Running": container.state == ContainerState::Running,
+            "Pid": container.pid,
+            "ExitCode": container.exit_code,
+            "StartedAt": container.started_at,
+            "FinishedAt": container.finished_at,
+        },
+        "Image": container.image_id,
+        "Config": config,
+        "NetworkSettings": { "Ports": ports::published_ports(&container) },
+    });
+
+    match serde_json::to_string_pretty(&output) {
+        Ok(text) => McpToolResult::success(text),
+        Err(e) => McpToolResult::error(e.to_string()),
+    }
+}
+
+async fn tool_wait(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(container) = str_arg(args, "container") else {
+        return McpToolResult::error("\"container\" is required");
+    };
+
+    let condition = match str_arg(args, "condition").unwrap_or("exited") {
+        "running" => WaitCondition::Running,
+        "healthy" => WaitCondition::Healthy,
+        "exited" => {
+            let exit_code = args.get("exit_code").and_then(Value::as_i64).unwrap_or(0) as i32;
+            WaitCondition::Exited(exit_code)
+        }
+        other => return McpToolResult::error(format!("unknown condition {:?}", other)),
+    };
+    let timeout = args
+        .get("timeout")
+        .and_then(Value::as_u64)
+        .map(std::time::Duration::from_secs);
+
+    let lifecycle = match ContainerLifecycle::new(Path::new(&cli.db_path), Path::new(&cli.root), &cli.runtime) {
+        Ok(lifecycle) => lifecycle,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    match lifecycle.wait_for(container, condition, timeout).await {
+        Ok(info) => McpToolResult::success(
+            json!({
+                "Id": info.id,
+                "Status": info.state.as_str(),
+                "Health": info.health_status.as_str(),
+                "ExitCode": info.exit_code,
+            })
+            .to_string(),
+        ),
+        Err(e) => McpToolResult::error(e.to_string()),
+    }
+}
+
+fn tool_images(cli: &Cli) -> McpToolResult {
+    let db_path = Path::new(&cli.db_path);
+    if !db_path.exists() {
+        return McpToolResult::success("[]");
+    }
+
+    let state = match StateManager::open(db_path) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+    let images = match state.list_images() {
+        Ok(images) => images,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    let entries: Vec<Value> = images
+        .iter()
+        .map(|image| {
+            json!({
+                "Id": image.id,
+                "Repository": image.repository,
+                "Tags": image.tags,
+                "Size": image.size,
+                "Created": image.created_at,
+            })
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&entries) {
+        Ok(text) => McpToolResult::success(text),
+        Err(e) => McpToolResult::error(e.to_string()),
+    }
+}
+
+async fn tool_pull(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(image) = str_arg(args, "image") else {
+        return McpToolResult::error("\"image\" is required");
+    };
+
+    match pull::pull_image(cli, image).await {
+        Ok(info) => McpToolResult::success(info.id),
+        Err(e) => McpToolResult::error(format!("{e:#}")),
+    }
+}
+
+fn tool_network_ls(cli: &Cli) -> McpToolResult {
+    let db_path = Path::new(&cli.db_path);
+    if !db_path.exists() {
+        return McpToolResult::success("[]");
+    }
+
+    let state = match StateManager::open(db_path) {
+        Ok(state) => state,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+    let networks = match state.list_networks() {
+        Ok(networks) => networks,
+        Err(e) => return McpToolResult::error(e.to_string()),
+    };
+
+    let entries: Vec<Value> = networks
+        .iter()
+        .map(|n| {
+            json!({
+                "Id": n.id,
+                "Name": n.name,
+                "Driver": n.driver,
+                "Subnet": n.subnet,
+                "Internal": n.internal,
+            })
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&entries) {
+        Ok(text) => McpToolResult::success(text),
+        Err(e) => McpToolResult::error(e.to_string()),
+    }
+}
+
+fn tool_network_create(args: &Value, cli: &Cli) -> McpToolResult {
+    let Some(name) = str_arg(args, "name") else {
+        return McpToolResult::error("\"name\" is required");
+    };
+    let driver = str_arg(args, "driver").unwrap_or("bridge");
+    let subnet = str_arg(args, "subnet");
+
+    match create_network(name, driver, subnet, cli) {
+        Ok(network_id) => McpToolResult::success(network_id),
+        Err(e) => McpToolResult::error(format!("{e:#}")),
+    }
+}
+
+/// `compose up`/`down` stream progress to stdout and, for `down`, prompt on
+/// stdin for confirmation before removing networks/volumes - both the
+/// stdout and stdin this server needs as a clean JSON-RPC channel. Until
+/// compose orchestration grows a non-interactive mode, the honest answer is
+/// to point callers at the CLI rather than risk the server hanging on a
+/// prompt it can never satisfy. Still validates `file` so a bad argument
+/// fails with a clear message instead of a generic one.
+fn tool_compose_unavailable(args: &Value) -> McpToolResult {
+    if let Some(file) = str_arg(args, "file") {
+        if !Path::new(file).exists() {
+            return McpToolResult::error(format!("compose file not found: {file}"));
+        }
+    }
+    McpToolResult::error(
+        "compose orchestration is not available over MCP yet - `compose up`/`down` stream progress \
+         and (for `down`) prompt for confirmation on the same stdin/stdout this server uses for \
+         JSON-RPC; run `vordr compose up`/`vordr compose down` directly instead",
+    )
+}
+
+/// Mirrors `network::create_network`, minus `--opt`/`--gateway`/`--internal`
+/// which the MCP tool schema doesn't expose.
+fn create_network(name: &str, driver: &str, subnet: Option<&str>, cli: &Cli) -> anyhow::Result<String> {
+    let db_path = Path::new(&cli.db_path);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let state = StateManager::open(db_path).context("Failed to open state database")?;
+
+    let network_id = uuid::Uuid::new_v4().to_string();
+    let no_options = HashMap::new();
+
+    let backend = build_network_driver(driver).with_context(|| format!("unsupported network driver {:?}", driver))?;
+    backend
+        .validate_create(&NetworkCreateRequest {
+            subnet,
+            gateway: None,
+            internal: false,
+            options: &no_options,
+        })
+        .with_context(|| format!("invalid options for network driver {:?}", driver))?;
+
+    let (subnet, gateway) = if !backend.has_own_address_space() {
+        (None, None)
+    } else {
+        match subnet {
+            Some(subnet) => (Some(subnet.to_string()), None),
+            None => {
+                let existing = state.list_networks()?;
+                let (subnet, gateway) = ipam::allocate_subnet(&existing).context("failed to allocate a subnet")?;
+                (Some(subnet), Some(gateway))
+            }
+        }
+    };
+
+    state.create_network(&network_id, name, driver, subnet.as_deref(), gateway.as_deref(), None, false)?;
+
+    Ok(network_id)
+}
@@ -0,0 +1,325 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Embedded authoritative DNS for same-network service discovery
+//!
+//! One resolver per DNS-enabled network, bound to that network's bridge
+//! gateway address on port 53. It answers A/AAAA queries for any
+//! `container_name` or alias registered on its network and forwards
+//! everything else upstream. [`DnsRegistry`] is the entry point:
+//! [`NetworkManager::setup`](super::netavark::NetworkManager::setup) calls
+//! [`DnsRegistry::register`] as a container joins a network and
+//! `teardown` calls [`DnsRegistry::deregister`] as it leaves. The
+//! per-network split means containers only ever resolve peers on
+//! networks they actually share.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use hickory_proto::op::{Message, MessageType, OpCode};
+use hickory_proto::rr::rdata::{A, AAAA};
+use hickory_proto::rr::{RData, Record, RecordType};
+use hickory_proto::serialize::binary::{BinDecodable, BinEncodable};
+use hickory_server::authority::MessageResponseBuilder;
+use hickory_server::server::{Request, RequestHandler, ResponseHandler, ResponseInfo, ServerFuture};
+use thiserror::Error;
+use tokio::net::UdpSocket;
+use tracing::{debug, warn};
+
+use super::netavark::{load_network_definition, NetworkError};
+
+const DNS_PORT: u16 = 53;
+const RECORD_TTL: u32 = 60;
+const UPSTREAM_FALLBACK: &str = "1.1.1.1:53";
+
+#[derive(Error, Debug)]
+pub enum DnsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("network lookup failed: {0}")]
+    Network(#[from] NetworkError),
+    #[error("invalid DNS bind address: {0}")]
+    InvalidAddress(String),
+}
+
+/// Hostname -> addresses registered on one network, shared between the
+/// resolver's request handler (reads) and `register`/`deregister` calls
+/// from container setup/teardown (writes).
+#[derive(Default)]
+struct NetworkRecords {
+    by_name: RwLock<HashMap<String, Vec<IpAddr>>>,
+}
+
+impl NetworkRecords {
+    fn add(&self, name: &str, ip: IpAddr) {
+        self.by_name
+            .write()
+            .unwrap()
+            .entry(name.to_ascii_lowercase())
+            .or_default()
+            .push(ip);
+    }
+
+    fn remove(&self, name: &str) {
+        self.by_name.write().unwrap().remove(&name.to_ascii_lowercase());
+    }
+
+    fn lookup(&self, name: &str) -> Vec<IpAddr> {
+        self.by_name
+            .read()
+            .unwrap()
+            .get(&name.to_ascii_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Owns one resolver (and its name registry) per DNS-enabled network.
+/// Resolvers are started lazily, the first time a container registers on
+/// a given network, and keep running for the life of the process - see
+/// [`spawn_resolver`]; dropping the [`DnsRegistry`] does not stop them.
+#[derive(Default)]
+pub struct DnsRegistry {
+    networks: RwLock<HashMap<String, Arc<NetworkRecords>>>,
+}
+
+impl DnsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `ip` under `container_name` and every entry in `aliases`
+    /// on `network_name`, starting that network's resolver first if this
+    /// is its first registration. A no-op if `network_name`'s
+    /// `dns_enabled` is unset or false.
+    pub fn register(
+        &self,
+        config_dir: &str,
+        network_name: &str,
+        container_name: &str,
+        aliases: &[String],
+        ip: IpAddr,
+    ) -> Result<(), DnsError> {
+        let Some(records) = self.records_for(config_dir, network_name)? else {
+            return Ok(());
+        };
+
+        records.add(container_name, ip);
+        for alias in aliases {
+            records.add(alias, ip);
+        }
+        Ok(())
+    }
+
+    /// Deregisters `container_name` and every entry in `aliases` from
+    /// `network_name`. A no-op if that network never started a resolver
+    /// (e.g. DNS was never enabled on it).
+    pub fn deregister(&self, network_name: &str, container_name: &str, aliases: &[String]) {
+        let Some(records) = self.networks.read().unwrap().get(network_name).cloned() else {
+            return;
+        };
+
+        records.remove(container_name);
+        for alias in aliases {
+            records.remove(alias);
+        }
+    }
+
+    /// Returns the record set for `network_name`, starting its resolver
+    /// if this is the first time it's been seen. Returns `None` when the
+    /// network has DNS disabled - callers should treat that as "nothing
+    /// to register".
+    fn records_for(&self, config_dir: &str, network_name: &str) -> Result<Option<Arc<NetworkRecords>>, DnsError> {
+        if let Some(existing) = self.networks.read().unwrap().get(network_name) {
+            return Ok(Some(existing.clone()));
+        }
+
+        let definition = load_network_definition(config_dir, network_name)?;
+        if !definition.dns_enabled.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let gateway = definition
+            .subnets
+            .as_ref()
+            .and_then(|subnets| subnets.first())
+            .and_then(|subnet| subnet.gateway.as_deref())
+            .ok_or_else(|| {
+                DnsError::InvalidAddress(format!("network '{}' has no gateway to bind DNS to", network_name))
+            })?;
+        let bind_ip = IpAddr::from_str(gateway)
+            .map_err(|e| DnsError::InvalidAddress(format!("invalid gateway {}: {}", gateway, e)))?;
+
+        let records = Arc::new(NetworkRecords::default());
+        spawn_resolver(network_name.to_string(), bind_ip, records.clone())?;
+
+        self.networks
+            .write()
+            .unwrap()
+            .insert(network_name.to_string(), records.clone());
+        Ok(Some(records))
+    }
+}
+
+/// Spawns a dedicated thread running a resolver bound to `bind_ip:53` for
+/// `network_name`, backed by `records`. Runs for the life of the process;
+/// there's currently no way to stop an individual network's resolver once
+/// started, since networks are rarely deleted while containers are
+/// running on them.
+fn spawn_resolver(network_name: String, bind_ip: IpAddr, records: Arc<NetworkRecords>) -> Result<(), DnsError> {
+    let bind_addr = SocketAddr::new(bind_ip, DNS_PORT);
+
+    std::thread::Builder::new()
+        .name(format!("dns-{}", network_name))
+        .spawn(move || {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    warn!("failed to start DNS resolver runtime for {}: {}", network_name, e);
+                    return;
+                }
+            };
+
+            runtime.block_on(async move {
+                let socket = match UdpSocket::bind(bind_addr).await {
+                    Ok(socket) => socket,
+                    Err(e) => {
+                        warn!("failed to bind DNS resolver for {} to {}: {}", network_name, bind_addr, e);
+                        return;
+                    }
+                };
+
+                debug!("DNS resolver for network {} listening on {}", network_name, bind_addr);
+
+                let mut server = ServerFuture::new(ContainerDnsHandler { records });
+                server.register_socket(socket);
+                if let Err(e) = server.block_until_done().await {
+                    warn!("DNS resolver for network {} exited: {}", network_name, e);
+                }
+            });
+        })
+        .map_err(DnsError::Io)?;
+
+    Ok(())
+}
+
+/// Answers from a network's [`NetworkRecords`] when it has a match,
+/// otherwise forwards the query upstream unmodified.
+struct ContainerDnsHandler {
+    records: Arc<NetworkRecords>,
+}
+
+#[async_trait::async_trait]
+impl RequestHandler for ContainerDnsHandler {
+    async fn handle_request<R: ResponseHandler>(&self, request: &Request, mut response_handle: R) -> ResponseInfo {
+        let query = request.query();
+        let name = query.name().to_string();
+        let lookup_name = name.trim_end_matches('.');
+        let ips = self.records.lookup(lookup_name);
+
+        if ips.is_empty() {
+            return match forward_upstream(request).await {
+                Ok(response) => send_message(request, &mut response_handle, response, false).await,
+                Err(e) => {
+                    warn!("upstream DNS forward for {} failed: {}", name, e);
+                    *request.header()
+                }
+            };
+        }
+
+        let records: Vec<Record> = ips
+            .iter()
+            .filter_map(|ip| match (ip, query.query_type()) {
+                (IpAddr::V4(v4), RecordType::A) => {
+                    Some(Record::from_rdata(query.name().clone(), RECORD_TTL, RData::A(A(*v4))))
+                }
+                (IpAddr::V6(v6), RecordType::AAAA) => Some(Record::from_rdata(
+                    query.name().clone(),
+                    RECORD_TTL,
+                    RData::AAAA(AAAA(*v6)),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        let builder = MessageResponseBuilder::from_message_request(request);
+        let mut header = *request.header();
+        header.set_message_type(MessageType::Response);
+        header.set_authoritative(true);
+        let response = builder.build(header, records.iter(), None, None, None);
+
+        response_handle
+            .send_response(response)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("failed to send DNS response for {}: {}", name, e);
+                *request.header()
+            })
+    }
+}
+
+/// Forwards `request` to the first nameserver in `/etc/resolv.conf`
+/// (falling back to a public resolver if none is configured) and returns
+/// its raw response message.
+pub(crate) async fn forward_upstream(request: &Request) -> Result<Message, DnsError> {
+    let mut message = Message::new();
+    message.set_id(request.id());
+    message.set_message_type(MessageType::Query);
+    message.set_op_code(OpCode::Query);
+    message.set_recursion_desired(true);
+    message.add_query(request.query().original().clone());
+
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    let upstream = upstream_nameserver();
+    socket.connect(&upstream).await?;
+
+    let request_bytes = message
+        .to_bytes()
+        .map_err(|e| DnsError::InvalidAddress(format!("failed to encode forwarded query: {}", e)))?;
+    socket.send(&request_bytes).await?;
+
+    let mut buf = [0u8; 512];
+    let len = socket.recv(&mut buf).await?;
+    Message::from_bytes(&buf[..len])
+        .map_err(|e| DnsError::InvalidAddress(format!("failed to decode upstream response: {}", e)))
+}
+
+/// First `nameserver` line in `/etc/resolv.conf`, or [`UPSTREAM_FALLBACK`]
+/// if none is configured or the file can't be read.
+pub(crate) fn upstream_nameserver() -> String {
+    std::fs::read_to_string("/etc/resolv.conf")
+        .ok()
+        .and_then(|contents| {
+            contents.lines().find_map(|line| {
+                let ip = line.trim().strip_prefix("nameserver")?.trim();
+                Some(format!("{}:{}", ip, DNS_PORT))
+            })
+        })
+        .unwrap_or_else(|| UPSTREAM_FALLBACK.to_string())
+}
+
+/// Relays `message`'s answers back through `response_handle` for
+/// `request`, marking the response authoritative or not per `authoritative`.
+pub(crate) async fn send_message<R: ResponseHandler>(
+    request: &Request,
+    response_handle: &mut R,
+    message: Message,
+    authoritative: bool,
+) -> ResponseInfo {
+    let builder = MessageResponseBuilder::from_message_request(request);
+    let mut header = *request.header();
+    header.set_message_type(MessageType::Response);
+    header.set_authoritative(authoritative);
+    let response = builder.build(
+        header,
+        message.answers().iter(),
+        message.name_servers().iter(),
+        &[],
+        message.additionals().iter(),
+    );
+
+    response_handle.send_response(response).await.unwrap_or_else(|e| {
+        warn!("failed to relay upstream DNS response: {}", e);
+        *request.header()
+    })
+}
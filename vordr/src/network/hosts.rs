@@ -0,0 +1,260 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Marker-delimited hosts-file management for container name resolution
+//!
+//! A lighter-weight alternative to the embedded resolver
+//! ([`crate::network::dns`]): keeps a single managed block of
+//! `ip  name` entries inside an existing hosts file, bracketed by
+//! sentinel comments, and rewrites only that block on every add/remove.
+//! Everything outside the markers - including the rest of `/etc/hosts`,
+//! if that's the target - is left exactly as found. Rewrites flock a
+//! sibling lock file for the duration (the hosts file's own inode changes
+//! across each rewrite, so locking it directly wouldn't close the race
+//! between a rename and the next writer's open) and land via
+//! temp-file-then-rename in the same directory, so a reader never
+//! observes a partially written file.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::net::IpAddr;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+const BEGIN_MARKER: &str = "# BEGIN vordr";
+const END_MARKER: &str = "# END vordr";
+
+#[derive(Error, Debug)]
+pub enum HostsFileError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Manages one hosts file's vordr-owned block. Typically one instance per
+/// container (pointed at its bind-mounted `/etc/hosts`), though it's
+/// equally safe to point several at the same path - writes are
+/// serialized via the lock file regardless of which instance issues them.
+pub struct HostsFile {
+    path: PathBuf,
+}
+
+impl HostsFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Maps `ip` to `container_name` and every entry in `aliases` within
+    /// the managed block, replacing any existing entry for the same name.
+    pub fn add_entries(&self, container_name: &str, aliases: &[String], ip: IpAddr) -> Result<(), HostsFileError> {
+        let ip = ip.to_string();
+        self.rewrite(|entries| {
+            entries.insert(container_name.to_string(), ip.clone());
+            for alias in aliases {
+                entries.insert(alias.clone(), ip.clone());
+            }
+        })
+    }
+
+    /// Removes `container_name` and every entry in `aliases` from the
+    /// managed block.
+    pub fn remove_entries(&self, container_name: &str, aliases: &[String]) -> Result<(), HostsFileError> {
+        self.rewrite(|entries| {
+            entries.remove(container_name);
+            for alias in aliases {
+                entries.remove(alias);
+            }
+        })
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".vordr.lock");
+        match self.path.parent() {
+            Some(dir) => dir.join(name),
+            None => PathBuf::from(name),
+        }
+    }
+
+    /// Locks, reads, mutates, and atomically rewrites the managed block.
+    fn rewrite(&self, mutate: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<(), HostsFileError> {
+        let lock_file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(self.lock_path())?;
+        flock_exclusive(&lock_file)?;
+
+        let existing = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(HostsFileError::Io(e)),
+        };
+
+        let (prefix, mut entries, suffix) = parse_managed_block(&existing);
+        mutate(&mut entries);
+        let rendered = render(&prefix, &entries, &suffix);
+
+        write_atomically(&self.path, &rendered)?;
+
+        // Locked for the whole read-mutate-write cycle; dropped (and thus
+        // unlocked) only once the new file is in place.
+        drop(lock_file);
+        Ok(())
+    }
+}
+
+/// Splits `contents` into the lines before [`BEGIN_MARKER`], the managed
+/// entries between the markers (deduped by name - last one wins, which
+/// matters if a prior run left a stale duplicate), and the lines after
+/// [`END_MARKER`]. If no markers are found, the whole file is treated as
+/// prefix and the managed block starts out empty.
+fn parse_managed_block(contents: &str) -> (Vec<String>, BTreeMap<String, String>, Vec<String>) {
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let begin = lines.iter().position(|line| line.trim() == BEGIN_MARKER);
+    let end = lines.iter().position(|line| line.trim() == END_MARKER);
+
+    let (Some(begin), Some(end)) = (begin, end) else {
+        return (
+            lines.into_iter().map(str::to_string).collect(),
+            BTreeMap::new(),
+            Vec::new(),
+        );
+    };
+    if end <= begin {
+        return (
+            lines.into_iter().map(str::to_string).collect(),
+            BTreeMap::new(),
+            Vec::new(),
+        );
+    }
+
+    let prefix = lines[..begin].iter().map(|l| l.to_string()).collect();
+    let suffix = lines[end + 1..].iter().map(|l| l.to_string()).collect();
+
+    let mut entries = BTreeMap::new();
+    for line in &lines[begin + 1..end] {
+        let mut fields = line.split_whitespace();
+        let (Some(ip), Some(name)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        entries.insert(name.to_string(), ip.to_string());
+    }
+
+    (prefix, entries, suffix)
+}
+
+/// Renders `prefix` + the managed block (sorted by name, for a stable
+/// diff between rewrites) + `suffix`, always ending in a trailing
+/// newline regardless of whether the original file had one.
+fn render(prefix: &[String], entries: &BTreeMap<String, String>, suffix: &[String]) -> String {
+    let mut out = String::new();
+    for line in prefix {
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out.push_str(BEGIN_MARKER);
+    out.push('\n');
+    for (name, ip) in entries {
+        out.push_str(ip);
+        out.push('\t');
+        out.push_str(name);
+        out.push('\n');
+    }
+    out.push_str(END_MARKER);
+    out.push('\n');
+
+    for line in suffix {
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Writes `contents` to `path` via a temp file in the same directory
+/// followed by a rename, so concurrent readers only ever see the file
+/// before or after the update, never a partial write.
+fn write_atomically(path: &Path, contents: &str) -> Result<(), HostsFileError> {
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let tmp_path = dir.join(format!(".{}.tmp.{}", file_name(path), std::process::id()));
+
+    let mut tmp_file = std::fs::File::create(&tmp_path)?;
+    tmp_file.write_all(contents.as_bytes())?;
+    tmp_file.sync_all()?;
+    drop(tmp_file);
+
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "hosts".to_string())
+}
+
+/// Blocks until an exclusive `flock` is held on `file`.
+fn flock_exclusive(file: &std::fs::File) -> Result<(), HostsFileError> {
+    let ret = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) };
+    if ret != 0 {
+        return Err(HostsFileError::Io(std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_and_removes_entries_preserving_surrounding_lines() {
+        let dir = std::env::temp_dir().join(format!("vordr-hosts-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hosts");
+        std::fs::write(&path, "127.0.0.1\tlocalhost\n::1\tlocalhost\n").unwrap();
+
+        let hosts = HostsFile::new(&path);
+        hosts
+            .add_entries("web", &["web-alias".to_string()], "10.89.0.2".parse().unwrap())
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("127.0.0.1\tlocalhost\n::1\tlocalhost\n"));
+        assert!(contents.contains(BEGIN_MARKER));
+        assert!(contents.contains("10.89.0.2\tweb"));
+        assert!(contents.contains("10.89.0.2\tweb-alias"));
+        assert!(contents.contains(END_MARKER));
+
+        hosts.remove_entries("web", &["web-alias".to_string()]).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("web"));
+        assert!(contents.contains("127.0.0.1\tlocalhost"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dedupes_stale_duplicate_entries_in_existing_managed_block() {
+        let dir = std::env::temp_dir().join(format!("vordr-hosts-test-dup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hosts");
+        std::fs::write(
+            &path,
+            format!(
+                "{}\n10.89.0.2\tweb\n10.89.0.3\tweb\n{}\n",
+                BEGIN_MARKER, END_MARKER
+            ),
+        )
+        .unwrap();
+
+        let hosts = HostsFile::new(&path);
+        hosts.add_entries("db", &[], "10.89.0.4".parse().unwrap()).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.matches("\tweb\n").count(), 1);
+        assert!(contents.contains("10.89.0.3\tweb"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
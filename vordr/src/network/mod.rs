@@ -1,6 +1,12 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
 //! Container networking
 
+pub mod dns;
+pub mod hosts;
 pub mod netavark;
+pub mod netlink;
+pub mod portforward;
+pub mod slirp4netns;
+pub mod sql_dns;
 
-pub use netavark::{NetworkConfig, NetworkManager, NetworkResult};
+pub use netavark::{NetworkBackend, NetworkBackendKind, NetworkConfig, NetworkManager, NetworkResult};
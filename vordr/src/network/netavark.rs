@@ -2,10 +2,16 @@
 //! Netavark integration for container networking
 
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::Arc;
 use thiserror::Error;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+use super::dns::DnsRegistry;
+use super::hosts::HostsFile;
 
 #[derive(Error, Debug)]
 pub enum NetworkError {
@@ -33,6 +39,12 @@ pub struct NetworkConfig {
     pub networks: Vec<NetworkAttachment>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port_mappings: Option<Vec<PortMapping>>,
+    /// Path to a hosts file bind-mounted into the container (typically its
+    /// `/etc/hosts`). When set, [`NetworkManager::setup`]/`teardown`
+    /// maintain a vordr-owned block in it via [`crate::network::hosts::HostsFile`]
+    /// as a lighter-weight alternative to the embedded DNS resolver.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts_path: Option<String>,
 }
 
 /// Network attachment specification
@@ -113,43 +125,43 @@ pub struct LeaseRange {
     pub end_ip: String,
 }
 
-/// Network manager using Netavark
-pub struct NetworkManager {
+/// Which mechanism actually configures a container's network namespace.
+/// Selected once, at [`NetworkManager`] construction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkBackendKind {
+    /// Shell out to the `netavark` binary (original behavior, still the
+    /// default - it's what's battle-tested against Podman's network
+    /// definitions).
+    Netavark,
+    /// Configure links/addresses/routes directly via netlink. No external
+    /// process dependency, but requires `CAP_NET_ADMIN` in this process.
+    Netlink,
+    /// Drive `slirp4netns` for unprivileged connectivity - no capabilities
+    /// required at all, which is what makes it the right default for
+    /// rootless invocations. See [`crate::network::slirp4netns`].
+    Rootless,
+}
+
+/// Wires up (or tears down) one container's networking inside its network
+/// namespace. [`crate::network::netlink::NetlinkBackend`] does this
+/// directly against the kernel; [`NetavarkBackend`] here shells out to the
+/// `netavark` binary, which is still the default - see
+/// [`NetworkBackendKind`].
+pub trait NetworkBackend: Send + Sync {
+    fn setup(&self, config: &NetworkConfig, netns_path: &str) -> Result<NetworkResult, NetworkError>;
+    fn teardown(&self, config: &NetworkConfig, netns_path: &str) -> Result<(), NetworkError>;
+}
+
+/// Original backend: pipes the `NetworkConfig` as JSON over stdin to the
+/// `netavark` binary and reads its JSON result back from stdout.
+struct NetavarkBackend {
     netavark_path: String,
     config_dir: String,
     run_dir: String,
 }
 
-impl NetworkManager {
-    /// Create a new network manager
-    pub fn new(config_dir: impl Into<String>, run_dir: impl Into<String>) -> Result<Self, NetworkError> {
-        let netavark_path = which::which("netavark")
-            .map_err(|_| NetworkError::NotFound)?
-            .to_string_lossy()
-            .into_owned();
-
-        Ok(Self {
-            netavark_path,
-            config_dir: config_dir.into(),
-            run_dir: run_dir.into(),
-        })
-    }
-
-    /// Create a new network manager with a custom netavark path
-    pub fn with_path(
-        netavark_path: impl Into<String>,
-        config_dir: impl Into<String>,
-        run_dir: impl Into<String>,
-    ) -> Self {
-        Self {
-            netavark_path: netavark_path.into(),
-            config_dir: config_dir.into(),
-            run_dir: run_dir.into(),
-        }
-    }
-
-    /// Set up networking for a container
-    pub fn setup(&self, config: &NetworkConfig, netns_path: &str) -> Result<NetworkResult, NetworkError> {
+impl NetworkBackend for NetavarkBackend {
+    fn setup(&self, config: &NetworkConfig, netns_path: &str) -> Result<NetworkResult, NetworkError> {
         info!(
             "Setting up network for container {} at {}",
             config.container_id, netns_path
@@ -185,8 +197,7 @@ impl NetworkManager {
         })
     }
 
-    /// Tear down networking for a container
-    pub fn teardown(&self, config: &NetworkConfig, netns_path: &str) -> Result<(), NetworkError> {
+    fn teardown(&self, config: &NetworkConfig, netns_path: &str) -> Result<(), NetworkError> {
         info!(
             "Tearing down network for container {}",
             config.container_id
@@ -217,6 +228,180 @@ impl NetworkManager {
 
         Ok(())
     }
+}
+
+/// Reads back a single network's definition from `<config_dir>/<name>.json`,
+/// the same file [`NetworkManager::create_network`] writes. Shared by
+/// [`DnsRegistry`], which needs a network's `dns_enabled` flag and
+/// gateway, and the netlink backend, which needs a network's
+/// subnet/gateway to actually configure an attachment.
+pub(crate) fn load_network_definition(config_dir: &str, name: &str) -> Result<NetworkDefinition, NetworkError> {
+    let network_file = Path::new(config_dir).join(format!("{}.json", name));
+    let content = std::fs::read_to_string(&network_file)
+        .map_err(|_| NetworkError::NetworkNotFound(name.to_string()))?;
+    serde_json::from_str(&content).map_err(NetworkError::from)
+}
+
+/// The address a backend assigned a newly set-up interface, parsed out of
+/// its first subnet's `ipnet` (e.g. `"10.89.0.2/24"`). Used to register
+/// that interface's container under its name/aliases with [`DnsRegistry`].
+fn first_address(interface: &InterfaceResult) -> Option<IpAddr> {
+    let subnet = interface.subnets.first()?;
+    let host = subnet.ipnet.split('/').next()?;
+    IpAddr::from_str(host).ok()
+}
+
+/// Network manager; delegates actual namespace configuration to whichever
+/// [`NetworkBackend`] it was constructed with.
+pub struct NetworkManager {
+    backend: Box<dyn NetworkBackend>,
+    kind: NetworkBackendKind,
+    config_dir: String,
+    run_dir: String,
+    dns: Arc<DnsRegistry>,
+}
+
+impl NetworkManager {
+    /// Create a new network manager using the `netavark` backend
+    pub fn new(config_dir: impl Into<String>, run_dir: impl Into<String>) -> Result<Self, NetworkError> {
+        let netavark_path = which::which("netavark")
+            .map_err(|_| NetworkError::NotFound)?
+            .to_string_lossy()
+            .into_owned();
+
+        Ok(Self::with_path(netavark_path, config_dir, run_dir))
+    }
+
+    /// Create a new network manager with a custom netavark path
+    pub fn with_path(
+        netavark_path: impl Into<String>,
+        config_dir: impl Into<String>,
+        run_dir: impl Into<String>,
+    ) -> Self {
+        let config_dir = config_dir.into();
+        let run_dir = run_dir.into();
+
+        Self {
+            backend: Box::new(NetavarkBackend {
+                netavark_path: netavark_path.into(),
+                config_dir: config_dir.clone(),
+                run_dir: run_dir.clone(),
+            }),
+            kind: NetworkBackendKind::Netavark,
+            config_dir,
+            run_dir,
+            dns: Arc::new(DnsRegistry::new()),
+        }
+    }
+
+    /// Create a network manager that configures networking directly via
+    /// netlink, with no `netavark` process dependency. Requires
+    /// `CAP_NET_ADMIN`.
+    pub fn new_netlink(config_dir: impl Into<String>, run_dir: impl Into<String>) -> Self {
+        let config_dir = config_dir.into();
+        let run_dir = run_dir.into();
+
+        Self {
+            backend: Box::new(crate::network::netlink::NetlinkBackend {
+                config_dir: config_dir.clone(),
+            }),
+            kind: NetworkBackendKind::Netlink,
+            config_dir,
+            run_dir,
+            dns: Arc::new(DnsRegistry::new()),
+        }
+    }
+
+    /// Create a network manager that provisions connectivity without any
+    /// elevated privileges, by driving `slirp4netns` against the
+    /// container's network namespace. The right default for rootless
+    /// invocations, where `NetworkBackendKind::Netavark` and `::Netlink`
+    /// both need capabilities this process won't have.
+    pub fn rootless(
+        slirp4netns_path: impl Into<String>,
+        config_dir: impl Into<String>,
+        run_dir: impl Into<String>,
+    ) -> Self {
+        let config_dir = config_dir.into();
+        let run_dir = run_dir.into();
+
+        Self {
+            backend: Box::new(crate::network::slirp4netns::Slirp4netnsBackend {
+                binary_path: slirp4netns_path.into(),
+                run_dir: run_dir.clone(),
+            }),
+            kind: NetworkBackendKind::Rootless,
+            config_dir,
+            run_dir,
+            dns: Arc::new(DnsRegistry::new()),
+        }
+    }
+
+    /// Which backend this manager was constructed with.
+    pub fn backend_kind(&self) -> NetworkBackendKind {
+        self.kind
+    }
+
+    /// Set up networking for a container, then register its assigned
+    /// address under its name/aliases on every DNS-enabled network it
+    /// joined.
+    pub fn setup(&self, config: &NetworkConfig, netns_path: &str) -> Result<NetworkResult, NetworkError> {
+        let result = self.backend.setup(config, netns_path)?;
+
+        for (attachment, interface) in config.networks.iter().zip(result.interfaces.iter()) {
+            let Some(ip) = first_address(interface) else {
+                continue;
+            };
+            let aliases = attachment.aliases.clone().unwrap_or_default();
+            if let Err(e) = self.dns.register(
+                &self.config_dir,
+                &attachment.network_name,
+                &config.container_name,
+                &aliases,
+                ip,
+            ) {
+                warn!(
+                    "failed to register DNS name for container {} on network {}: {}",
+                    config.container_id, attachment.network_name, e
+                );
+            }
+
+            if let Some(hosts_path) = &config.hosts_path {
+                if let Err(e) = HostsFile::new(hosts_path).add_entries(&config.container_name, &aliases, ip) {
+                    warn!(
+                        "failed to update hosts file {} for container {}: {}",
+                        hosts_path, config.container_id, e
+                    );
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Tear down networking for a container, deregistering its name from
+    /// every network it was attached to and from its hosts file, if one
+    /// was configured.
+    pub fn teardown(&self, config: &NetworkConfig, netns_path: &str) -> Result<(), NetworkError> {
+        self.backend.teardown(config, netns_path)?;
+
+        for attachment in &config.networks {
+            let aliases = attachment.aliases.clone().unwrap_or_default();
+            self.dns
+                .deregister(&attachment.network_name, &config.container_name, &aliases);
+
+            if let Some(hosts_path) = &config.hosts_path {
+                if let Err(e) = HostsFile::new(hosts_path).remove_entries(&config.container_name, &aliases) {
+                    warn!(
+                        "failed to update hosts file {} for container {}: {}",
+                        hosts_path, config.container_id, e
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
 
     /// Create a new network
     pub fn create_network(&self, definition: &NetworkDefinition) -> Result<(), NetworkError> {
@@ -273,6 +458,11 @@ impl NetworkManager {
         &self.config_dir
     }
 
+    /// Get the scratch/runtime directory passed at construction
+    pub fn run_dir(&self) -> &str {
+        &self.run_dir
+    }
+
     /// Create default bridge network if it doesn't exist
     pub fn ensure_default_network(&self) -> Result<(), NetworkError> {
         let default_file = Path::new(&self.config_dir).join("vordr.json");
@@ -328,6 +518,7 @@ mod tests {
                 host_port: 8080,
                 protocol: "tcp".to_string(),
             }]),
+            hosts_path: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
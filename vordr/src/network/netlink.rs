@@ -0,0 +1,389 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Netlink-based networking backend
+//!
+//! Configures a container's bridge networking directly against the kernel
+//! - no `netavark` process dependency. For each attachment: ensure the
+//! bridge link exists (idempotently), create a veth pair, move the
+//! container-side end into the target netns, then enter that namespace
+//! (via `setns`) to rename the link, assign it an address, bring it up,
+//! and install a default route. All of this requires `CAP_NET_ADMIN`.
+
+use std::net::Ipv4Addr;
+use std::os::unix::io::AsRawFd;
+use std::str::FromStr;
+
+use futures::stream::TryStreamExt;
+use rtnetlink::{new_connection, Handle};
+
+use super::netavark::{
+    load_network_definition, InterfaceResult, NetworkBackend, NetworkConfig, NetworkError,
+    NetworkResult, Subnet, SubnetResult,
+};
+
+/// Configures networking directly via netlink. See the module docs for the
+/// overall approach; [`load_network_definition`] is how it finds a given
+/// attachment's bridge name/subnet/gateway.
+pub struct NetlinkBackend {
+    pub(crate) config_dir: String,
+}
+
+impl NetworkBackend for NetlinkBackend {
+    fn setup(&self, config: &NetworkConfig, netns_path: &str) -> Result<NetworkResult, NetworkError> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
+            NetworkError::ExecutionFailed(format!("failed to start netlink runtime: {}", e))
+        })?;
+        runtime.block_on(self.setup_async(config, netns_path))
+    }
+
+    fn teardown(&self, config: &NetworkConfig, netns_path: &str) -> Result<(), NetworkError> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
+            NetworkError::ExecutionFailed(format!("failed to start netlink runtime: {}", e))
+        })?;
+        runtime.block_on(self.teardown_async(config, netns_path))
+    }
+}
+
+impl NetlinkBackend {
+    async fn setup_async(
+        &self,
+        config: &NetworkConfig,
+        netns_path: &str,
+    ) -> Result<NetworkResult, NetworkError> {
+        let (connection, handle, _) = new_connection()
+            .map_err(|e| NetworkError::ExecutionFailed(format!("netlink connection: {}", e)))?;
+        tokio::spawn(connection);
+
+        let mut interfaces = Vec::new();
+        for (idx, attachment) in config.networks.iter().enumerate() {
+            let definition = load_network_definition(&self.config_dir, &attachment.network_name)?;
+            let subnet = definition
+                .subnets
+                .as_ref()
+                .and_then(|subnets| subnets.first())
+                .ok_or_else(|| {
+                    NetworkError::InvalidConfig(format!(
+                        "network '{}' has no subnet configured",
+                        attachment.network_name
+                    ))
+                })?;
+            let bridge_name = definition
+                .network_interface
+                .clone()
+                .unwrap_or_else(|| format!("br-{}", &definition.id[..12.min(definition.id.len())]));
+
+            ensure_bridge(&handle, &bridge_name, subnet).await?;
+
+            let host_ifname = veth_name(&config.container_id, idx, 'h');
+            let peer_ifname = veth_name(&config.container_id, idx, 'p');
+
+            create_veth_pair(&handle, &host_ifname, &peer_ifname).await?;
+            attach_to_bridge(&handle, &host_ifname, &bridge_name).await?;
+            set_link_up_by_name(&handle, &host_ifname).await?;
+
+            // The netns fd must stay open for the whole move - the kernel
+            // resolves it only at the moment `setns_by_fd` executes.
+            let netns_file = std::fs::File::open(netns_path)?;
+            let peer_index = link_index_by_name(&handle, &peer_ifname).await?;
+            handle
+                .link()
+                .set(peer_index)
+                .setns_by_fd(netns_file.as_raw_fd())
+                .execute()
+                .await
+                .map_err(|e| NetworkError::ExecutionFailed(format!("move veth into netns: {}", e)))?;
+
+            let ip = allocate_address(subnet, &attachment.static_ips)?;
+            let prefix_len = subnet_prefix_len(&subnet.subnet)?;
+            let gateway = subnet.gateway.clone();
+
+            let interface = configure_in_namespace(
+                netns_path,
+                &peer_ifname,
+                &attachment.interface_name,
+                ip,
+                prefix_len,
+                gateway.as_deref(),
+            )?;
+
+            interfaces.push(interface);
+        }
+
+        Ok(NetworkResult { interfaces })
+    }
+
+    async fn teardown_async(&self, config: &NetworkConfig, netns_path: &str) -> Result<(), NetworkError> {
+        let _ = netns_path;
+        let (connection, handle, _) = new_connection()
+            .map_err(|e| NetworkError::ExecutionFailed(format!("netlink connection: {}", e)))?;
+        tokio::spawn(connection);
+
+        for idx in 0..config.networks.len() {
+            let host_ifname = veth_name(&config.container_id, idx, 'h');
+
+            // The container-side end and its addresses disappear with the
+            // netns itself; deleting the host-side end of the veth pair is
+            // enough to reclaim everything on this side.
+            if let Ok(index) = link_index_by_name(&handle, &host_ifname).await {
+                handle
+                    .link()
+                    .del(index)
+                    .execute()
+                    .await
+                    .map_err(|e| NetworkError::ExecutionFailed(format!("delete veth: {}", e)))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Deterministic veth endpoint name for the `idx`-th network attachment of
+/// `container_id`, kept within Linux's 15-character `IFNAMSIZ` limit. `side`
+/// distinguishes the host end (`'h'`) from the peer end (`'p'`) so both
+/// sides of a pair, and every attachment's pair, get distinct names.
+fn veth_name(container_id: &str, idx: usize, side: char) -> String {
+    let short = &container_id[..8.min(container_id.len())];
+    format!("v{}{}{}", short, idx, side)
+}
+
+/// Ensures `name` exists as a bridge with `subnet`'s gateway address
+/// assigned, creating it if missing. Bridge creation is idempotent: an
+/// `EEXIST` from a concurrent or prior setup is not an error.
+async fn ensure_bridge(handle: &Handle, name: &str, subnet: &Subnet) -> Result<(), NetworkError> {
+    match handle.link().add().bridge(name.to_string()).execute().await {
+        Ok(()) => {}
+        Err(e) if is_eexist(&e) => {}
+        Err(e) => return Err(NetworkError::ExecutionFailed(format!("create bridge {}: {}", name, e))),
+    }
+
+    let index = link_index_by_name(handle, name).await?;
+    set_link_up(handle, index).await?;
+
+    if let Some(gateway) = &subnet.gateway {
+        let prefix_len = subnet_prefix_len(&subnet.subnet)?;
+        let addr = Ipv4Addr::from_str(gateway)
+            .map_err(|e| NetworkError::InvalidConfig(format!("invalid gateway {}: {}", gateway, e)))?;
+        match handle
+            .address()
+            .add(index, std::net::IpAddr::V4(addr), prefix_len)
+            .execute()
+            .await
+        {
+            Ok(()) => {}
+            Err(e) if is_eexist(&e) => {}
+            Err(e) => {
+                return Err(NetworkError::ExecutionFailed(format!(
+                    "assign gateway address to {}: {}",
+                    name, e
+                )))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn create_veth_pair(handle: &Handle, host: &str, peer: &str) -> Result<(), NetworkError> {
+    handle
+        .link()
+        .add()
+        .veth(host.to_string(), peer.to_string())
+        .execute()
+        .await
+        .map_err(|e| NetworkError::ExecutionFailed(format!("create veth pair {}/{}: {}", host, peer, e)))
+}
+
+async fn attach_to_bridge(handle: &Handle, ifname: &str, bridge_name: &str) -> Result<(), NetworkError> {
+    let if_index = link_index_by_name(handle, ifname).await?;
+    let bridge_index = link_index_by_name(handle, bridge_name).await?;
+
+    handle
+        .link()
+        .set(if_index)
+        .master(bridge_index)
+        .execute()
+        .await
+        .map_err(|e| NetworkError::ExecutionFailed(format!("attach {} to bridge {}: {}", ifname, bridge_name, e)))
+}
+
+async fn set_link_up_by_name(handle: &Handle, ifname: &str) -> Result<(), NetworkError> {
+    let index = link_index_by_name(handle, ifname).await?;
+    set_link_up(handle, index).await
+}
+
+async fn set_link_up(handle: &Handle, index: u32) -> Result<(), NetworkError> {
+    handle
+        .link()
+        .set(index)
+        .up()
+        .execute()
+        .await
+        .map_err(|e| NetworkError::ExecutionFailed(format!("bring up link {}: {}", index, e)))
+}
+
+async fn link_index_by_name(handle: &Handle, ifname: &str) -> Result<u32, NetworkError> {
+    handle
+        .link()
+        .get()
+        .match_name(ifname.to_string())
+        .execute()
+        .try_next()
+        .await
+        .map_err(|e| NetworkError::ExecutionFailed(format!("look up link {}: {}", ifname, e)))?
+        .map(|msg| msg.header.index)
+        .ok_or_else(|| NetworkError::ExecutionFailed(format!("link {} not found", ifname)))
+}
+
+/// `true` if a netlink operation failed because the object already exists
+/// - the signal that makes bridge creation idempotent.
+fn is_eexist(err: &rtnetlink::Error) -> bool {
+    matches!(err, rtnetlink::Error::NetlinkError(msg) if msg.to_string().contains("File exists"))
+}
+
+/// Picks the address a container's interface should get: the first
+/// `static_ips` entry if the attachment requested one, otherwise the first
+/// address in the subnet's `lease_range` (falling back to the address just
+/// after the gateway if no lease range is configured).
+fn allocate_address(subnet: &Subnet, static_ips: &Option<Vec<String>>) -> Result<Ipv4Addr, NetworkError> {
+    if let Some(ips) = static_ips {
+        if let Some(first) = ips.first() {
+            let host = first.split('/').next().unwrap_or(first);
+            return Ipv4Addr::from_str(host)
+                .map_err(|e| NetworkError::InvalidConfig(format!("invalid static IP {}: {}", first, e)));
+        }
+    }
+
+    if let Some(lease_range) = &subnet.lease_range {
+        return Ipv4Addr::from_str(&lease_range.start_ip)
+            .map_err(|e| NetworkError::InvalidConfig(format!("invalid lease_range start: {}", e)));
+    }
+
+    let gateway = subnet
+        .gateway
+        .as_ref()
+        .ok_or_else(|| NetworkError::InvalidConfig("subnet has neither a lease_range nor a gateway to derive an address from".to_string()))?;
+    let gateway_addr = Ipv4Addr::from_str(gateway)
+        .map_err(|e| NetworkError::InvalidConfig(format!("invalid gateway {}: {}", gateway, e)))?;
+    Ok(Ipv4Addr::from(u32::from(gateway_addr) + 1))
+}
+
+fn subnet_prefix_len(cidr: &str) -> Result<u8, NetworkError> {
+    cidr.split_once('/')
+        .and_then(|(_, len)| len.parse().ok())
+        .ok_or_else(|| NetworkError::InvalidConfig(format!("invalid subnet CIDR: {}", cidr)))
+}
+
+/// Enters the target network namespace to finish configuring the moved
+/// veth end - rename, address, up, default route - then returns to this
+/// process's original namespace. Link renaming and addressing only work
+/// from inside the namespace that owns the link, since the link no longer
+/// exists in this process's (host) netns index space once moved.
+fn configure_in_namespace(
+    netns_path: &str,
+    temp_ifname: &str,
+    final_ifname: &str,
+    ip: Ipv4Addr,
+    prefix_len: u8,
+    gateway: Option<&str>,
+) -> Result<InterfaceResult, NetworkError> {
+    let original_ns = std::fs::File::open("/proc/self/ns/net")?;
+    let target_ns = std::fs::File::open(netns_path)?;
+
+    let result = (|| -> Result<InterfaceResult, NetworkError> {
+        enter_namespace(&target_ns)?;
+
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
+            NetworkError::ExecutionFailed(format!("failed to start in-namespace runtime: {}", e))
+        })?;
+        runtime.block_on(async {
+            let (connection, handle, _) = new_connection().map_err(|e| {
+                NetworkError::ExecutionFailed(format!("in-namespace netlink connection: {}", e))
+            })?;
+            tokio::spawn(connection);
+
+            let index = link_index_by_name(&handle, temp_ifname).await?;
+
+            handle
+                .link()
+                .set(index)
+                .name(final_ifname.to_string())
+                .execute()
+                .await
+                .map_err(|e| NetworkError::ExecutionFailed(format!("rename interface: {}", e)))?;
+
+            handle
+                .address()
+                .add(index, std::net::IpAddr::V4(ip), prefix_len)
+                .execute()
+                .await
+                .map_err(|e| NetworkError::ExecutionFailed(format!("assign address: {}", e)))?;
+
+            set_link_up(&handle, index).await?;
+
+            if let Some(gateway) = gateway {
+                let gateway_addr = Ipv4Addr::from_str(gateway).map_err(|e| {
+                    NetworkError::InvalidConfig(format!("invalid gateway {}: {}", gateway, e))
+                })?;
+                handle
+                    .route()
+                    .add()
+                    .v4()
+                    .gateway(gateway_addr)
+                    .execute()
+                    .await
+                    .map_err(|e| NetworkError::ExecutionFailed(format!("install default route: {}", e)))?;
+            }
+
+            let mac_address = handle
+                .link()
+                .get()
+                .match_name(final_ifname.to_string())
+                .execute()
+                .try_next()
+                .await
+                .map_err(|e| NetworkError::ExecutionFailed(format!("read back interface: {}", e)))?
+                .and_then(|msg| mac_address_of(&msg))
+                .unwrap_or_default();
+
+            Ok(InterfaceResult {
+                name: final_ifname.to_string(),
+                mac_address,
+                subnets: vec![SubnetResult {
+                    ipnet: format!("{}/{}", ip, prefix_len),
+                    gateway: gateway.map(str::to_string),
+                }],
+            })
+        })
+    })();
+
+    enter_namespace(&original_ns)?;
+    result
+}
+
+/// Switches this thread into the network namespace held open by `ns_file`.
+/// Requires `CAP_SYS_ADMIN` in addition to `CAP_NET_ADMIN`.
+fn enter_namespace(ns_file: &std::fs::File) -> Result<(), NetworkError> {
+    let ret = unsafe { libc::setns(ns_file.as_raw_fd(), libc::CLONE_NEWNET) };
+    if ret != 0 {
+        return Err(NetworkError::ExecutionFailed(format!(
+            "setns failed: {}",
+            std::io::Error::last_os_error()
+        )));
+    }
+    Ok(())
+}
+
+fn mac_address_of(msg: &rtnetlink::packet_route::link::LinkMessage) -> Option<String> {
+    use rtnetlink::packet_route::link::LinkAttribute;
+
+    msg.attributes.iter().find_map(|nla| match nla {
+        LinkAttribute::Address(bytes) => Some(
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(":"),
+        ),
+        _ => None,
+    })
+}
@@ -0,0 +1,45 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Installs/tears down the host-side forwarding rules for published ports
+//!
+//! A real backend (e.g. the `slirp4netns` `add_hostfwd` control call in
+//! [`super::slirp4netns`], or a DNAT rule into a bridge-attached netns)
+//! needs a container to actually have a network namespace set up before
+//! there is anything to forward into. `run::execute` doesn't attach one
+//! yet - container start is still simulated - so [`install`]/[`teardown`]
+//! are the seam a real backend will hang off of, not a working forwarder:
+//! today they only log what they would have done. Every other `-p` step
+//! (parsing, privileged-port rejection, host-port collision detection,
+//! and persisting the normalized mappings for `ps`/`inspect`) is fully
+//! implemented in [`crate::cli::ports`]; this is the one piece with no
+//! netns to act on in this build.
+
+use tracing::info;
+
+use crate::cli::ports::PortMapping;
+
+/// Would install one DNAT/portmap rule per mapping into the container's
+/// netns. No-op until `run::execute` actually attaches one.
+pub fn install(mappings: &[PortMapping]) {
+    for mapping in mappings {
+        info!(
+            "port forwarding not yet wired to a real netns backend: would forward {}:{}/{} -> {}",
+            mapping.host_ip(),
+            mapping.host_port,
+            mapping.protocol,
+            mapping.container_port
+        );
+    }
+}
+
+/// Would remove the forwarding rules [`install`] set up. No-op for the
+/// same reason `install` is.
+pub fn teardown(mappings: &[PortMapping]) {
+    for mapping in mappings {
+        info!(
+            "port forwarding not yet wired to a real netns backend: would stop forwarding {}:{}/{}",
+            mapping.host_ip(),
+            mapping.host_port,
+            mapping.protocol
+        );
+    }
+}
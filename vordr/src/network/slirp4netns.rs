@@ -0,0 +1,252 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Rootless networking via `slirp4netns`
+//!
+//! Gives a container connectivity without `CAP_NET_ADMIN` or any other
+//! elevated privilege: `slirp4netns` is spawned as an unprivileged
+//! subprocess bound to the container's network namespace and user-mode
+//! NATs traffic through a tap device it creates inside that namespace.
+//! Port mappings are installed afterwards by issuing `add_hostfwd`
+//! commands over the helper's own JSON control API, reached via a unix
+//! socket. This is what lets [`crate::network::netavark::NetworkManager::rootless`]
+//! work without ever touching host netlink/iptables state, mirroring
+//! Vordr's daemonless design goal for non-root invocations.
+
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info};
+
+use super::netavark::{
+    InterfaceResult, NetworkBackend, NetworkConfig, NetworkError, NetworkResult, PortMapping,
+    SubnetResult,
+};
+
+/// Address slirp4netns assigns to the container side of its tap device,
+/// and the gateway (its own user-mode router) it NATs everything through.
+/// Both are fixed by slirp4netns itself, not configurable here.
+const SLIRP_GUEST_ADDR: &str = "10.0.2.100";
+const SLIRP_GATEWAY: &str = "10.0.2.2";
+const SLIRP_PREFIX_LEN: u8 = 24;
+const TAP_IFNAME: &str = "tap0";
+
+/// How long to wait for slirp4netns's api-socket to accept connections,
+/// once its ready-fd has fired, before giving up. The ready-fd wait itself
+/// has no timeout.
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Rootless backend: drives `slirp4netns` instead of touching host
+/// netlink/iptables state directly, so it never needs `CAP_NET_ADMIN`.
+/// See the module docs for the overall approach.
+pub struct Slirp4netnsBackend {
+    pub(crate) binary_path: String,
+    pub(crate) run_dir: String,
+}
+
+impl NetworkBackend for Slirp4netnsBackend {
+    fn setup(&self, config: &NetworkConfig, netns_path: &str) -> Result<NetworkResult, NetworkError> {
+        info!(
+            "Setting up rootless network for container {} at {}",
+            config.container_id, netns_path
+        );
+
+        std::fs::create_dir_all(&self.run_dir)?;
+
+        let api_socket = self.api_socket_path(&config.container_id);
+        let _ = std::fs::remove_file(&api_socket);
+
+        let (ready_reader, ready_writer) = ready_pipe()?;
+        let ready_fd = ready_writer.as_raw_fd();
+
+        let mut command = Command::new(&self.binary_path);
+        command
+            .args([
+                "--configure",
+                "--mtu=65520",
+                "--disable-host-loopback",
+                "--netns-type=path",
+                netns_path,
+                TAP_IFNAME,
+                "--api-socket",
+                &api_socket.to_string_lossy(),
+                "--ready-fd",
+                &ready_fd.to_string(),
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Clear FD_CLOEXEC on the ready pipe's write end right before exec,
+        // so slirp4netns inherits it whatever flags the pipe was opened
+        // with, instead of having it vanish at exec time.
+        unsafe {
+            command.pre_exec(move || clear_cloexec(ready_fd));
+        }
+
+        let child = command.spawn()?;
+        drop(ready_writer);
+
+        wait_for_ready(ready_reader, &config.container_id)?;
+        self.write_pid_file(&config.container_id, &child)?;
+        wait_for_api_socket(&api_socket)?;
+
+        for mapping in config.port_mappings.iter().flatten() {
+            add_hostfwd(&api_socket, mapping)?;
+        }
+
+        // slirp4netns runs for the container's whole lifetime; the pid
+        // file written above is all `teardown` needs to find it again.
+        drop(child);
+
+        Ok(NetworkResult {
+            interfaces: vec![InterfaceResult {
+                name: TAP_IFNAME.to_string(),
+                mac_address: String::new(),
+                subnets: vec![SubnetResult {
+                    ipnet: format!("{}/{}", SLIRP_GUEST_ADDR, SLIRP_PREFIX_LEN),
+                    gateway: Some(SLIRP_GATEWAY.to_string()),
+                }],
+            }],
+        })
+    }
+
+    fn teardown(&self, config: &NetworkConfig, _netns_path: &str) -> Result<(), NetworkError> {
+        info!(
+            "Tearing down rootless network for container {}",
+            config.container_id
+        );
+
+        let pid_file = self.pid_file_path(&config.container_id);
+        if let Ok(contents) = std::fs::read_to_string(&pid_file) {
+            if let Ok(pid) = contents.trim().parse::<i32>() {
+                // slirp4netns has no other shutdown path; killing it tears
+                // down the tap device and NAT state with it.
+                unsafe {
+                    libc::kill(pid, libc::SIGTERM);
+                }
+            }
+            let _ = std::fs::remove_file(&pid_file);
+        }
+
+        let _ = std::fs::remove_file(self.api_socket_path(&config.container_id));
+
+        Ok(())
+    }
+}
+
+impl Slirp4netnsBackend {
+    fn pid_file_path(&self, container_id: &str) -> PathBuf {
+        Path::new(&self.run_dir).join(format!("slirp4netns-{}.pid", container_id))
+    }
+
+    fn api_socket_path(&self, container_id: &str) -> PathBuf {
+        Path::new(&self.run_dir).join(format!("slirp4netns-{}.sock", container_id))
+    }
+
+    fn write_pid_file(&self, container_id: &str, child: &Child) -> Result<(), NetworkError> {
+        std::fs::write(self.pid_file_path(container_id), child.id().to_string())?;
+        Ok(())
+    }
+}
+
+/// Opens a pipe whose write end we hand to slirp4netns as `--ready-fd`; it
+/// writes a byte (and we read one back) the moment its tap device and NAT
+/// rules are up.
+fn ready_pipe() -> Result<(std::fs::File, std::fs::File), NetworkError> {
+    let mut fds = [0i32; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(NetworkError::Io(std::io::Error::last_os_error()));
+    }
+    let reader = unsafe { std::fs::File::from_raw_fd(fds[0]) };
+    let writer = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+    Ok((reader, writer))
+}
+
+/// Clears `FD_CLOEXEC` on `fd`. Safe to call only between `fork` and
+/// `exec`, which is exactly where `Command::pre_exec` runs it.
+fn clear_cloexec(fd: i32) -> std::io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    if unsafe { libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Blocks until slirp4netns signals readiness over its ready-fd, or until
+/// it exits/crashes before doing so (the pipe closes, and the read
+/// returns `Ok(0)` either way - both are treated as "ready enough to
+/// proceed", since a crashed helper will also fail the api-socket
+/// connection right after).
+fn wait_for_ready(mut reader: std::fs::File, container_id: &str) -> Result<(), NetworkError> {
+    let mut buf = [0u8; 16];
+    reader.read(&mut buf).map_err(NetworkError::Io)?;
+    debug!("slirp4netns ready for container {}", container_id);
+    Ok(())
+}
+
+/// Polls for the api-socket to accept connections. slirp4netns creates it
+/// as part of becoming ready, but there's no guarantee it's listening by
+/// the instant the ready-fd fires.
+fn wait_for_api_socket(path: &Path) -> Result<(), NetworkError> {
+    let deadline = Instant::now() + READY_TIMEOUT;
+    loop {
+        if UnixStream::connect(path).is_ok() {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(NetworkError::ExecutionFailed(format!(
+                "timed out waiting for slirp4netns api-socket at {}",
+                path.display()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Issues a single `add_hostfwd` command over slirp4netns' JSON control
+/// API to expose one [`PortMapping`].
+fn add_hostfwd(api_socket: &Path, mapping: &PortMapping) -> Result<(), NetworkError> {
+    let mut stream = UnixStream::connect(api_socket).map_err(|e| {
+        NetworkError::ExecutionFailed(format!(
+            "connect to slirp4netns api-socket {}: {}",
+            api_socket.display(),
+            e
+        ))
+    })?;
+
+    let request = serde_json::json!({
+        "execute": "add_hostfwd",
+        "arguments": {
+            "proto": mapping.protocol,
+            "host_addr": mapping.host_ip.clone().unwrap_or_else(|| "0.0.0.0".to_string()),
+            "host_port": mapping.host_port,
+            "guest_addr": SLIRP_GUEST_ADDR,
+            "guest_port": mapping.container_port,
+        }
+    });
+
+    stream.write_all(format!("{}\n", request).as_bytes())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    debug!("slirp4netns add_hostfwd response: {}", response);
+
+    let parsed: serde_json::Value = serde_json::from_str(response.trim())
+        .map_err(|e| NetworkError::ExecutionFailed(format!("invalid slirp4netns response: {}", e)))?;
+    if parsed.get("error").is_some() {
+        return Err(NetworkError::ExecutionFailed(format!(
+            "slirp4netns add_hostfwd for {}:{} failed: {}",
+            mapping.container_port, mapping.host_port, parsed
+        )));
+    }
+
+    Ok(())
+}
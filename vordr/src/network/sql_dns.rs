@@ -0,0 +1,171 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! DNS resolution for the SQLite-backed network model (`vordr network
+//! create`/`connect`/`disconnect`), as distinct from [`super::dns`]'s
+//! resolver for the netavark JSON-config network model.
+//!
+//! [`super::dns::DnsRegistry`] is push-based: something calls `register`/
+//! `deregister` as containers join and leave a network. That doesn't fit
+//! here, because `connect_container_network`/`disconnect_container_network`
+//! run inside one-shot `vordr network connect`/`disconnect` invocations that
+//! exit immediately - there's no in-process registry for them to push into.
+//! Instead each query re-reads [`StateManager::network_dns_records`] fresh,
+//! so resolution is never more stale than the last `connect`/`disconnect`
+//! regardless of which process ran it.
+
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::str::FromStr;
+
+use hickory_proto::op::MessageType;
+use hickory_proto::rr::rdata::A;
+use hickory_proto::rr::{RData, Record, RecordType};
+use hickory_server::authority::MessageResponseBuilder;
+use hickory_server::server::{Request, RequestHandler, ResponseHandler, ResponseInfo, ServerFuture};
+use thiserror::Error;
+use tokio::net::UdpSocket;
+use tracing::{debug, warn};
+
+use crate::engine::{NetworkInfo, StateManager};
+
+use super::dns::{forward_upstream, send_message};
+
+const DNS_PORT: u16 = 53;
+const RECORD_TTL: u32 = 60;
+
+#[derive(Error, Debug)]
+pub enum SqlDnsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("network {0} has no gateway to bind DNS to")]
+    NoGateway(String),
+    #[error("invalid gateway address for network {0}: {1}")]
+    InvalidGateway(String, String),
+}
+
+/// Starts one resolver thread per network in `networks` that has a gateway
+/// address, bound to that address on port 53. Intended to run once, at
+/// `vordr manager` startup; networks created after that won't get a
+/// resolver until the manager is restarted.
+pub fn start_all(db_path: &str, networks: &[NetworkInfo]) {
+    for network in networks {
+        if let Err(e) = start_for_network(db_path, network) {
+            warn!("failed to start DNS resolver for network {}: {}", network.name, e);
+        }
+    }
+}
+
+fn start_for_network(db_path: &str, network: &NetworkInfo) -> Result<(), SqlDnsError> {
+    let gateway = network
+        .gateway
+        .as_deref()
+        .ok_or_else(|| SqlDnsError::NoGateway(network.name.clone()))?;
+    let bind_ip = IpAddr::from_str(gateway)
+        .map_err(|e| SqlDnsError::InvalidGateway(network.name.clone(), e.to_string()))?;
+    let bind_addr = SocketAddr::new(bind_ip, DNS_PORT);
+
+    let network_id = network.id.clone();
+    let network_name = network.name.clone();
+    let db_path = db_path.to_string();
+
+    std::thread::Builder::new()
+        .name(format!("dns-sql-{}", network_name))
+        .spawn(move || {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    warn!("failed to start SQL DNS resolver runtime for {}: {}", network_name, e);
+                    return;
+                }
+            };
+
+            runtime.block_on(async move {
+                let socket = match UdpSocket::bind(bind_addr).await {
+                    Ok(socket) => socket,
+                    Err(e) => {
+                        warn!("failed to bind SQL DNS resolver for {} to {}: {}", network_name, bind_addr, e);
+                        return;
+                    }
+                };
+
+                debug!("SQL-backed DNS resolver for network {} listening on {}", network_name, bind_addr);
+
+                let mut server = ServerFuture::new(SqlDnsHandler { db_path, network_id });
+                server.register_socket(socket);
+                if let Err(e) = server.block_until_done().await {
+                    warn!("SQL DNS resolver for network {} exited: {}", network_name, e);
+                }
+            });
+        })
+        .map_err(SqlDnsError::Io)?;
+
+    Ok(())
+}
+
+/// Answers A queries for names registered on one network by reading
+/// [`StateManager::network_dns_records`] straight from disk on every
+/// request, falling back to the usual upstream forward on a miss.
+struct SqlDnsHandler {
+    db_path: String,
+    network_id: String,
+}
+
+impl SqlDnsHandler {
+    fn lookup(&self, name: &str) -> Vec<IpAddr> {
+        let Ok(state) = StateManager::open(Path::new(&self.db_path)) else {
+            return Vec::new();
+        };
+        let Ok(records) = state.network_dns_records(&self.network_id) else {
+            return Vec::new();
+        };
+
+        records
+            .into_iter()
+            .filter(|record| {
+                record.container_name.eq_ignore_ascii_case(name)
+                    || record.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+            })
+            .filter_map(|record| IpAddr::from_str(&record.ip_address).ok())
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestHandler for SqlDnsHandler {
+    async fn handle_request<R: ResponseHandler>(&self, request: &Request, mut response_handle: R) -> ResponseInfo {
+        let query = request.query();
+        let name = query.name().to_string();
+        let lookup_name = name.trim_end_matches('.');
+        let ips = self.lookup(lookup_name);
+
+        if ips.is_empty() {
+            return match forward_upstream(request).await {
+                Ok(response) => send_message(request, &mut response_handle, response, false).await,
+                Err(e) => {
+                    warn!("upstream DNS forward for {} failed: {}", name, e);
+                    *request.header()
+                }
+            };
+        }
+
+        let records: Vec<Record> = ips
+            .iter()
+            .filter_map(|ip| match ip {
+                IpAddr::V4(v4) if query.query_type() == RecordType::A => {
+                    Some(Record::from_rdata(query.name().clone(), RECORD_TTL, RData::A(A(*v4))))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let builder = MessageResponseBuilder::from_message_request(request);
+        let mut header = *request.header();
+        header.set_message_type(MessageType::Response);
+        header.set_authoritative(true);
+        let response = builder.build(header, records.iter(), None, None, None);
+
+        response_handle.send_response(response).await.unwrap_or_else(|e| {
+            warn!("failed to send SQL DNS response for {}: {}", name, e);
+            *request.header()
+        })
+    }
+}
@@ -0,0 +1,304 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Pluggable credential storage for registry authentication
+//!
+//! [`CredentialProvider`] abstracts over where `login`/`logout` persist
+//! secrets. The plaintext `auth.json` remains the default (handled
+//! directly by `crate::cli::auth`). [`KeyringProvider`] stores secrets in
+//! the host OS's native secret store instead - GNOME/KDE Secret Service
+//! on Linux, Keychain on macOS, Credential Manager on Windows - and
+//! [`ProcessProvider`] delegates to an external helper binary, speaking a
+//! small JSON-line protocol over its stdin/stdout: one request object in,
+//! one response object out, each a single line of JSON.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CredentialError {
+    #[error("credential helper not found: {0}")]
+    HelperNotFound(String),
+    #[error("credential helper execution failed: {0}")]
+    ExecutionFailed(String),
+    #[error("credential helper reported an error: {0}")]
+    HelperError(String),
+    #[error("unexpected response from credential helper")]
+    UnexpectedResponse,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// One secret a [`CredentialProvider`] stores, returns, or erases.
+#[derive(Debug, Clone)]
+pub struct Credential {
+    pub username: String,
+    pub secret: String,
+}
+
+/// Abstracts over where registry credentials actually live, so
+/// `login`/`logout` don't need to know whether they're writing
+/// `auth.json` or talking to an external helper.
+pub trait CredentialProvider: Send + Sync {
+    fn get(&self, registry: &str) -> Result<Option<Credential>, CredentialError>;
+    fn store(&self, registry: &str, credential: &Credential) -> Result<(), CredentialError>;
+    fn erase(&self, registry: &str) -> Result<(), CredentialError>;
+}
+
+const KEYRING_SERVICE: &str = "vordr";
+
+/// One entry's worth of secret material as stored in the OS keyring. The
+/// native stores expose only a single opaque password string per entry,
+/// so the username travels alongside the secret as a small JSON blob
+/// rather than as a separate keyring field.
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyringSecret {
+    username: String,
+    secret: String,
+}
+
+/// Stores credentials in the host OS's native secret store via the
+/// `keyring` crate, one entry per registry keyed by the already
+/// `normalize_registry`-d URL.
+#[derive(Default)]
+pub struct KeyringProvider;
+
+impl KeyringProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn entry(registry: &str) -> Result<keyring::Entry, CredentialError> {
+        keyring::Entry::new(KEYRING_SERVICE, registry).map_err(|e| CredentialError::ExecutionFailed(e.to_string()))
+    }
+}
+
+impl CredentialProvider for KeyringProvider {
+    fn get(&self, registry: &str) -> Result<Option<Credential>, CredentialError> {
+        let entry = Self::entry(registry)?;
+        match entry.get_password() {
+            Ok(raw) => {
+                let parsed: KeyringSecret = serde_json::from_str(&raw)?;
+                Ok(Some(Credential {
+                    username: parsed.username,
+                    secret: parsed.secret,
+                }))
+            }
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(CredentialError::ExecutionFailed(e.to_string())),
+        }
+    }
+
+    fn store(&self, registry: &str, credential: &Credential) -> Result<(), CredentialError> {
+        let entry = Self::entry(registry)?;
+        let payload = serde_json::to_string(&KeyringSecret {
+            username: credential.username.clone(),
+            secret: credential.secret.clone(),
+        })?;
+        entry
+            .set_password(&payload)
+            .map_err(|e| CredentialError::ExecutionFailed(e.to_string()))
+    }
+
+    fn erase(&self, registry: &str) -> Result<(), CredentialError> {
+        let entry = Self::entry(registry)?;
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(CredentialError::ExecutionFailed(e.to_string())),
+        }
+    }
+}
+
+/// The name this platform's native keyring backend reports as its
+/// `credential_store`/`METHOD` value - what `auto` resolves to, and the
+/// only one of `secret-service`/`keychain`/`wincred` that's actually
+/// usable here.
+pub fn native_backend_name() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "secret-service"
+    } else if cfg!(target_os = "macos") {
+        "keychain"
+    } else if cfg!(target_os = "windows") {
+        "wincred"
+    } else {
+        "keyring"
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HelperRequest<'a> {
+    v: u8,
+    registry: &'a str,
+    operation: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<&'a str>,
+}
+
+/// Internally tagged on `kind`, so a successful `get`/`store`/`erase`
+/// echoes back the operation it completed and an `error` response can
+/// arrive no matter which operation was requested.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum HelperResponse {
+    Get {
+        username: Option<String>,
+        secret: Option<String>,
+        #[serde(default)]
+        #[allow(dead_code)]
+        cache: Option<bool>,
+    },
+    Store,
+    Erase,
+    Error {
+        message: String,
+    },
+}
+
+/// Drives an external credential-helper process. The helper path is
+/// resolved once, at construction, by [`resolve_helper_path`].
+pub struct ProcessProvider {
+    helper_path: PathBuf,
+}
+
+impl ProcessProvider {
+    /// Resolves `name_or_path` to a helper binary - see
+    /// [`resolve_helper_path`] for how short names and bundled helpers
+    /// are handled - and fails immediately if it can't be found, rather
+    /// than deferring that to the first `get`/`store`/`erase` call.
+    pub fn new(name_or_path: &str) -> Result<Self, CredentialError> {
+        Ok(Self {
+            helper_path: resolve_helper_path(name_or_path)?,
+        })
+    }
+
+    fn call(
+        &self,
+        registry: &str,
+        operation: &str,
+        username: Option<&str>,
+        secret: Option<&str>,
+    ) -> Result<HelperResponse, CredentialError> {
+        let request = HelperRequest {
+            v: 1,
+            registry,
+            operation,
+            username,
+            secret,
+        };
+        let payload = serde_json::to_string(&request)?;
+
+        let mut child = Command::new(&self.helper_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| CredentialError::ExecutionFailed(format!("{}: {}", self.helper_path.display(), e)))?;
+
+        if let Some(stdin) = child.stdin.as_mut() {
+            writeln!(stdin, "{}", payload)?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CredentialError::ExecutionFailed(stderr.into_owned()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.lines().next().unwrap_or_default();
+        let response: HelperResponse = serde_json::from_str(line)?;
+
+        if let HelperResponse::Error { message } = response {
+            return Err(CredentialError::HelperError(message));
+        }
+
+        Ok(response)
+    }
+}
+
+impl CredentialProvider for ProcessProvider {
+    fn get(&self, registry: &str) -> Result<Option<Credential>, CredentialError> {
+        match self.call(registry, "get", None, None)? {
+            HelperResponse::Get { username, secret, .. } => match (username, secret) {
+                (Some(username), Some(secret)) => Ok(Some(Credential { username, secret })),
+                _ => Ok(None),
+            },
+            _ => Err(CredentialError::UnexpectedResponse),
+        }
+    }
+
+    fn store(&self, registry: &str, credential: &Credential) -> Result<(), CredentialError> {
+        match self.call(registry, "store", Some(&credential.username), Some(&credential.secret))? {
+            HelperResponse::Store => Ok(()),
+            _ => Err(CredentialError::UnexpectedResponse),
+        }
+    }
+
+    fn erase(&self, registry: &str) -> Result<(), CredentialError> {
+        match self.call(registry, "erase", None, None)? {
+            HelperResponse::Erase => Ok(()),
+            _ => Err(CredentialError::UnexpectedResponse),
+        }
+    }
+}
+
+/// Resolves a helper reference to an executable path:
+/// - a path containing a separator (or that's absolute) is used as-is
+/// - `svalinn:<name>` is looked up as `svalinn-credential-<name>` in the
+///   bundled helper directory next to the current executable
+/// - anything else is looked up on `PATH`, following the same
+///   `docker-credential-<name>` convention external helpers use
+fn resolve_helper_path(name_or_path: &str) -> Result<PathBuf, CredentialError> {
+    if let Some(short_name) = name_or_path.strip_prefix("svalinn:") {
+        let exe_dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .ok_or_else(|| CredentialError::HelperNotFound(name_or_path.to_string()))?;
+        let helper = exe_dir
+            .join("credential-helpers")
+            .join(format!("svalinn-credential-{}", short_name));
+        return if helper.exists() {
+            Ok(helper)
+        } else {
+            Err(CredentialError::HelperNotFound(helper.display().to_string()))
+        };
+    }
+
+    let path = Path::new(name_or_path);
+    if path.is_absolute() || path.components().count() > 1 {
+        return Ok(path.to_path_buf());
+    }
+
+    which::which(format!("docker-credential-{}", name_or_path))
+        .or_else(|_| which::which(name_or_path))
+        .map_err(|_| CredentialError::HelperNotFound(name_or_path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_absolute_path_as_is() {
+        let resolved = resolve_helper_path("/usr/local/bin/my-helper").unwrap();
+        assert_eq!(resolved, PathBuf::from("/usr/local/bin/my-helper"));
+    }
+
+    #[test]
+    fn native_backend_name_matches_build_target() {
+        let name = native_backend_name();
+        assert!(["secret-service", "keychain", "wincred", "keyring"].contains(&name));
+    }
+
+    #[test]
+    fn missing_bundled_helper_is_not_found() {
+        let err = resolve_helper_path("svalinn:does-not-exist").unwrap_err();
+        assert!(matches!(err, CredentialError::HelperNotFound(_)));
+    }
+}
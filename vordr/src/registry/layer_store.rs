@@ -0,0 +1,265 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Content-addressable blob store shared across images
+//!
+//! Layer blobs are stored on disk keyed by their `sha256:` digest, one
+//! file per digest under `<root>/layers/`, so two images that share a
+//! base layer (both built on `alpine`, say) only ever occupy one copy on
+//! disk and only ever need downloading once. [`LayerStore::insert`] writes
+//! to a temporary file, `fsync`s it, then renames it into place, so a
+//! process killed mid-write never leaves a blob that looks present but
+//! isn't. This is purely a digest -> bytes cache; deciding which digests
+//! are still referenced is [`crate::engine::StateManager`]'s job, not
+//! this module's.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LayerStoreError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("digest mismatch storing blob: expected {expected}, computed {actual}")]
+    DigestMismatch { expected: String, actual: String },
+}
+
+/// A persistent, digest-keyed blob store rooted at a single directory.
+/// Cheap to [`Clone`] - it's just a path - so it can be handed to
+/// concurrent download tasks the same way [`super::RegistryClient`] is.
+#[derive(Debug, Clone)]
+pub struct LayerStore {
+    root: PathBuf,
+}
+
+impl LayerStore {
+    /// Opens a store rooted at `root`, creating the directory if it
+    /// doesn't exist yet.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, LayerStoreError> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// The on-disk path a blob for `digest` is (or would be) stored at.
+    pub fn blob_path(&self, digest: &str) -> PathBuf {
+        let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+        self.root.join(hex)
+    }
+
+    /// Whether `digest` is already present in the store.
+    pub fn contains(&self, digest: &str) -> bool {
+        self.blob_path(digest).is_file()
+    }
+
+    /// Reads a stored blob back, if present.
+    pub fn read(&self, digest: &str) -> Result<Option<Vec<u8>>, LayerStoreError> {
+        match fs::read(self.blob_path(digest)) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Like [`Self::read`], but just the size - for callers that only
+    /// need to know a blob is present and how big it is, without paying
+    /// for a full read.
+    pub fn blob_size(&self, digest: &str) -> Result<Option<u64>, LayerStoreError> {
+        match fs::metadata(self.blob_path(digest)) {
+            Ok(meta) => Ok(Some(meta.len())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stores `data` under `digest`, first verifying it actually hashes to
+    /// it - the store's whole purpose is that the key can be trusted to
+    /// match the content, so this isn't an optional extra check. Writes
+    /// to a temp file beside the destination, `fsync`s it, and renames it
+    /// into place, so a crash mid-write can never leave a partial blob
+    /// where a complete one is expected.
+    pub fn insert(&self, digest: &str, data: &[u8]) -> Result<(), LayerStoreError> {
+        let computed = format!("sha256:{}", hex::encode(Sha256::digest(data)));
+        if computed != digest {
+            return Err(LayerStoreError::DigestMismatch {
+                expected: digest.to_string(),
+                actual: computed,
+            });
+        }
+
+        let tmp_path = self.staging_path(digest);
+        let mut tmp_file = fs::File::create(&tmp_path)?;
+        tmp_file.write_all(data)?;
+        tmp_file.sync_all()?;
+        drop(tmp_file);
+
+        self.commit_staged(&tmp_path, digest)
+    }
+
+    /// The temporary path [`Self::insert`] and a streaming downloader (see
+    /// [`crate::registry::RegistryClient::download_blob`]) write to before
+    /// the blob's digest is known to be correct - named so
+    /// [`Self::verify_all`] can recognize and skip it if a crash leaves it
+    /// behind.
+    pub fn staging_path(&self, digest: &str) -> PathBuf {
+        let dest = self.blob_path(digest);
+        self.root
+            .join(format!(".{}.tmp-{}", dest.file_name().and_then(|n| n.to_str()).unwrap_or("blob"), std::process::id()))
+    }
+
+    /// Renames an already-verified blob at `staging_path` into its final
+    /// digest-named location. The caller - [`Self::insert`], or a
+    /// streaming download that verified the digest itself - is trusted to
+    /// have checked `staging_path`'s content actually hashes to `digest`.
+    pub fn commit_staged(&self, staging_path: &Path, digest: &str) -> Result<(), LayerStoreError> {
+        fs::rename(staging_path, self.blob_path(digest))?;
+        Ok(())
+    }
+
+    /// Removes a stored blob. A missing blob is not an error.
+    pub fn remove(&self, digest: &str) -> Result<(), LayerStoreError> {
+        match fs::remove_file(self.blob_path(digest)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Re-hashes the blob stored for `digest` and reports whether it
+    /// still matches. `Ok(false)` means the blob is present but corrupt;
+    /// a missing blob is also reported as `Ok(false)` rather than an
+    /// error, since "not there" and "there but wrong" are both failures a
+    /// `vordr image verify-layers` run needs to report.
+    pub fn verify(&self, digest: &str) -> Result<bool, LayerStoreError> {
+        match self.read(digest)? {
+            Some(data) => Ok(format!("sha256:{}", hex::encode(Sha256::digest(&data))) == digest),
+            None => Ok(false),
+        }
+    }
+
+    /// Re-hashes every blob on disk against the digest its filename
+    /// claims, returning `(digest, is_valid)` for each. Used by `vordr
+    /// image verify-layers` to detect bit rot or a truncated write that
+    /// somehow slipped past [`Self::insert`]'s own check.
+    pub fn verify_all(&self) -> Result<Vec<(String, bool)>, LayerStoreError> {
+        let mut results = Vec::new();
+        if !self.root.is_dir() {
+            return Ok(results);
+        }
+
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let Some(hex_name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            // Temp files from an interrupted insert() never got renamed
+            // into their final digest-named path, so they're not blobs.
+            if hex_name.starts_with('.') {
+                continue;
+            }
+
+            let digest = format!("sha256:{}", hex_name);
+            let valid = self.verify(&digest)?;
+            results.push((digest, valid));
+        }
+
+        results.sort();
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> LayerStore {
+        let dir = std::env::temp_dir().join(format!("vordr-layer-store-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        LayerStore::open(&dir).unwrap()
+    }
+
+    fn digest_of(data: &[u8]) -> String {
+        format!("sha256:{}", hex::encode(Sha256::digest(data)))
+    }
+
+    #[test]
+    fn inserts_and_reads_back_a_blob() {
+        let store = temp_store("roundtrip");
+        let data = b"hello layer".to_vec();
+        let digest = digest_of(&data);
+
+        store.insert(&digest, &data).unwrap();
+
+        assert!(store.contains(&digest));
+        assert_eq!(store.read(&digest).unwrap(), Some(data.clone()));
+        assert_eq!(store.blob_size(&digest).unwrap(), Some(data.len() as u64));
+
+        fs::remove_dir_all(&store.root).ok();
+    }
+
+    #[test]
+    fn rejects_insert_with_wrong_digest() {
+        let store = temp_store("mismatch");
+        let err = store.insert("sha256:deadbeef", b"not matching").unwrap_err();
+        assert!(matches!(err, LayerStoreError::DigestMismatch { .. }));
+        assert!(!store.contains("sha256:deadbeef"));
+
+        fs::remove_dir_all(&store.root).ok();
+    }
+
+    #[test]
+    fn missing_blob_reads_as_none_and_verifies_false() {
+        let store = temp_store("missing");
+        assert_eq!(store.read("sha256:0000").unwrap(), None);
+        assert!(!store.verify("sha256:0000").unwrap());
+
+        fs::remove_dir_all(&store.root).ok();
+    }
+
+    #[test]
+    fn staged_blob_is_committed_into_place() {
+        let store = temp_store("staged");
+        let data = b"streamed straight to disk".to_vec();
+        let digest = digest_of(&data);
+
+        let staging_path = store.staging_path(&digest);
+        fs::write(&staging_path, &data).unwrap();
+        store.commit_staged(&staging_path, &digest).unwrap();
+
+        assert!(!staging_path.exists());
+        assert!(store.contains(&digest));
+        assert_eq!(store.read(&digest).unwrap(), Some(data));
+
+        fs::remove_dir_all(&store.root).ok();
+    }
+
+    #[test]
+    fn verify_all_detects_corrupted_blob() {
+        let store = temp_store("corrupt");
+        let data = b"original contents".to_vec();
+        let digest = digest_of(&data);
+        store.insert(&digest, &data).unwrap();
+
+        // Simulate bit rot by overwriting the blob in place, bypassing
+        // insert()'s own digest check.
+        fs::write(store.blob_path(&digest), b"corrupted contents").unwrap();
+
+        let results = store.verify_all().unwrap();
+        assert_eq!(results, vec![(digest, false)]);
+
+        fs::remove_dir_all(&store.root).ok();
+    }
+
+    #[test]
+    fn remove_is_idempotent() {
+        let store = temp_store("remove");
+        store.remove("sha256:never-existed").unwrap();
+
+        fs::remove_dir_all(&store.root).ok();
+    }
+}
@@ -1,6 +1,13 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
 //! OCI Distribution Specification client for image pull/push
 
+pub mod credentials;
+pub mod layer_store;
+pub mod paseto;
+
+pub use layer_store::{LayerStore, LayerStoreError};
+
+use base64::Engine;
 use oci_spec::image::{ImageConfiguration, ImageManifest};
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
 use serde::{Deserialize, Serialize};
@@ -132,17 +139,220 @@ impl ImageReference {
     }
 }
 
+const OCI_INDEX_MEDIA_TYPE: &str = "application/vnd.oci.image.index.v1+json";
+const DOCKER_MANIFEST_LIST_MEDIA_TYPE: &str = "application/vnd.docker.distribution.manifest.list.v2+json";
+
+fn is_index_media_type(media_type: &str) -> bool {
+    media_type == OCI_INDEX_MEDIA_TYPE || media_type == DOCKER_MANIFEST_LIST_MEDIA_TYPE
+}
+
+/// An OCI platform descriptor (`os`/`architecture`/`variant`) - either
+/// embedded in a multi-arch index's child [`IndexEntry`], or used as the
+/// selector passed to [`RegistryClient::get_manifest_for_platform`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Platform {
+    pub os: String,
+    pub architecture: String,
+    #[serde(default)]
+    pub variant: Option<String>,
+}
+
+impl Platform {
+    /// The platform this binary is actually running on, mapped from
+    /// `std::env::consts::OS`/`ARCH` to the GOOS/GOARCH vocabulary
+    /// registries embed in index entries.
+    pub fn host() -> Self {
+        Self {
+            os: std::env::consts::OS.to_string(),
+            architecture: goarch(std::env::consts::ARCH).to_string(),
+            variant: None,
+        }
+    }
+
+    /// Whether `candidate` satisfies this selector. `variant` is only
+    /// checked when this selector specifies one - most callers don't care
+    /// whether they get `arm/v7` or `arm/v8`.
+    fn matches(&self, candidate: &Platform) -> bool {
+        if self.os != candidate.os || self.architecture != candidate.architecture {
+            return false;
+        }
+        match &self.variant {
+            Some(variant) => candidate.variant.as_deref() == Some(variant.as_str()),
+            None => true,
+        }
+    }
+}
+
+/// Maps a Rust `std::env::consts::ARCH` value to the GOARCH name OCI
+/// registries use in `platform.architecture`.
+fn goarch(rust_arch: &str) -> &str {
+    match rust_arch {
+        "x86_64" => "amd64",
+        "x86" => "386",
+        "aarch64" => "arm64",
+        other => other,
+    }
+}
+
+/// One child of a multi-arch index: enough to resolve or enumerate it
+/// without pulling the child manifest itself.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexEntry {
+    #[serde(rename = "mediaType")]
+    pub media_type: String,
+    pub digest: String,
+    #[serde(default)]
+    pub platform: Option<Platform>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ManifestIndexDoc {
+    #[serde(default)]
+    manifests: Vec<IndexEntry>,
+}
+
+/// What pulling a reference turned out to be: a concrete manifest, or a
+/// multi-arch index's child list - returned raw so callers that want to
+/// enumerate available platforms aren't forced through resolution.
+pub enum ManifestOrIndex {
+    Manifest(ImageManifest),
+    Index(Vec<IndexEntry>),
+}
+
 /// Authentication token response
 #[derive(Debug, Deserialize)]
 struct AuthResponse {
     token: Option<String>,
     access_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+/// A bearer token obtained from [`RegistryClient::login_handshake`], along
+/// with how long (in seconds) it's valid for.
+#[derive(Debug)]
+pub struct LoginToken {
+    pub token: String,
+    pub expires_in: u64,
+}
+
+/// Parses a `WWW-Authenticate: Bearer realm="...",service="...",scope="..."`
+/// challenge into its key/value pairs.
+fn parse_bearer_challenge(www_auth: &str) -> std::collections::HashMap<&str, &str> {
+    www_auth
+        .strip_prefix("Bearer ")
+        .unwrap_or("")
+        .split(',')
+        .filter_map(|part| {
+            let mut kv = part.splitn(2, '=');
+            let key = kv.next()?.trim();
+            let value = kv.next()?.trim().trim_matches('"');
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Blobs at or under this size are pushed as a single monolithic `PUT`;
+/// anything larger is split into `PATCH` range uploads of this size.
+const CHUNKED_UPLOAD_THRESHOLD: usize = 10 * 1024 * 1024;
+
+/// How long a PASETO token minted for an `--asymmetric` login stays
+/// valid - mirrors `cli::auth`'s own `PASETO_TOKEN_TTL`, kept independent
+/// here since `registry` must not depend on `cli`.
+const PASETO_TOKEN_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Resolves an upload session's `Location` header to an absolute URL -
+/// the spec allows registries to send either an absolute URL or one
+/// relative to `registry`.
+fn resolve_upload_location(registry: &str, location: &str) -> String {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        location.to_string()
+    } else {
+        format!("https://{}/{}", registry, location.trim_start_matches('/'))
+    }
+}
+
+/// Appends `digest=<digest>` to an upload URL, respecting whatever query
+/// string the session's `Location` already carries.
+fn append_digest_param(upload_url: &str, digest: &str) -> String {
+    let separator = if upload_url.contains('?') { '&' } else { '?' };
+    format!("{}{}digest={}", upload_url, separator, digest)
+}
+
+/// Maps a registry host (as carried on [`ImageReference`]) to the key its
+/// credentials are stored under in a Docker-config-style `auths` map -
+/// mirrors the `cli::auth` module's own `normalize_registry`, but kept
+/// independent here since `registry` must not depend on `cli`.
+fn auth_lookup_key(registry: &str) -> String {
+    match registry {
+        "docker.io" | "registry-1.docker.io" | "index.docker.io" => "https://index.docker.io/v1/".to_string(),
+        r if r.starts_with("https://") || r.starts_with("http://") => r.to_string(),
+        r => format!("https://{}", r),
+    }
+}
+
+/// The `auths` map shape shared by Docker's own `~/.docker/config.json`
+/// and vordr's native `auth.json`. [`RegistryClient::load_auths_file`]
+/// turns `auth` into Basic credentials, `identitytoken` into a stored
+/// refresh token for the bearer-token exchange, and `paseto_key` (paired
+/// with `username`) into a signing key for `--asymmetric` logins;
+/// `registrytoken` entries are still skipped - nothing writes those today.
+#[derive(Debug, Deserialize, Default)]
+struct DockerAuthFile {
+    #[serde(default)]
+    auths: std::collections::HashMap<String, DockerAuthEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerAuthEntry {
+    auth: Option<String>,
+    username: Option<String>,
+    #[serde(rename = "identitytoken")]
+    identity_token: Option<String>,
+    paseto_key: Option<String>,
+}
+
+/// A credential attached to either a token exchange (as HTTP Basic, at
+/// the realm) or sent directly (when the registry itself challenges with
+/// `WWW-Authenticate: Basic`).
+#[derive(Debug, Clone)]
+enum AuthToken {
+    Bearer(String),
+    Basic(String),
+}
+
+/// A cached [`AuthToken`] plus when to stop trusting it. `expires_at` is
+/// `None` for tokens whose response carried no `expires_in` - those are
+/// cached for the life of the `RegistryClient`.
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: AuthToken,
+    expires_at: Option<u64>,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs()
 }
 
-/// OCI registry client
+/// OCI registry client. Cheap to [`Clone`] - the underlying HTTP client,
+/// token cache, and credential map are all reference-counted, so a clone
+/// shares the same cache/credentials rather than starting fresh, which is
+/// what lets [`RegistryClient::pull_image`] hand a clone to each
+/// concurrent download task.
+#[derive(Clone)]
 pub struct RegistryClient {
     http_client: reqwest::Client,
-    auth_cache: std::collections::HashMap<String, String>,
+    auth_cache: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, CachedToken>>>,
+    credentials: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, (String, String)>>>,
+    /// Registry -> stored `identitytoken`, as an alternative to a
+    /// `credentials` username/password pair. Exchanged for a scoped bearer
+    /// token via the OAuth2 `refresh_token` grant in [`Self::do_token_auth`]
+    /// rather than sent as HTTP Basic, since it isn't a password.
+    identity_tokens: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>,
+    /// Registry -> (username, key pair) for `--asymmetric` logins. Instead
+    /// of exchanging anything at a realm, [`Self::get_token`] mints a
+    /// fresh short-lived `v4.public` token straight from this key and
+    /// presents it as the `Bearer` credential itself.
+    paseto_keys: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, (String, paseto::PasetoKeyPair)>>>,
 }
 
 impl RegistryClient {
@@ -153,91 +363,294 @@ impl RegistryClient {
                 .user_agent("vordr/0.1.0")
                 .build()
                 .expect("Failed to create HTTP client"),
-            auth_cache: std::collections::HashMap::new(),
+            auth_cache: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            credentials: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            identity_tokens: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            paseto_keys: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
         }
     }
 
-    /// Get authentication token for a registry
-    async fn get_token(&mut self, registry: &str, repository: &str) -> Result<Option<String>, RegistryError> {
-        // Check cache
-        let cache_key = format!("{}/{}", registry, repository);
-        if let Some(token) = self.auth_cache.get(&cache_key) {
-            return Ok(Some(token.clone()));
+    /// Registers `username`/`password` for `registry`, to be used for
+    /// both `Basic`-challenge registries and as the realm credentials
+    /// during bearer token exchange. Chainable, so callers can do
+    /// `RegistryClient::new().with_credentials("ghcr.io", user, pass)`.
+    pub fn with_credentials(self, registry: &str, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials.lock().unwrap().insert(auth_lookup_key(registry), (username.into(), password.into()));
+        self
+    }
+
+    /// Loads every `auth: base64(user:pass)`, `identitytoken`, or
+    /// `paseto_key` entry out of a Docker-config-style JSON file. Missing
+    /// files, entries with a `paseto_key` but no `username`, and
+    /// `registrytoken` entries are silently skipped - none of those is an
+    /// error, since most hosts won't have a matching entry at all and
+    /// nothing writes `registrytoken` today.
+    fn load_auths_file(&self, path: &Path) -> Result<(), RegistryError> {
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let Ok(config) = serde_json::from_str::<DockerAuthFile>(&content) else {
+            return Ok(());
+        };
+
+        let mut credentials = self.credentials.lock().unwrap();
+        let mut identity_tokens = self.identity_tokens.lock().unwrap();
+        let mut paseto_keys = self.paseto_keys.lock().unwrap();
+        for (registry, entry) in config.auths {
+            if let Some(stored_key) = entry.paseto_key {
+                if let (Some(username), Ok(key_pair)) = (entry.username, paseto::PasetoKeyPair::from_stored(&stored_key)) {
+                    paseto_keys.insert(registry, (username, key_pair));
+                }
+                continue;
+            }
+            if let Some(token) = entry.identity_token {
+                identity_tokens.insert(registry, token);
+                continue;
+            }
+            let Some(auth) = entry.auth else { continue };
+            let Ok(decoded) = base64::engine::general_purpose::STANDARD.decode(&auth) else { continue };
+            let Ok(text) = String::from_utf8(decoded) else { continue };
+            let Some((username, password)) = text.split_once(':') else { continue };
+            credentials.insert(registry, (username.to_string(), password.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Loads credentials from Docker's own `~/.docker/config.json`, if
+    /// present.
+    pub fn load_docker_config(&self) -> Result<(), RegistryError> {
+        let Some(home) = dirs::home_dir() else { return Ok(()) };
+        self.load_auths_file(&home.join(".docker").join("config.json"))
+    }
+
+    /// Loads credentials from vordr's native `auth.json` - the same file
+    /// `vordr login` writes to.
+    pub fn load_vordr_auth(&self) -> Result<(), RegistryError> {
+        let Some(config_dir) = dirs::config_dir() else { return Ok(()) };
+        self.load_auths_file(&config_dir.join("vordr").join("auth.json"))
+    }
+
+    /// Get an authentication token scoped to `action` (e.g. `"pull"` for
+    /// reads, `"pull,push"` for anything that writes). Returns a cached
+    /// token when one is on file and unexpired; a freshly minted
+    /// `v4.public` token when `registry` has a `--asymmetric` key on file
+    /// (no challenge probe needed - the token is self-contained and
+    /// presented directly); otherwise probes `/v2/` and answers its
+    /// challenge - `Basic` directly from any registered credential,
+    /// `Bearer` via [`Self::do_token_auth`].
+    async fn get_token(&self, registry: &str, repository: &str, action: &str) -> Result<Option<AuthToken>, RegistryError> {
+        let cache_key = format!("{}/{}:{}", registry, repository, action);
+        let cached = self.auth_cache.lock().unwrap().get(&cache_key).cloned();
+        if let Some(cached) = cached {
+            if cached.expires_at.map(|expiry| expiry > unix_now()).unwrap_or(true) {
+                return Ok(Some(cached.token.clone()));
+            }
+        }
+
+        let paseto_key = self.paseto_keys.lock().unwrap().get(&auth_lookup_key(registry)).cloned();
+        if let Some((username, key_pair)) = paseto_key {
+            let token = paseto::mint(&key_pair, &username, registry, PASETO_TOKEN_TTL)
+                .map_err(|e| RegistryError::AuthFailed(e.to_string()))?;
+            let auth_token = AuthToken::Bearer(token);
+            self.auth_cache.lock().unwrap().insert(
+                cache_key,
+                CachedToken {
+                    token: auth_token.clone(),
+                    expires_at: Some(unix_now() + PASETO_TOKEN_TTL.as_secs()),
+                },
+            );
+            return Ok(Some(auth_token));
         }
 
         // Try to access without auth first
         let url = format!("https://{}/v2/", registry);
         let response = self.http_client.get(&url).send().await?;
 
-        if response.status() == 401 {
-            // Need authentication
-            if let Some(www_auth) = response.headers().get("www-authenticate") {
-                let auth_str = www_auth.to_str().unwrap_or("");
-                return self.do_token_auth(auth_str, repository).await;
+        if response.status() != 401 {
+            return Ok(None);
+        }
+
+        let Some(www_auth) = response.headers().get("www-authenticate") else {
+            return Ok(None);
+        };
+        let www_auth = www_auth.to_str().unwrap_or("").to_string();
+
+        let (token, expires_at) = if www_auth.starts_with("Basic") {
+            match self.credentials.lock().unwrap().get(&auth_lookup_key(registry)).cloned() {
+                Some((username, password)) => {
+                    let basic = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+                    (Some(AuthToken::Basic(basic)), None)
+                }
+                None => (None, None),
+            }
+        } else if www_auth.starts_with("Bearer") {
+            match self.do_token_auth(registry, &www_auth, repository, action).await? {
+                Some((token, expires_in)) => (Some(AuthToken::Bearer(token)), expires_in.map(|ttl| unix_now() + ttl)),
+                None => (None, None),
             }
+        } else {
+            (None, None)
+        };
+
+        if let Some(token) = &token {
+            self.auth_cache.lock().unwrap().insert(cache_key, CachedToken { token: token.clone(), expires_at });
         }
 
-        Ok(None)
+        Ok(token)
     }
 
-    /// Perform token authentication
+    /// Exchanges the `Bearer` challenge's realm/service/scope for a
+    /// token. When `registry` has a stored `identitytoken` (from a prior
+    /// `vordr login` against a registry that answered with one), that's
+    /// redeemed via the OAuth2 `refresh_token` grant, `POST`ed to the
+    /// realm as form data - the identity token isn't a password, so it
+    /// can't go over HTTP Basic. Otherwise falls back to attaching any
+    /// registered username/password credential as HTTP Basic on a plain
+    /// `GET`. Returns the token and its `expires_in`, if the response
+    /// carried one.
     async fn do_token_auth(
-        &mut self,
+        &self,
+        registry: &str,
         www_auth: &str,
         repository: &str,
-    ) -> Result<Option<String>, RegistryError> {
-        // Parse Bearer realm="...",service="...",scope="..."
-        let parts: std::collections::HashMap<&str, &str> = www_auth
-            .strip_prefix("Bearer ")
-            .unwrap_or("")
-            .split(',')
-            .filter_map(|part| {
-                let mut kv = part.splitn(2, '=');
-                let key = kv.next()?.trim();
-                let value = kv.next()?.trim().trim_matches('"');
-                Some((key, value))
-            })
-            .collect();
+        action: &str,
+    ) -> Result<Option<(String, Option<u64>)>, RegistryError> {
+        let parts = parse_bearer_challenge(www_auth);
 
         let realm = parts.get("realm").ok_or_else(|| {
             RegistryError::AuthFailed("missing realm in www-authenticate".to_string())
         })?;
 
         let service = parts.get("service").map(|s| s.to_string());
-        let scope = format!("repository:{}:pull", repository);
+        let scope = format!("repository:{}:{}", repository, action);
+
+        let identity_token = self.identity_tokens.lock().unwrap().get(&auth_lookup_key(registry)).cloned();
+
+        let response: AuthResponse = if let Some(identity_token) = identity_token {
+            debug!("Authenticating at {} via refresh_token grant", realm);
+
+            let mut form = vec![("grant_type", "refresh_token".to_string()), ("refresh_token", identity_token)];
+            if let Some(svc) = &service {
+                form.push(("service", svc.clone()));
+            }
+            form.push(("scope", scope));
 
-        // Build auth URL
-        let mut auth_url = format!("{}?scope={}", realm, scope);
-        if let Some(svc) = service {
-            auth_url.push_str(&format!("&service={}", svc));
+            self.http_client.post(*realm).form(&form).send().await?.json().await?
+        } else {
+            // Build auth URL
+            let mut auth_url = format!("{}?scope={}", realm, scope);
+            if let Some(svc) = service {
+                auth_url.push_str(&format!("&service={}", svc));
+            }
+
+            debug!("Authenticating at: {}", auth_url);
+
+            let credential = self.credentials.lock().unwrap().get(&auth_lookup_key(registry)).cloned();
+            let mut request = self.http_client.get(&auth_url);
+            if let Some((username, password)) = credential {
+                request = request.basic_auth(username, Some(password));
+            }
+
+            request.send().await?.json().await?
+        };
+
+        let token = response.token.or(response.access_token);
+        Ok(token.map(|t| (t, response.expires_in)))
+    }
+
+    /// Performs the same handshake `docker login` does: probes `GET /v2/`
+    /// on `host`, and if it challenges for a bearer token, exchanges
+    /// `username`/`password` as HTTP Basic auth at the realm for one.
+    /// Returns `Ok(None)` when the registry doesn't use bearer tokens at
+    /// all (anonymous `/v2/`, or Basic-auth-only) - the caller should fall
+    /// back to storing the password directly in that case. A bad password
+    /// surfaces as `Err(RegistryError::AuthFailed)`.
+    pub async fn login_handshake(
+        &self,
+        host: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<LoginToken>, RegistryError> {
+        let url = format!("https://{}/v2/", host);
+        let response = self.http_client.get(&url).send().await?;
+
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(None);
+        }
+
+        let www_auth = response
+            .headers()
+            .get("www-authenticate")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+
+        if !www_auth.starts_with("Bearer ") {
+            return Ok(None);
         }
 
-        debug!("Authenticating at: {}", auth_url);
+        let parts = parse_bearer_challenge(&www_auth);
+        let realm = parts
+            .get("realm")
+            .ok_or_else(|| RegistryError::AuthFailed("missing realm in www-authenticate".to_string()))?;
 
-        let response: AuthResponse = self
+        let mut auth_url = reqwest::Url::parse(realm).map_err(|e| RegistryError::AuthFailed(e.to_string()))?;
+        {
+            let mut query = auth_url.query_pairs_mut();
+            if let Some(service) = parts.get("service") {
+                query.append_pair("service", service);
+            }
+            if let Some(scope) = parts.get("scope") {
+                query.append_pair("scope", scope);
+            }
+        }
+
+        let response = self
             .http_client
-            .get(&auth_url)
+            .get(auth_url)
+            .basic_auth(username, Some(password))
             .send()
-            .await?
-            .json()
             .await?;
 
-        let token = response.token.or(response.access_token);
-        Ok(token)
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED || response.status() == reqwest::StatusCode::FORBIDDEN {
+            return Err(RegistryError::AuthFailed(format!("invalid credentials for {}", username)));
+        }
+        if !response.status().is_success() {
+            return Err(RegistryError::AuthFailed(format!("token endpoint returned HTTP {}", response.status())));
+        }
+
+        let parsed: AuthResponse = response.json().await?;
+        let token = parsed
+            .token
+            .or(parsed.access_token)
+            .ok_or_else(|| RegistryError::AuthFailed("token endpoint returned no token".to_string()))?;
+
+        Ok(Some(LoginToken {
+            token,
+            expires_in: parsed.expires_in.unwrap_or(60),
+        }))
     }
 
     /// Build headers with authentication
-    fn build_headers(&self, token: Option<&str>) -> HeaderMap {
+    fn build_headers(&self, token: Option<&AuthToken>) -> HeaderMap {
         let mut headers = HeaderMap::new();
         headers.insert(
             ACCEPT,
             HeaderValue::from_static(
-                "application/vnd.oci.image.manifest.v1+json, application/vnd.docker.distribution.manifest.v2+json",
+                "application/vnd.oci.image.manifest.v1+json, application/vnd.docker.distribution.manifest.v2+json, \
+                 application/vnd.oci.image.index.v1+json, application/vnd.docker.distribution.manifest.list.v2+json",
             ),
         );
 
         if let Some(token) = token {
-            if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+            let value = match token {
+                AuthToken::Bearer(t) => format!("Bearer {}", t),
+                AuthToken::Basic(b) => format!("Basic {}", b),
+            };
+            if let Ok(value) = HeaderValue::from_str(&value) {
                 headers.insert(AUTHORIZATION, value);
             }
         }
@@ -245,9 +658,12 @@ impl RegistryClient {
         headers
     }
 
-    /// Pull an image manifest
-    pub async fn get_manifest(&mut self, reference: &ImageReference) -> Result<ImageManifest, RegistryError> {
-        let token = self.get_token(&reference.registry, &reference.repository).await?;
+    /// Fetches whatever `reference` points at and classifies the result as
+    /// either a concrete manifest or a multi-arch index, preferring the
+    /// embedded `mediaType` field over the `Content-Type` header since
+    /// some registries don't bother setting the latter precisely.
+    async fn fetch_manifest_or_index(&self, reference: &ImageReference) -> Result<ManifestOrIndex, RegistryError> {
+        let token = self.get_token(&reference.registry, &reference.repository, "pull").await?;
 
         let tag_or_digest = reference
             .digest
@@ -265,7 +681,7 @@ impl RegistryClient {
         let response = self
             .http_client
             .get(&url)
-            .headers(self.build_headers(token.as_deref()))
+            .headers(self.build_headers(token.as_ref()))
             .send()
             .await?;
 
@@ -281,13 +697,71 @@ impl RegistryClient {
             )));
         }
 
-        let manifest: ImageManifest = response.json().await?;
-        Ok(manifest)
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+
+        let bytes = response.bytes().await?;
+        let media_type = serde_json::from_slice::<serde_json::Value>(&bytes)
+            .ok()
+            .and_then(|v| v.get("mediaType").and_then(|m| m.as_str()).map(String::from))
+            .unwrap_or(content_type);
+
+        if is_index_media_type(&media_type) {
+            let index: ManifestIndexDoc = serde_json::from_slice(&bytes)?;
+            Ok(ManifestOrIndex::Index(index.manifests))
+        } else {
+            let manifest: ImageManifest = serde_json::from_slice(&bytes)?;
+            Ok(ManifestOrIndex::Manifest(manifest))
+        }
+    }
+
+    /// Pulls a manifest, transparently resolving a multi-arch index down
+    /// to the child matching `platform` - [`Platform::host`] when not
+    /// given - by recursing on the chosen child's digest.
+    pub async fn get_manifest_for_platform(
+        &self,
+        reference: &ImageReference,
+        platform: Option<&Platform>,
+    ) -> Result<ImageManifest, RegistryError> {
+        match self.fetch_manifest_or_index(reference).await? {
+            ManifestOrIndex::Manifest(manifest) => Ok(manifest),
+            ManifestOrIndex::Index(entries) => {
+                let wanted = platform.cloned().unwrap_or_else(Platform::host);
+
+                let chosen = entries
+                    .iter()
+                    .find(|entry| entry.platform.as_ref().is_some_and(|p| wanted.matches(p)))
+                    .ok_or_else(|| {
+                        RegistryError::NotFound(format!(
+                            "no manifest for platform {}/{} in {}",
+                            wanted.os,
+                            wanted.architecture,
+                            reference.full_reference()
+                        ))
+                    })?;
+
+                let mut child_reference = reference.clone();
+                child_reference.tag = None;
+                child_reference.digest = Some(chosen.digest.clone());
+
+                Box::pin(self.get_manifest_for_platform(&child_reference, Some(&wanted))).await
+            }
+        }
+    }
+
+    /// Pull an image manifest, resolving a multi-arch index to the host
+    /// platform's child if `reference` points at one.
+    pub async fn get_manifest(&self, reference: &ImageReference) -> Result<ImageManifest, RegistryError> {
+        self.get_manifest_for_platform(reference, None).await
     }
 
     /// Pull an image configuration
     pub async fn get_config(
-        &mut self,
+        &self,
         reference: &ImageReference,
         config_digest: &str,
     ) -> Result<ImageConfiguration, RegistryError> {
@@ -296,13 +770,16 @@ impl RegistryClient {
         Ok(config)
     }
 
-    /// Pull a blob by digest
+    /// Pull a blob by digest, streaming the response chunk-by-chunk into
+    /// an incremental hasher instead of buffering it whole before
+    /// verifying - the blob itself still ends up in memory as the return
+    /// value, but never duplicated across a separate hashing pass.
     pub async fn get_blob(
-        &mut self,
+        &self,
         reference: &ImageReference,
         digest: &str,
     ) -> Result<Vec<u8>, RegistryError> {
-        let token = self.get_token(&reference.registry, &reference.repository).await?;
+        let token = self.get_token(&reference.registry, &reference.repository, "pull").await?;
 
         let url = format!(
             "https://{}/v2/{}/blobs/{}",
@@ -311,10 +788,10 @@ impl RegistryClient {
 
         debug!("Fetching blob: {}", digest);
 
-        let response = self
+        let mut response = self
             .http_client
             .get(&url)
-            .headers(self.build_headers(token.as_deref()))
+            .headers(self.build_headers(token.as_ref()))
             .send()
             .await?;
 
@@ -330,10 +807,14 @@ impl RegistryClient {
             )));
         }
 
-        let bytes = response.bytes().await?.to_vec();
+        let mut hasher = Sha256::new();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+            bytes.extend_from_slice(&chunk);
+        }
 
-        // Verify digest
-        let computed_digest = format!("sha256:{}", hex::encode(Sha256::digest(&bytes)));
+        let computed_digest = format!("sha256:{}", hex::encode(hasher.finalize()));
         if computed_digest != digest {
             return Err(RegistryError::DigestMismatch {
                 expected: digest.to_string(),
@@ -344,50 +825,519 @@ impl RegistryClient {
         Ok(bytes)
     }
 
-    /// Download a blob to a file
+    /// Downloads a blob to `path`, streaming it chunk-by-chunk so memory
+    /// use stays bounded regardless of layer size, verifying the digest
+    /// incrementally as chunks arrive. If `path` already holds a partial
+    /// download, resumes it with a `Range: bytes=<offset>-` request,
+    /// seeding the hasher from the bytes already on disk; if the server
+    /// answers with a full `200` instead of a `206`, falls back to
+    /// re-fetching from scratch. A digest mismatch at the end deletes
+    /// `path` so a corrupt blob is never left behind looking complete.
     pub async fn download_blob(
-        &mut self,
+        &self,
         reference: &ImageReference,
         digest: &str,
         path: &Path,
     ) -> Result<u64, RegistryError> {
-        let token = self.get_token(&reference.registry, &reference.repository).await?;
+        use std::io::{Read, Write};
+
+        let token = self.get_token(&reference.registry, &reference.repository, "pull").await?;
 
         let url = format!(
             "https://{}/v2/{}/blobs/{}",
             reference.registry, reference.repository, digest
         );
 
+        let resume_offset = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+
         debug!("Downloading blob {} to {}", digest, path.display());
 
+        let mut request = self.http_client.get(&url).headers(self.build_headers(token.as_ref()));
+        if resume_offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_offset));
+        }
+
+        let mut response = request.send().await?;
+
+        if response.status() == 404 {
+            return Err(RegistryError::NotFound(digest.to_string()));
+        }
+
+        let resuming = resume_offset > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+
+        if !response.status().is_success() {
+            return Err(RegistryError::RegistryError(format!(
+                "HTTP {}",
+                response.status()
+            )));
+        }
+
+        let mut hasher = Sha256::new();
+        let mut written = 0u64;
+
+        let mut file = if resuming {
+            debug!("Resuming download of {} from byte {}", digest, resume_offset);
+            let mut existing = std::fs::File::open(path)?;
+            let mut buf = [0u8; 64 * 1024];
+            loop {
+                let n = existing.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                hasher.update(&buf[..n]);
+            }
+            written = resume_offset;
+            std::fs::OpenOptions::new().append(true).open(path)?
+        } else {
+            std::fs::File::create(path)?
+        };
+
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+            file.write_all(&chunk)?;
+            written += chunk.len() as u64;
+        }
+
+        let computed_digest = format!("sha256:{}", hex::encode(hasher.finalize()));
+        if computed_digest != digest {
+            drop(file);
+            let _ = std::fs::remove_file(path);
+            return Err(RegistryError::DigestMismatch {
+                expected: digest.to_string(),
+                actual: computed_digest,
+            });
+        }
+
+        Ok(written)
+    }
+
+    /// Checks whether `digest` already exists in `reference`'s repository,
+    /// so `push_blob` can skip re-uploading a layer the registry already
+    /// has.
+    pub async fn blob_exists(&self, reference: &ImageReference, digest: &str) -> Result<bool, RegistryError> {
+        let token = self.get_token(&reference.registry, &reference.repository, "pull").await?;
+
+        let url = format!(
+            "https://{}/v2/{}/blobs/{}",
+            reference.registry, reference.repository, digest
+        );
+
         let response = self
             .http_client
-            .get(&url)
-            .headers(self.build_headers(token.as_deref()))
+            .head(&url)
+            .headers(self.build_headers(token.as_ref()))
             .send()
             .await?;
 
-        if response.status() == 404 {
-            return Err(RegistryError::NotFound(digest.to_string()));
+        Ok(response.status().is_success())
+    }
+
+    /// Attempts to mount `digest` from `from_repository` into `reference`'s
+    /// repository instead of re-uploading it. Returns `true` only on a
+    /// `201 Created` - anything else (including the `202 Accepted` upload
+    /// session some registries open instead) means the mount didn't
+    /// happen and the caller should fall back to a normal upload.
+    pub async fn mount_blob(
+        &self,
+        reference: &ImageReference,
+        digest: &str,
+        from_repository: &str,
+    ) -> Result<bool, RegistryError> {
+        let token = self.get_token(&reference.registry, &reference.repository, "pull,push").await?;
+
+        let url = format!(
+            "https://{}/v2/{}/blobs/uploads/?mount={}&from={}",
+            reference.registry, reference.repository, digest, from_repository
+        );
+
+        let response = self
+            .http_client
+            .post(&url)
+            .headers(self.build_headers(token.as_ref()))
+            .send()
+            .await?;
+
+        Ok(response.status() == reqwest::StatusCode::CREATED)
+    }
+
+    /// Pushes a blob, skipping the upload entirely if the registry already
+    /// has it and trying a cross-repo mount from `mount_from` (if given)
+    /// before falling back to a real upload. Chunks the upload via
+    /// `PATCH` when `data` is larger than [`CHUNKED_UPLOAD_THRESHOLD`],
+    /// otherwise sends it as a single monolithic `PUT`.
+    pub async fn push_blob(
+        &self,
+        reference: &ImageReference,
+        digest: &str,
+        data: &[u8],
+        mount_from: Option<&str>,
+    ) -> Result<(), RegistryError> {
+        if self.blob_exists(reference, digest).await? {
+            debug!("blob {} already present, skipping upload", digest);
+            return Ok(());
+        }
+
+        if let Some(from_repository) = mount_from {
+            if self.mount_blob(reference, digest, from_repository).await? {
+                debug!("blob {} mounted from {}", digest, from_repository);
+                return Ok(());
+            }
         }
 
+        let token = self.get_token(&reference.registry, &reference.repository, "pull,push").await?;
+        let upload_url = self.start_blob_upload(reference, token.as_ref()).await?;
+
+        if data.len() > CHUNKED_UPLOAD_THRESHOLD {
+            self.chunked_blob_upload(upload_url, digest, data, token.as_ref()).await
+        } else {
+            self.monolithic_blob_upload(&upload_url, digest, data, token.as_ref()).await
+        }
+    }
+
+    /// `POST /v2/<repo>/blobs/uploads/`, returning the (possibly relative)
+    /// `Location` URL resolved to an absolute one.
+    async fn start_blob_upload(&self, reference: &ImageReference, token: Option<&AuthToken>) -> Result<String, RegistryError> {
+        let url = format!(
+            "https://{}/v2/{}/blobs/uploads/",
+            reference.registry, reference.repository
+        );
+
+        let response = self
+            .http_client
+            .post(&url)
+            .headers(self.build_headers(token))
+            .send()
+            .await?;
+
+        if response.status() != reqwest::StatusCode::ACCEPTED {
+            return Err(RegistryError::RegistryError(format!(
+                "HTTP {} starting blob upload",
+                response.status()
+            )));
+        }
+
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| RegistryError::RegistryError("upload response missing Location header".to_string()))?;
+
+        Ok(resolve_upload_location(&reference.registry, location))
+    }
+
+    /// Single `PUT ...?digest=<digest>` with the whole blob as the body.
+    async fn monolithic_blob_upload(
+        &self,
+        upload_url: &str,
+        digest: &str,
+        data: &[u8],
+        token: Option<&AuthToken>,
+    ) -> Result<(), RegistryError> {
+        let url = append_digest_param(upload_url, digest);
+
+        let response = self
+            .http_client
+            .put(&url)
+            .headers(self.build_headers(token))
+            .body(data.to_vec())
+            .send()
+            .await?;
+
         if !response.status().is_success() {
             return Err(RegistryError::RegistryError(format!(
-                "HTTP {}",
+                "HTTP {} completing blob upload",
                 response.status()
             )));
         }
 
-        let mut file = std::fs::File::create(path)?;
-        let bytes = response.bytes().await?;
+        Ok(())
+    }
+
+    /// A series of `PATCH` range uploads, each advancing the session's
+    /// `Location` as the registry reports it, followed by a closing
+    /// empty-bodied `PUT ...?digest=<digest>`.
+    async fn chunked_blob_upload(
+        &self,
+        mut upload_url: String,
+        digest: &str,
+        data: &[u8],
+        token: Option<&AuthToken>,
+    ) -> Result<(), RegistryError> {
+        let mut offset: u64 = 0;
+
+        for chunk in data.chunks(CHUNKED_UPLOAD_THRESHOLD) {
+            let end = offset + chunk.len() as u64;
+
+            let response = self
+                .http_client
+                .patch(&upload_url)
+                .headers(self.build_headers(token))
+                .header(reqwest::header::CONTENT_RANGE, format!("{}-{}", offset, end.saturating_sub(1)))
+                .body(chunk.to_vec())
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                return Err(RegistryError::RegistryError(format!(
+                    "HTTP {} uploading blob chunk at offset {}",
+                    response.status(),
+                    offset
+                )));
+            }
 
-        use std::io::Write;
-        file.write_all(&bytes)?;
+            if let Some(location) = response.headers().get(reqwest::header::LOCATION).and_then(|v| v.to_str().ok()) {
+                upload_url = location.to_string();
+            }
+
+            offset = end;
+        }
+
+        let url = append_digest_param(&upload_url, digest);
+        let response = self
+            .http_client
+            .put(&url)
+            .headers(self.build_headers(token))
+            .send()
+            .await?;
 
-        Ok(bytes.len() as u64)
+        if !response.status().is_success() {
+            return Err(RegistryError::RegistryError(format!(
+                "HTTP {} completing chunked blob upload",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// `PUT /v2/<repo>/manifests/<tag>` with the given `Content-Type`.
+    /// Returns the `Docker-Content-Digest` the registry computed, if it
+    /// sent one back.
+    pub async fn push_manifest(
+        &self,
+        reference: &ImageReference,
+        manifest: &ImageManifest,
+        media_type: &str,
+    ) -> Result<Option<String>, RegistryError> {
+        let token = self.get_token(&reference.registry, &reference.repository, "pull,push").await?;
+
+        let tag_or_digest = reference
+            .tag
+            .as_ref()
+            .or(reference.digest.as_ref())
+            .ok_or_else(|| RegistryError::InvalidReference("no tag or digest".to_string()))?;
+
+        let url = format!(
+            "https://{}/v2/{}/manifests/{}",
+            reference.registry, reference.repository, tag_or_digest
+        );
+
+        let body = serde_json::to_vec(manifest)?;
+
+        let response = self
+            .http_client
+            .put(&url)
+            .headers(self.build_headers(token.as_ref()))
+            .header(reqwest::header::CONTENT_TYPE, media_type)
+            .body(body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(RegistryError::RegistryError(format!(
+                "HTTP {} pushing manifest: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )));
+        }
+
+        Ok(response
+            .headers()
+            .get("docker-content-digest")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from))
+    }
+
+    /// Pulls `reference` end-to-end: fetches the manifest, then the config
+    /// and every layer blob concurrently (bounded by
+    /// [`DEFAULT_PULL_CONCURRENCY`]) instead of one at a time. A digest
+    /// shared by more than one descriptor - the same base-image layer
+    /// appearing twice, say - is only ever fetched once. When `store` is
+    /// given, each digest already present in it is served straight from
+    /// disk with no network request at all; a layer digest not yet
+    /// present is streamed straight into the store via
+    /// [`Self::download_blob`], so memory use stays bounded by one chunk
+    /// at a time no matter the layer's size rather than by the config
+    /// (which is small and still fetched whole, since the caller needs
+    /// its actual bytes to parse).
+    pub async fn pull_image(
+        &self,
+        reference: &ImageReference,
+        progress: Option<std::sync::Arc<dyn PullProgress>>,
+        store: Option<LayerStore>,
+    ) -> Result<PulledImage, RegistryError> {
+        self.pull_image_with_concurrency(reference, DEFAULT_PULL_CONCURRENCY, progress, store).await
+    }
+
+    /// Same as [`Self::pull_image`], but with an explicit bound on how many
+    /// blobs may be in flight at once.
+    pub async fn pull_image_with_concurrency(
+        &self,
+        reference: &ImageReference,
+        concurrency: usize,
+        progress: Option<std::sync::Arc<dyn PullProgress>>,
+        store: Option<LayerStore>,
+    ) -> Result<PulledImage, RegistryError> {
+        let manifest = self.get_manifest(reference).await?;
+
+        let config_digest = manifest.config().digest().to_string();
+        let mut wanted_digests = vec![config_digest.clone()];
+        for layer in manifest.layers() {
+            wanted_digests.push(layer.digest().to_string());
+        }
+
+        let mut unique_digests = Vec::new();
+        for digest in &wanted_digests {
+            if !unique_digests.contains(digest) {
+                unique_digests.push(digest.clone());
+            }
+        }
+
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(concurrency.max(1)));
+        let mut tasks = tokio::task::JoinSet::new();
+
+        for digest in unique_digests {
+            // Only the config blob's actual bytes are needed by the
+            // caller (to parse as JSON) - everything else just needs to
+            // land verified on disk, so it's fetched as a bounded-memory
+            // stream instead.
+            let needs_bytes = digest == config_digest;
+            let client = self.clone();
+            let reference = reference.clone();
+            let semaphore = semaphore.clone();
+            let progress = progress.clone();
+            let store = store.clone();
+            tasks.spawn(async move {
+                if needs_bytes {
+                    if let Some(store) = &store {
+                        if let Some(data) = store.read(&digest)? {
+                            if let Some(progress) = &progress {
+                                progress.on_blob_complete(&digest, data.len() as u64);
+                            }
+                            return Ok::<_, RegistryError>((digest, FetchedBlob::Bytes(data)));
+                        }
+                    }
+
+                    let _permit = semaphore.acquire_owned().await.expect("pull_image semaphore was closed");
+                    let data = client.get_blob(&reference, &digest).await?;
+                    if let Some(store) = &store {
+                        store.insert(&digest, &data).map_err(|e| RegistryError::RegistryError(e.to_string()))?;
+                    }
+                    if let Some(progress) = &progress {
+                        progress.on_blob_complete(&digest, data.len() as u64);
+                    }
+                    return Ok::<_, RegistryError>((digest, FetchedBlob::Bytes(data)));
+                }
+
+                if let Some(store) = &store {
+                    if let Some(size) = store.blob_size(&digest).map_err(|e| RegistryError::RegistryError(e.to_string()))? {
+                        if let Some(progress) = &progress {
+                            progress.on_blob_complete(&digest, size);
+                        }
+                        return Ok::<_, RegistryError>((digest, FetchedBlob::Size(size)));
+                    }
+
+                    let _permit = semaphore.acquire_owned().await.expect("pull_image semaphore was closed");
+                    let staging_path = store.staging_path(&digest);
+                    let size = client.download_blob(&reference, &digest, &staging_path).await?;
+                    store
+                        .commit_staged(&staging_path, &digest)
+                        .map_err(|e| RegistryError::RegistryError(e.to_string()))?;
+                    if let Some(progress) = &progress {
+                        progress.on_blob_complete(&digest, size);
+                    }
+                    return Ok::<_, RegistryError>((digest, FetchedBlob::Size(size)));
+                }
+
+                // No local store at all - there's nowhere to stream to,
+                // so fall back to an in-memory fetch purely to learn the
+                // size; the bytes are dropped once we have it.
+                let _permit = semaphore.acquire_owned().await.expect("pull_image semaphore was closed");
+                let data = client.get_blob(&reference, &digest).await?;
+                let size = data.len() as u64;
+                if let Some(progress) = &progress {
+                    progress.on_blob_complete(&digest, size);
+                }
+                Ok::<_, RegistryError>((digest, FetchedBlob::Size(size)))
+            });
+        }
+
+        let mut fetched: std::collections::HashMap<String, FetchedBlob> = std::collections::HashMap::new();
+        while let Some(result) = tasks.join_next().await {
+            let (digest, blob) = result
+                .map_err(|e| RegistryError::RegistryError(format!("blob fetch task panicked: {}", e)))??;
+            fetched.insert(digest, blob);
+        }
+
+        let config = match fetched.remove(&config_digest) {
+            Some(FetchedBlob::Bytes(data)) => data,
+            _ => return Err(RegistryError::RegistryError(format!("missing fetched config blob {}", config_digest))),
+        };
+
+        let mut layers = Vec::with_capacity(manifest.layers().len());
+        for layer in manifest.layers() {
+            let digest = layer.digest().to_string();
+            let size = match fetched.get(&digest) {
+                Some(FetchedBlob::Size(size)) => *size,
+                Some(FetchedBlob::Bytes(data)) => data.len() as u64,
+                None => return Err(RegistryError::RegistryError(format!("missing fetched layer blob {}", digest))),
+            };
+            layers.push(PulledBlob { digest, size });
+        }
+
+        Ok(PulledImage { manifest, config, layers })
     }
 }
 
+/// How many blobs [`RegistryClient::pull_image`] fetches at once when the
+/// caller doesn't ask for a specific bound.
+const DEFAULT_PULL_CONCURRENCY: usize = 4;
+
+/// What a single blob fetch in [`RegistryClient::pull_image_with_concurrency`]
+/// produced - the config blob's actual bytes (needed to parse it), or just
+/// a layer's size once it's verified and safely on disk.
+enum FetchedBlob {
+    Bytes(Vec<u8>),
+    Size(u64),
+}
+
+/// One layer blob fetched by [`RegistryClient::pull_image`]. Carries only
+/// its size, not its bytes - the blob itself lives in the [`LayerStore`]
+/// passed to `pull_image`, streamed there directly rather than held in
+/// memory for the whole pull.
+#[derive(Debug, Clone)]
+pub struct PulledBlob {
+    pub digest: String,
+    pub size: u64,
+}
+
+/// The full result of a [`RegistryClient::pull_image`] call: the manifest
+/// plus every blob it names, already downloaded and digest-verified.
+#[derive(Debug, Clone)]
+pub struct PulledImage {
+    pub manifest: ImageManifest,
+    pub config: Vec<u8>,
+    pub layers: Vec<PulledBlob>,
+}
+
+/// Reports per-blob completion during [`RegistryClient::pull_image`], so a
+/// caller can render per-layer progress bars. Implementations must be
+/// cheap and non-blocking - this is invoked from inside the download task
+/// for each blob as soon as it finishes.
+pub trait PullProgress: Send + Sync {
+    fn on_blob_complete(&self, digest: &str, bytes: u64);
+}
+
 impl Default for RegistryClient {
     fn default() -> Self {
         Self::new()
@@ -398,6 +1348,175 @@ impl Default for RegistryClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parses_bearer_challenge() {
+        let parts = parse_bearer_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/alpine:pull""#,
+        );
+        assert_eq!(parts.get("realm"), Some(&"https://auth.docker.io/token"));
+        assert_eq!(parts.get("service"), Some(&"registry.docker.io"));
+        assert_eq!(parts.get("scope"), Some(&"repository:library/alpine:pull"));
+    }
+
+    #[test]
+    fn resolves_absolute_upload_location_as_is() {
+        let url = resolve_upload_location("ghcr.io", "https://uploads.ghcr.io/v2/owner/repo/blobs/uploads/abc?x=1");
+        assert_eq!(url, "https://uploads.ghcr.io/v2/owner/repo/blobs/uploads/abc?x=1");
+    }
+
+    #[test]
+    fn resolves_relative_upload_location_against_registry() {
+        let url = resolve_upload_location("ghcr.io", "/v2/owner/repo/blobs/uploads/abc?x=1");
+        assert_eq!(url, "https://ghcr.io/v2/owner/repo/blobs/uploads/abc?x=1");
+    }
+
+    #[test]
+    fn appends_digest_param_with_right_separator() {
+        assert_eq!(
+            append_digest_param("https://ghcr.io/upload/abc", "sha256:deadbeef"),
+            "https://ghcr.io/upload/abc?digest=sha256:deadbeef"
+        );
+        assert_eq!(
+            append_digest_param("https://ghcr.io/upload/abc?x=1", "sha256:deadbeef"),
+            "https://ghcr.io/upload/abc?x=1&digest=sha256:deadbeef"
+        );
+    }
+
+    #[test]
+    fn auth_lookup_key_normalizes_docker_hub_aliases() {
+        assert_eq!(auth_lookup_key("docker.io"), "https://index.docker.io/v1/");
+        assert_eq!(auth_lookup_key("registry-1.docker.io"), "https://index.docker.io/v1/");
+        assert_eq!(auth_lookup_key("ghcr.io"), "https://ghcr.io");
+        assert_eq!(auth_lookup_key("https://ghcr.io"), "https://ghcr.io");
+    }
+
+    #[test]
+    fn loads_basic_credentials_from_docker_config_style_file() {
+        let dir = std::env::temp_dir().join(format!("vordr-registry-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+        std::fs::write(
+            &path,
+            r#"{"auths":{"https://ghcr.io":{"auth":"dXNlcjpwYXNz"},"registry.example.com":{"identitytoken":"abc"}}}"#,
+        )
+        .unwrap();
+
+        let client = RegistryClient::new();
+        client.load_auths_file(&path).unwrap();
+
+        let credentials = client.credentials.lock().unwrap();
+        assert_eq!(
+            credentials.get("https://ghcr.io"),
+            Some(&("user".to_string(), "pass".to_string()))
+        );
+        assert!(!credentials.contains_key("registry.example.com"));
+        drop(credentials);
+
+        let identity_tokens = client.identity_tokens.lock().unwrap();
+        assert_eq!(identity_tokens.get("registry.example.com"), Some(&"abc".to_string()));
+        drop(identity_tokens);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn loads_paseto_key_from_docker_config_style_file() {
+        let key_pair = paseto::PasetoKeyPair::generate();
+        let dir = std::env::temp_dir().join(format!("vordr-registry-paseto-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+        std::fs::write(
+            &path,
+            serde_json::json!({
+                "auths": {
+                    "https://ghcr.io": {"username": "alice", "paseto_key": key_pair.to_stored()}
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let client = RegistryClient::new();
+        client.load_auths_file(&path).unwrap();
+
+        let paseto_keys = client.paseto_keys.lock().unwrap();
+        let (username, stored_pair) = paseto_keys.get("https://ghcr.io").expect("paseto key loaded");
+        assert_eq!(username, "alice");
+        assert_eq!(stored_pair.key_id(), key_pair.key_id());
+        drop(paseto_keys);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn with_credentials_is_keyed_by_normalized_registry() {
+        let client = RegistryClient::new().with_credentials("ghcr.io", "user", "pass");
+        assert_eq!(
+            client.credentials.lock().unwrap().get("https://ghcr.io"),
+            Some(&("user".to_string(), "pass".to_string()))
+        );
+    }
+
+    #[test]
+    fn platform_matches_ignores_variant_when_selector_has_none() {
+        let wanted = Platform {
+            os: "linux".to_string(),
+            architecture: "arm64".to_string(),
+            variant: None,
+        };
+        let candidate = Platform {
+            os: "linux".to_string(),
+            architecture: "arm64".to_string(),
+            variant: Some("v8".to_string()),
+        };
+        assert!(wanted.matches(&candidate));
+    }
+
+    #[test]
+    fn platform_matches_requires_exact_variant_when_selector_has_one() {
+        let wanted = Platform {
+            os: "linux".to_string(),
+            architecture: "arm".to_string(),
+            variant: Some("v7".to_string()),
+        };
+        let v8 = Platform {
+            os: "linux".to_string(),
+            architecture: "arm".to_string(),
+            variant: Some("v8".to_string()),
+        };
+        assert!(!wanted.matches(&v8));
+    }
+
+    #[test]
+    fn platform_matches_rejects_different_architecture() {
+        let wanted = Platform {
+            os: "linux".to_string(),
+            architecture: "amd64".to_string(),
+            variant: None,
+        };
+        let arm64 = Platform {
+            os: "linux".to_string(),
+            architecture: "arm64".to_string(),
+            variant: None,
+        };
+        assert!(!wanted.matches(&arm64));
+    }
+
+    #[test]
+    fn goarch_maps_rust_arch_names() {
+        assert_eq!(goarch("x86_64"), "amd64");
+        assert_eq!(goarch("aarch64"), "arm64");
+        assert_eq!(goarch("x86"), "386");
+        assert_eq!(goarch("riscv64"), "riscv64");
+    }
+
+    #[test]
+    fn is_index_media_type_accepts_both_oci_and_docker_forms() {
+        assert!(is_index_media_type(OCI_INDEX_MEDIA_TYPE));
+        assert!(is_index_media_type(DOCKER_MANIFEST_LIST_MEDIA_TYPE));
+        assert!(!is_index_media_type("application/vnd.oci.image.manifest.v1+json"));
+    }
+
     #[test]
     fn test_parse_simple_image() {
         let ref1 = ImageReference::parse("alpine").unwrap();
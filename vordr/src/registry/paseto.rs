@@ -0,0 +1,228 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! PASETO v4.public tokens for asymmetric registry authentication
+//!
+//! Implements just enough of the v4.public profile (<https://paseto.io>)
+//! to mint and verify the short-lived bearer tokens `svalinn login
+//! --asymmetric` hands registries instead of a password: Ed25519-signed
+//! claims with a PASERK key-id footer, so a registry trusting several
+//! keys can tell which one to verify against. This is not a general
+//! PASETO library - only the one token type used here.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha384};
+use thiserror::Error;
+
+const HEADER: &str = "v4.public.";
+const SIGNATURE_LEN: usize = 64;
+
+#[derive(Error, Debug)]
+pub enum PasetoError {
+    #[error("token is not a v4.public token")]
+    BadHeader,
+    #[error("token is malformed: {0}")]
+    Malformed(String),
+    #[error("signature verification failed")]
+    BadSignature,
+    #[error("token has expired")]
+    Expired,
+    #[error("token audience does not match {0}")]
+    AudienceMismatch(String),
+    #[error("base64 decode error: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// An Ed25519 key pair for signing/verifying v4.public tokens. Persisted
+/// as just the base64-encoded secret seed in `RegistryAuth::paseto_key` -
+/// never a password.
+#[derive(Clone)]
+pub struct PasetoKeyPair {
+    signing_key: SigningKey,
+}
+
+impl PasetoKeyPair {
+    pub fn generate() -> Self {
+        Self {
+            signing_key: SigningKey::generate(&mut rand::rngs::OsRng),
+        }
+    }
+
+    pub fn to_stored(&self) -> String {
+        base64::engine::general_purpose::STANDARD.encode(self.signing_key.to_bytes())
+    }
+
+    pub fn from_stored(stored: &str) -> Result<Self, PasetoError> {
+        let bytes = base64::engine::general_purpose::STANDARD.decode(stored)?;
+        let seed: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| PasetoError::Malformed("stored key is not a 32-byte Ed25519 seed".to_string()))?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&seed),
+        })
+    }
+
+    fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// The PASERK key-id (`k4.pid....`) for this key's public half -
+    /// `base64url(sha384("k4.pid." || public_key)[..33])`, prefixed with
+    /// its own type tag. Used as the token footer so a verifier knows
+    /// which key was used without the claims themselves naming one.
+    pub fn key_id(&self) -> String {
+        let mut hasher = Sha384::new();
+        hasher.update(b"k4.pid.");
+        hasher.update(self.verifying_key().as_bytes());
+        let digest = hasher.finalize();
+        format!(
+            "k4.pid.{}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&digest[..33])
+        )
+    }
+}
+
+/// The claims carried by a minted token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub aud: String,
+    pub iat: u64,
+    pub exp: u64,
+}
+
+/// Pre-Authentication Encoding (PAE): a length-prefixed concatenation of
+/// each piece, so the signed message unambiguously separates
+/// header/payload/footer rather than relying on delimiters that could
+/// appear inside them.
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(pieces.len() as u64).to_le_bytes());
+    for piece in pieces {
+        out.extend_from_slice(&(piece.len() as u64).to_le_bytes());
+        out.extend_from_slice(piece);
+    }
+    out
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Mints a `v4.public` token asserting `username` for `registry`, valid
+/// for `ttl` from now.
+pub fn mint(key_pair: &PasetoKeyPair, username: &str, registry: &str, ttl: Duration) -> Result<String, PasetoError> {
+    let iat = now_unix();
+    let claims = Claims {
+        sub: username.to_string(),
+        aud: registry.to_string(),
+        iat,
+        exp: iat + ttl.as_secs(),
+    };
+    let payload = serde_json::to_vec(&claims)?;
+    let footer = key_pair.key_id();
+
+    let to_sign = pae(&[HEADER.as_bytes(), &payload, footer.as_bytes()]);
+    let signature: Signature = key_pair.signing_key.sign(&to_sign);
+
+    let mut signed_body = payload;
+    signed_body.extend_from_slice(&signature.to_bytes());
+
+    Ok(format!(
+        "{}{}.{}",
+        HEADER,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&signed_body),
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(footer.as_bytes())
+    ))
+}
+
+/// Verifies a `v4.public` token's signature against `key_pair`, then
+/// rejects it if `exp` is in the past or `aud` doesn't match
+/// `expected_registry` (already `normalize_registry`-d). Returns the
+/// parsed claims on success.
+pub fn verify(key_pair: &PasetoKeyPair, token: &str, expected_registry: &str) -> Result<Claims, PasetoError> {
+    let rest = token.strip_prefix(HEADER).ok_or(PasetoError::BadHeader)?;
+    let mut parts = rest.splitn(2, '.');
+    let body_b64 = parts.next().filter(|s| !s.is_empty()).ok_or_else(|| PasetoError::Malformed("missing body".to_string()))?;
+    let footer_b64 = parts.next().unwrap_or_default();
+
+    let body = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(body_b64)?;
+    if body.len() <= SIGNATURE_LEN {
+        return Err(PasetoError::Malformed("body too short to contain a signature".to_string()));
+    }
+    let (payload, sig_bytes) = body.split_at(body.len() - SIGNATURE_LEN);
+    let footer = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(footer_b64)?;
+
+    let to_verify = pae(&[HEADER.as_bytes(), payload, &footer]);
+    let signature = Signature::from_slice(sig_bytes).map_err(|_| PasetoError::BadSignature)?;
+    key_pair
+        .verifying_key()
+        .verify(&to_verify, &signature)
+        .map_err(|_| PasetoError::BadSignature)?;
+
+    let claims: Claims = serde_json::from_slice(payload)?;
+
+    if claims.exp < now_unix() {
+        return Err(PasetoError::Expired);
+    }
+    if claims.aud != expected_registry {
+        return Err(PasetoError::AudienceMismatch(expected_registry.to_string()));
+    }
+
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mints_and_verifies_a_token() {
+        let key_pair = PasetoKeyPair::generate();
+        let token = mint(&key_pair, "alice", "https://ghcr.io", Duration::from_secs(300)).unwrap();
+        assert!(token.starts_with(HEADER));
+
+        let claims = verify(&key_pair, &token, "https://ghcr.io").unwrap();
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.aud, "https://ghcr.io");
+    }
+
+    #[test]
+    fn rejects_expired_token() {
+        let key_pair = PasetoKeyPair::generate();
+        let token = mint(&key_pair, "alice", "https://ghcr.io", Duration::from_secs(0)).unwrap();
+        // exp == iat, and verification runs strictly after minting.
+        std::thread::sleep(Duration::from_millis(1100));
+        let err = verify(&key_pair, &token, "https://ghcr.io").unwrap_err();
+        assert!(matches!(err, PasetoError::Expired));
+    }
+
+    #[test]
+    fn rejects_audience_mismatch() {
+        let key_pair = PasetoKeyPair::generate();
+        let token = mint(&key_pair, "alice", "https://ghcr.io", Duration::from_secs(300)).unwrap();
+        let err = verify(&key_pair, &token, "https://quay.io").unwrap_err();
+        assert!(matches!(err, PasetoError::AudienceMismatch(_)));
+    }
+
+    #[test]
+    fn rejects_tampered_signature() {
+        let key_pair = PasetoKeyPair::generate();
+        let mut token = mint(&key_pair, "alice", "https://ghcr.io", Duration::from_secs(300)).unwrap();
+        token.push('x');
+        let other_key = PasetoKeyPair::generate();
+        assert!(verify(&other_key, &token, "https://ghcr.io").is_err());
+    }
+
+    #[test]
+    fn stored_key_round_trips() {
+        let key_pair = PasetoKeyPair::generate();
+        let stored = key_pair.to_stored();
+        let restored = PasetoKeyPair::from_stored(&stored).unwrap();
+        assert_eq!(key_pair.key_id(), restored.key_id());
+    }
+}
@@ -0,0 +1,188 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Wire protocol shared by `vordr manager` and any CLI invocation using
+//! `--host vordr://<addr>` to target it.
+//!
+//! Framing is newline-delimited JSON over a Unix socket: simple to log and
+//! to hand-decode, which matters while this protocol is still growing one
+//! relayed command at a time. Every connection opens with a version and
+//! capability [`Handshake`] so an older client fails fast against an
+//! incompatible manager instead of mis-parsing its responses.
+
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+
+/// Protocol version advertised in the handshake. Two peers are considered
+/// compatible when their major version component matches.
+pub const PROTOCOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Commands the manager side knows how to execute on behalf of a remote
+/// client today. Grows as more subcommands gain remote relaying.
+pub const SUPPORTED_COMMANDS: &[&str] = &["ps"];
+
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    #[error("invalid --host address {0:?}: {1}")]
+    InvalidAddr(String, String),
+    #[error("failed to connect to manager at {0}: {1}")]
+    ConnectionFailed(String, String),
+    #[error(
+        "manager handshake failed: client is v{client_version} but manager is v{manager_version} ({reason})"
+    )]
+    IncompatibleHandshake {
+        client_version: String,
+        manager_version: String,
+        reason: String,
+    },
+    #[error("manager returned an error: {0}")]
+    RemoteFailure(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed message: {0}")]
+    Protocol(#[from] serde_json::Error),
+}
+
+/// A parsed `--host` endpoint. Only Unix socket paths are supported today;
+/// `tcp`/`tls` endpoints are left for a later change once the manager has
+/// real transport security.
+#[derive(Debug, Clone)]
+pub enum RemoteAddr {
+    Unix(PathBuf),
+}
+
+impl RemoteAddr {
+    pub fn parse(raw: &str) -> Result<Self, RemoteError> {
+        let rest = raw.strip_prefix("vordr://").ok_or_else(|| {
+            RemoteError::InvalidAddr(raw.to_string(), "expected a vordr:// URL".to_string())
+        })?;
+
+        if rest.starts_with('/') {
+            Ok(RemoteAddr::Unix(PathBuf::from(rest)))
+        } else {
+            Err(RemoteError::InvalidAddr(
+                raw.to_string(),
+                "only unix socket paths (vordr:///path/to.sock) are supported today".to_string(),
+            ))
+        }
+    }
+}
+
+/// Exchanged as the first message in both directions on every connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Handshake {
+    pub version: String,
+    pub capabilities: Vec<String>,
+}
+
+/// A command relayed to the manager, with its arguments carried as JSON so
+/// new commands don't need a new envelope type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteRequest {
+    pub command: String,
+    pub payload: serde_json::Value,
+}
+
+/// The manager's reply to a [`RemoteRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteResponse {
+    pub ok: bool,
+    pub payload: serde_json::Value,
+    pub error: Option<String>,
+}
+
+fn major_version(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+pub async fn write_json_line<T: Serialize>(
+    writer: &mut (impl AsyncWrite + Unpin),
+    value: &T,
+) -> Result<(), RemoteError> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+pub async fn read_json_line<T: DeserializeOwned>(
+    reader: &mut (impl tokio::io::AsyncBufRead + Unpin),
+) -> Result<T, RemoteError> {
+    let mut line = String::new();
+    let bytes_read = reader.read_line(&mut line).await?;
+    if bytes_read == 0 {
+        return Err(RemoteError::ConnectionFailed(
+            "<remote>".to_string(),
+            "connection closed before a response arrived".to_string(),
+        ));
+    }
+    Ok(serde_json::from_str(line.trim_end())?)
+}
+
+/// A connected, handshaken session to a `vordr manager`.
+pub struct RemoteClient {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl RemoteClient {
+    /// Dials `addr` and performs the version/capability handshake,
+    /// rejecting a manager whose major protocol version doesn't match
+    /// ours rather than risk mis-parsing its responses.
+    pub async fn connect(addr: &RemoteAddr) -> Result<Self, RemoteError> {
+        let RemoteAddr::Unix(path) = addr;
+        let stream = UnixStream::connect(path)
+            .await
+            .map_err(|e| RemoteError::ConnectionFailed(path.display().to_string(), e.to_string()))?;
+        let (read_half, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(read_half);
+
+        write_json_line(
+            &mut writer,
+            &Handshake {
+                version: PROTOCOL_VERSION.to_string(),
+                capabilities: SUPPORTED_COMMANDS.iter().map(|s| s.to_string()).collect(),
+            },
+        )
+        .await?;
+        let manager_handshake: Handshake = read_json_line(&mut reader).await?;
+
+        if major_version(&manager_handshake.version) != major_version(PROTOCOL_VERSION) {
+            return Err(RemoteError::IncompatibleHandshake {
+                client_version: PROTOCOL_VERSION.to_string(),
+                manager_version: manager_handshake.version,
+                reason: "major version mismatch".to_string(),
+            });
+        }
+
+        Ok(Self { reader, writer })
+    }
+
+    /// Sends `command` with `payload` and waits for the manager's reply.
+    pub async fn call(
+        &mut self,
+        command: &str,
+        payload: serde_json::Value,
+    ) -> Result<serde_json::Value, RemoteError> {
+        write_json_line(
+            &mut self.writer,
+            &RemoteRequest {
+                command: command.to_string(),
+                payload,
+            },
+        )
+        .await?;
+
+        let response: RemoteResponse = read_json_line(&mut self.reader).await?;
+        if response.ok {
+            Ok(response.payload)
+        } else {
+            Err(RemoteError::RemoteFailure(
+                response.error.unwrap_or_else(|| "unknown error".to_string()),
+            ))
+        }
+    }
+}
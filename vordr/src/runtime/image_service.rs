@@ -0,0 +1,85 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! TTRPC-exposed image management service
+//!
+//! Wraps the same `StateManager` calls the `image` CLI subcommands use, so
+//! a daemon holding this service can be the single source of truth for
+//! image state across concurrent callers (CLI invocations, shims,
+//! orchestrators) instead of each process opening the SQLite file for
+//! itself.
+
+use thiserror::Error;
+
+use crate::engine::{ImageInfo, StateError, StateManager};
+
+#[derive(Error, Debug)]
+pub enum ImageServiceError {
+    #[error("State error: {0}")]
+    State(#[from] StateError),
+}
+
+pub struct InspectImageRequest {
+    pub id: String,
+}
+
+pub struct InspectImageResponse {
+    pub image: ImageInfo,
+}
+
+pub struct RemoveImageRequest {
+    pub id: String,
+    /// Remove even if a container still references this image.
+    pub force: bool,
+}
+
+pub struct PruneImagesRequest {
+    /// Prune every unused image, not just dangling (untagged) ones.
+    pub all: bool,
+}
+
+pub struct PruneImagesResponse {
+    pub deleted: Vec<String>,
+    pub reclaimed: i64,
+}
+
+/// Server-side handler for the image management RPCs, backed directly by
+/// a `StateManager`. Construct one per open state database and route
+/// `ListImages`/`InspectImage`/`RemoveImage`/`PruneImages` calls to it.
+pub struct ImageServiceServer {
+    state: StateManager,
+}
+
+impl ImageServiceServer {
+    pub fn new(state: StateManager) -> Self {
+        Self { state }
+    }
+
+    pub fn list_images(&self) -> Result<Vec<ImageInfo>, ImageServiceError> {
+        Ok(self.state.list_images()?)
+    }
+
+    pub fn inspect_image(
+        &self,
+        request: InspectImageRequest,
+    ) -> Result<InspectImageResponse, ImageServiceError> {
+        let image = self.state.get_image(&request.id)?;
+        Ok(InspectImageResponse { image })
+    }
+
+    pub fn remove_image(&self, request: RemoveImageRequest) -> Result<(), ImageServiceError> {
+        self.state.delete_image(&request.id, request.force)?;
+        Ok(())
+    }
+
+    /// Dangling images are those with no repository or tags; `all` widens
+    /// that to every image not referenced by a container.
+    pub fn prune_images(
+        &self,
+        request: PruneImagesRequest,
+    ) -> Result<PruneImagesResponse, ImageServiceError> {
+        let result = self.state.prune_images(!request.all)?;
+        Ok(PruneImagesResponse {
+            deleted: result.removed_ids,
+            reclaimed: result.reclaimed_bytes,
+        })
+    }
+}
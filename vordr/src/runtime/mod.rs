@@ -1,7 +1,10 @@
 //! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
 //! Container runtime integration
 
+pub mod image_service;
 pub mod shim;
 pub mod ttrpc;
 
-pub use shim::ShimClient;
+pub use image_service::ImageServiceServer;
+pub use shim::{CheckpointOptions, ShimClient};
+pub use ttrpc::{daemon_socket_path, ImageServiceClient};
@@ -2,11 +2,23 @@
 //! TTRPC client for shim communication
 //!
 //! This module provides TTRPC-based communication with container shims,
-//! compatible with the containerd shim v2 protocol.
+//! compatible with the containerd shim v2 protocol. TTRPC frames every
+//! message over the Unix socket with a fixed 10-byte header (4-byte
+//! big-endian payload length, 4-byte stream ID, 1-byte message type,
+//! 1-byte flags) followed by a protobuf-encoded body, and multiplexes
+//! concurrent calls on a single connection by stream ID.
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
+
 use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+use tokio::sync::{oneshot, Mutex as AsyncMutex};
 
 #[derive(Error, Debug)]
 pub enum TtrpcError {
@@ -20,10 +32,29 @@ pub enum TtrpcError {
     Io(#[from] std::io::Error),
 }
 
+const HEADER_LEN: usize = 10;
+const MESSAGE_TYPE_REQUEST: u8 = 1;
+const MESSAGE_TYPE_RESPONSE: u8 = 2;
+
+const TASK_SERVICE: &str = "containerd.task.v2.Task";
+
+type PendingMap = Arc<StdMutex<HashMap<u32, oneshot::Sender<Result<Vec<u8>, TtrpcError>>>>>;
+
+/// One live connection to a shim socket: the write half (requests may be
+/// sent from several calling tasks at once, so it is guarded by an async
+/// mutex) plus the table of in-flight calls keyed by stream ID, drained by
+/// the background reader task spawned in [`TtrpcClient::ensure_connected`].
+struct Connection {
+    writer: AsyncMutex<OwnedWriteHalf>,
+    pending: PendingMap,
+    next_stream_id: AtomicU32,
+}
+
 /// TTRPC client for shim communication
 pub struct TtrpcClient {
     socket_path: String,
     timeout: Duration,
+    conn: AsyncMutex<Option<Arc<Connection>>>,
 }
 
 impl TtrpcClient {
@@ -32,12 +63,24 @@ impl TtrpcClient {
         Self {
             socket_path: socket_path.as_ref().to_string_lossy().into_owned(),
             timeout: Duration::from_secs(timeout_secs),
+            conn: AsyncMutex::new(None),
         }
     }
 
     /// Connect to the shim socket
     pub async fn connect(&self) -> Result<(), TtrpcError> {
-        // Check if socket exists
+        self.ensure_connected().await?;
+        Ok(())
+    }
+
+    /// Returns the shared connection, dialing the socket and spawning its
+    /// reader task on first use.
+    async fn ensure_connected(&self) -> Result<Arc<Connection>, TtrpcError> {
+        let mut guard = self.conn.lock().await;
+        if let Some(conn) = guard.as_ref() {
+            return Ok(conn.clone());
+        }
+
         let path = Path::new(&self.socket_path);
         if !path.exists() {
             return Err(TtrpcError::ConnectionFailed(format!(
@@ -46,9 +89,51 @@ impl TtrpcClient {
             )));
         }
 
-        // In production, this would establish the TTRPC connection
-        // For now, we verify the socket exists
-        Ok(())
+        let stream = UnixStream::connect(&self.socket_path)
+            .await
+            .map_err(|e| TtrpcError::ConnectionFailed(e.to_string()))?;
+        let (read_half, write_half) = stream.into_split();
+
+        let pending: PendingMap = Arc::new(StdMutex::new(HashMap::new()));
+        spawn_reader(read_half, pending.clone());
+
+        let conn = Arc::new(Connection {
+            writer: AsyncMutex::new(write_half),
+            pending,
+            next_stream_id: AtomicU32::new(1), // client stream IDs are odd
+        });
+        *guard = Some(conn.clone());
+        Ok(conn)
+    }
+
+    /// Sends a request frame for `service`/`method` carrying `payload`,
+    /// waits for the matching response by stream ID, and returns its
+    /// payload - or `TtrpcError::Timeout` if `self.timeout` elapses first.
+    async fn call(&self, service: &str, method: &str, payload: Vec<u8>) -> Result<Vec<u8>, TtrpcError> {
+        let conn = self.ensure_connected().await?;
+        let stream_id = conn.next_stream_id.fetch_add(2, Ordering::SeqCst);
+
+        let body = encode_request(service, method, &payload, self.timeout.as_nanos() as u64);
+        let frame = encode_frame(stream_id, MESSAGE_TYPE_REQUEST, 0, &body);
+
+        let (tx, rx) = oneshot::channel();
+        conn.pending.lock().unwrap().insert(stream_id, tx);
+
+        if let Err(e) = conn.writer.lock().await.write_all(&frame).await {
+            conn.pending.lock().unwrap().remove(&stream_id);
+            return Err(TtrpcError::Io(e));
+        }
+
+        match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(result)) => result.and_then(|body| decode_response(&body)),
+            Ok(Err(_)) => Err(TtrpcError::RpcError(
+                "shim connection closed before a response arrived".to_string(),
+            )),
+            Err(_) => {
+                conn.pending.lock().unwrap().remove(&stream_id);
+                Err(TtrpcError::Timeout(self.timeout.as_secs()))
+            }
+        }
     }
 
     /// Create a container task
@@ -59,8 +144,6 @@ impl TtrpcClient {
         stdout: &str,
         stderr: &str,
     ) -> Result<u32, TtrpcError> {
-        // TODO: Implement TTRPC create call
-        // This would send a CreateTaskRequest and return the PID
         tracing::debug!(
             "TTRPC create: id={}, bundle={}, stdout={}, stderr={}",
             id,
@@ -69,38 +152,338 @@ impl TtrpcClient {
             stderr
         );
 
-        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+        let mut req = Vec::new();
+        encode_string(1, id, &mut req);
+        encode_string(2, bundle, &mut req);
+        encode_string(6, stdout, &mut req);
+        encode_string(7, stderr, &mut req);
+
+        let resp = self.call(TASK_SERVICE, "Create", req).await?;
+        Ok(get_u64(&decode_fields(&resp), 1) as u32)
     }
 
     /// Start a created container
     pub async fn start(&self, id: &str) -> Result<u32, TtrpcError> {
         tracing::debug!("TTRPC start: id={}", id);
-        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+
+        let mut req = Vec::new();
+        encode_string(1, id, &mut req);
+
+        let resp = self.call(TASK_SERVICE, "Start", req).await?;
+        Ok(get_u64(&decode_fields(&resp), 1) as u32)
     }
 
     /// Kill a container process
     pub async fn kill(&self, id: &str, signal: u32, all: bool) -> Result<(), TtrpcError> {
         tracing::debug!("TTRPC kill: id={}, signal={}, all={}", id, signal, all);
-        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+
+        let mut req = Vec::new();
+        encode_string(1, id, &mut req);
+        encode_varint_field(3, signal as u64, &mut req);
+        encode_bool(4, all, &mut req);
+
+        self.call(TASK_SERVICE, "Kill", req).await?;
+        Ok(())
     }
 
     /// Delete a container
     pub async fn delete(&self, id: &str) -> Result<(u32, u32), TtrpcError> {
         tracing::debug!("TTRPC delete: id={}", id);
-        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+
+        let mut req = Vec::new();
+        encode_string(1, id, &mut req);
+
+        let resp = self.call(TASK_SERVICE, "Delete", req).await?;
+        let fields = decode_fields(&resp);
+        let exit_status = get_u64(&fields, 4) as u32;
+        let pid = get_u64(&fields, 3) as u32;
+        Ok((exit_status, pid))
     }
 
     /// Wait for container exit
     pub async fn wait(&self, id: &str) -> Result<u32, TtrpcError> {
         tracing::debug!("TTRPC wait: id={}", id);
-        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+
+        let mut req = Vec::new();
+        encode_string(1, id, &mut req);
+
+        let resp = self.call(TASK_SERVICE, "Wait", req).await?;
+        Ok(get_u64(&decode_fields(&resp), 1) as u32)
     }
 
     /// Get container state
     pub async fn state(&self, id: &str) -> Result<TaskState, TtrpcError> {
         tracing::debug!("TTRPC state: id={}", id);
+
+        let mut req = Vec::new();
+        encode_string(1, id, &mut req);
+
+        let resp = self.call(TASK_SERVICE, "State", req).await?;
+        let fields = decode_fields(&resp);
+        Ok(TaskState {
+            id: get_string(&fields, 1),
+            bundle: get_string(&fields, 2),
+            pid: get_u64(&fields, 3) as u32,
+            status: TaskStatus::from_proto(get_u64(&fields, 4)),
+        })
+    }
+}
+
+/// Reads frames off the socket for as long as the connection lives,
+/// handing each response body to whichever caller is waiting on its
+/// stream ID. Call frames going the other direction (the shim calling
+/// back into us) are not expected on this client and are dropped.
+fn spawn_reader(mut reader: OwnedReadHalf, pending: PendingMap) {
+    tokio::spawn(async move {
+        loop {
+            match read_frame(&mut reader).await {
+                Ok((stream_id, msg_type, body)) => {
+                    if msg_type != MESSAGE_TYPE_RESPONSE {
+                        continue;
+                    }
+                    if let Some(tx) = pending.lock().unwrap().remove(&stream_id) {
+                        let _ = tx.send(Ok(body));
+                    }
+                }
+                Err(e) => {
+                    let message = e.to_string();
+                    for (_, tx) in pending.lock().unwrap().drain() {
+                        let _ = tx.send(Err(TtrpcError::ConnectionFailed(message.clone())));
+                    }
+                    break;
+                }
+            }
+        }
+    });
+}
+
+fn encode_frame(stream_id: u32, msg_type: u8, flags: u8, body: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(HEADER_LEN + body.len());
+    frame.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&stream_id.to_be_bytes());
+    frame.push(msg_type);
+    frame.push(flags);
+    frame.extend_from_slice(body);
+    frame
+}
+
+async fn read_frame(reader: &mut OwnedReadHalf) -> std::io::Result<(u32, u8, Vec<u8>)> {
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header).await?;
+    let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let stream_id = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let msg_type = header[8];
+
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body).await?;
+    Ok((stream_id, msg_type, body))
+}
+
+/// Builds a ttrpc `Request` message: `service` (1), `method` (2), `payload`
+/// (3, the serialized method-argument message), `timeout_nano` (4).
+fn encode_request(service: &str, method: &str, payload: &[u8], timeout_nano: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_string(1, service, &mut out);
+    encode_string(2, method, &mut out);
+    encode_bytes(3, payload, &mut out);
+    encode_varint_field(4, timeout_nano, &mut out);
+    out
+}
+
+/// Parses a ttrpc `Response` message: a `Status` (1, grpc code + message)
+/// and the serialized result `payload` (2). A non-zero status code is
+/// surfaced as [`TtrpcError::RpcError`].
+fn decode_response(data: &[u8]) -> Result<Vec<u8>, TtrpcError> {
+    let fields = decode_fields(data);
+    let (code, message) = match fields.get(&1) {
+        Some(Field::LengthDelimited(status)) => {
+            let status_fields = decode_fields(status);
+            (get_u64(&status_fields, 1), get_string(&status_fields, 2))
+        }
+        _ => (0, String::new()),
+    };
+
+    if code != 0 {
+        return Err(TtrpcError::RpcError(if message.is_empty() {
+            format!("shim returned grpc status {}", code)
+        } else {
+            message
+        }));
+    }
+
+    Ok(get_bytes(&fields, 2))
+}
+
+// --- Minimal hand-rolled protobuf wire format -------------------------------
+//
+// There is no protobuf codegen in this tree, so request/response messages
+// are encoded and decoded directly against the wire format: varints, and
+// length-delimited (string/bytes/submessage) fields tagged with
+// `(field_number << 3) | wire_type`. proto3 omits fields at their default
+// value, so the encoders below skip zero/empty values to match.
+
+enum Field {
+    Varint(u64),
+    LengthDelimited(Vec<u8>),
+}
+
+fn encode_varint(value: u64, out: &mut Vec<u8>) {
+    let mut v = value;
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if v == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn decode_varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+        shift += 7;
+    }
+}
+
+fn encode_tag(field: u32, wire_type: u8, out: &mut Vec<u8>) {
+    encode_varint(((field as u64) << 3) | wire_type as u64, out);
+}
+
+fn encode_varint_field(field: u32, value: u64, out: &mut Vec<u8>) {
+    if value == 0 {
+        return;
+    }
+    encode_tag(field, 0, out);
+    encode_varint(value, out);
+}
+
+fn encode_bool(field: u32, value: bool, out: &mut Vec<u8>) {
+    if !value {
+        return;
+    }
+    encode_tag(field, 0, out);
+    encode_varint(1, out);
+}
+
+fn encode_bytes(field: u32, value: &[u8], out: &mut Vec<u8>) {
+    if value.is_empty() {
+        return;
+    }
+    encode_tag(field, 2, out);
+    encode_varint(value.len() as u64, out);
+    out.extend_from_slice(value);
+}
+
+fn encode_string(field: u32, value: &str, out: &mut Vec<u8>) {
+    encode_bytes(field, value.as_bytes(), out);
+}
+
+fn decode_fields(data: &[u8]) -> HashMap<u32, Field> {
+    let mut fields = HashMap::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let Some(tag) = decode_varint(data, &mut pos) else {
+            break;
+        };
+        let field = (tag >> 3) as u32;
+        match tag & 0x7 {
+            0 => {
+                let Some(v) = decode_varint(data, &mut pos) else {
+                    break;
+                };
+                fields.insert(field, Field::Varint(v));
+            }
+            2 => {
+                let Some(len) = decode_varint(data, &mut pos) else {
+                    break;
+                };
+                let len = len as usize;
+                if pos + len > data.len() {
+                    break;
+                }
+                fields.insert(field, Field::LengthDelimited(data[pos..pos + len].to_vec()));
+                pos += len;
+            }
+            _ => break, // fixed32/fixed64 unused by the messages this client sends
+        }
+    }
+    fields
+}
+
+fn get_u64(fields: &HashMap<u32, Field>, field: u32) -> u64 {
+    match fields.get(&field) {
+        Some(Field::Varint(v)) => *v,
+        _ => 0,
+    }
+}
+
+fn get_string(fields: &HashMap<u32, Field>, field: u32) -> String {
+    match fields.get(&field) {
+        Some(Field::LengthDelimited(bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+        _ => String::new(),
+    }
+}
+
+fn get_bytes(fields: &HashMap<u32, Field>, field: u32) -> Vec<u8> {
+    match fields.get(&field) {
+        Some(Field::LengthDelimited(bytes)) => bytes.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// Client for the image management service exposed by a running daemon,
+/// so CLI invocations can share one view of image state instead of each
+/// opening the state database directly. Callers should only construct this
+/// when [`daemon_socket_path`] reports a live socket; every RPC here is a
+/// thin wrapper that will surface [`TtrpcError::RpcError`] until the wire
+/// protocol itself is implemented, matching [`TtrpcClient`] above.
+pub struct ImageServiceClient {
+    socket_path: String,
+}
+
+impl ImageServiceClient {
+    pub fn new(socket_path: impl AsRef<Path>) -> Self {
+        Self {
+            socket_path: socket_path.as_ref().to_string_lossy().into_owned(),
+        }
+    }
+
+    pub async fn list_images(&self) -> Result<Vec<crate::engine::ImageInfo>, TtrpcError> {
+        tracing::debug!("TTRPC ListImages via {}", self.socket_path);
         Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
     }
+
+    pub async fn inspect_image(&self, id: &str) -> Result<crate::engine::ImageInfo, TtrpcError> {
+        tracing::debug!("TTRPC InspectImage: id={}", id);
+        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+    }
+
+    pub async fn remove_image(&self, id: &str, force: bool) -> Result<(), TtrpcError> {
+        tracing::debug!("TTRPC RemoveImage: id={} force={}", id, force);
+        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+    }
+
+    pub async fn prune_images(&self, all: bool) -> Result<(Vec<String>, i64), TtrpcError> {
+        tracing::debug!("TTRPC PruneImages: all={}", all);
+        Err(TtrpcError::RpcError("TTRPC not yet implemented".to_string()))
+    }
+}
+
+/// Path to the daemon's ttrpc socket under a Vordr root directory, if a
+/// daemon happens to be listening there. CLI commands dial this when
+/// present and fall back to opening the state database directly otherwise.
+pub fn daemon_socket_path(root: &Path) -> Option<std::path::PathBuf> {
+    let path = root.join("vordr.sock");
+    path.exists().then_some(path)
 }
 
 /// Container task state
@@ -135,6 +518,18 @@ impl TaskStatus {
         }
     }
 
+    /// Maps a `containerd.task.v2.Status` enum value off the wire.
+    pub fn from_proto(code: u64) -> Self {
+        match code {
+            1 => TaskStatus::Created,
+            2 => TaskStatus::Running,
+            3 => TaskStatus::Stopped,
+            5 => TaskStatus::Paused,
+            6 => TaskStatus::Pausing,
+            _ => TaskStatus::Unknown,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             TaskStatus::Unknown => "unknown",
@@ -0,0 +1,374 @@
+//! SPDX-License-Identifier: MIT OR AGPL-3.0-or-later
+//! Extracts pulled OCI layer blobs into a usable root filesystem
+//!
+//! Each layer blob pulled by [`crate::registry`] is a gzip-compressed tar
+//! archive. [`unpack_layers`] applies them onto a destination directory in
+//! order - later layers can delete or replace whatever earlier ones wrote,
+//! which is the entire point of layering - using the OCI/AUFS whiteout
+//! convention registries rely on to represent those deletions: a
+//! `.wh.<name>` entry removes `<name>` from everything extracted so far,
+//! and a `.wh..wh..opq` entry inside a directory clears every entry
+//! already extracted into that directory (an "opaque" whiteout) before
+//! the rest of the layer is applied. The resulting directory is a plain
+//! root filesystem the engine can hand to [`crate::engine::StateManager`]
+//! as a container's `bundle_path`.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use tar::{Entry, EntryType};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum UnpackError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("layer entry '{0}' escapes the destination root")]
+    PathTraversal(String),
+    #[error("layer entry has no file name: {0}")]
+    MissingFileName(String),
+}
+
+const WHITEOUT_PREFIX: &str = ".wh.";
+const OPAQUE_WHITEOUT: &str = ".wh..wh..opq";
+
+/// Extracts `layers` (paths to local, gzip+tar layer blobs) onto `dest` in
+/// order, creating `dest` if it doesn't already exist. The caller must
+/// pass layers in the same order the image manifest lists them - this
+/// function does no reordering or validation of its own.
+pub fn unpack_layers(layers: &[PathBuf], dest: &Path) -> Result<(), UnpackError> {
+    fs::create_dir_all(dest)?;
+    for layer in layers {
+        unpack_layer(layer, dest)?;
+    }
+    Ok(())
+}
+
+/// Extracts a single gzip+tar layer blob onto `dest`, which may already
+/// hold content from earlier layers.
+fn unpack_layer(layer: &Path, dest: &Path) -> Result<(), UnpackError> {
+    let file = fs::File::open(layer)?;
+    let decoder = GzDecoder::new(file);
+    let mut archive = tar::Archive::new(decoder);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+
+        let file_name = entry_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| UnpackError::MissingFileName(entry_path.display().to_string()))?;
+
+        if file_name == OPAQUE_WHITEOUT {
+            let parent = entry_path.parent().unwrap_or_else(|| Path::new(""));
+            let dir = resolve_dest_path(dest, parent)?;
+            clear_directory(&dir)?;
+            continue;
+        }
+
+        if let Some(target_name) = file_name.strip_prefix(WHITEOUT_PREFIX) {
+            let parent = entry_path.parent().unwrap_or_else(|| Path::new(""));
+            let target = resolve_dest_path(dest, &parent.join(target_name))?;
+            remove_path(&target)?;
+            continue;
+        }
+
+        let target = resolve_dest_path(dest, &entry_path)?;
+        extract_entry(&mut entry, dest, &target)?;
+    }
+
+    Ok(())
+}
+
+/// Resolves a layer entry's path against `dest`, rejecting any entry that
+/// would escape it via a `..` component or a baked-in absolute path -
+/// both are the basis of real path-traversal CVEs against naive tar
+/// extraction.
+fn resolve_dest_path(dest: &Path, entry_path: &Path) -> Result<PathBuf, UnpackError> {
+    let mut resolved = dest.to_path_buf();
+    for component in entry_path.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(UnpackError::PathTraversal(entry_path.display().to_string()));
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Resolves a symlink's target the way the kernel will once it's on disk -
+/// relative to the symlink's own parent directory, not the archive root -
+/// then rejects it if that lands outside `dest`. An absolute target is
+/// treated as rooted at `dest` itself (what it will mean once `dest`
+/// becomes the container's `/`), not the host's root.
+///
+/// This is deliberately not the same lexical walk as [`resolve_dest_path`]:
+/// that function is for entry *paths*, which tar always stores relative to
+/// the archive root, so any `..` in one is already an escape attempt.
+/// A symlink *target* legitimately uses `..` to reach a sibling subtree
+/// (`/var/run -> ../run`, multiarch `include` symlinks, ...), and only
+/// escapes `dest` if it climbs past where its own entry lives.
+fn resolve_symlink_target(dest: &Path, target: &Path, link_name: &Path) -> Result<PathBuf, UnpackError> {
+    let mut stack: Vec<&std::ffi::OsStr> = if link_name.is_absolute() {
+        Vec::new()
+    } else {
+        let base = target.parent().unwrap_or(dest);
+        base.strip_prefix(dest).unwrap_or(base).components().map(|c| c.as_os_str()).collect()
+    };
+
+    for component in link_name.components() {
+        match component {
+            Component::Normal(part) => stack.push(part),
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => stack.clear(),
+            Component::ParentDir => {
+                if stack.pop().is_none() {
+                    return Err(UnpackError::PathTraversal(link_name.display().to_string()));
+                }
+            }
+        }
+    }
+
+    let mut resolved = dest.to_path_buf();
+    resolved.extend(stack);
+    Ok(resolved)
+}
+
+/// Extracts one non-whiteout tar entry to `target`, replacing whatever -
+/// if anything - an earlier layer left there. Regular files go through
+/// `tar`'s own `unpack`, which preserves mode and mtime; symlinks and
+/// hardlinks are recreated by hand since their target needs the same
+/// traversal-safe resolution as any other entry path.
+fn extract_entry<R: Read>(entry: &mut Entry<'_, R>, dest: &Path, target: &Path) -> Result<(), UnpackError> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match entry.header().entry_type() {
+        EntryType::Directory => {
+            fs::create_dir_all(target)?;
+        }
+        EntryType::Symlink => {
+            let link_name = entry
+                .link_name()?
+                .ok_or_else(|| UnpackError::MissingFileName(target.display().to_string()))?;
+            // A raw `..`-laden or absolute target would let a later entry
+            // extract straight through this symlink to anywhere on the
+            // host, once the filesystem itself follows it - see
+            // `resolve_symlink_target`. The symlink is still written with
+            // its original (validated) target, not the resolved one, since
+            // that's what makes it useful once the container runs.
+            resolve_symlink_target(dest, target, &link_name)?;
+            remove_path(target)?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&link_name, target)?;
+        }
+        EntryType::Link => {
+            let link_name = entry
+                .link_name()?
+                .ok_or_else(|| UnpackError::MissingFileName(target.display().to_string()))?;
+            let source = resolve_dest_path(dest, &link_name)?;
+            remove_path(target)?;
+            fs::hard_link(&source, target)?;
+        }
+        _ => {
+            remove_path(target)?;
+            entry.unpack(target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes whatever is at `path`, recursing into directories - used both
+/// for whiteouts and to clear the way when a later layer replaces an
+/// earlier entry with one of a different type (e.g. a file where a
+/// directory used to be). A missing path is not an error.
+fn remove_path(path: &Path) -> Result<(), UnpackError> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path)?,
+        Ok(_) => fs::remove_file(path)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+/// Removes every entry already extracted into `dir`, without removing
+/// `dir` itself - the effect of an opaque whiteout (`.wh..wh..opq`).
+fn clear_directory(dir: &Path) -> Result<(), UnpackError> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        remove_path(&entry?.path())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_gzip_tar(path: &Path, entries: impl FnOnce(&mut tar::Builder<Vec<u8>>)) {
+        let mut builder = tar::Builder::new(Vec::new());
+        entries(&mut builder);
+        let tar_bytes = builder.into_inner().unwrap();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar_bytes).unwrap();
+        let gz_bytes = encoder.finish().unwrap();
+
+        fs::write(path, gz_bytes).unwrap();
+    }
+
+    fn add_file(builder: &mut tar::Builder<Vec<u8>>, path: &str, contents: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_path(path).unwrap();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append(&header, contents).unwrap();
+    }
+
+    fn add_symlink(builder: &mut tar::Builder<Vec<u8>>, path: &str, link_target: &str) {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(EntryType::Symlink);
+        header.set_path(path).unwrap();
+        header.set_size(0);
+        header.set_mode(0o777);
+        header.set_cksum();
+        builder.append_link(&mut header, path, link_target).unwrap();
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vordr-unpack-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn extracts_plain_files_across_layers() {
+        let work = temp_dir("plain");
+        let layer1 = work.join("layer1.tar.gz");
+        write_gzip_tar(&layer1, |b| add_file(b, "etc/hostname", b"layer1\n"));
+
+        let dest = work.join("rootfs");
+        unpack_layers(&[layer1], &dest).unwrap();
+
+        assert_eq!(fs::read_to_string(dest.join("etc/hostname")).unwrap(), "layer1\n");
+
+        fs::remove_dir_all(&work).ok();
+    }
+
+    #[test]
+    fn later_layer_overwrites_earlier_file() {
+        let work = temp_dir("overwrite");
+        let layer1 = work.join("layer1.tar.gz");
+        write_gzip_tar(&layer1, |b| add_file(b, "etc/hostname", b"layer1\n"));
+        let layer2 = work.join("layer2.tar.gz");
+        write_gzip_tar(&layer2, |b| add_file(b, "etc/hostname", b"layer2\n"));
+
+        let dest = work.join("rootfs");
+        unpack_layers(&[layer1, layer2], &dest).unwrap();
+
+        assert_eq!(fs::read_to_string(dest.join("etc/hostname")).unwrap(), "layer2\n");
+
+        fs::remove_dir_all(&work).ok();
+    }
+
+    #[test]
+    fn whiteout_deletes_earlier_file() {
+        let work = temp_dir("whiteout");
+        let layer1 = work.join("layer1.tar.gz");
+        write_gzip_tar(&layer1, |b| add_file(b, "etc/hostname", b"layer1\n"));
+        let layer2 = work.join("layer2.tar.gz");
+        write_gzip_tar(&layer2, |b| add_file(b, "etc/.wh.hostname", b""));
+
+        let dest = work.join("rootfs");
+        unpack_layers(&[layer1, layer2], &dest).unwrap();
+
+        assert!(!dest.join("etc/hostname").exists());
+
+        fs::remove_dir_all(&work).ok();
+    }
+
+    #[test]
+    fn opaque_whiteout_clears_directory_contents() {
+        let work = temp_dir("opaque");
+        let layer1 = work.join("layer1.tar.gz");
+        write_gzip_tar(&layer1, |b| {
+            add_file(b, "data/a.txt", b"a");
+            add_file(b, "data/b.txt", b"b");
+        });
+        let layer2 = work.join("layer2.tar.gz");
+        write_gzip_tar(&layer2, |b| {
+            add_file(b, "data/.wh..wh..opq", b"");
+            add_file(b, "data/c.txt", b"c");
+        });
+
+        let dest = work.join("rootfs");
+        unpack_layers(&[layer1, layer2], &dest).unwrap();
+
+        assert!(!dest.join("data/a.txt").exists());
+        assert!(!dest.join("data/b.txt").exists());
+        assert_eq!(fs::read_to_string(dest.join("data/c.txt")).unwrap(), "c");
+
+        fs::remove_dir_all(&work).ok();
+    }
+
+    #[test]
+    fn rejects_path_traversal_entries() {
+        let work = temp_dir("traversal");
+        let layer1 = work.join("layer1.tar.gz");
+        write_gzip_tar(&layer1, |b| add_file(b, "../../etc/passwd", b"pwned"));
+
+        let dest = work.join("rootfs");
+        let err = unpack_layers(&[layer1], &dest).unwrap_err();
+        assert!(matches!(err, UnpackError::PathTraversal(_)));
+
+        fs::remove_dir_all(&work).ok();
+    }
+
+    #[test]
+    fn rejects_path_traversal_via_symlink_target() {
+        let work = temp_dir("symlink-traversal");
+        let layer1 = work.join("layer1.tar.gz");
+        write_gzip_tar(&layer1, |b| add_symlink(b, "escape", "../../etc"));
+
+        let dest = work.join("rootfs");
+        let err = unpack_layers(&[layer1], &dest).unwrap_err();
+        assert!(matches!(err, UnpackError::PathTraversal(_)));
+        assert!(!dest.join("escape").exists());
+
+        fs::remove_dir_all(&work).ok();
+    }
+
+    #[test]
+    fn allows_benign_relative_symlink_target_reaching_a_sibling() {
+        let work = temp_dir("symlink-sibling");
+        let layer1 = work.join("layer1.tar.gz");
+        write_gzip_tar(&layer1, |b| {
+            add_file(b, "run/resolv.conf", b"nameserver 127.0.0.1\n");
+            add_symlink(b, "var/run", "../run");
+        });
+
+        let dest = work.join("rootfs");
+        unpack_layers(&[layer1], &dest).unwrap();
+
+        assert_eq!(fs::read_link(dest.join("var/run")).unwrap(), Path::new("../run"));
+        assert_eq!(
+            fs::read_to_string(dest.join("var/run/resolv.conf")).unwrap(),
+            "nameserver 127.0.0.1\n"
+        );
+
+        fs::remove_dir_all(&work).ok();
+    }
+}